@@ -240,13 +240,22 @@ mod get_record {
         NonSecretsUtils::get_wallet_record(wallet_handle, type_, id, "{}").unwrap();
     }
 
+    // Uncached and cached reads are measured in one benchmark, so report compares them directly
     pub fn bench(c: &mut Criterion) {
         let wallet_handle = init_wallet();
+        let cached_wallet_handle = init_wallet_with_cache(COUNT);
+
+        // warm up cache, so the bench measures cache hits
+        for i in 0..COUNT {
+            get_record(cached_wallet_handle, &_type(i), &_id(i));
+        }
 
         c.bench(
             "wallet_get_record",
             Benchmark::new("wallet_get_record", move |b|
                 b.iter_with_setup(get_rand_key, |(type_, id): (String, String)| get_record(wallet_handle, &type_, &id)))
+                .with_function("wallet_get_record_cached", move |b|
+                    b.iter_with_setup(get_rand_key, |(type_, id): (String, String)| get_record(cached_wallet_handle, &type_, &id)))
                 .sample_size(50));
     }
 }
@@ -482,9 +491,21 @@ fn add_records(wallet_handle: i32) {
 fn init_wallet() -> i32 {
     TestUtils::cleanup_storage();
 
-    let config = json!({
+    init_wallet_with_config(json!({
             "id": format!("default-wallet_id-{}", SequenceUtils::get_next_id())
-        }).to_string();
+        }))
+}
+
+// Keeps storage of wallets initialized before, so both wallets can be used in one bench
+fn init_wallet_with_cache(cache_size: usize) -> i32 {
+    init_wallet_with_config(json!({
+            "id": format!("default-wallet_id-{}", SequenceUtils::get_next_id()),
+            "cache_size": cache_size
+        }))
+}
+
+fn init_wallet_with_config(config: serde_json::Value) -> i32 {
+    let config = config.to_string();
 
     WalletUtils::create_wallet(&config, WALLET_CREDENTIALS_RAW).unwrap();
     let wallet_handle = WalletUtils::open_wallet(&config, WALLET_CREDENTIALS_RAW).unwrap();
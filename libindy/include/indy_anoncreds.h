@@ -82,6 +82,17 @@ extern "C" {
                                                                            const char*   revoc_reg_delta_json)
                                                       );
 
+    extern indy_error_t indy_issuer_revoke_credentials(indy_handle_t command_handle,
+                                                       indy_handle_t wallet_handle,
+                                                       indy_i32_t    blob_storage_reader_handle,
+                                                       const char *  rev_reg_id,
+                                                       const char *  cred_revoc_ids_json,
+
+                                                       void           (*cb)(indy_handle_t xcommand_handle,
+                                                                            indy_error_t  err,
+                                                                            const char*   revoc_reg_delta_json)
+                                                       );
+
 /*    extern indy_error_t indy_issuer_recover_credential(indy_handle_t command_handle,
                                                        indy_handle_t wallet_handle,
                                                        indy_i32_t    blob_storage_reader_handle,
@@ -111,6 +122,43 @@ extern "C" {
                                                                               indy_error_t  err,
                                                                               const char*   out_master_secret_id)
                                                          );
+
+    extern indy_error_t indy_prover_list_master_secrets(indy_handle_t command_handle,
+                                                        indy_handle_t wallet_handle,
+
+                                                        void           (*cb)(indy_handle_t xcommand_handle,
+                                                                             indy_error_t  err,
+                                                                             const char*   master_secrets_json)
+                                                        );
+
+    extern indy_error_t indy_prover_export_master_secret(indy_handle_t command_handle,
+                                                         indy_handle_t wallet_handle,
+                                                         const char *  master_secret_id,
+                                                         const char *  passphrase,
+
+                                                         void           (*cb)(indy_handle_t xcommand_handle,
+                                                                              indy_error_t  err,
+                                                                              const char*   exported_master_secret_json)
+                                                         );
+
+    extern indy_error_t indy_prover_import_master_secret(indy_handle_t command_handle,
+                                                         indy_handle_t wallet_handle,
+                                                         const char *  master_secret_id,
+                                                         const char *  exported_master_secret_json,
+                                                         const char *  passphrase,
+
+                                                         void           (*cb)(indy_handle_t xcommand_handle,
+                                                                              indy_error_t  err,
+                                                                              const char*   out_master_secret_id)
+                                                         );
+
+    extern indy_error_t indy_prover_delete_master_secret(indy_handle_t command_handle,
+                                                         indy_handle_t wallet_handle,
+                                                         const char *  master_secret_id,
+
+                                                         void           (*cb)(indy_handle_t xcommand_handle,
+                                                                              indy_error_t  err)
+                                                         );
     
     
     extern indy_error_t indy_prover_create_credential_req(indy_handle_t command_handle,
@@ -193,6 +241,17 @@ extern "C" {
                                                                   );
     
 
+    extern indy_error_t indy_prover_select_credentials_for_proof_req(indy_handle_t command_handle,
+                                                                     indy_handle_t wallet_handle,
+                                                                     const char *  proof_request_json,
+                                                                     const char *  selection_policy_json,
+
+                                                                     void           (*cb)(indy_handle_t xcommand_handle,
+                                                                                          indy_error_t  err,
+                                                                                          const char*   requested_credentials_json,
+                                                                                          const char*   unmet_referents_json)
+                                                                     );
+
     extern indy_error_t indy_prover_search_credentials_for_proof_req(indy_handle_t command_handle,
                                                                      indy_handle_t wallet_handle,
                                                                      const char *  proof_request_json,
@@ -249,6 +308,31 @@ extern "C" {
                                                    );
 
 
+    extern indy_error_t indy_verifier_verify_proof_with_options(indy_handle_t command_handle,
+                                                                const char *  proof_request_json,
+                                                                const char *  proof_json,
+                                                                const char *  schemas_json,
+                                                                const char *  credential_defs_jsons,
+                                                                const char *  rev_reg_defs_json,
+                                                                const char *  rev_regs_json,
+                                                                const char *  options_json,
+
+                                                                void           (*cb)(indy_handle_t xcommand_handle,
+                                                                                     indy_error_t  err,
+                                                                                     indy_bool_t   valid )
+                                                                );
+
+
+    extern indy_error_t indy_validate_proof_request(indy_handle_t command_handle,
+                                                    const char *  proof_request_json,
+                                                    const char *  schemas_json,
+                                                    const char *  attr_types_json,
+
+                                                    void           (*cb)(indy_handle_t xcommand_handle,
+                                                                         indy_error_t  err,
+                                                                         const char*   problems_json)
+                                                    );
+
     extern indy_error_t indy_create_revocation_state(indy_handle_t command_handle,
                                                      indy_i32_t    blob_storage_reader_handle,
                                                      const char *  rev_reg_def_json,
@@ -15,7 +15,7 @@ extern "C" {
     /// key_json: Key information as json. Example:
     /// {
     ///     "seed": string, // Optional (if not set random one will be used); Seed information that allows deterministic key creation.
-    ///     "crypto_type": string, // Optional (if not set then ed25519 curve is used); Currently only 'ed25519' value is supported for this field.
+    ///     "crypto_type": string, // Optional (if not set then ed25519 curve is used); Supported values are 'ed25519', 'secp256k1' and 'secp256r1' (NIST P-256; EC types require libindy built with ec_openssl feature).
     /// }
     /// cb: Callback that takes command result as parameter.
     ///
@@ -164,6 +164,52 @@ extern "C" {
                                                                 indy_bool_t   valid )
                                           );
 
+    /// Verify a signature made by a DID.
+    ///
+    /// Resolves the DID verkey the same way as indy_key_for_did (my DIDs and cached their DIDs
+    /// from the wallet, otherwise GET_NYM request to the ledger) and verifies the signature with it.
+    /// Abbreviated verkeys returned by the ledger are expanded to full ones.
+    ///
+    /// If require_current is set the verkey is always requested from the ledger, so a signature
+    /// made by a key that has been rotated out is rejected. The ledger reply is accepted only if it
+    /// has a valid state proof or is confirmed by enough nodes. Cached verkey of their DID is
+    /// refreshed with the ledger one.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// pool_handle: pool handle (created by open_pool_ledger).
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// did: DID of the message signer
+    /// message_raw: a pointer to first byte of message that has been signed
+    /// message_len: a message length
+    /// signature_raw: a pointer to first byte of signature to be verified
+    /// signature_len: a signature length
+    /// require_current: true - resolve verkey from the ledger, false - use local wallet data if present
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// valid: true - if signature is valid, false - otherwise
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Ledger*
+    /// Crypto*
+    extern indy_error_t indy_crypto_verify_for_did(indy_handle_t      command_handle,
+                                                   indy_handle_t      pool_handle,
+                                                   indy_handle_t      wallet_handle,
+                                                   const char *       did,
+                                                   const indy_u8_t *  message_raw,
+                                                   indy_u32_t         message_len,
+                                                   const indy_u8_t *  signature_raw,
+                                                   indy_u32_t         signature_len,
+                                                   indy_bool_t        require_current,
+
+                                                   void           (*cb)(indy_handle_t xcommand_handle,
+                                                                        indy_error_t  err,
+                                                                        indy_bool_t   valid )
+                                                  );
+
     /// Encrypt a message by authenticated-encryption scheme.
     ///
     /// Sender can encrypt a confidential message specifically for Recipient, using Sender's public key.
@@ -316,6 +362,613 @@ extern "C" {
                                                                       indy_u32_t        decrypted_msg_len)
                                                  );
 
+    /// Packs a message for one or more recipients into a JWE-like envelope.
+    ///
+    /// Message content is encrypted once with a random content key (chacha20poly1305_ietf).
+    /// The content key is wrapped for every recipient by authenticated-encryption scheme
+    /// if sender verkey is given (Authcrypt) or by anonymous-encryption scheme otherwise (Anoncrypt).
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// message: a pointer to first byte of message to be packed
+    /// message_len: a message length
+    /// receiver_keys: a json array of recipients verkeys
+    /// sender: (Optional) id (verkey) of my key. If not set Anoncrypt is used.
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// a packed message as a pointer to array of bytes (utf8 encoded json)
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_pack_message(indy_handle_t      command_handle,
+                                          indy_handle_t      wallet_handle,
+                                          const indy_u8_t*   message,
+                                          indy_u32_t         message_len,
+                                          const char *       receiver_keys,
+                                          const char *       sender,
+
+                                          void           (*cb)(indy_handle_t     xcommand_handle,
+                                                               indy_error_t      err,
+                                                               const indy_u8_t*  jwe_data,
+                                                               indy_u32_t        jwe_len)
+                                          );
+
+    /// Unpacks a message packed by indy_pack_message.
+    ///
+    /// The first recipient which key is stored in the wallet is used to unwrap the content key.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// jwe_data: a pointer to first byte of packed message
+    /// jwe_len: a packed message length
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// unpacked message as a pointer to array of bytes (utf8 encoded json):
+    /// {
+    ///     "message": <decrypted message bytes, base64url encoded without padding>,
+    ///     "recipient_verkey": <verkey of my key used to unpack>,
+    ///     "sender_verkey": <sender verkey> // present only for Authcrypt
+    /// }
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_unpack_message(indy_handle_t      command_handle,
+                                            indy_handle_t      wallet_handle,
+                                            const indy_u8_t*   jwe_data,
+                                            indy_u32_t         jwe_len,
+
+                                            void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                 indy_error_t      err,
+                                                                 const indy_u8_t*  res_json_data,
+                                                                 indy_u32_t        res_json_len)
+                                            );
+
+    /// Creates JWS (RFC 7515) signed by EdDSA with a key stored in the wallet.
+    ///
+    /// Signer can be referenced by my DID or by verkey of my key. The signer reference is put to "kid" field
+    /// of protected header as is, so a key referenced by verkey doesn't need to belong to any of my DIDs. Note only ed25519 keys can be used.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// signer: my DID or verkey of my key
+    /// payload_data: a pointer to first byte of payload to be signed
+    /// payload_len: a payload length
+    /// options_json: (Optional) signing options as json. Example:
+    /// {
+    ///     "serialization": string, // Optional (default "compact"); "compact" or "json" (flattened JWS JSON serialization)
+    ///     "detached": bool, // Optional (default false); if true payload is not included to JWS
+    ///     "typ": string, // Optional; "typ" field of protected header
+    /// }
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// jws: JWS in requested serialization
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_crypto_jws_sign(indy_handle_t      command_handle,
+                                             indy_handle_t      wallet_handle,
+                                             const char *       signer,
+                                             const indy_u8_t*   payload_data,
+                                             indy_u32_t         payload_len,
+                                             const char *       options_json,
+
+                                             void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                  indy_error_t      err,
+                                                                  const char *      jws)
+                                             );
+
+    /// Verifies JWS (RFC 7515) signed by EdDSA.
+    ///
+    /// Verification key is resolved by "kid" field of protected header: it must be my or their DID stored in the wallet
+    /// or verkey of my key. Other kid that is a verkey itself is trusted only if "allow_verkey_kid" option is set,
+    /// as anyone can create such JWS.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// jws: JWS in compact or flattened JSON serialization
+    /// payload_data: (Optional) a pointer to first byte of detached payload
+    /// payload_len: a detached payload length
+    /// options_json: (Optional) verification options as json. Example:
+    /// {
+    ///     "signer": string, // Optional; expected signer DID or verkey, JWS signed by anyone else isn't valid
+    ///     "allow_verkey_kid": bool, // Optional (default false); if true kid that isn't DID or key known to the wallet is used as verkey
+    /// }
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// valid: true - if signature is valid and made by expected signer (if given), false - otherwise
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_crypto_jws_verify(indy_handle_t      command_handle,
+                                               indy_handle_t      wallet_handle,
+                                               const char *       jws,
+                                               const indy_u8_t*   payload_data,
+                                               indy_u32_t         payload_len,
+                                               const char *       options_json,
+
+                                               void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                    indy_error_t      err,
+                                                                    indy_bool_t       valid)
+                                               );
+
+    /// Exports a single key (with its metadata) from the wallet as a passphrase-encrypted blob
+    /// (chacha20poly1305_ietf with a key derived from the passphrase by argon2i).
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// wallet_handle: Wallet handle (created by open_wallet).
+    /// verkey: id (verkey) of the key to export
+    /// passphrase: passphrase used to encrypt exported data
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// exported_key: exported data as json:
+    /// {
+    ///     "salt": string, // base64 encoded
+    ///     "nonce": string, // base64 encoded
+    ///     "ciphertext": string, // base64 encoded
+    /// }
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_export_key(indy_handle_t     command_handle,
+                                        indy_handle_t     wallet_handle,
+                                        const char *      verkey,
+                                        const char *      passphrase,
+
+                                        void           (*cb)(indy_handle_t     xcommand_handle,
+                                                             indy_error_t      err,
+                                                             const char *      exported_key)
+                                        );
+
+    /// Imports a key exported by indy_export_key into the wallet.
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// wallet_handle: Wallet handle (created by open_wallet).
+    /// exported_key: exported data as json (see indy_export_key)
+    /// passphrase: passphrase used on export
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// verkey: id (verkey) of imported key
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_import_key(indy_handle_t     command_handle,
+                                        indy_handle_t     wallet_handle,
+                                        const char *      exported_key,
+                                        const char *      passphrase,
+
+                                        void           (*cb)(indy_handle_t     xcommand_handle,
+                                                             indy_error_t      err,
+                                                             const char *      verkey)
+                                        );
+
+    /// Creates root seed in the wallet. The root seed is used for deterministic derivation of
+    /// keys and DIDs (see indy_create_key_derived and indy_create_and_store_my_did_derived).
+    ///
+    /// If mnemonic is passed the root seed is derived from it, so all derived keys and DIDs
+    /// can be recovered later in another wallet from the backed-up mnemonic only.
+    /// The root seed can't be replaced once created.
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// wallet_handle: Wallet handle (created by open_wallet).
+    /// mnemonic: (optional) secret phrase to derive root seed from; random root seed is generated if not set
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error Code
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_create_root_seed(indy_handle_t     command_handle,
+                                              indy_handle_t     wallet_handle,
+                                              const char *      mnemonic,
+
+                                              void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                   indy_error_t      err)
+                                              );
+
+    /// Exports the wallet root seed as a passphrase-encrypted blob, so it can be backed up
+    /// and imported to another wallet by indy_import_root_seed.
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// wallet_handle: Wallet handle (created by open_wallet).
+    /// passphrase: passphrase used to encrypt the root seed
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// exported_root_seed: exported data as json (same format as for indy_export_key)
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet* (WalletItemNotFound if root seed is not created)
+    /// Crypto*
+
+    extern indy_error_t indy_export_root_seed(indy_handle_t     command_handle,
+                                              indy_handle_t     wallet_handle,
+                                              const char *      passphrase,
+
+                                              void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                   indy_error_t      err,
+                                                                   const char *      exported_root_seed)
+                                              );
+
+    /// Imports a root seed exported by indy_export_root_seed into the wallet.
+    /// The root seed can't be replaced once created.
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// wallet_handle: Wallet handle (created by open_wallet).
+    /// exported_root_seed: exported data as json (see indy_export_root_seed)
+    /// passphrase: passphrase used on export
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error Code
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet* (WalletItemAlreadyExists if root seed is already created)
+    /// Crypto*
+
+    extern indy_error_t indy_import_root_seed(indy_handle_t     command_handle,
+                                              indy_handle_t     wallet_handle,
+                                              const char *      exported_root_seed,
+                                              const char *      passphrase,
+
+                                              void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                   indy_error_t      err)
+                                              );
+
+    /// Deterministically derives ed25519 key from the wallet root seed and stores it in the wallet.
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// wallet_handle: Wallet handle (created by open_wallet).
+    /// path: derivation path like "m/0/1". Every index is hardened, so trailing ' is optional.
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// verkey: Ver key of derived key pair to use
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet* (WalletItemNotFound if root seed is not created)
+    /// Crypto*
+
+    extern indy_error_t indy_create_key_derived(indy_handle_t     command_handle,
+                                                indy_handle_t     wallet_handle,
+                                                const char *      path,
+
+                                                void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                     indy_error_t      err,
+                                                                     const char *      verkey)
+                                                );
+
+    /// Replaces tags of the key stored in the wallet. Tags are used for search by indy_search_keys.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// verkey: the key (verkey, key id) stored in the wallet
+    /// tags_json: the tags to replace existing tags of the key with, as json:
+    ///   {
+    ///     "tagName1": <str>, // string tag (will be stored encrypted)
+    ///     "~tagName2": <str>, // string tag (will be stored un-encrypted)
+    ///   }
+    ///   If tag name starts with "~" the tag will be stored un-encrypted that will allow
+    ///   usage of this tag in complex search queries (comparison, predicates)
+    ///   Encrypted tags can be searched only for exact matching
+    ///   "~created_at" tag is set by libindy on creation (unix time in seconds), it is reserved and kept on replace
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error code
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_set_key_tags(indy_handle_t     command_handle,
+                                          indy_handle_t     wallet_handle,
+                                          const char *      verkey,
+                                          const char *      tags_json,
+
+                                          void           (*cb)(indy_handle_t     xcommand_handle,
+                                                               indy_error_t      err)
+                                          );
+
+    /// Searches keys stored in the wallet by their tags.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// query_json: MongoDB style query to key tags (see indy_open_wallet_search), for example:
+    ///  {
+    ///    "label": "Alice",
+    ///    "~created_at": { "$gt": "1530000000" },
+    ///  }
+    ///  Use "{}" to get all keys.
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// keys: list of found keys as json:
+    ///   [{
+    ///     "verkey": string,
+    ///     "metadata": string,
+    ///     "tags": {"tagName": "tagValue", ...},
+    ///   }, ...]
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_search_keys(indy_handle_t     command_handle,
+                                         indy_handle_t     wallet_handle,
+                                         const char *      query_json,
+
+                                         void           (*cb)(indy_handle_t     xcommand_handle,
+                                                              indy_error_t      err,
+                                                              const char *      keys)
+                                         );
+
+    /// Opens a stream to encrypt large data for one or more recipients chunk by chunk.
+    ///
+    /// Data is encrypted with a random content key (chacha20poly1305_ietf) in chunks, every chunk
+    /// is authenticated separately. The content key is wrapped for every recipient the same way
+    /// as in indy_pack_message:
+    /// - if sender verkey is given (Authcrypt) the key is encrypted by authenticated-encryption scheme;
+    /// - otherwise (Anoncrypt) the key is encrypted by anonymous-encryption scheme.
+    ///
+    /// Data is passed by indy_crypto_encrypt_stream_feed calls and the stream is completed by
+    /// indy_crypto_encrypt_stream_finish. Encrypted stream is a concatenation of bytes returned
+    /// by these calls. The stream that won't be finished must be closed by indy_crypto_encrypt_stream_close.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// receiver_keys: a json array of recipients verkeys. Example:
+    ///     ["verkey1", "verkey2"]
+    /// sender: (Optional) id (verkey) of my key. The key must be created by calling indy_create_key or indy_create_and_store_my_did
+    ///     If not set Anoncrypt is used.
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// stream_handle: handle of encryption stream
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_crypto_encrypt_stream_open(indy_handle_t     command_handle,
+                                                        indy_handle_t     wallet_handle,
+                                                        const char *      receiver_keys,
+                                                        const char *      sender,
+
+                                                        void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                             indy_error_t      err,
+                                                                             indy_handle_t     stream_handle)
+                                                        );
+
+    /// Encrypts the next part of data in encryption stream.
+    ///
+    /// The result of the first call starts with stream header. Only complete chunks are encrypted,
+    /// so the results of next calls can be empty.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// stream_handle: handle of encryption stream (created by indy_crypto_encrypt_stream_open).
+    /// data_raw: a pointer to first byte of data to be encrypted
+    /// data_len: a data length
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// next part of encrypted stream as a pointer to array of bytes.
+    ///
+    /// #Errors
+    /// Common*
+
+    extern indy_error_t indy_crypto_encrypt_stream_feed(indy_handle_t     command_handle,
+                                                        indy_handle_t     stream_handle,
+                                                        const indy_u8_t * data_raw,
+                                                        indy_u32_t        data_len,
+
+                                                        void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                             indy_error_t      err,
+                                                                             const indy_u8_t * encrypted_raw,
+                                                                             indy_u32_t        encrypted_len)
+                                                        );
+
+    /// Completes encryption stream and closes its handle.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// stream_handle: handle of encryption stream (created by indy_crypto_encrypt_stream_open).
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// the rest of encrypted stream as a pointer to array of bytes.
+    ///
+    /// #Errors
+    /// Common*
+
+    extern indy_error_t indy_crypto_encrypt_stream_finish(indy_handle_t     command_handle,
+                                                          indy_handle_t     stream_handle,
+
+                                                          void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                               indy_error_t      err,
+                                                                               const indy_u8_t * encrypted_raw,
+                                                                               indy_u32_t        encrypted_len)
+                                                          );
+
+    /// Closes encryption stream handle without completing the stream.
+    ///
+    /// Should be used to release the stream if it won't be finished, e.g. on error.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// stream_handle: handle of encryption stream (created by indy_crypto_encrypt_stream_open).
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error code
+    ///
+    /// #Errors
+    /// Common*
+
+    extern indy_error_t indy_crypto_encrypt_stream_close(indy_handle_t     command_handle,
+                                                         indy_handle_t     stream_handle,
+
+                                                         void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                              indy_error_t      err)
+                                                         );
+
+    /// Opens a stream to decrypt data encrypted by indy_crypto_encrypt_stream_* functions.
+    ///
+    /// The first recipient which key is stored in the wallet is used to unwrap the content key.
+    ///
+    /// Decrypted data is returned as soon as complete chunks are received, but it must not be
+    /// trusted until indy_crypto_decrypt_stream_finish succeeds: only then it is checked that
+    /// the stream isn't truncated. The stream that won't be finished must be closed by
+    /// indy_crypto_decrypt_stream_close.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// stream_handle: handle of decryption stream
+    ///
+    /// #Errors
+    /// Common*
+
+    extern indy_error_t indy_crypto_decrypt_stream_open(indy_handle_t     command_handle,
+                                                        indy_handle_t     wallet_handle,
+
+                                                        void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                             indy_error_t      err,
+                                                                             indy_handle_t     stream_handle)
+                                                        );
+
+    /// Decrypts the next part of encrypted stream.
+    ///
+    /// Only complete chunks are decrypted, so the result can be empty.
+    /// The stream is closed on any error.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// stream_handle: handle of decryption stream (created by indy_crypto_decrypt_stream_open).
+    /// encrypted_raw: a pointer to first byte of the next part of encrypted stream
+    /// encrypted_len: a length of the part
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// next part of decrypted data as a pointer to array of bytes.
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_crypto_decrypt_stream_feed(indy_handle_t     command_handle,
+                                                        indy_handle_t     stream_handle,
+                                                        const indy_u8_t * encrypted_raw,
+                                                        indy_u32_t        encrypted_len,
+
+                                                        void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                             indy_error_t      err,
+                                                                             const indy_u8_t * data_raw,
+                                                                             indy_u32_t        data_len)
+                                                        );
+
+    /// Completes decryption stream and closes its handle.
+    ///
+    /// Fails if the stream is truncated.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// stream_handle: handle of decryption stream (created by indy_crypto_decrypt_stream_open).
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// metadata: stream metadata as json:
+    ///     {
+    ///         "recipient_verkey": <recipient verkey used to decrypt the stream>,
+    ///         "sender_verkey": <sender verkey, only for Authcrypt>
+    ///     }
+    /// the rest of decrypted data as a pointer to array of bytes.
+    ///
+    /// #Errors
+    /// Common*
+
+    extern indy_error_t indy_crypto_decrypt_stream_finish(indy_handle_t     command_handle,
+                                                          indy_handle_t     stream_handle,
+
+                                                          void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                               indy_error_t      err,
+                                                                               const char *      metadata,
+                                                                               const indy_u8_t * data_raw,
+                                                                               indy_u32_t        data_len)
+                                                          );
+
+    /// Closes decryption stream handle without completing the stream.
+    ///
+    /// Should be used to release the stream if it won't be finished, e.g. on error.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// stream_handle: handle of decryption stream (created by indy_crypto_decrypt_stream_open).
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error code
+    ///
+    /// #Errors
+    /// Common*
+
+    extern indy_error_t indy_crypto_decrypt_stream_close(indy_handle_t     command_handle,
+                                                         indy_handle_t     stream_handle,
+
+                                                         void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                              indy_error_t      err)
+                                                         );
+
 #ifdef __cplusplus
 }
 #endif
@@ -22,7 +22,7 @@ extern "C" {
     ///             if provided, then keys will be replaced - key rotation use case)
     ///     "seed": string, (optional; if not provide then a random one will be created)
     ///     "crypto_type": string, (optional; if not set then ed25519 curve is used;
-    ///               currently only 'ed25519' value is supported for this field)
+    ///               supported values are 'ed25519', 'secp256k1' and 'secp256r1' (NIST P-256; EC types require libindy built with ec_openssl feature))
     ///     "cid": bool, (optional; if not set then false is used;)
     /// }
     /// cb: Callback that takes command result as parameter.
@@ -60,7 +60,7 @@ extern "C" {
     /// {
     ///     "seed": string, (optional; if not provide then a random one will be created)
     ///     "crypto_type": string, (optional; if not set then ed25519 curve is used;
-    ///               currently only 'ed25519' value is supported for this field)
+    ///               supported values are 'ed25519', 'secp256k1' and 'secp256r1' (NIST P-256; EC types require libindy built with ec_openssl feature))
     /// }
     /// cb: Callback that takes command result as parameter.
     ///
@@ -422,6 +422,208 @@ extern "C" {
                                                                  const char *const verkey)
                                             );
 
+    /// Exports my DID together with its key and metadata from the wallet as a passphrase-encrypted blob
+    /// (chacha20poly1305_ietf with a key derived from the passphrase by argon2i).
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// wallet_handle: Wallet handle (created by open_wallet).
+    /// did: my DID to export
+    /// passphrase: passphrase used to encrypt exported data
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// exported_did: exported data as json:
+    /// {
+    ///     "salt": string, // base64 encoded
+    ///     "nonce": string, // base64 encoded
+    ///     "ciphertext": string, // base64 encoded
+    /// }
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_export_did(indy_handle_t     command_handle,
+                                        indy_handle_t     wallet_handle,
+                                        const char *      did,
+                                        const char *      passphrase,
+
+                                        void           (*cb)(indy_handle_t     xcommand_handle,
+                                                             indy_error_t      err,
+                                                             const char *      exported_did)
+                                        );
+
+    /// Imports my DID exported by indy_export_did into the wallet.
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// wallet_handle: Wallet handle (created by open_wallet).
+    /// exported_did: exported data as json (see indy_export_did)
+    /// passphrase: passphrase used on export
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// did: imported DID
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_import_did(indy_handle_t     command_handle,
+                                        indy_handle_t     wallet_handle,
+                                        const char *      exported_did,
+                                        const char *      passphrase,
+
+                                        void           (*cb)(indy_handle_t     xcommand_handle,
+                                                             indy_error_t      err,
+                                                             const char *      did)
+                                        );
+
+    /// Deterministically derives DID and ed25519 keys from the wallet root seed (see indy_create_root_seed)
+    /// and stores them in the wallet. Derived DID can be recovered later from the same root seed and path.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to caller context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// path: derivation path like "m/0/1". Every index is hardened, so trailing ' is optional.
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// did: DID generated and stored in the wallet
+    /// verkey: The DIDs verification key
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet* (WalletItemNotFound if root seed is not created)
+    /// Crypto*
+
+    extern indy_error_t indy_create_and_store_my_did_derived(indy_handle_t     command_handle,
+                                                             indy_handle_t     wallet_handle,
+                                                             const char *      path,
+
+                                                             void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                                  indy_error_t      err,
+                                                                                  const char *      did,
+                                                                                  const char *      verkey)
+                                                             );
+
+    /// Rotates keys of an existing DID (owned by the caller of the library) on the ledger and in the wallet.
+    ///
+    /// Performs the whole flow that otherwise requires indy_replace_keys_start, sending of NYM
+    /// transaction signed by the current key and indy_replace_keys_apply:
+    /// 1. Generates temporary keys for the DID.
+    /// 2. Sends NYM transaction with the new verkey signed by the current key.
+    /// 3. Checks by GET_NYM (verified by state proof or consensus of nodes) that the ledger contains the new verkey.
+    /// 4. Applies the new keys as main for the DID.
+    ///
+    /// If the ledger rejects NYM transaction the temporary keys are deleted and the DID keeps the current keys.
+    /// If NYM transaction is written, but the new verkey can't be confirmed, the temporary keys are kept,
+    /// so the rotation can be finished by indy_replace_keys_apply.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// pool_handle: pool handle (created by open_pool).
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// did: target did to rotate keys.
+    /// key_info: key information as json (see indy_replace_keys_start). Example:
+    /// {
+    ///     "seed": string, (optional; if not provide then a random one will be created)
+    ///     "crypto_type": string, (optional; if not set then ed25519 curve is used)
+    /// }
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// verkey: The DIDs new verification key
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Ledger*
+    /// Crypto*
+
+    extern indy_error_t indy_rotate_did_key(indy_handle_t     command_handle,
+                                            indy_handle_t     pool_handle,
+                                            indy_handle_t     wallet_handle,
+                                            const char *      did,
+                                            const char *      key_info,
+
+                                            void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                 indy_error_t      err,
+                                                                 const char *      verkey)
+                                            );
+
+    /// Replaces tags of the DID stored in the wallet. Tags are used for search by indy_search_my_dids.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// did: DID stored in the wallet
+    /// tags_json: the tags to replace existing tags of the DID with, as json:
+    ///   {
+    ///     "tagName1": <str>, // string tag (will be stored encrypted)
+    ///     "~tagName2": <str>, // string tag (will be stored un-encrypted)
+    ///   }
+    ///   If tag name starts with "~" the tag will be stored un-encrypted that will allow
+    ///   usage of this tag in complex search queries (comparison, predicates)
+    ///   Encrypted tags can be searched only for exact matching
+    ///   "~created_at" tag is set by libindy on creation (unix time in seconds), it is reserved and kept on replace
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error code
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+    /// Crypto*
+
+    extern indy_error_t indy_set_did_tags(indy_handle_t     command_handle,
+                                          indy_handle_t     wallet_handle,
+                                          const char *      did,
+                                          const char *      tags_json,
+
+                                          void           (*cb)(indy_handle_t     xcommand_handle,
+                                                               indy_error_t      err)
+                                          );
+
+    /// Searches DIDs stored in the wallet by their tags.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// query_json: MongoDB style query to DID tags (see indy_open_wallet_search), for example:
+    ///  {
+    ///    "label": "Alice",
+    ///    "~created_at": { "$gt": "1530000000" },
+    ///  }
+    ///  Use "{}" to get all DIDs.
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// dids: list of found DIDs as json:
+    ///   [{
+    ///     "did": string,
+    ///     "verkey": string,
+    ///     "metadata": string,
+    ///     "tags": {"tagName": "tagValue", ...},
+    ///   }, ...]
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_search_my_dids(indy_handle_t     command_handle,
+                                            indy_handle_t     wallet_handle,
+                                            const char *      query_json,
+
+                                            void           (*cb)(indy_handle_t     xcommand_handle,
+                                                                 indy_error_t      err,
+                                                                 const char *      dids)
+                                            );
+
 #ifdef __cplusplus
 }
 #endif
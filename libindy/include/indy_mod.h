@@ -98,6 +98,9 @@ typedef enum
     // Returned if provided wallet query is invalid
     WalletQueryError = 214,
 
+    // Returned if record version passed to conditional update or delete doesn't match the stored one
+    WalletItemVersionMismatch = 215,
+
     // Ledger errors
     // Trying to open pool ledger that wasn't created before
     PoolLedgerNotCreatedError = 300,
@@ -142,6 +145,9 @@ typedef enum
     // Attempt to create credential definition with duplicated did schema pair
     AnoncredsCredDefAlreadyExistsError = 407,
 
+    // Attempt to delete master secret that is still referenced by stored credentials
+    AnoncredsMasterSecretInUseError = 408,
+
     // Crypto errors
     // Unknown format of DID entity keys
     UnknownCryptoTypeError = 500,
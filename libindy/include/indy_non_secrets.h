@@ -35,6 +35,37 @@ extern "C" {
                                                                     indy_error_t err)
                                               );
 
+    /// Create a new non-secret record in the wallet with the given options
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to caller context
+    /// wallet_handle: wallet handle (created by open_wallet)
+    /// type_: allows to separate different record types collections
+    /// id: the id of record
+    /// value: the value of record
+    /// tags_json: (optional) the record tags used for search and storing meta information as json
+    ///   (see indy_add_wallet_record)
+    /// options_json: (optional) the record options as json:
+    ///   {
+    ///     expiresAt: (optional) unix time in seconds since which the record is hidden from reads
+    ///       and searches until it is purged (see indy_purge_expired_wallet_records)
+    ///   }
+    ///   Note that null means no options
+    ///
+    /// #Errors
+    /// WalletStorageError if wallet storage doesn't support record expiry
+
+    extern indy_error_t indy_add_wallet_record_with_options(indy_handle_t  command_handle,
+                                                            indy_handle_t  wallet_handle,
+                                                            const char*    type_,
+                                                            const char*    id,
+                                                            const char*    value,
+                                                            const char*    tags_json,
+                                                            const char*    options_json,
+                                                            void           (*fn)(indy_handle_t xcommand_handle,
+                                                                                 indy_error_t err)
+                                                           );
+
     /// Update a non-secret wallet record value
     ///
     /// #Params
@@ -53,6 +84,29 @@ extern "C" {
                                                                              indy_error_t err)
                                                        );
 
+    /// Update a non-secret wallet record value if the record wasn't changed since it was read.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to caller context
+    /// wallet_handle: wallet handle (created by open_wallet)
+    /// type_: allows to separate different record types collections
+    /// id: the id of record
+    /// value: the new value of record
+    /// version: the expected version of record (see retrieveVersion option of indy_get_wallet_record)
+    ///
+    /// #Errors
+    /// WalletItemVersionMismatch if the record version differs from the expected one
+
+    extern indy_error_t indy_update_wallet_record_value_if_version(indy_handle_t  command_handle,
+                                                                   indy_handle_t  wallet_handle,
+                                                                   const char*    type_,
+                                                                   const char*    id,
+                                                                   const char*    value,
+                                                                   indy_u64_t     version,
+                                                                   void           (*fn)(indy_handle_t xcommand_handle,
+                                                                                        indy_error_t err)
+                                                                  );
+
 /// Update a non-secret wallet record tags
 ///
 /// #Params
@@ -80,6 +134,29 @@ extern "C" {
                                                                             indy_error_t err)
                                                       );
 
+    /// Update a non-secret wallet record tags if the record wasn't changed since it was read.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to caller context
+    /// wallet_handle: wallet handle (created by open_wallet)
+    /// type_: allows to separate different record types collections
+    /// id: the id of record
+    /// tags_json: the record tags in the same format as for indy_update_wallet_record_tags
+    /// version: the expected version of record (see retrieveVersion option of indy_get_wallet_record)
+    ///
+    /// #Errors
+    /// WalletItemVersionMismatch if the record version differs from the expected one
+
+    extern indy_error_t indy_update_wallet_record_tags_if_version(indy_handle_t  command_handle,
+                                                                  indy_handle_t  wallet_handle,
+                                                                  const char*    type_,
+                                                                  const char*    id,
+                                                                  const char*    tags_json,
+                                                                  indy_u64_t     version,
+                                                                  void           (*fn)(indy_handle_t xcommand_handle,
+                                                                                       indy_error_t err)
+                                                                 );
+
     /// Add new tags to the wallet record
     ///
     /// #Params
@@ -144,6 +221,53 @@ extern "C" {
                                                                        indy_error_t err)
                                                  );
 
+    /// Delete an existing wallet record in the wallet if the record wasn't changed since it was read.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to caller context
+    /// wallet_handle: wallet handle (created by open_wallet)
+    /// type_: record type
+    /// id: the id of record
+    /// version: the expected version of record (see retrieveVersion option of indy_get_wallet_record)
+    ///
+    /// #Errors
+    /// WalletItemVersionMismatch if the record version differs from the expected one
+
+    extern indy_error_t indy_delete_wallet_record_if_version(indy_handle_t  command_handle,
+                                                             indy_handle_t  wallet_handle,
+                                                             const char*    type_,
+                                                             const char*    id,
+                                                             indy_u64_t     version,
+                                                             void           (*fn)(indy_handle_t xcommand_handle,
+                                                                                  indy_error_t err)
+                                                            );
+
+    /// Apply the list of non-secret record operations to the wallet atomically:
+    /// either all operations are applied or none of them (e.g. if any record is missing).
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to caller context
+    /// wallet_handle: wallet handle (created by open_wallet)
+    /// operations_json: the list of operations applied in the given order as json array:
+    ///   [
+    ///     {"op": "add", "type": <str>, "id": <str>, "value": <str>, "tags": <tags json, optional>,
+    ///      "expires_at": <unix time in seconds, optional>},
+    ///     {"op": "update", "type": <str>, "id": <str>, "value": <str>},
+    ///     {"op": "add_tags", "type": <str>, "id": <str>, "tags": <tags json>},
+    ///     {"op": "update_tags", "type": <str>, "id": <str>, "tags": <tags json>},
+    ///     {"op": "delete_tags", "type": <str>, "id": <str>, "tag_names": ["tagName1", ...]},
+    ///     {"op": "delete", "type": <str>, "id": <str>},
+    ///   ]
+    ///   Tags json has the same format as for indy_add_wallet_record
+    ///   Note that plugged storages must register batch handler with indy_register_wallet_storage_batch
+
+    extern indy_error_t indy_wallet_batch(indy_handle_t  command_handle,
+                                          indy_handle_t  wallet_handle,
+                                          const char*    operations_json,
+                                          void           (*fn)(indy_handle_t xcommand_handle,
+                                                               indy_error_t err)
+                                         );
+
     /// Get an wallet record by id
     ///
     /// #Params
@@ -155,7 +279,9 @@ extern "C" {
     ///  {
     ///    retrieveType: (optional, false by default) Retrieve record type,
     ///    retrieveValue: (optional, true by default) Retrieve record value,
-    ///    retrieveTags: (optional, true by default) Retrieve record tags
+    ///    retrieveTags: (optional, true by default) Retrieve record tags,
+    ///    retrieveVersion: (optional, false by default) Retrieve record version
+    ///      (changed on any change of record, never reused by re-added record)
     ///  }
     /// #Returns
     /// wallet record json:
@@ -164,6 +290,7 @@ extern "C" {
     ///   type: "Some type", // present only if retrieveType set to true
     ///   value: "Some value", // present only if retrieveValue set to true
     ///   tags: <tags json>, // present only if retrieveTags set to true
+    ///   version: <u64>, // present only if retrieveVersion set to true and storage supports versions
     /// }
 
     extern indy_error_t indy_get_wallet_record(indy_handle_t  command_handle,
@@ -193,6 +320,10 @@ extern "C" {
     ///      "tagName3": { $gte: '123' },
     ///    },
     ///  }
+    ///  Note $gt, $gte, $lt, $lte compare values of unencrypted ("~" prefixed) tags as strings,
+    ///  use JSON number as operand (for example { $gte: 123 }) to compare them numerically.
+    ///  Numeric comparison matches only tag values like "-12" or "0.5" (optional minus, digits,
+    ///  optional fraction), integers that fit 64-bit signed integer are compared exactly, the rest as doubles.
     /// options_json: //TODO: FIXME: Think about replacing by bitmask
     ///  {
     ///    retrieveRecords: (optional, true by default) If false only "counts" will be calculated,
@@ -200,6 +331,11 @@ extern "C" {
     ///    retrieveType: (optional, false by default) Retrieve record type,
     ///    retrieveValue: (optional, true by default) Retrieve record value,
     ///    retrieveTags: (optional, true by default) Retrieve record tags,
+    ///    retrieveVersion: (optional, false by default) Retrieve record version,
+    ///    sortBy: (optional) Name of unencrypted ("~" prefixed) tag to sort records by,
+    ///    sortOrder: (optional, "asc" by default) Sort order: "asc" or "desc",
+    ///    offset: (optional) Number of matching records to skip,
+    ///    limit: (optional) Max number of records to retrieve,
     ///  }
     /// #Returns
     /// search_handle: Wallet search handle that can be used later
@@ -226,7 +362,8 @@ extern "C" {
     ///  {
     ///    retrieveType: (optional, false by default) Retrieve record type,
     ///    retrieveValue: (optional, true by default) Retrieve record value,
-    ///    retrieveTags: (optional, true by default) Retrieve record tags
+    ///    retrieveTags: (optional, true by default) Retrieve record tags,
+    ///    retrieveVersion: (optional, false by default) Retrieve record version
     ///  }
     /// #Returns
     /// wallet record json:
@@ -235,6 +372,7 @@ extern "C" {
     ///   type: "Some type", // present only if retrieveType set to true
     ///   value: "Some value", // present only if retrieveValue set to true
     ///   tags: <tags json>, // present only if retrieveTags set to true
+    ///   version: <u64>, // present only if retrieveVersion set to true and storage supports versions
     /// }
 
     extern indy_error_t indy_fetch_wallet_search_next_records(indy_handle_t  command_handle,
@@ -257,6 +395,74 @@ extern "C" {
                                                                       indy_error_t  err)
                                                 );
 
+    /// Count wallet records that match the query.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to caller context
+    /// wallet_handle: wallet handle (created by open_wallet)
+    /// type_: allows to separate different record types collections
+    /// query_json: MongoDB style query to wallet record tags (see indy_open_wallet_search)
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// count: count of records that match the query
+
+    extern indy_error_t indy_count_wallet_records(indy_handle_t  command_handle,
+                                                  indy_handle_t  wallet_handle,
+                                                  const char*    type_,
+                                                  const char*    query_json,
+                                                  void           (*fn)(indy_handle_t xcommand_handle,
+                                                                       indy_error_t  err,
+                                                                       indy_u32_t    count)
+                                                 );
+
+    /// Count wallet records that match the query grouped by value of unencrypted tag.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to caller context
+    /// wallet_handle: wallet handle (created by open_wallet)
+    /// type_: allows to separate different record types collections
+    /// query_json: MongoDB style query to wallet record tags (see indy_open_wallet_search)
+    /// tag_name: name of unencrypted ("~" prefixed) tag to group records by
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// counts_json: count of records per tag value, records without this tag aren't counted:
+    /// {
+    ///   "tagValue1": <int>,
+    ///   "tagValue2": <int>,
+    /// }
+
+    extern indy_error_t indy_count_wallet_records_by_tag(indy_handle_t  command_handle,
+                                                         indy_handle_t  wallet_handle,
+                                                         const char*    type_,
+                                                         const char*    query_json,
+                                                         const char*    tag_name,
+                                                         void           (*fn)(indy_handle_t xcommand_handle,
+                                                                              indy_error_t  err,
+                                                                              const char*   counts_json)
+                                                        );
+
+    /// Delete all expired non-secret records from the wallet.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to caller context
+    /// wallet_handle: wallet handle (created by open_wallet)
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// count: count of deleted records
+    ///
+    /// #Errors
+    /// WalletStorageError if wallet storage doesn't support record expiry
+
+    extern indy_error_t indy_purge_expired_wallet_records(indy_handle_t  command_handle,
+                                                          indy_handle_t  wallet_handle,
+                                                          void           (*fn)(indy_handle_t xcommand_handle,
+                                                                               indy_error_t  err,
+                                                                               indy_u32_t    count)
+                                                         );
+
 #ifdef __cplusplus
 }
 #endif
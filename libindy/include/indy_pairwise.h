@@ -63,6 +63,9 @@ extern "C" {
 
     /// Get list of saved pairwise.
     ///
+    /// NOTE: This method immediately returns all saved pairwise.
+    /// Use <indy_open_pairwise_search> to fetch pairwise by small batches.
+    ///
     /// #Params
     /// wallet_handle: wallet handler (created by open_wallet).
     /// command_handle: command handle to map callback to user context.
@@ -135,6 +138,211 @@ extern "C" {
                                                   );
 
 
+    /// Replaces tags of the pairwise stored in the wallet. Tags are used for search by indy_search_pairwise.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// their_did: their DID of the pairwise
+    /// tags_json: the tags to replace existing tags of the pairwise with, as json:
+    ///   {
+    ///     "tagName1": <str>, // string tag (will be stored encrypted)
+    ///     "~tagName2": <str>, // string tag (will be stored un-encrypted)
+    ///   }
+    ///   If tag name starts with "~" the tag will be stored un-encrypted that will allow
+    ///   usage of this tag in complex search queries (comparison, predicates)
+    ///   Encrypted tags can be searched only for exact matching
+    ///   "~created_at" tag (creation unix time in seconds) and "my_did" tag are set by libindy, they are reserved and kept on replace
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error code
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_set_pairwise_tags(indy_handle_t command_handle,
+                                               indy_handle_t wallet_handle,
+                                               const char *  their_did,
+                                               const char *  tags_json,
+
+                                               void          (*cb)(indy_handle_t  xcommand_handle,
+                                                                   indy_error_t   err)
+                                              );
+
+
+    /// Searches pairwise stored in the wallet by their tags.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// query_json: MongoDB style query to pairwise tags (see indy_open_wallet_search), for example:
+    ///  {
+    ///    "label": "Alice",
+    ///    "~created_at": { "$gt": "1530000000" },
+    ///  }
+    ///  Use "{}" to get all pairwise.
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// list_pairwise: list of found pairwise as json:
+    ///   [{
+    ///     "my_did": string,
+    ///     "their_did": string,
+    ///     "metadata": string, (optional)
+    ///     "tags": {"tagName": "tagValue", ...},
+    ///   }, ...]
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_search_pairwise(indy_handle_t command_handle,
+                                             indy_handle_t wallet_handle,
+                                             const char *  query_json,
+
+                                             void          (*cb)(indy_handle_t  xcommand_handle,
+                                                                 indy_error_t   err,
+                                                                 const char*    list_pairwise)
+                                            );
+
+
+    /// Deletes pairwise from the Wallet.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// their_did: their DID of the pairwise
+    /// cascade: also delete their DID of the pairwise with its metadata and endpoint.
+    ///   My DID and its keys are never deleted, as they can be used beyond the pairwise.
+    ///   Records are deleted atomically if wallet storage supports batches.
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error code
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_delete_pairwise(indy_handle_t command_handle,
+                                             indy_handle_t wallet_handle,
+                                             const char *  their_did,
+                                             indy_bool_t   cascade,
+
+                                             void          (*cb)(indy_handle_t  xcommand_handle,
+                                                                 indy_error_t   err)
+                                            );
+
+
+    /// Re-points pairwise to the new DID of the counterparty (for example after the counterparty rotated its DID).
+    /// Metadata and tags of the pairwise are kept.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// their_did: current their DID of the pairwise
+    /// new_their_did: new their DID of the pairwise. Should be already stored in the wallet (see indy_store_their_did).
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error code
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_update_pairwise_their_did(indy_handle_t command_handle,
+                                                       indy_handle_t wallet_handle,
+                                                       const char *  their_did,
+                                                       const char *  new_their_did,
+
+                                                       void          (*cb)(indy_handle_t  xcommand_handle,
+                                                                           indy_error_t   err)
+                                                      );
+
+
+    /// Opens search for pairwise stored in the Wallet.
+    ///
+    /// Instead of immediately returning of all pairwise (as indy_list_pairwise does)
+    /// this call returns search_handle that can be used later
+    /// to fetch pairwise by small batches (with indy_fetch_pairwise_search_next).
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// wallet_handle: wallet handler (created by open_wallet).
+    /// query_json: (optional) MongoDB style query to pairwise tags (see indy_search_pairwise).
+    ///   All pairwise are returned if not set.
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// search_handle: Search handle that can be used later to fetch pairwise by small batches (with indy_fetch_pairwise_search_next)
+    /// total_count: Total count of pairwise
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_open_pairwise_search(indy_handle_t command_handle,
+                                                  indy_handle_t wallet_handle,
+                                                  const char *  query_json,
+
+                                                  void          (*cb)(indy_handle_t  xcommand_handle,
+                                                                      indy_error_t   err,
+                                                                      indy_handle_t  search_handle,
+                                                                      indy_u32_t     total_count)
+                                                 );
+
+
+    /// Fetches next pairwise for search.
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// search_handle: Search handle (created by indy_open_pairwise_search)
+    /// count: Count of pairwise to fetch
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// list_pairwise: list of pairwise as json (see indy_search_pairwise)
+    /// NOTE: The list of length less than the requested count means pairwise search iterator is completed.
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_fetch_pairwise_search_next(indy_handle_t command_handle,
+                                                        indy_handle_t search_handle,
+                                                        indy_u32_t    count,
+
+                                                        void          (*cb)(indy_handle_t  xcommand_handle,
+                                                                            indy_error_t   err,
+                                                                            const char*    list_pairwise)
+                                                       );
+
+
+    /// Closes pairwise search (make search handle invalid)
+    ///
+    /// #Params
+    /// command_handle: command handle to map callback to user context.
+    /// search_handle: Search handle (created by indy_open_pairwise_search)
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Error code
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_close_pairwise_search(indy_handle_t command_handle,
+                                                   indy_handle_t search_handle,
+
+                                                   void          (*cb)(indy_handle_t  xcommand_handle,
+                                                                       indy_error_t   err)
+                                                  );
+
+
 #ifdef __cplusplus
 }
 #endif
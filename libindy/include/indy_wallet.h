@@ -144,6 +144,53 @@ extern "C" {
                                                   void         (*fn)(indy_handle_t xcommand_handle, indy_error_t err)
                                                   );
 
+    /// Register batch handler for custom wallet storage implementation.
+    /// Storage type must be registered with indy_register_wallet_storage before.
+    /// Without this handler indy_wallet_batch calls will fail for wallets of this storage type
+    /// and libindy changes of several related records (e.g. pairwise deletion) are applied one by one.
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// type_: Storage type name.
+    /// batch: WalletType batch operation handler
+    ///
+    /// #Returns
+    /// Error code
+
+    extern indy_error_t indy_register_wallet_storage_batch(indy_handle_t  command_handle,
+                                                           const char*    type_,
+                                                           indy_error_t (*batchFn)(indy_handle_t handle,
+                                                                                   const char* operations_json),
+
+                                                           void         (*fn)(indy_handle_t xcommand_handle, indy_error_t err)
+                                                           );
+
+    /// Register record version handler for custom wallet storage implementation.
+    /// Storage type must be registered with indy_register_wallet_storage before.
+    /// Without this handler records of this storage type are returned without versions
+    /// and conditional record updates and deletes fail.
+    ///
+    /// Storage supporting versions must strictly increase record version on each change of
+    /// record value or tags and handle "check_version" operation of batch handler.
+    /// Versions are opaque: only versions of the same record can be compared.
+    ///
+    /// #Params
+    /// command_handle: Command handle to map callback to caller context.
+    /// type_: Storage type name.
+    /// get_record_version: WalletType get record version handler
+    ///
+    /// #Returns
+    /// Error code
+
+    extern indy_error_t indy_register_wallet_storage_versioning(indy_handle_t  command_handle,
+                                                                const char*    type_,
+                                                                indy_error_t (*getRecordVersionFn)(indy_handle_t handle,
+                                                                                                   indy_handle_t record_handle,
+                                                                                                   indy_u64_t*   version_p),
+
+                                                                void         (*fn)(indy_handle_t xcommand_handle, indy_error_t err)
+                                                                );
+
     /// Create a new secure wallet.
     ///
     /// #Params
@@ -153,14 +200,16 @@ extern "C" {
     ///         Configured storage uses this identifier to lookup exact wallet data placement.
     ///   "storage_type": optional<string>, Type of the wallet storage. Defaults to 'default'.
     ///                  'Default' storage type allows to store wallet data in the local file.
+    ///                  'kv' storage type allows to store wallet data in the local file of embedded key-value store.
+    ///                  'inmem' storage type keeps wallet data in the process memory only, data is lost on process exit.
     ///                  Custom storage types can be registered with indy_register_wallet_storage call.
     ///   "storage_config": optional<object>, Storage configuration json. Storage type defines set of supported keys.
     ///                     Can be optional if storage supports default configuration.
-    ///                     For 'default' storage type configuration is:
+    ///                     For 'default' and 'kv' storage types configuration is:
     ///   {
     ///     "path": optional<string>, Path to the directory with wallet files.
     ///             Defaults to $HOME/.indy_client/wallets.
-    ///             Wallet will be stored in the file {path}/{id}/sqlite.db
+    ///             Wallet will be stored in the file {path}/{id}/sqlite.db ({path}/{id}/kv.db for 'kv' storage type)
     ///   }
     /// }
     /// credentials: Wallet credentials json
@@ -201,15 +250,19 @@ extern "C" {
     ///             Configured storage uses this identifier to lookup exact wallet data placement.
     ///       "storage_type": optional<string>, Type of the wallet storage. Defaults to 'default'.
     ///                       'Default' storage type allows to store wallet data in the local file.
+    ///                       'kv' storage type allows to store wallet data in the local file of embedded key-value store.
+    ///                       'inmem' storage type keeps wallet data in the process memory only, data is lost on process exit.
     ///                       Custom storage types can be registered with indy_register_wallet_storage call.
     ///       "storage_config": optional<object>, Storage configuration json. Storage type defines set of supported keys.
     ///                         Can be optional if storage supports default configuration.
-    ///                         For 'default' storage type configuration is:
+    ///                         For 'default' and 'kv' storage types configuration is:
     ///           {
     ///              "path": optional<string>, Path to the directory with wallet files.
     ///                      Defaults to $HOME/.indy_client/wallets.
-    ///                      Wallet will be stored in the file {path}/{id}/sqlite.db
+    ///                      Wallet will be stored in the file {path}/{id}/sqlite.db ({path}/{id}/kv.db for 'kv' storage type)
     ///           }
+    ///       "cache_size": optional<int>, Max number of decrypted records cached in memory while the wallet is open.
+    ///                     Cached records are invalidated on update and delete. Cache is disabled by default.
     ///
     ///   }
     /// credentials: Wallet credentials json
@@ -275,6 +328,48 @@ extern "C" {
                                            void           (*fn)(indy_handle_t xcommand_handle, indy_error_t err)
                                            );
 
+    /// Checks integrity of opened wallet
+    ///
+    /// Walks all wallet records and validates decryption of their types, ids, values and tags.
+    /// Unreadable records are reported and can be quarantined: such record is replaced with
+    /// a record of "Indy::QuarantinedRecord" type which value keeps raw encrypted content
+    /// of the original one. So the rest of the wallet can be read and searched.
+    /// Tags left without their records are reported too and deleted on quarantine.
+    /// Storage errors met while records are read are reported and the check goes on.
+    ///
+    /// #Params:
+    /// wallet_handle: wallet handle returned by indy_open_wallet
+    /// check_config: JSON containing settings for check operation.
+    ///   {
+    ///     "quarantine": optional<bool>, Quarantine unreadable records and delete orphan tags. Defaults to false.
+    ///   }
+    /// cb: Callback that takes command result as parameter.
+    ///
+    /// #Returns
+    /// Check report json:
+    ///   {
+    ///     "checked": <int>, Number of checked records
+    ///     "failed": [{
+    ///         "type": optional<string>, Record type if it can be decrypted
+    ///         "id": optional<string>, Record id if it can be decrypted
+    ///         "encrypted_id": <string>, Base64 encoded encrypted record id
+    ///         "error": <string>, Description of the failure
+    ///     }],
+    ///     "quarantined": <int>, Number of quarantined records
+    ///     "orphan_tags": <int>, Number of tags left without their records
+    ///     "storage_errors": [<string>], Descriptions of storage errors met while records were read
+    ///   }
+    ///
+    /// #Errors
+    /// Common*
+    /// Wallet*
+
+    extern indy_error_t indy_check_wallet(indy_handle_t  command_handle,
+                                          indy_handle_t  wallet_handle,
+                                          const char*    check_config_json,
+                                          void           (*fn)(indy_handle_t xcommand_handle, indy_error_t err, const char* report_json)
+                                          );
+
 
     /// Creates a new secure wallet and then imports its content
     /// according to fields provided in import_config
@@ -287,14 +382,16 @@ extern "C" {
     ///         Configured storage uses this identifier to lookup exact wallet data placement.
     ///   "storage_type": optional<string>, Type of the wallet storage. Defaults to 'default'.
     ///                  'Default' storage type allows to store wallet data in the local file.
+    ///                  'kv' storage type allows to store wallet data in the local file of embedded key-value store.
+    ///                  'inmem' storage type keeps wallet data in the process memory only, data is lost on process exit.
     ///                  Custom storage types can be registered with indy_register_wallet_storage call.
     ///   "storage_config": optional<object>, Storage configuration json. Storage type defines set of supported keys.
     ///                     Can be optional if storage supports default configuration.
-    ///                     For 'default' storage type configuration is:
+    ///                     For 'default' and 'kv' storage types configuration is:
     ///   {
     ///     "path": optional<string>, Path to the directory with wallet files.
     ///             Defaults to $HOME/.indy_client/wallets.
-    ///             Wallet will be stored in the file {path}/{id}/sqlite.db
+    ///             Wallet will be stored in the file {path}/{id}/sqlite.db ({path}/{id}/kv.db for 'kv' storage type)
     ///   }
     /// }
     /// credentials: Wallet credentials json
@@ -356,14 +453,16 @@ extern "C" {
     ///         Configured storage uses this identifier to lookup exact wallet data placement.
     ///   "storage_type": optional<string>, Type of the wallet storage. Defaults to 'default'.
     ///                  'Default' storage type allows to store wallet data in the local file.
+    ///                  'kv' storage type allows to store wallet data in the local file of embedded key-value store.
+    ///                  'inmem' storage type keeps wallet data in the process memory only, data is lost on process exit.
     ///                  Custom storage types can be registered with indy_register_wallet_storage call.
     ///   "storage_config": optional<object>, Storage configuration json. Storage type defines set of supported keys.
     ///                     Can be optional if storage supports default configuration.
-    ///                     For 'default' storage type configuration is:
+    ///                     For 'default' and 'kv' storage types configuration is:
     ///   {
     ///     "path": optional<string>, Path to the directory with wallet files.
     ///             Defaults to $HOME/.indy_client/wallets.
-    ///             Wallet will be stored in the file {path}/{id}/sqlite.db
+    ///             Wallet will be stored in the file {path}/{id}/sqlite.db ({path}/{id}/kv.db for 'kv' storage type)
     ///   }
     /// }
     /// credentials: Wallet credentials json
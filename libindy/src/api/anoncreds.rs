@@ -16,9 +16,11 @@ use domain::anoncreds::credential_request::{CredentialRequest, CredentialRequest
 use domain::anoncreds::credential::{Credential, AttributeValues};
 use domain::anoncreds::revocation_registry_definition::{RevocationRegistryConfig, RevocationRegistryDefinition};
 use domain::anoncreds::revocation_registry_delta::RevocationRegistryDelta;
-use domain::anoncreds::proof::Proof;
-use domain::anoncreds::proof_request::{ProofRequest, ProofRequestExtraQuery};
+use domain::anoncreds::proof::{Proof, VerifyProofOptions};
+use domain::anoncreds::proof_request::{ProofRequest, ProofRequestExtraQuery, SchemaAttributeTypes};
 use domain::anoncreds::requested_credential::RequestedCredentials;
+use domain::anoncreds::credential_selection::CredentialSelectionPolicy;
+use domain::crypto::export::EncryptedExport;
 use domain::anoncreds::revocation_registry::RevocationRegistry;
 use domain::anoncreds::revocation_state::RevocationState;
 use utils::ctypes;
@@ -501,6 +503,74 @@ pub extern fn indy_issuer_revoke_credential(command_handle: i32,
     res
 }
 
+/// Revoke a list of credentials identified by cred_revoc_ids (returned by indy_issuer_create_credential)
+/// from the same revocation registry.
+///
+/// The corresponding credential definition and revocation registry must be already
+/// created an stored into the wallet.
+///
+/// All ids are checked before revocation registry is changed, so in case of error no credential is revoked.
+/// The revocation registry is stored in the wallet only once.
+///
+/// This call returns one revoc registry delta as json file covering all revoked credentials
+/// intended to be shared as single REVOC_REG_ENTRY transaction.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// blob_storage_reader_cfg_handle: configuration of blob storage reader handle that will allow to read revocation tails
+/// rev_reg_id: id of revocation registry stored in wallet
+/// cred_revoc_ids_json: list of local ids for revocation info as json: ["<cred_revoc_id>", ...]
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// revoc_reg_delta_json: Revocation registry delta json with all revoked credentials
+///
+/// #Errors
+/// Annoncreds*
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_issuer_revoke_credentials(command_handle: i32,
+                                             wallet_handle: i32,
+                                             blob_storage_reader_cfg_handle: i32,
+                                             rev_reg_id: *const c_char,
+                                             cred_revoc_ids_json: *const c_char,
+                                             cb: Option<extern fn(xcommand_handle: i32, err: ErrorCode,
+                                                                  revoc_reg_delta_json: *const c_char)>) -> ErrorCode {
+    trace!("indy_issuer_revoke_credentials: >>> wallet_handle: {:?}, blob_storage_reader_cfg_handle: {:?}, rev_reg_id: {:?}, cred_revoc_ids_json: {:?}",
+           wallet_handle, blob_storage_reader_cfg_handle, rev_reg_id, cred_revoc_ids_json);
+
+    check_useful_c_str!(rev_reg_id, ErrorCode::CommonInvalidParam4);
+    check_useful_json!(cred_revoc_ids_json, ErrorCode::CommonInvalidParam5, Vec<String>);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam6);
+
+    trace!("indy_issuer_revoke_credentials: entities >>> wallet_handle: {:?}, blob_storage_reader_cfg_handle: {:?}, rev_reg_id: {:?}, cred_revoc_ids_json: {:?}",
+           wallet_handle, blob_storage_reader_cfg_handle, rev_reg_id, secret!(&cred_revoc_ids_json));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Anoncreds(
+            AnoncredsCommand::Issuer(
+                IssuerCommand::RevokeCredentials(
+                    wallet_handle,
+                    blob_storage_reader_cfg_handle,
+                    rev_reg_id,
+                    cred_revoc_ids_json,
+                    Box::new(move |result| {
+                        let (err, revoc_reg_delta_json) = result_to_err_code_1!(result, String::new());
+                        trace!("indy_issuer_revoke_credentials: revoc_reg_delta_json: {:?}", revoc_reg_delta_json);
+                        let revoc_reg_delta_json = ctypes::string_to_cstring(revoc_reg_delta_json);
+                        cb(command_handle, err, revoc_reg_delta_json.as_ptr())
+                    })
+                ))));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_issuer_revoke_credentials: <<< res: {:?}", res);
+
+    res
+}
+
 /*/// Recover a credential identified by a cred_revoc_id (returned by indy_issuer_create_credential).
 ///
 /// The corresponding credential definition and revocation registry must be already
@@ -657,6 +727,221 @@ pub extern fn indy_prover_create_master_secret(command_handle: i32,
     res
 }
 
+/// Lists master secrets stored in the wallet.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// master_secrets_json: list of master secrets with number of stored credentials bound to each of them
+///     [{
+///         "id": string, // master secret id
+///         "cred_count": int, // number of stored credentials issued for this master secret
+///     }]
+/// NOTE: Credentials stored before credentials were bound to master secrets are not counted.
+///
+/// #Errors
+/// Annoncreds*
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_prover_list_master_secrets(command_handle: i32,
+                                              wallet_handle: i32,
+                                              cb: Option<extern fn(xcommand_handle: i32, err: ErrorCode,
+                                                                   master_secrets_json: *const c_char)>) -> ErrorCode {
+    trace!("indy_prover_list_master_secrets: >>> wallet_handle: {:?}", wallet_handle);
+
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam3);
+
+    trace!("indy_prover_list_master_secrets: entities >>> wallet_handle: {:?}", wallet_handle);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Anoncreds(
+            AnoncredsCommand::Prover(
+                ProverCommand::ListMasterSecrets(
+                    wallet_handle,
+                    Box::new(move |result| {
+                        let (err, master_secrets_json) = result_to_err_code_1!(result, String::new());
+                        trace!("indy_prover_list_master_secrets: master_secrets_json: {:?}", master_secrets_json);
+                        let master_secrets_json = ctypes::string_to_cstring(master_secrets_json);
+                        cb(command_handle, err, master_secrets_json.as_ptr())
+                    })
+                ))));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_prover_list_master_secrets: <<< res: {:?}", res);
+
+    res
+}
+
+/// Exports a master secret stored in the wallet encrypted with a passphrase.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// master_secret_id: id of master secret to export
+/// passphrase: passphrase the encryption key is derived from (argon2i)
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// exported_master_secret_json: master secret encrypted with chacha20poly1305_ietf
+///     {
+///         "salt": string, // base64 encoded key derivation salt
+///         "nonce": string, // base64 encoded nonce
+///         "ciphertext": string, // base64 encoded encrypted master secret
+///     }
+///
+/// #Errors
+/// Annoncreds*
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_prover_export_master_secret(command_handle: i32,
+                                               wallet_handle: i32,
+                                               master_secret_id: *const c_char,
+                                               passphrase: *const c_char,
+                                               cb: Option<extern fn(xcommand_handle: i32, err: ErrorCode,
+                                                                    exported_master_secret_json: *const c_char)>) -> ErrorCode {
+    trace!("indy_prover_export_master_secret: >>> wallet_handle: {:?}, master_secret_id: {:?}, passphrase: {:?}", wallet_handle, master_secret_id, passphrase);
+
+    check_useful_c_str!(master_secret_id, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(passphrase, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_prover_export_master_secret: entities >>> wallet_handle: {:?}, master_secret_id: {:?}, passphrase: {:?}",
+           wallet_handle, master_secret_id, secret!(&passphrase));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Anoncreds(
+            AnoncredsCommand::Prover(
+                ProverCommand::ExportMasterSecret(
+                    wallet_handle,
+                    master_secret_id,
+                    passphrase,
+                    Box::new(move |result| {
+                        let (err, exported_master_secret_json) = result_to_err_code_1!(result, String::new());
+                        trace!("indy_prover_export_master_secret: exported_master_secret_json: {:?}", exported_master_secret_json);
+                        let exported_master_secret_json = ctypes::string_to_cstring(exported_master_secret_json);
+                        cb(command_handle, err, exported_master_secret_json.as_ptr())
+                    })
+                ))));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_prover_export_master_secret: <<< res: {:?}", res);
+
+    res
+}
+
+/// Imports a master secret exported by indy_prover_export_master_secret and stores it in the wallet.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// master_secret_id: (optional, if not present random one will be generated) id to store master secret with
+/// exported_master_secret_json: exported master secret json (see indy_prover_export_master_secret)
+/// passphrase: passphrase used on export
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// out_master_secret_id: Id of imported master secret
+///
+/// #Errors
+/// Annoncreds*
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_prover_import_master_secret(command_handle: i32,
+                                               wallet_handle: i32,
+                                               master_secret_id: *const c_char,
+                                               exported_master_secret_json: *const c_char,
+                                               passphrase: *const c_char,
+                                               cb: Option<extern fn(xcommand_handle: i32, err: ErrorCode,
+                                                                    out_master_secret_id: *const c_char)>) -> ErrorCode {
+    trace!("indy_prover_import_master_secret: >>> wallet_handle: {:?}, master_secret_id: {:?}, exported_master_secret_json: {:?}, passphrase: {:?}",
+           wallet_handle, master_secret_id, exported_master_secret_json, passphrase);
+
+    check_useful_opt_c_str!(master_secret_id, ErrorCode::CommonInvalidParam3);
+    check_useful_json!(exported_master_secret_json, ErrorCode::CommonInvalidParam4, EncryptedExport);
+    check_useful_c_str!(passphrase, ErrorCode::CommonInvalidParam5);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam6);
+
+    trace!("indy_prover_import_master_secret: entities >>> wallet_handle: {:?}, master_secret_id: {:?}, exported_master_secret_json: {:?}, passphrase: {:?}",
+           wallet_handle, master_secret_id, exported_master_secret_json, secret!(&passphrase));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Anoncreds(
+            AnoncredsCommand::Prover(
+                ProverCommand::ImportMasterSecret(
+                    wallet_handle,
+                    master_secret_id,
+                    exported_master_secret_json,
+                    passphrase,
+                    Box::new(move |result| {
+                        let (err, out_master_secret_id) = result_to_err_code_1!(result, String::new());
+                        trace!("indy_prover_import_master_secret: out_master_secret_id: {:?}", out_master_secret_id);
+                        let out_master_secret_id = ctypes::string_to_cstring(out_master_secret_id);
+                        cb(command_handle, err, out_master_secret_id.as_ptr())
+                    })
+                ))));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_prover_import_master_secret: <<< res: {:?}", res);
+
+    res
+}
+
+/// Deletes a master secret from the wallet.
+///
+/// A master secret still referenced by stored credentials can't be deleted.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// master_secret_id: id of master secret to delete
+/// cb: Callback that takes command result as parameter.
+///
+/// #Errors
+/// AnoncredsMasterSecretInUseError - if there are stored credentials bound to the master secret
+/// Annoncreds*
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_prover_delete_master_secret(command_handle: i32,
+                                               wallet_handle: i32,
+                                               master_secret_id: *const c_char,
+                                               cb: Option<extern fn(xcommand_handle: i32, err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_prover_delete_master_secret: >>> wallet_handle: {:?}, master_secret_id: {:?}", wallet_handle, master_secret_id);
+
+    check_useful_c_str!(master_secret_id, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_prover_delete_master_secret: entities >>> wallet_handle: {:?}, master_secret_id: {:?}", wallet_handle, master_secret_id);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Anoncreds(
+            AnoncredsCommand::Prover(
+                ProverCommand::DeleteMasterSecret(
+                    wallet_handle,
+                    master_secret_id,
+                    Box::new(move |result| {
+                        let err = result_to_err_code!(result);
+                        trace!("indy_prover_delete_master_secret:");
+                        cb(command_handle, err)
+                    })
+                ))));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_prover_delete_master_secret: <<< res: {:?}", res);
+
+    res
+}
+
 /// Creates a credential request for the given credential offer.
 ///
 /// The method creates a blinded master secret for a master secret identified by a provided name.
@@ -1212,6 +1497,96 @@ pub extern fn indy_prover_get_credentials_for_proof_req(command_handle: i32,
     res
 }
 
+/// Selects credentials from the wallet to satisfy the given proof request.
+///
+/// For each requested attribute and predicate one matching credential is chosen according to
+/// the selection policy. The result can be passed directly to indy_prover_create_proof.
+///
+/// Revocation is the caller's job: revocation registries are not read and only credentials listed
+/// in "revoked" of the policy are skipped. Without it a revoked credential can be selected.
+///
+/// Restrictions of the proof request can't reference tags libindy sets on credentials for own needs
+/// ("master_secret_id", "stored_at"), such proof request is rejected as invalid.
+///
+/// #Params
+/// wallet_handle: wallet handler (created by open_wallet).
+/// proof_request_json: proof request json (see indy_prover_search_credentials_for_proof_req)
+/// selection_policy_json:(Optional) policy used to choose between matching credentials:
+///     {
+///         "strategy": Optional<string>, // "newest" - the most recently stored credential (default),
+///                                       // "fewest_credentials" - minimize the number of distinct credentials used
+///         "issuer_did": Optional<string>, // prefer credentials issued by this DID
+///         "revoked": Optional<{ // credentials known to be revoked at the requested interval
+///             "<rev_reg_id>": [<cred_rev_id>, ...],
+///             ...,
+///         }>,
+///     }
+///     The caller is responsible to get the revoked ids from the ledger for the requested interval.
+///     Credentials stored by previous versions of libindy have no time of storing and are
+///     considered older than any other credential.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// requested_credentials_json: selected credentials in the format accepted by indy_prover_create_proof
+///     {
+///         "self_attested_attributes": {},
+///         "requested_attributes": {
+///             "<attr_referent>": {"cred_id": string, "timestamp": Optional<number>, "revealed": true},
+///         },
+///         "requested_predicates": {
+///             "<predicate_referent>": {"cred_id": string, "timestamp": Optional<number>},
+///         },
+///     }
+/// unmet_referents_json: list of attribute and predicate referents no credential was found for
+///     ["<attr_referent>", "<predicate_referent>", ...]
+///
+/// #Errors
+/// Annoncreds*
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_prover_select_credentials_for_proof_req(command_handle: i32,
+                                                           wallet_handle: i32,
+                                                           proof_request_json: *const c_char,
+                                                           selection_policy_json: *const c_char,
+                                                           cb: Option<extern fn(
+                                                               xcommand_handle: i32, err: ErrorCode,
+                                                               requested_credentials_json: *const c_char,
+                                                               unmet_referents_json: *const c_char)>) -> ErrorCode {
+    trace!("indy_prover_select_credentials_for_proof_req: >>> wallet_handle: {:?}, proof_request_json: {:?}, selection_policy_json: {:?}",
+           wallet_handle, proof_request_json, selection_policy_json);
+
+    check_useful_json!(proof_request_json, ErrorCode::CommonInvalidParam3, ProofRequest);
+    check_useful_opt_json!(selection_policy_json, ErrorCode::CommonInvalidParam4, CredentialSelectionPolicy);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_prover_select_credentials_for_proof_req: entities >>> wallet_handle: {:?}, proof_request_json: {:?}, selection_policy_json: {:?}",
+           wallet_handle, proof_request_json, selection_policy_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Anoncreds(
+            AnoncredsCommand::Prover(
+                ProverCommand::SelectCredentialsForProofReq(
+                    wallet_handle,
+                    proof_request_json,
+                    selection_policy_json,
+                    Box::new(move |result| {
+                        let (err, requested_credentials_json, unmet_referents_json) = result_to_err_code_2!(result, String::new(), String::new());
+                        trace!("indy_prover_select_credentials_for_proof_req: requested_credentials_json: {:?}, unmet_referents_json: {:?}",
+                               requested_credentials_json, unmet_referents_json);
+                        let requested_credentials_json = ctypes::string_to_cstring(requested_credentials_json);
+                        let unmet_referents_json = ctypes::string_to_cstring(unmet_referents_json);
+                        cb(command_handle, err, requested_credentials_json.as_ptr(), unmet_referents_json.as_ptr())
+                    })
+                ))));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_prover_select_credentials_for_proof_req: <<< res: {:?}", res);
+
+    res
+}
+
 /// Search for credentials matching the given proof request.
 ///
 /// Instead of immediately returning of fetched credentials
@@ -1707,6 +2082,7 @@ pub extern fn indy_verifier_verify_proof(command_handle: i32,
             credential_defs_json,
             rev_reg_defs_json,
             rev_regs_json,
+            VerifyProofOptions::default(),
             Box::new(move |result| {
                 let (err, valid) = result_to_err_code_1!(result, false);
                 trace!("indy_verifier_verify_proof: valid: {:?}", valid);
@@ -1722,6 +2098,159 @@ pub extern fn indy_verifier_verify_proof(command_handle: i32,
     res
 }
 
+/// Verifies a proof (of multiple credential) with additional verification options.
+///
+/// Without options behaves exactly like indy_verifier_verify_proof. With "strict_encoding" every
+/// revealed raw value must match its encoded value under the standard encoding (32-bit integers in
+/// normalized form as themselves, other values as the decimal representation of their sha256 digest),
+/// so integers like "+42" or "0042" must be encoded as "42".
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// proof_request_json: proof request json (see indy_verifier_verify_proof)
+/// proof_json: created for request proof json (see indy_verifier_verify_proof)
+/// schemas_json: all schemas json participating in the proof
+/// credential_defs_json: all credential definitions json participating in the proof
+/// rev_reg_defs_json: all revocation registry definitions json participating in the proof
+/// rev_regs_json: all revocation registries json participating in the proof
+/// options_json: (optional) verification options
+///     {
+///         "strict_encoding": Optional<bool>, // reject revealed raw values not matching the standard encoding (false by default)
+///     }
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// valid: true - if signature is valid, false - otherwise
+///
+/// #Errors
+/// Annoncreds*
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_verifier_verify_proof_with_options(command_handle: i32,
+                                                      proof_request_json: *const c_char,
+                                                      proof_json: *const c_char,
+                                                      schemas_json: *const c_char,
+                                                      credential_defs_json: *const c_char,
+                                                      rev_reg_defs_json: *const c_char,
+                                                      rev_regs_json: *const c_char,
+                                                      options_json: *const c_char,
+                                                      cb: Option<extern fn(xcommand_handle: i32, err: ErrorCode,
+                                                                           valid: bool)>) -> ErrorCode {
+    trace!("indy_verifier_verify_proof_with_options: >>> proof_request_json: {:?}, proof_json: {:?}, schemas_json: {:?}, credential_defs_json: {:?}, \
+    rev_reg_defs_json: {:?}, rev_regs_json: {:?}, options_json: {:?}", proof_request_json, proof_json, schemas_json, credential_defs_json, rev_reg_defs_json, rev_regs_json, options_json);
+
+    check_useful_json!(proof_request_json, ErrorCode::CommonInvalidParam2, ProofRequest);
+    check_useful_json!(proof_json, ErrorCode::CommonInvalidParam3, Proof);
+    check_useful_json!(schemas_json, ErrorCode::CommonInvalidParam4, HashMap<String, Schema>);
+    check_useful_json!(credential_defs_json, ErrorCode::CommonInvalidParam5, HashMap<String, CredentialDefinition>);
+    check_useful_json!(rev_reg_defs_json, ErrorCode::CommonInvalidParam6, HashMap<String, RevocationRegistryDefinition>);
+    check_useful_json!(rev_regs_json, ErrorCode::CommonInvalidParam7, HashMap<String, HashMap<u64, RevocationRegistry>>);
+    check_useful_opt_json!(options_json, ErrorCode::CommonInvalidParam8, VerifyProofOptions);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam9);
+
+    trace!("indy_verifier_verify_proof_with_options: entities >>> proof_request_json: {:?}, proof_json: {:?}, schemas_json: {:?}, credential_defs_json: {:?}, \
+    rev_reg_defs_json: {:?}, rev_regs_json: {:?}, options_json: {:?}", proof_request_json, proof_json, schemas_json, credential_defs_json, rev_reg_defs_json, rev_regs_json, options_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Anoncreds(AnoncredsCommand::Verifier(VerifierCommand::VerifyProof(
+            proof_request_json,
+            proof_json,
+            schemas_json,
+            credential_defs_json,
+            rev_reg_defs_json,
+            rev_regs_json,
+            options_json.unwrap_or_default(),
+            Box::new(move |result| {
+                let (err, valid) = result_to_err_code_1!(result, false);
+                trace!("indy_verifier_verify_proof_with_options: valid: {:?}", valid);
+
+                cb(command_handle, err, valid)
+            })
+        ))));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_verifier_verify_proof_with_options: <<< res: {:?}", res);
+
+    res
+}
+
+/// Validates a proof request before it is sent to a prover.
+///
+/// Checks that restrictions are valid wql (or restrictions in the old array format) using only
+/// credential tags known to the prover, that non_revoked intervals are well-formed and
+/// attribute level intervals intersect the proof request level one, and that requested attributes
+/// and predicates are present in the schemas they are restricted to (or in any of given schemas
+/// if restrictions do not reference a schema). Schemas don't define attribute types, so predicates
+/// are checked to be on numeric attributes only if attribute types are given. A predicate is reported
+/// only if its attribute is not numeric in every schema it can be proven from.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// proof_request_json: proof request json (see indy_verifier_verify_proof)
+/// schemas_json: (optional) schemas to check requested attributes against
+///     {
+///         <schema1_id>: <schema1_json>,
+///         <schema2_id>: <schema2_json>,
+///     }
+/// attr_types_json: (optional) types of schema attributes to check predicates against
+///     {
+///         <schema1_id>: {
+///             <attr_name>: "number" | "string",
+///         },
+///     }
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// problems_json: list of found problems, empty if proof request is valid
+///     [{
+///         "type": string, // one of: "unknown_restriction_key", "malformed_restriction",
+///                         // "invalid_non_revoked_interval", "conflicting_non_revoked_interval",
+///                         // "attribute_not_in_schema", "predicate_on_non_numeric_attribute"
+///         "referent": Optional<string>, // attribute or predicate referent, null for proof request level problems
+///         "message": string // human readable explanation
+///     }]
+///
+/// #Errors
+/// Annoncreds*
+/// Common*
+#[no_mangle]
+pub extern fn indy_validate_proof_request(command_handle: i32,
+                                          proof_request_json: *const c_char,
+                                          schemas_json: *const c_char,
+                                          attr_types_json: *const c_char,
+                                          cb: Option<extern fn(xcommand_handle: i32, err: ErrorCode,
+                                                               problems_json: *const c_char)>) -> ErrorCode {
+    trace!("indy_validate_proof_request: >>> proof_request_json: {:?}, schemas_json: {:?}, attr_types_json: {:?}", proof_request_json, schemas_json, attr_types_json);
+
+    check_useful_json!(proof_request_json, ErrorCode::CommonInvalidParam2, ProofRequest);
+    check_useful_opt_json!(schemas_json, ErrorCode::CommonInvalidParam3, HashMap<String, Schema>);
+    check_useful_opt_json!(attr_types_json, ErrorCode::CommonInvalidParam4, SchemaAttributeTypes);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_validate_proof_request: entities >>> proof_request_json: {:?}, schemas_json: {:?}, attr_types_json: {:?}", proof_request_json, schemas_json, attr_types_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Anoncreds(AnoncredsCommand::Verifier(VerifierCommand::ValidateProofRequest(
+            proof_request_json,
+            schemas_json.unwrap_or(HashMap::new()),
+            attr_types_json.unwrap_or(HashMap::new()),
+            Box::new(move |result| {
+                let (err, problems_json) = result_to_err_code_1!(result, String::new());
+                trace!("indy_validate_proof_request: problems_json: {:?}", problems_json);
+                let problems_json = ctypes::string_to_cstring(problems_json);
+                cb(command_handle, err, problems_json.as_ptr())
+            })
+        ))));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_validate_proof_request: <<< res: {:?}", res);
+
+    res
+}
+
 /// Create revocation state for a credential in the particular time moment.
 ///
 /// #Params
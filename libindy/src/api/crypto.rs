@@ -3,7 +3,11 @@ extern crate libc;
 use api::ErrorCode;
 use commands::{Command, CommandExecutor};
 use commands::crypto::CryptoCommand;
+use commands::did::DidCommand;
 use domain::crypto::key::KeyInfo;
+use domain::crypto::jws::{JWSSignOptions, JWSVerifyOptions};
+use domain::crypto::export::EncryptedExport;
+use domain::wallet::Tags;
 use errors::common::CommonError;
 use errors::ToErrorCode;
 use utils::ctypes;
@@ -20,7 +24,7 @@ use self::libc::c_char;
 /// key_json: Key information as json. Example:
 /// {
 ///     "seed": string, // Optional (if not set random one will be used); Seed information that allows deterministic key creation.
-///     "crypto_type": string, // Optional (if not set then ed25519 curve is used); Currently only 'ed25519' value is supported for this field.
+///     "crypto_type": string, // Optional (if not set then ed25519 curve is used); Supported values are 'ed25519', 'secp256k1' and 'secp256r1' (NIST P-256; EC types require libindy built with ec_openssl feature).
 /// }
 /// cb: Callback that takes command result as parameter.
 ///
@@ -295,6 +299,85 @@ pub  extern fn indy_crypto_verify(command_handle: i32,
     res
 }
 
+/// Verify a signature made by a DID.
+///
+/// Resolves the DID verkey the same way as indy_key_for_did (my DIDs and cached their DIDs
+/// from the wallet, otherwise GET_NYM request to the ledger) and verifies the signature with it.
+/// Abbreviated verkeys returned by the ledger are expanded to full ones.
+///
+/// If require_current is set the verkey is always requested from the ledger, so a signature
+/// made by a key that has been rotated out is rejected. The ledger reply is accepted only if it
+/// has a valid state proof or is confirmed by enough nodes. Cached verkey of their DID is
+/// refreshed with the ledger one.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// pool_handle: pool handle (created by open_pool_ledger).
+/// wallet_handle: wallet handler (created by open_wallet).
+/// did: DID of the message signer
+/// message_raw: a pointer to first byte of message that has been signed
+/// message_len: a message length
+/// signature_raw: a pointer to first byte of signature to be verified
+/// signature_len: a signature length
+/// require_current: true - resolve verkey from the ledger, false - use local wallet data if present
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// valid: true - if signature is valid, false - otherwise
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Ledger*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_crypto_verify_for_did(command_handle: i32,
+                                         pool_handle: i32,
+                                         wallet_handle: i32,
+                                         did: *const c_char,
+                                         message_raw: *const u8,
+                                         message_len: u32,
+                                         signature_raw: *const u8,
+                                         signature_len: u32,
+                                         require_current: bool,
+                                         cb: Option<extern fn(xcommand_handle: i32,
+                                                              err: ErrorCode,
+                                                              valid: bool)>) -> ErrorCode {
+    trace!("indy_crypto_verify_for_did: >>> pool_handle: {:?}, wallet_handle: {:?}, did: {:?}, message_raw: {:?}, message_len: {:?}, \
+    signature_raw: {:?}, signature_len: {:?}, require_current: {:?}",
+           pool_handle, wallet_handle, did, message_raw, message_len, signature_raw, signature_len, require_current);
+
+    check_useful_c_str!(did, ErrorCode::CommonInvalidParam4);
+    check_useful_c_byte_array!(message_raw, message_len, ErrorCode::CommonInvalidParam5, ErrorCode::CommonInvalidParam6);
+    check_useful_c_byte_array!(signature_raw, signature_len, ErrorCode::CommonInvalidParam7, ErrorCode::CommonInvalidParam8);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam10);
+
+    trace!("indy_crypto_verify_for_did: entities >>> pool_handle: {:?}, wallet_handle: {:?}, did: {:?}, message_raw: {:?}, message_len: {:?}, \
+    signature_raw: {:?}, signature_len: {:?}, require_current: {:?}",
+           pool_handle, wallet_handle, did, message_raw, message_len, signature_raw, signature_len, require_current);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Did(DidCommand::VerifyForDid(
+            pool_handle,
+            wallet_handle,
+            did,
+            message_raw,
+            signature_raw,
+            require_current,
+            Box::new(move |result| {
+                let (err, valid) = result_to_err_code_1!(result, false);
+                trace!("indy_crypto_verify_for_did: valid: {:?}", valid);
+                cb(command_handle, err, valid)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_verify_for_did: <<< res: {:?}", res);
+
+    res
+}
+
 /// Encrypt a message by authenticated-encryption scheme.
 ///
 /// Sender can encrypt a confidential message specifically for Recipient, using Sender's public key.
@@ -556,4 +639,1125 @@ pub  extern fn indy_crypto_anon_decrypt(command_handle: i32,
     trace!("indy_crypto_anon_decrypt: <<< res: {:?}", res);
 
     res
-}
\ No newline at end of file
+}
+/// Packs a message for one or more recipients into a JWE-like envelope.
+///
+/// Message content is encrypted once with a random content key (chacha20poly1305_ietf).
+/// The content key is wrapped separately for every recipient:
+/// - if sender verkey is given (Authcrypt) the key is encrypted by authenticated-encryption scheme
+///   and sender verkey is sealed for every recipient, so only recipients can see who sent the message;
+/// - otherwise (Anoncrypt) the key is encrypted by anonymous-encryption scheme.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// message: a pointer to first byte of message to be packed
+/// message_len: a message length
+/// receiver_keys: a json array of recipients verkeys. Example:
+///     ["verkey1", "verkey2"]
+/// sender: (Optional) id (verkey) of my key. The key must be created by calling indy_create_key or indy_create_and_store_my_did
+///     If not set Anoncrypt is used.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// a packed message as a pointer to array of bytes. It is utf8 encoded json:
+/// {
+///     "protected": <base64url encoded protected header without padding>,
+///     "iv": <base64url encoded content nonce without padding>,
+///     "ciphertext": <base64url encoded encrypted content without padding>,
+///     "tag": <base64url encoded authentication tag without padding>
+/// }
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub  extern fn indy_pack_message(command_handle: i32,
+                                 wallet_handle: i32,
+                                 message: *const u8,
+                                 message_len: u32,
+                                 receiver_keys: *const c_char,
+                                 sender: *const c_char,
+                                 cb: Option<extern fn(command_handle_: i32,
+                                                      err: ErrorCode,
+                                                      jwe_data: *const u8,
+                                                      jwe_len: u32)>) -> ErrorCode {
+    trace!("indy_pack_message: >>> wallet_handle: {:?}, message: {:?}, message_len: {:?}, receiver_keys: {:?}, sender: {:?}",
+           wallet_handle, message, message_len, receiver_keys, sender);
+
+    check_useful_c_byte_array!(message, message_len, ErrorCode::CommonInvalidParam3, ErrorCode::CommonInvalidParam4);
+    check_useful_json!(receiver_keys, ErrorCode::CommonInvalidParam5, Vec<String>);
+    check_useful_opt_c_str!(sender, ErrorCode::CommonInvalidParam6);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam7);
+
+    trace!("indy_pack_message: entities >>> wallet_handle: {:?}, message: {:?}, message_len: {:?}, receiver_keys: {:?}, sender: {:?}",
+           wallet_handle, message, message_len, receiver_keys, sender);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::PackMessage(
+            wallet_handle,
+            message,
+            receiver_keys,
+            sender,
+            Box::new(move |result| {
+                let (err, jwe) = result_to_err_code_1!(result, Vec::new());
+                trace!("indy_pack_message: jwe: {:?}", jwe);
+                let (jwe_data, jwe_len) = ctypes::vec_to_pointer(&jwe);
+                cb(command_handle, err, jwe_data, jwe_len)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_pack_message: <<< res: {:?}", res);
+
+    res
+}
+
+/// Unpacks a message packed by indy_pack_message.
+///
+/// The first recipient which key is stored in the wallet is used to unwrap the content key.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// jwe_data: a pointer to first byte of packed message
+/// jwe_len: a packed message length
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// unpacked message as a pointer to array of bytes. It is utf8 encoded json:
+/// {
+///     "message": <decrypted message bytes, base64url encoded without padding>,
+///     "recipient_verkey": <verkey of my key used to unpack>,
+///     "sender_verkey": <sender verkey> // present only for Authcrypt
+/// }
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub  extern fn indy_unpack_message(command_handle: i32,
+                                   wallet_handle: i32,
+                                   jwe_data: *const u8,
+                                   jwe_len: u32,
+                                   cb: Option<extern fn(command_handle_: i32,
+                                                        err: ErrorCode,
+                                                        res_json_data: *const u8,
+                                                        res_json_len: u32)>) -> ErrorCode {
+    trace!("indy_unpack_message: >>> wallet_handle: {:?}, jwe_data: {:?}, jwe_len: {:?}", wallet_handle, jwe_data, jwe_len);
+
+    check_useful_c_byte_array!(jwe_data, jwe_len, ErrorCode::CommonInvalidParam3, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_unpack_message: entities >>> wallet_handle: {:?}, jwe_data: {:?}, jwe_len: {:?}", wallet_handle, jwe_data, jwe_len);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::UnpackMessage(
+            wallet_handle,
+            jwe_data,
+            Box::new(move |result| {
+                let (err, res_json) = result_to_err_code_1!(result, Vec::new());
+                trace!("indy_unpack_message: res_json: {:?}", res_json);
+                let (res_json_data, res_json_len) = ctypes::vec_to_pointer(&res_json);
+                cb(command_handle, err, res_json_data, res_json_len)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_unpack_message: <<< res: {:?}", res);
+
+    res
+}
+
+/// Creates JWS (RFC 7515) signed by EdDSA with a key stored in the wallet.
+///
+/// Signer can be referenced by my DID or by verkey of my key. The signer reference is put to "kid" field
+/// of protected header as is, so a key referenced by verkey doesn't need to belong to any of my DIDs.
+///
+/// Note only ed25519 keys can be used.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// signer: my DID or verkey of my key. The key must be created by calling indy_create_key or indy_create_and_store_my_did
+/// payload_data: a pointer to first byte of payload to be signed
+/// payload_len: a payload length
+/// options_json: (Optional) signing options as json. Example:
+/// {
+///     "serialization": string, // Optional (default "compact"); "compact" or "json" (flattened JWS JSON serialization)
+///     "detached": bool, // Optional (default false); if true payload is not included to JWS
+///     "typ": string, // Optional; "typ" field of protected header
+/// }
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// jws: JWS in requested serialization
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub  extern fn indy_crypto_jws_sign(command_handle: i32,
+                                    wallet_handle: i32,
+                                    signer: *const c_char,
+                                    payload_data: *const u8,
+                                    payload_len: u32,
+                                    options_json: *const c_char,
+                                    cb: Option<extern fn(xcommand_handle: i32,
+                                                         err: ErrorCode,
+                                                         jws: *const c_char)>) -> ErrorCode {
+    trace!("indy_crypto_jws_sign: >>> wallet_handle: {:?}, signer: {:?}, payload_data: {:?}, payload_len: {:?}, options_json: {:?}",
+           wallet_handle, signer, payload_data, payload_len, options_json);
+
+    check_useful_c_str!(signer, ErrorCode::CommonInvalidParam3);
+    check_useful_c_byte_array!(payload_data, payload_len, ErrorCode::CommonInvalidParam4, ErrorCode::CommonInvalidParam5);
+    check_useful_opt_json!(options_json, ErrorCode::CommonInvalidParam6, JWSSignOptions);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam7);
+
+    trace!("indy_crypto_jws_sign: entities >>> wallet_handle: {:?}, signer: {:?}, payload_data: {:?}, payload_len: {:?}, options_json: {:?}",
+           wallet_handle, signer, payload_data, payload_len, options_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::JwsSign(
+            wallet_handle,
+            signer,
+            payload_data,
+            options_json.unwrap_or_default(),
+            Box::new(move |result| {
+                let (err, jws) = result_to_err_code_1!(result, String::new());
+                trace!("indy_crypto_jws_sign: jws: {:?}", jws);
+                let jws = ctypes::string_to_cstring(jws);
+                cb(command_handle, err, jws.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_jws_sign: <<< res: {:?}", res);
+
+    res
+}
+
+/// Verifies JWS (RFC 7515) signed by EdDSA.
+///
+/// Verification key is resolved by "kid" field of protected header: it must be my or their DID stored in the wallet
+/// or verkey of my key. Other kid that is a verkey itself is trusted only if "allow_verkey_kid" option is set,
+/// as anyone can create such JWS.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// jws: JWS in compact or flattened JSON serialization
+/// payload_data: (Optional) a pointer to first byte of detached payload. Must be set only if JWS doesn't contain payload.
+/// payload_len: a detached payload length
+/// options_json: (Optional) verification options as json. Example:
+/// {
+///     "signer": string, // Optional; expected signer DID or verkey, JWS signed by anyone else isn't valid
+///     "allow_verkey_kid": bool, // Optional (default false); if true kid that isn't DID or key known to the wallet is used as verkey
+/// }
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// valid: true - if signature is valid and made by expected signer (if given), false - otherwise
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub  extern fn indy_crypto_jws_verify(command_handle: i32,
+                                      wallet_handle: i32,
+                                      jws: *const c_char,
+                                      payload_data: *const u8,
+                                      payload_len: u32,
+                                      options_json: *const c_char,
+                                      cb: Option<extern fn(xcommand_handle: i32,
+                                                           err: ErrorCode,
+                                                           valid: bool)>) -> ErrorCode {
+    trace!("indy_crypto_jws_verify: >>> wallet_handle: {:?}, jws: {:?}, payload_data: {:?}, payload_len: {:?}, options_json: {:?}",
+           wallet_handle, jws, payload_data, payload_len, options_json);
+
+    check_useful_c_str!(jws, ErrorCode::CommonInvalidParam3);
+    check_useful_opt_c_byte_array!(payload_data, payload_len, ErrorCode::CommonInvalidParam5);
+    check_useful_opt_json!(options_json, ErrorCode::CommonInvalidParam6, JWSVerifyOptions);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam7);
+
+    trace!("indy_crypto_jws_verify: entities >>> wallet_handle: {:?}, jws: {:?}, payload_data: {:?}, payload_len: {:?}, options_json: {:?}",
+           wallet_handle, jws, payload_data, payload_len, options_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::JwsVerify(
+            wallet_handle,
+            jws,
+            payload_data,
+            options_json.unwrap_or_default(),
+            Box::new(move |result| {
+                let (err, valid) = result_to_err_code_1!(result, false);
+                trace!("indy_crypto_jws_verify: valid: {:?}", valid);
+                cb(command_handle, err, valid)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_jws_verify: <<< res: {:?}", res);
+
+    res
+}
+
+/// Exports a single key (with its metadata) from the wallet as a passphrase-encrypted blob.
+///
+/// The blob is encrypted by chacha20poly1305_ietf with a key derived from the passphrase by argon2i
+/// and can be imported into another wallet by indy_import_key.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// wallet_handle: Wallet handle (created by open_wallet).
+/// verkey: id (verkey) of the key to export
+/// passphrase: passphrase used to encrypt exported data
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+/// - exported_key: exported data as json:
+/// {
+///     "salt": string, // base64 encoded
+///     "nonce": string, // base64 encoded
+///     "ciphertext": string, // base64 encoded
+/// }
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_export_key(command_handle: i32,
+                              wallet_handle: i32,
+                              verkey: *const c_char,
+                              passphrase: *const c_char,
+                              cb: Option<extern fn(command_handle_: i32,
+                                                   err: ErrorCode,
+                                                   exported_key: *const c_char)>) -> ErrorCode {
+    trace!("indy_export_key: >>> wallet_handle: {:?}, verkey: {:?}, passphrase: {:?}", wallet_handle, verkey, passphrase);
+
+    check_useful_c_str!(verkey, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(passphrase, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_export_key: entities >>> wallet_handle: {:?}, verkey: {:?}, passphrase: {:?}", wallet_handle, verkey, secret!(&passphrase));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::ExportKey(
+            wallet_handle,
+            verkey,
+            passphrase,
+            Box::new(move |result| {
+                let (err, exported_key) = result_to_err_code_1!(result, String::new());
+                trace!("indy_export_key: exported_key: {:?}", exported_key);
+                let exported_key = ctypes::string_to_cstring(exported_key);
+                cb(command_handle, err, exported_key.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_export_key: <<< res: {:?}", res);
+
+    res
+}
+
+/// Imports a key exported by indy_export_key into the wallet.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// wallet_handle: Wallet handle (created by open_wallet).
+/// exported_key: exported data as json (see indy_export_key)
+/// passphrase: passphrase used on export
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+/// - verkey: id (verkey) of imported key
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_import_key(command_handle: i32,
+                              wallet_handle: i32,
+                              exported_key: *const c_char,
+                              passphrase: *const c_char,
+                              cb: Option<extern fn(command_handle_: i32,
+                                                   err: ErrorCode,
+                                                   verkey: *const c_char)>) -> ErrorCode {
+    trace!("indy_import_key: >>> wallet_handle: {:?}, exported_key: {:?}, passphrase: {:?}", wallet_handle, exported_key, passphrase);
+
+    check_useful_json!(exported_key, ErrorCode::CommonInvalidParam3, EncryptedExport);
+    check_useful_c_str!(passphrase, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_import_key: entities >>> wallet_handle: {:?}, exported_key: {:?}, passphrase: {:?}", wallet_handle, exported_key, secret!(&passphrase));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::ImportKey(
+            wallet_handle,
+            exported_key,
+            passphrase,
+            Box::new(move |result| {
+                let (err, verkey) = result_to_err_code_1!(result, String::new());
+                trace!("indy_import_key: verkey: {:?}", verkey);
+                let verkey = ctypes::string_to_cstring(verkey);
+                cb(command_handle, err, verkey.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_import_key: <<< res: {:?}", res);
+
+    res
+}
+
+/// Creates root seed in the wallet. The root seed is used for deterministic derivation of
+/// keys and DIDs (see indy_create_key_derived and indy_create_and_store_my_did_derived).
+///
+/// If mnemonic is passed the root seed is derived from it, so all derived keys and DIDs
+/// can be recovered later in another wallet from the backed-up mnemonic only.
+/// Random root seed can be backed up by indy_export_root_seed.
+/// The root seed can't be replaced once created.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// wallet_handle: Wallet handle (created by open_wallet).
+/// mnemonic: (optional) secret phrase to derive root seed from; random root seed is generated if not set
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_create_root_seed(command_handle: i32,
+                                    wallet_handle: i32,
+                                    mnemonic: *const c_char,
+                                    cb: Option<extern fn(command_handle_: i32,
+                                                         err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_create_root_seed: >>> wallet_handle: {:?}, mnemonic: {:?}", wallet_handle, mnemonic);
+
+    check_useful_opt_c_str!(mnemonic, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_create_root_seed: entities >>> wallet_handle: {:?}, mnemonic: {:?}", wallet_handle, secret!(&mnemonic));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::CreateRootSeed(
+            wallet_handle,
+            mnemonic,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_create_root_seed:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_create_root_seed: <<< res: {:?}", res);
+
+    res
+}
+
+/// Exports the wallet root seed as a passphrase-encrypted blob, so it can be backed up
+/// and imported to another wallet by indy_import_root_seed.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// wallet_handle: Wallet handle (created by open_wallet).
+/// passphrase: passphrase used to encrypt the root seed
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+/// - exported_root_seed: exported data as json (same format as for indy_export_key)
+///
+/// #Errors
+/// Common*
+/// Wallet* (WalletItemNotFound if root seed is not created)
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_export_root_seed(command_handle: i32,
+                                    wallet_handle: i32,
+                                    passphrase: *const c_char,
+                                    cb: Option<extern fn(command_handle_: i32,
+                                                         err: ErrorCode,
+                                                         exported_root_seed: *const c_char)>) -> ErrorCode {
+    trace!("indy_export_root_seed: >>> wallet_handle: {:?}, passphrase: {:?}", wallet_handle, passphrase);
+
+    check_useful_c_str!(passphrase, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_export_root_seed: entities >>> wallet_handle: {:?}, passphrase: {:?}", wallet_handle, secret!(&passphrase));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::ExportRootSeed(
+            wallet_handle,
+            passphrase,
+            Box::new(move |result| {
+                let (err, exported_root_seed) = result_to_err_code_1!(result, String::new());
+                trace!("indy_export_root_seed: exported_root_seed: {:?}", exported_root_seed);
+                let exported_root_seed = ctypes::string_to_cstring(exported_root_seed);
+                cb(command_handle, err, exported_root_seed.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_export_root_seed: <<< res: {:?}", res);
+
+    res
+}
+
+/// Imports a root seed exported by indy_export_root_seed into the wallet.
+/// The root seed can't be replaced once created.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// wallet_handle: Wallet handle (created by open_wallet).
+/// exported_root_seed: exported data as json (see indy_export_root_seed)
+/// passphrase: passphrase used on export
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+///
+/// #Errors
+/// Common*
+/// Wallet* (WalletItemAlreadyExists if root seed is already created)
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_import_root_seed(command_handle: i32,
+                                    wallet_handle: i32,
+                                    exported_root_seed: *const c_char,
+                                    passphrase: *const c_char,
+                                    cb: Option<extern fn(command_handle_: i32,
+                                                         err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_import_root_seed: >>> wallet_handle: {:?}, exported_root_seed: {:?}, passphrase: {:?}", wallet_handle, exported_root_seed, passphrase);
+
+    check_useful_json!(exported_root_seed, ErrorCode::CommonInvalidParam3, EncryptedExport);
+    check_useful_c_str!(passphrase, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_import_root_seed: entities >>> wallet_handle: {:?}, exported_root_seed: {:?}, passphrase: {:?}", wallet_handle, exported_root_seed, secret!(&passphrase));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::ImportRootSeed(
+            wallet_handle,
+            exported_root_seed,
+            passphrase,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_import_root_seed:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_import_root_seed: <<< res: {:?}", res);
+
+    res
+}
+
+/// Deterministically derives ed25519 key from the wallet root seed and stores it in the wallet.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// wallet_handle: Wallet handle (created by open_wallet).
+/// path: derivation path like "m/0/1". Every index is hardened, so trailing ' is optional.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+/// - verkey: Ver key of derived key pair to use
+///
+/// #Errors
+/// Common*
+/// Wallet* (WalletItemNotFound if root seed is not created)
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_create_key_derived(command_handle: i32,
+                                      wallet_handle: i32,
+                                      path: *const c_char,
+                                      cb: Option<extern fn(command_handle_: i32,
+                                                           err: ErrorCode,
+                                                           verkey: *const c_char)>) -> ErrorCode {
+    trace!("indy_create_key_derived: >>> wallet_handle: {:?}, path: {:?}", wallet_handle, path);
+
+    check_useful_c_str!(path, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_create_key_derived: entities >>> wallet_handle: {:?}, path: {:?}", wallet_handle, path);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::CreateDerivedKey(
+            wallet_handle,
+            path,
+            Box::new(move |result| {
+                let (err, verkey) = result_to_err_code_1!(result, String::new());
+                trace!("indy_create_key_derived: verkey: {:?}", verkey);
+                let verkey = ctypes::string_to_cstring(verkey);
+                cb(command_handle, err, verkey.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_create_key_derived: <<< res: {:?}", res);
+
+    res
+}
+
+/// Replaces tags of the key stored in the wallet. Tags are used for search by indy_search_keys.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// verkey: the key (verkey, key id) stored in the wallet
+/// tags_json: the tags to replace existing tags of the key with, as json:
+///   {
+///     "tagName1": <str>, // string tag (will be stored encrypted)
+///     "~tagName2": <str>, // string tag (will be stored un-encrypted)
+///   }
+///   If tag name starts with "~" the tag will be stored un-encrypted that will allow
+///   usage of this tag in complex search queries (comparison, predicates)
+///   Encrypted tags can be searched only for exact matching
+///   "~created_at" tag is set by libindy on creation (unix time in seconds), it is reserved and kept on replace
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_set_key_tags(command_handle: i32,
+                                wallet_handle: i32,
+                                verkey: *const c_char,
+                                tags_json: *const c_char,
+                                cb: Option<extern fn(xcommand_handle: i32,
+                                                     err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_set_key_tags: >>> wallet_handle: {:?}, verkey: {:?}, tags_json: {:?}", wallet_handle, verkey, tags_json);
+
+    check_useful_c_str!(verkey, ErrorCode::CommonInvalidParam3);
+    check_useful_json!(tags_json, ErrorCode::CommonInvalidParam4, Tags);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_set_key_tags: entities >>> wallet_handle: {:?}, verkey: {:?}, tags_json: {:?}", wallet_handle, verkey, tags_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::SetKeyTags(
+            wallet_handle,
+            verkey,
+            tags_json,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_set_key_tags:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_set_key_tags: <<< res: {:?}", res);
+
+    res
+}
+
+/// Searches keys stored in the wallet by their tags.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// query_json: MongoDB style query to key tags (see indy_open_wallet_search), for example:
+///  {
+///    "label": "Alice",
+///    "~created_at": { "$gt": "1530000000" },
+///  }
+///  Use "{}" to get all keys.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+/// - keys: list of found keys as json:
+///   [{
+///     "verkey": string,
+///     "metadata": string,
+///     "tags": {"tagName": "tagValue", ...},
+///   }, ...]
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_search_keys(command_handle: i32,
+                               wallet_handle: i32,
+                               query_json: *const c_char,
+                               cb: Option<extern fn(xcommand_handle: i32,
+                                                    err: ErrorCode,
+                                                    keys: *const c_char)>) -> ErrorCode {
+    trace!("indy_search_keys: >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+    check_useful_c_str!(query_json, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_search_keys: entities >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::SearchKeys(
+            wallet_handle,
+            query_json,
+            Box::new(move |result| {
+                let (err, keys) = result_to_err_code_1!(result, String::new());
+                trace!("indy_search_keys: keys: {:?}", keys);
+                let keys = ctypes::string_to_cstring(keys);
+                cb(command_handle, err, keys.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_search_keys: <<< res: {:?}", res);
+
+    res
+}
+
+/// Opens a stream to encrypt large data for one or more recipients chunk by chunk.
+///
+/// Data is encrypted with a random content key (chacha20poly1305_ietf) in chunks, every chunk
+/// is authenticated separately. The content key is wrapped for every recipient the same way
+/// as in indy_pack_message:
+/// - if sender verkey is given (Authcrypt) the key is encrypted by authenticated-encryption scheme;
+/// - otherwise (Anoncrypt) the key is encrypted by anonymous-encryption scheme.
+///
+/// Data is passed by indy_crypto_encrypt_stream_feed calls and the stream is completed by
+/// indy_crypto_encrypt_stream_finish. Encrypted stream is a concatenation of bytes returned
+/// by these calls. The stream that won't be finished must be closed by indy_crypto_encrypt_stream_close.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// receiver_keys: a json array of recipients verkeys. Example:
+///     ["verkey1", "verkey2"]
+/// sender: (Optional) id (verkey) of my key. The key must be created by calling indy_create_key or indy_create_and_store_my_did
+///     If not set Anoncrypt is used.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// stream_handle: handle of encryption stream
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_crypto_encrypt_stream_open(command_handle: i32,
+                                              wallet_handle: i32,
+                                              receiver_keys: *const c_char,
+                                              sender: *const c_char,
+                                              cb: Option<extern fn(xcommand_handle: i32,
+                                                                   err: ErrorCode,
+                                                                   stream_handle: i32)>) -> ErrorCode {
+    trace!("indy_crypto_encrypt_stream_open: >>> wallet_handle: {:?}, receiver_keys: {:?}, sender: {:?}", wallet_handle, receiver_keys, sender);
+
+    check_useful_json!(receiver_keys, ErrorCode::CommonInvalidParam3, Vec<String>);
+    check_useful_opt_c_str!(sender, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_crypto_encrypt_stream_open: entities >>> wallet_handle: {:?}, receiver_keys: {:?}, sender: {:?}", wallet_handle, receiver_keys, sender);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::OpenEncryptStream(
+            wallet_handle,
+            receiver_keys,
+            sender,
+            Box::new(move |result| {
+                let (err, stream_handle) = result_to_err_code_1!(result, 0);
+                trace!("indy_crypto_encrypt_stream_open: stream_handle: {:?}", stream_handle);
+                cb(command_handle, err, stream_handle)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_encrypt_stream_open: <<< res: {:?}", res);
+
+    res
+}
+
+/// Encrypts the next part of data in encryption stream.
+///
+/// The result of the first call starts with stream header. Only complete chunks are encrypted,
+/// so the results of next calls can be empty.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// stream_handle: handle of encryption stream (created by indy_crypto_encrypt_stream_open).
+/// data_raw: a pointer to first byte of data to be encrypted
+/// data_len: a data length
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// next part of encrypted stream as a pointer to array of bytes.
+///
+/// #Errors
+/// Common*
+#[no_mangle]
+pub extern fn indy_crypto_encrypt_stream_feed(command_handle: i32,
+                                              stream_handle: i32,
+                                              data_raw: *const u8,
+                                              data_len: u32,
+                                              cb: Option<extern fn(xcommand_handle: i32,
+                                                                   err: ErrorCode,
+                                                                   encrypted_raw: *const u8,
+                                                                   encrypted_len: u32)>) -> ErrorCode {
+    trace!("indy_crypto_encrypt_stream_feed: >>> stream_handle: {:?}, data_raw: {:?}, data_len: {:?}", stream_handle, data_raw, data_len);
+
+    check_useful_c_byte_array!(data_raw, data_len, ErrorCode::CommonInvalidParam3, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_crypto_encrypt_stream_feed: entities >>> stream_handle: {:?}, data_len: {:?}", stream_handle, data_len);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::FeedEncryptStream(
+            stream_handle,
+            data_raw,
+            Box::new(move |result| {
+                let (err, encrypted) = result_to_err_code_1!(result, Vec::new());
+                trace!("indy_crypto_encrypt_stream_feed: encrypted len: {:?}", encrypted.len());
+                let (encrypted_raw, encrypted_len) = ctypes::vec_to_pointer(&encrypted);
+                cb(command_handle, err, encrypted_raw, encrypted_len)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_encrypt_stream_feed: <<< res: {:?}", res);
+
+    res
+}
+
+/// Completes encryption stream and closes its handle.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// stream_handle: handle of encryption stream (created by indy_crypto_encrypt_stream_open).
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// the rest of encrypted stream as a pointer to array of bytes.
+///
+/// #Errors
+/// Common*
+#[no_mangle]
+pub extern fn indy_crypto_encrypt_stream_finish(command_handle: i32,
+                                                stream_handle: i32,
+                                                cb: Option<extern fn(xcommand_handle: i32,
+                                                                     err: ErrorCode,
+                                                                     encrypted_raw: *const u8,
+                                                                     encrypted_len: u32)>) -> ErrorCode {
+    trace!("indy_crypto_encrypt_stream_finish: >>> stream_handle: {:?}", stream_handle);
+
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam3);
+
+    trace!("indy_crypto_encrypt_stream_finish: entities >>> stream_handle: {:?}", stream_handle);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::FinishEncryptStream(
+            stream_handle,
+            Box::new(move |result| {
+                let (err, encrypted) = result_to_err_code_1!(result, Vec::new());
+                trace!("indy_crypto_encrypt_stream_finish: encrypted len: {:?}", encrypted.len());
+                let (encrypted_raw, encrypted_len) = ctypes::vec_to_pointer(&encrypted);
+                cb(command_handle, err, encrypted_raw, encrypted_len)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_encrypt_stream_finish: <<< res: {:?}", res);
+
+    res
+}
+
+/// Closes encryption stream handle without completing the stream.
+///
+/// Should be used to release the stream if it won't be finished, e.g. on error.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// stream_handle: handle of encryption stream (created by indy_crypto_encrypt_stream_open).
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+///
+/// #Errors
+/// Common*
+#[no_mangle]
+pub extern fn indy_crypto_encrypt_stream_close(command_handle: i32,
+                                               stream_handle: i32,
+                                               cb: Option<extern fn(xcommand_handle: i32,
+                                                                    err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_crypto_encrypt_stream_close: >>> stream_handle: {:?}", stream_handle);
+
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam3);
+
+    trace!("indy_crypto_encrypt_stream_close: entities >>> stream_handle: {:?}", stream_handle);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::CloseEncryptStream(
+            stream_handle,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_crypto_encrypt_stream_close:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_encrypt_stream_close: <<< res: {:?}", res);
+
+    res
+}
+
+/// Opens a stream to decrypt data encrypted by indy_crypto_encrypt_stream_* functions.
+///
+/// The first recipient which key is stored in the wallet is used to unwrap the content key.
+///
+/// Decrypted data is returned as soon as complete chunks are received, but it must not be
+/// trusted until indy_crypto_decrypt_stream_finish succeeds: only then it is checked that
+/// the stream isn't truncated. The stream that won't be finished must be closed by
+/// indy_crypto_decrypt_stream_close.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// stream_handle: handle of decryption stream
+///
+/// #Errors
+/// Common*
+#[no_mangle]
+pub extern fn indy_crypto_decrypt_stream_open(command_handle: i32,
+                                              wallet_handle: i32,
+                                              cb: Option<extern fn(xcommand_handle: i32,
+                                                                   err: ErrorCode,
+                                                                   stream_handle: i32)>) -> ErrorCode {
+    trace!("indy_crypto_decrypt_stream_open: >>> wallet_handle: {:?}", wallet_handle);
+
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam3);
+
+    trace!("indy_crypto_decrypt_stream_open: entities >>> wallet_handle: {:?}", wallet_handle);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::OpenDecryptStream(
+            wallet_handle,
+            Box::new(move |result| {
+                let (err, stream_handle) = result_to_err_code_1!(result, 0);
+                trace!("indy_crypto_decrypt_stream_open: stream_handle: {:?}", stream_handle);
+                cb(command_handle, err, stream_handle)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_decrypt_stream_open: <<< res: {:?}", res);
+
+    res
+}
+
+/// Decrypts the next part of encrypted stream.
+///
+/// Only complete chunks are decrypted, so the result can be empty.
+/// The stream is closed on any error.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// stream_handle: handle of decryption stream (created by indy_crypto_decrypt_stream_open).
+/// encrypted_raw: a pointer to first byte of the next part of encrypted stream
+/// encrypted_len: a length of the part
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// next part of decrypted data as a pointer to array of bytes.
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_crypto_decrypt_stream_feed(command_handle: i32,
+                                              stream_handle: i32,
+                                              encrypted_raw: *const u8,
+                                              encrypted_len: u32,
+                                              cb: Option<extern fn(xcommand_handle: i32,
+                                                                   err: ErrorCode,
+                                                                   data_raw: *const u8,
+                                                                   data_len: u32)>) -> ErrorCode {
+    trace!("indy_crypto_decrypt_stream_feed: >>> stream_handle: {:?}, encrypted_raw: {:?}, encrypted_len: {:?}", stream_handle, encrypted_raw, encrypted_len);
+
+    check_useful_c_byte_array!(encrypted_raw, encrypted_len, ErrorCode::CommonInvalidParam3, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_crypto_decrypt_stream_feed: entities >>> stream_handle: {:?}, encrypted_len: {:?}", stream_handle, encrypted_len);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::FeedDecryptStream(
+            stream_handle,
+            encrypted_raw,
+            Box::new(move |result| {
+                let (err, data) = result_to_err_code_1!(result, Vec::new());
+                trace!("indy_crypto_decrypt_stream_feed: data len: {:?}", data.len());
+                let (data_raw, data_len) = ctypes::vec_to_pointer(&data);
+                cb(command_handle, err, data_raw, data_len)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_decrypt_stream_feed: <<< res: {:?}", res);
+
+    res
+}
+
+/// Completes decryption stream and closes its handle.
+///
+/// Fails if the stream is truncated.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// stream_handle: handle of decryption stream (created by indy_crypto_decrypt_stream_open).
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// metadata: stream metadata as json:
+///     {
+///         "recipient_verkey": <recipient verkey used to decrypt the stream>,
+///         "sender_verkey": <sender verkey, only for Authcrypt>
+///     }
+/// the rest of decrypted data as a pointer to array of bytes.
+///
+/// #Errors
+/// Common*
+#[no_mangle]
+pub extern fn indy_crypto_decrypt_stream_finish(command_handle: i32,
+                                                stream_handle: i32,
+                                                cb: Option<extern fn(xcommand_handle: i32,
+                                                                     err: ErrorCode,
+                                                                     metadata: *const c_char,
+                                                                     data_raw: *const u8,
+                                                                     data_len: u32)>) -> ErrorCode {
+    trace!("indy_crypto_decrypt_stream_finish: >>> stream_handle: {:?}", stream_handle);
+
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam3);
+
+    trace!("indy_crypto_decrypt_stream_finish: entities >>> stream_handle: {:?}", stream_handle);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::FinishDecryptStream(
+            stream_handle,
+            Box::new(move |result| {
+                let (err, metadata, data) = result_to_err_code_2!(result, String::new(), Vec::new());
+                trace!("indy_crypto_decrypt_stream_finish: metadata: {:?}, data len: {:?}", metadata, data.len());
+                let metadata = ctypes::string_to_cstring(metadata);
+                let (data_raw, data_len) = ctypes::vec_to_pointer(&data);
+                cb(command_handle, err, metadata.as_ptr(), data_raw, data_len)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_decrypt_stream_finish: <<< res: {:?}", res);
+
+    res
+}
+
+/// Closes decryption stream handle without completing the stream.
+///
+/// Should be used to release the stream if it won't be finished, e.g. on error.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// stream_handle: handle of decryption stream (created by indy_crypto_decrypt_stream_open).
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+///
+/// #Errors
+/// Common*
+#[no_mangle]
+pub extern fn indy_crypto_decrypt_stream_close(command_handle: i32,
+                                               stream_handle: i32,
+                                               cb: Option<extern fn(xcommand_handle: i32,
+                                                                    err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_crypto_decrypt_stream_close: >>> stream_handle: {:?}", stream_handle);
+
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam3);
+
+    trace!("indy_crypto_decrypt_stream_close: entities >>> stream_handle: {:?}", stream_handle);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Crypto(CryptoCommand::CloseDecryptStream(
+            stream_handle,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_crypto_decrypt_stream_close:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_crypto_decrypt_stream_close: <<< res: {:?}", res);
+
+    res
+}
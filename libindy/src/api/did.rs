@@ -5,6 +5,8 @@ use commands::{Command, CommandExecutor};
 use commands::did::DidCommand;
 use domain::crypto::did::{MyDidInfo, TheirDidInfo};
 use domain::crypto::key::KeyInfo;
+use domain::crypto::export::EncryptedExport;
+use domain::wallet::Tags;
 use errors::common::CommonError;
 use errors::ToErrorCode;
 use utils::ctypes;
@@ -33,7 +35,7 @@ use std::ptr;
 ///             if provided, then keys will be replaced - key rotation use case)
 ///     "seed": string, (optional; if not provide then a random one will be created)
 ///     "crypto_type": string, (optional; if not set then ed25519 curve is used;
-///               currently only 'ed25519' value is supported for this field)
+///               supported values are 'ed25519', 'secp256k1' and 'secp256r1' (NIST P-256; EC types require libindy built with ec_openssl feature))
 ///     "cid": bool, (optional; if not set then false is used;)
 /// }
 /// cb: Callback that takes command result as parameter.
@@ -96,7 +98,7 @@ pub  extern fn indy_create_and_store_my_did(command_handle: i32,
 /// {
 ///     "seed": string, (optional; if not provide then a random one will be created)
 ///     "crypto_type": string, (optional; if not set then ed25519 curve is used;
-///               currently only 'ed25519' value is supported for this field)
+///               supported values are 'ed25519', 'secp256k1' and 'secp256r1' (NIST P-256; EC types require libindy built with ec_openssl feature))
 /// }
 /// cb: Callback that takes command result as parameter.
 ///
@@ -757,4 +759,377 @@ pub  extern fn indy_abbreviate_verkey(command_handle: i32,
     trace!("indy_abbreviate_verkey: <<< res: {:?}", res);
 
     res
-}
\ No newline at end of file
+}
+/// Exports my DID together with its key and metadata from the wallet as a passphrase-encrypted blob.
+///
+/// The blob is encrypted by chacha20poly1305_ietf with a key derived from the passphrase by argon2i
+/// and can be imported into another wallet by indy_import_did.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// wallet_handle: Wallet handle (created by open_wallet).
+/// did: my DID to export
+/// passphrase: passphrase used to encrypt exported data
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+/// - exported_did: exported data as json:
+/// {
+///     "salt": string, // base64 encoded
+///     "nonce": string, // base64 encoded
+///     "ciphertext": string, // base64 encoded
+/// }
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_export_did(command_handle: i32,
+                              wallet_handle: i32,
+                              did: *const c_char,
+                              passphrase: *const c_char,
+                              cb: Option<extern fn(command_handle_: i32,
+                                                   err: ErrorCode,
+                                                   exported_did: *const c_char)>) -> ErrorCode {
+    trace!("indy_export_did: >>> wallet_handle: {:?}, did: {:?}, passphrase: {:?}", wallet_handle, did, passphrase);
+
+    check_useful_c_str!(did, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(passphrase, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_export_did: entities >>> wallet_handle: {:?}, did: {:?}, passphrase: {:?}", wallet_handle, did, secret!(&passphrase));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Did(DidCommand::ExportDid(
+            wallet_handle,
+            did,
+            passphrase,
+            Box::new(move |result| {
+                let (err, exported_did) = result_to_err_code_1!(result, String::new());
+                trace!("indy_export_did: exported_did: {:?}", exported_did);
+                let exported_did = ctypes::string_to_cstring(exported_did);
+                cb(command_handle, err, exported_did.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_export_did: <<< res: {:?}", res);
+
+    res
+}
+
+/// Imports my DID exported by indy_export_did into the wallet.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// wallet_handle: Wallet handle (created by open_wallet).
+/// exported_did: exported data as json (see indy_export_did)
+/// passphrase: passphrase used on export
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+/// - did: imported DID
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_import_did(command_handle: i32,
+                              wallet_handle: i32,
+                              exported_did: *const c_char,
+                              passphrase: *const c_char,
+                              cb: Option<extern fn(command_handle_: i32,
+                                                   err: ErrorCode,
+                                                   did: *const c_char)>) -> ErrorCode {
+    trace!("indy_import_did: >>> wallet_handle: {:?}, exported_did: {:?}, passphrase: {:?}", wallet_handle, exported_did, passphrase);
+
+    check_useful_json!(exported_did, ErrorCode::CommonInvalidParam3, EncryptedExport);
+    check_useful_c_str!(passphrase, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_import_did: entities >>> wallet_handle: {:?}, exported_did: {:?}, passphrase: {:?}", wallet_handle, exported_did, secret!(&passphrase));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Did(DidCommand::ImportDid(
+            wallet_handle,
+            exported_did,
+            passphrase,
+            Box::new(move |result| {
+                let (err, did) = result_to_err_code_1!(result, String::new());
+                trace!("indy_import_did: did: {:?}", did);
+                let did = ctypes::string_to_cstring(did);
+                cb(command_handle, err, did.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_import_did: <<< res: {:?}", res);
+
+    res
+}
+
+/// Deterministically derives DID and ed25519 keys from the wallet root seed (see indy_create_root_seed)
+/// and stores them in the wallet. Derived DID can be recovered later from the same root seed and path.
+///
+/// #Params
+/// command_handle: command handle to map callback to caller context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// path: derivation path like "m/0/1". Every index is hardened, so trailing ' is optional.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+///   did: DID generated and stored in the wallet
+///   verkey: The DIDs verification key
+///
+/// #Errors
+/// Common*
+/// Wallet* (WalletItemNotFound if root seed is not created)
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_create_and_store_my_did_derived(command_handle: i32,
+                                                   wallet_handle: i32,
+                                                   path: *const c_char,
+                                                   cb: Option<extern fn(command_handle_: i32,
+                                                                        err: ErrorCode,
+                                                                        did: *const c_char,
+                                                                        verkey: *const c_char)>) -> ErrorCode {
+    trace!("indy_create_and_store_my_did_derived: >>> wallet_handle: {:?}, path: {:?}", wallet_handle, path);
+
+    check_useful_c_str!(path, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_create_and_store_my_did_derived: entities >>> wallet_handle: {:?}, path: {:?}", wallet_handle, path);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Did(DidCommand::CreateAndStoreDerivedMyDid(
+            wallet_handle,
+            path,
+            Box::new(move |result| {
+                let (err, did, verkey) = result_to_err_code_2!(result, String::new(), String::new());
+                trace!("indy_create_and_store_my_did_derived: did: {:?}, verkey: {:?}", did, verkey);
+                let did = ctypes::string_to_cstring(did);
+                let verkey = ctypes::string_to_cstring(verkey);
+                cb(command_handle, err, did.as_ptr(), verkey.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_create_and_store_my_did_derived: <<< res: {:?}", res);
+
+    res
+}
+
+/// Rotates keys of an existing DID (owned by the caller of the library) on the ledger and in the wallet.
+///
+/// Performs the whole flow that otherwise requires indy_replace_keys_start, sending of NYM
+/// transaction signed by the current key and indy_replace_keys_apply:
+/// 1. Generates temporary keys for the DID.
+/// 2. Sends NYM transaction with the new verkey signed by the current key.
+/// 3. Checks by GET_NYM (verified by state proof or consensus of nodes) that the ledger contains the new verkey.
+/// 4. Applies the new keys as main for the DID.
+///
+/// If the ledger rejects NYM transaction the temporary keys are deleted and the DID keeps the current keys.
+/// If NYM transaction is written, but the new verkey can't be confirmed, the temporary keys are kept,
+/// so the rotation can be finished by indy_replace_keys_apply.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// pool_handle: pool handle (created by open_pool).
+/// wallet_handle: wallet handler (created by open_wallet).
+/// did: target did to rotate keys.
+/// key_info: key information as json (see indy_replace_keys_start). Example:
+/// {
+///     "seed": string, (optional; if not provide then a random one will be created)
+///     "crypto_type": string, (optional; if not set then ed25519 curve is used)
+/// }
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error Code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+///   verkey: The DIDs new verification key
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Ledger*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_rotate_did_key(command_handle: i32,
+                                  pool_handle: i32,
+                                  wallet_handle: i32,
+                                  did: *const c_char,
+                                  key_info: *const c_char,
+                                  cb: Option<extern fn(xcommand_handle: i32,
+                                                       err: ErrorCode,
+                                                       verkey: *const c_char)>) -> ErrorCode {
+    trace!("indy_rotate_did_key: >>> pool_handle: {:?}, wallet_handle: {:?}, did: {:?}, key_info: {:?}", pool_handle, wallet_handle, did, key_info);
+
+    check_useful_c_str!(did, ErrorCode::CommonInvalidParam4);
+    check_useful_json!(key_info, ErrorCode::CommonInvalidParam5, KeyInfo);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam6);
+
+    trace!("indy_rotate_did_key: entities >>> pool_handle: {:?}, wallet_handle: {:?}, did: {:?}, key_info: {:?}", pool_handle, wallet_handle, did, secret!(&key_info));
+
+    let result = CommandExecutor::instance()
+        .send(Command::Did(DidCommand::RotateKey(
+            pool_handle,
+            wallet_handle,
+            did,
+            key_info,
+            Box::new(move |result| {
+                let (err, verkey) = result_to_err_code_1!(result, String::new());
+                trace!("indy_rotate_did_key: verkey: {:?}", verkey);
+                let verkey = ctypes::string_to_cstring(verkey);
+                cb(command_handle, err, verkey.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_rotate_did_key: <<< res: {:?}", res);
+
+    res
+}
+
+/// Replaces tags of the DID stored in the wallet. Tags are used for search by indy_search_my_dids.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// did: DID stored in the wallet
+/// tags_json: the tags to replace existing tags of the DID with, as json:
+///   {
+///     "tagName1": <str>, // string tag (will be stored encrypted)
+///     "~tagName2": <str>, // string tag (will be stored un-encrypted)
+///   }
+///   If tag name starts with "~" the tag will be stored un-encrypted that will allow
+///   usage of this tag in complex search queries (comparison, predicates)
+///   Encrypted tags can be searched only for exact matching
+///   "~created_at" tag is set by libindy on creation (unix time in seconds), it is reserved and kept on replace
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+///
+/// #Errors
+/// Common*
+/// Wallet*
+/// Crypto*
+#[no_mangle]
+pub extern fn indy_set_did_tags(command_handle: i32,
+                                wallet_handle: i32,
+                                did: *const c_char,
+                                tags_json: *const c_char,
+                                cb: Option<extern fn(xcommand_handle: i32,
+                                                     err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_set_did_tags: >>> wallet_handle: {:?}, did: {:?}, tags_json: {:?}", wallet_handle, did, tags_json);
+
+    check_useful_c_str!(did, ErrorCode::CommonInvalidParam3);
+    check_useful_json!(tags_json, ErrorCode::CommonInvalidParam4, Tags);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_set_did_tags: entities >>> wallet_handle: {:?}, did: {:?}, tags_json: {:?}", wallet_handle, did, tags_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Did(DidCommand::SetDidTags(
+            wallet_handle,
+            did,
+            tags_json,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_set_did_tags:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_set_did_tags: <<< res: {:?}", res);
+
+    res
+}
+
+/// Searches DIDs stored in the wallet by their tags.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// query_json: MongoDB style query to DID tags (see indy_open_wallet_search), for example:
+///  {
+///    "label": "Alice",
+///    "~created_at": { "$gt": "1530000000" },
+///  }
+///  Use "{}" to get all DIDs.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+/// - dids: list of found DIDs as json:
+///   [{
+///     "did": string,
+///     "verkey": string,
+///     "metadata": string,
+///     "tags": {"tagName": "tagValue", ...},
+///   }, ...]
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_search_my_dids(command_handle: i32,
+                                  wallet_handle: i32,
+                                  query_json: *const c_char,
+                                  cb: Option<extern fn(xcommand_handle: i32,
+                                                       err: ErrorCode,
+                                                       dids: *const c_char)>) -> ErrorCode {
+    trace!("indy_search_my_dids: >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+    check_useful_c_str!(query_json, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_search_my_dids: entities >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Did(DidCommand::SearchMyDids(
+            wallet_handle,
+            query_json,
+            Box::new(move |result| {
+                let (err, dids) = result_to_err_code_1!(result, String::new());
+                trace!("indy_search_my_dids: dids: {:?}", dids);
+                let dids = ctypes::string_to_cstring(dids);
+                cb(command_handle, err, dids.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_search_my_dids: <<< res: {:?}", res);
+
+    res
+}
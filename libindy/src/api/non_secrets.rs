@@ -3,7 +3,7 @@ extern crate libc;
 use api::ErrorCode;
 use commands::{Command, CommandExecutor};
 use commands::non_secrets::NonSecretsCommand;
-use domain::wallet::Tags;
+use domain::wallet::{AddRecordOptions, RecordOperation, Tags};
 use errors::common::CommonError;
 use errors::ToErrorCode;
 use utils::ctypes;
@@ -56,6 +56,7 @@ pub extern fn indy_add_wallet_record(command_handle: i32,
                 id,
                 value,
                 tags_json,
+                None,
                 Box::new(move |result| {
                     let err = result_to_err_code!(result);
                     trace!("indy_add_wallet_record:");
@@ -70,6 +71,68 @@ pub extern fn indy_add_wallet_record(command_handle: i32,
     res
 }
 
+/// Create a new non-secret record in the wallet with the given options
+///
+/// #Params
+/// command_handle: command handle to map callback to caller context
+/// wallet_handle: wallet handle (created by open_wallet)
+/// type_: allows to separate different record types collections
+/// id: the id of record
+/// value: the value of record
+/// tags_json: (optional) the record tags used for search and storing meta information as json
+///   (see indy_add_wallet_record)
+/// options_json: (optional) the record options as json:
+///   {
+///     expiresAt: (optional) unix time in seconds since which the record is hidden from reads
+///       and searches until it is purged (see indy_purge_expired_wallet_records)
+///   }
+///   Note that null means no options
+///
+/// #Errors
+/// WalletStorageError if wallet storage doesn't support record expiry
+#[no_mangle]
+pub extern fn indy_add_wallet_record_with_options(command_handle: i32,
+                                                  wallet_handle: i32,
+                                                  type_: *const c_char,
+                                                  id: *const c_char,
+                                                  value: *const c_char,
+                                                  tags_json: *const c_char,
+                                                  options_json: *const c_char,
+                                                  cb: Option<extern fn(command_handle_: i32, err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_add_wallet_record_with_options: >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, value: {:?}, tags_json: {:?}, options_json: {:?}", wallet_handle, type_, id, value, tags_json, options_json);
+
+    check_useful_c_str!(type_, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(id, ErrorCode::CommonInvalidParam4);
+    check_useful_c_str!(value, ErrorCode::CommonInvalidParam5);
+    check_useful_opt_json!(tags_json, ErrorCode::CommonInvalidParam6, Tags);
+    check_useful_opt_json!(options_json, ErrorCode::CommonInvalidParam7, AddRecordOptions);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam8);
+
+    trace!("indy_add_wallet_record_with_options: entities >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, value: {:?}, tags_json: {:?}, options_json: {:?}", wallet_handle, type_, id, value, tags_json, options_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::NonSecrets(
+            NonSecretsCommand::AddRecord(
+                wallet_handle,
+                type_,
+                id,
+                value,
+                tags_json,
+                options_json,
+                Box::new(move |result| {
+                    let err = result_to_err_code!(result);
+                    trace!("indy_add_wallet_record_with_options:");
+                    cb(command_handle, err)
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_add_wallet_record_with_options: <<< res: {:?}", res);
+
+    res
+}
+
 /// Update a non-secret wallet record value
 ///
 /// #Params
@@ -115,6 +178,57 @@ pub extern fn indy_update_wallet_record_value(command_handle: i32,
     res
 }
 
+/// Update a non-secret wallet record value if the record wasn't changed since it was read.
+///
+/// #Params
+/// command_handle: command handle to map callback to caller context
+/// wallet_handle: wallet handle (created by open_wallet)
+/// type_: allows to separate different record types collections
+/// id: the id of record
+/// value: the new value of record
+/// version: the expected version of record (see retrieveVersion option of indy_get_wallet_record)
+///
+/// #Errors
+/// WalletItemVersionMismatch if the record version differs from the expected one
+#[no_mangle]
+pub extern fn indy_update_wallet_record_value_if_version(command_handle: i32,
+                                                         wallet_handle: i32,
+                                                         type_: *const c_char,
+                                                         id: *const c_char,
+                                                         value: *const c_char,
+                                                         version: u64,
+                                                         cb: Option<extern fn(command_handle_: i32, err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_update_wallet_record_value_if_version: >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, value: {:?}, version: {:?}", wallet_handle, type_, id, value, version);
+
+    check_useful_c_str!(type_, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(id, ErrorCode::CommonInvalidParam4);
+    check_useful_c_str!(value, ErrorCode::CommonInvalidParam5);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam7);
+
+    trace!("indy_update_wallet_record_value_if_version: entities >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, value: {:?}, version: {:?}", wallet_handle, type_, id, value, version);
+
+    let result = CommandExecutor::instance()
+        .send(Command::NonSecrets(
+            NonSecretsCommand::UpdateRecordValueIfVersion(
+                wallet_handle,
+                type_,
+                id,
+                value,
+                version,
+                Box::new(move |result| {
+                    let err = result_to_err_code!(result);
+                    trace!("indy_update_wallet_record_value_if_version:");
+                    cb(command_handle, err)
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_update_wallet_record_value_if_version: <<< res: {:?}", res);
+
+    res
+}
+
 /// Update a non-secret wallet record tags
 ///
 /// #Params
@@ -169,6 +283,57 @@ pub extern fn indy_update_wallet_record_tags(command_handle: i32,
     res
 }
 
+/// Update a non-secret wallet record tags if the record wasn't changed since it was read.
+///
+/// #Params
+/// command_handle: command handle to map callback to caller context
+/// wallet_handle: wallet handle (created by open_wallet)
+/// type_: allows to separate different record types collections
+/// id: the id of record
+/// tags_json: the record tags in the same format as for indy_update_wallet_record_tags
+/// version: the expected version of record (see retrieveVersion option of indy_get_wallet_record)
+///
+/// #Errors
+/// WalletItemVersionMismatch if the record version differs from the expected one
+#[no_mangle]
+pub extern fn indy_update_wallet_record_tags_if_version(command_handle: i32,
+                                                        wallet_handle: i32,
+                                                        type_: *const c_char,
+                                                        id: *const c_char,
+                                                        tags_json: *const c_char,
+                                                        version: u64,
+                                                        cb: Option<extern fn(command_handle_: i32, err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_update_wallet_record_tags_if_version: >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, tags_json: {:?}, version: {:?}", wallet_handle, type_, id, tags_json, version);
+
+    check_useful_c_str!(type_, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(id, ErrorCode::CommonInvalidParam4);
+    check_useful_json!(tags_json, ErrorCode::CommonInvalidParam5, Tags);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam7);
+
+    trace!("indy_update_wallet_record_tags_if_version: entities >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, tags_json: {:?}, version: {:?}", wallet_handle, type_, id, tags_json, version);
+
+    let result = CommandExecutor::instance()
+        .send(Command::NonSecrets(
+            NonSecretsCommand::UpdateRecordTagsIfVersion(
+                wallet_handle,
+                type_,
+                id,
+                tags_json,
+                version,
+                Box::new(move |result| {
+                    let err = result_to_err_code!(result);
+                    trace!("indy_update_wallet_record_tags_if_version:");
+                    cb(command_handle, err)
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_update_wallet_record_tags_if_version: <<< res: {:?}", res);
+
+    res
+}
+
 /// Add new tags to the wallet record
 ///
 /// #Params
@@ -312,6 +477,102 @@ pub extern fn indy_delete_wallet_record(command_handle: i32,
     res
 }
 
+/// Delete an existing wallet record in the wallet if the record wasn't changed since it was read.
+///
+/// #Params
+/// command_handle: command handle to map callback to caller context
+/// wallet_handle: wallet handle (created by open_wallet)
+/// type_: record type
+/// id: the id of record
+/// version: the expected version of record (see retrieveVersion option of indy_get_wallet_record)
+///
+/// #Errors
+/// WalletItemVersionMismatch if the record version differs from the expected one
+#[no_mangle]
+pub extern fn indy_delete_wallet_record_if_version(command_handle: i32,
+                                                   wallet_handle: i32,
+                                                   type_: *const c_char,
+                                                   id: *const c_char,
+                                                   version: u64,
+                                                   cb: Option<extern fn(command_handle_: i32, err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_delete_wallet_record_if_version: >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, version: {:?}", wallet_handle, type_, id, version);
+
+    check_useful_c_str!(type_, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(id, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam6);
+
+    trace!("indy_delete_wallet_record_if_version: entities >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, version: {:?}", wallet_handle, type_, id, version);
+
+    let result = CommandExecutor::instance()
+        .send(Command::NonSecrets(
+            NonSecretsCommand::DeleteRecordIfVersion(
+                wallet_handle,
+                type_,
+                id,
+                version,
+                Box::new(move |result| {
+                    let err = result_to_err_code!(result);
+                    trace!("indy_delete_wallet_record_if_version:");
+                    cb(command_handle, err)
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_delete_wallet_record_if_version: <<< res: {:?}", res);
+
+    res
+}
+
+/// Apply the list of non-secret record operations to the wallet atomically:
+/// either all operations are applied or none of them (e.g. if any record is missing).
+///
+/// #Params
+/// command_handle: command handle to map callback to caller context
+/// wallet_handle: wallet handle (created by open_wallet)
+/// operations_json: the list of operations applied in the given order as json array:
+///   [
+///     {"op": "add", "type": <str>, "id": <str>, "value": <str>, "tags": <tags json, optional>,
+///      "expires_at": <unix time in seconds, optional>},
+///     {"op": "update", "type": <str>, "id": <str>, "value": <str>},
+///     {"op": "add_tags", "type": <str>, "id": <str>, "tags": <tags json>},
+///     {"op": "update_tags", "type": <str>, "id": <str>, "tags": <tags json>},
+///     {"op": "delete_tags", "type": <str>, "id": <str>, "tag_names": ["tagName1", ...]},
+///     {"op": "delete", "type": <str>, "id": <str>},
+///   ]
+///   Tags json has the same format as for indy_add_wallet_record
+///   Note that plugged storages must register batch handler with indy_register_wallet_storage_batch
+#[no_mangle]
+pub extern fn indy_wallet_batch(command_handle: i32,
+                                wallet_handle: i32,
+                                operations_json: *const c_char,
+                                cb: Option<extern fn(command_handle_: i32, err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_wallet_batch: >>> wallet_handle: {:?}, operations_json: {:?}", wallet_handle, operations_json);
+
+    check_useful_json!(operations_json, ErrorCode::CommonInvalidParam3, Vec<RecordOperation>);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_wallet_batch: entities >>> wallet_handle: {:?}, operations_json: {:?}", wallet_handle, operations_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::NonSecrets(
+            NonSecretsCommand::Batch(
+                wallet_handle,
+                operations_json,
+                Box::new(move |result| {
+                    let err = result_to_err_code!(result);
+                    trace!("indy_wallet_batch:");
+                    cb(command_handle, err)
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_wallet_batch: <<< res: {:?}", res);
+
+    res
+}
+
 /// Get an wallet record by id
 ///
 /// #Params
@@ -323,7 +584,9 @@ pub extern fn indy_delete_wallet_record(command_handle: i32,
 ///  {
 ///    retrieveType: (optional, false by default) Retrieve record type,
 ///    retrieveValue: (optional, true by default) Retrieve record value,
-///    retrieveTags: (optional, false by default) Retrieve record tags
+///    retrieveTags: (optional, false by default) Retrieve record tags,
+///    retrieveVersion: (optional, false by default) Retrieve record version
+///      (changed on any change of record, never reused by re-added record)
 ///  }
 /// #Returns
 /// wallet record json:
@@ -332,6 +595,7 @@ pub extern fn indy_delete_wallet_record(command_handle: i32,
 ///   type: "Some type", // present only if retrieveType set to true
 ///   value: "Some value", // present only if retrieveValue set to true
 ///   tags: <tags json>, // present only if retrieveTags set to true
+///   version: <u64>, // present only if retrieveVersion set to true and storage supports versions
 /// }
 #[no_mangle]
 pub  extern fn indy_get_wallet_record(command_handle: i32,
@@ -389,6 +653,10 @@ pub  extern fn indy_get_wallet_record(command_handle: i32,
 ///      "tagName3": { $gte: '123' },
 ///    },
 ///  }
+///  Note $gt, $gte, $lt, $lte compare values of unencrypted ("~" prefixed) tags as strings,
+///  use JSON number as operand (for example { $gte: 123 }) to compare them numerically.
+///  Numeric comparison matches only tag values like "-12" or "0.5" (optional minus, digits,
+///  optional fraction), integers that fit 64-bit signed integer are compared exactly, the rest as doubles.
 /// options_json: //TODO: FIXME: Think about replacing by bitmask
 ///  {
 ///    retrieveRecords: (optional, true by default) If false only "counts" will be calculated,
@@ -396,6 +664,11 @@ pub  extern fn indy_get_wallet_record(command_handle: i32,
 ///    retrieveType: (optional, false by default) Retrieve record type,
 ///    retrieveValue: (optional, true by default) Retrieve record value,
 ///    retrieveTags: (optional, false by default) Retrieve record tags,
+///    retrieveVersion: (optional, false by default) Retrieve record version,
+///    sortBy: (optional) Name of unencrypted ("~" prefixed) tag to sort records by,
+///    sortOrder: (optional, "asc" by default) Sort order: "asc" or "desc",
+///    offset: (optional) Number of matching records to skip,
+///    limit: (optional) Max number of records to retrieve,
 ///  }
 /// #Returns
 /// search_handle: Wallet search handle that can be used later
@@ -456,6 +729,7 @@ pub  extern fn indy_open_wallet_search(command_handle: i32,
 ///       type: "Some type", // present only if retrieveType set to true
 ///       value: "Some value", // present only if retrieveValue set to true
 ///       tags: <tags json>, // present only if retrieveTags set to true
+///       version: <u64>, // present only if retrieveVersion set to true and storage supports versions
 ///   }],
 /// }
 #[no_mangle]
@@ -522,4 +796,146 @@ pub  extern fn indy_close_wallet_search(command_handle: i32,
     trace!("indy_close_wallet_search: <<< res: {:?}", res);
 
     res
-}
\ No newline at end of file
+}
+
+/// Count wallet records that match the query.
+///
+/// #Params
+/// command_handle: command handle to map callback to caller context
+/// wallet_handle: wallet handle (created by open_wallet)
+/// type_: allows to separate different record types collections
+/// query_json: MongoDB style query to wallet record tags (see indy_open_wallet_search)
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// count: count of records that match the query
+#[no_mangle]
+pub extern fn indy_count_wallet_records(command_handle: i32,
+                                        wallet_handle: i32,
+                                        type_: *const c_char,
+                                        query_json: *const c_char,
+                                        cb: Option<extern fn(command_handle_: i32, err: ErrorCode,
+                                                             count: usize)>) -> ErrorCode {
+    trace!("indy_count_wallet_records: >>> wallet_handle: {:?}, type_: {:?}, query_json: {:?}", wallet_handle, type_, query_json);
+
+    check_useful_c_str!(type_, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(query_json, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_count_wallet_records: entities >>> wallet_handle: {:?}, type_: {:?}, query_json: {:?}", wallet_handle, type_, query_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::NonSecrets(
+            NonSecretsCommand::CountRecords(
+                wallet_handle,
+                type_,
+                query_json,
+                Box::new(move |result| {
+                    let (err, count) = result_to_err_code_1!(result, 0);
+                    trace!("indy_count_wallet_records: count: {:?}", count);
+                    cb(command_handle, err, count)
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_count_wallet_records: <<< res: {:?}", res);
+
+    res
+}
+
+/// Count wallet records that match the query grouped by value of unencrypted tag.
+///
+/// #Params
+/// command_handle: command handle to map callback to caller context
+/// wallet_handle: wallet handle (created by open_wallet)
+/// type_: allows to separate different record types collections
+/// query_json: MongoDB style query to wallet record tags (see indy_open_wallet_search)
+/// tag_name: name of unencrypted ("~" prefixed) tag to group records by
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// counts_json: count of records per tag value, records without this tag aren't counted:
+/// {
+///   "tagValue1": <int>,
+///   "tagValue2": <int>,
+/// }
+#[no_mangle]
+pub extern fn indy_count_wallet_records_by_tag(command_handle: i32,
+                                               wallet_handle: i32,
+                                               type_: *const c_char,
+                                               query_json: *const c_char,
+                                               tag_name: *const c_char,
+                                               cb: Option<extern fn(command_handle_: i32, err: ErrorCode,
+                                                                    counts_json: *const c_char)>) -> ErrorCode {
+    trace!("indy_count_wallet_records_by_tag: >>> wallet_handle: {:?}, type_: {:?}, query_json: {:?}, tag_name: {:?}", wallet_handle, type_, query_json, tag_name);
+
+    check_useful_c_str!(type_, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(query_json, ErrorCode::CommonInvalidParam4);
+    check_useful_c_str!(tag_name, ErrorCode::CommonInvalidParam5);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam6);
+
+    trace!("indy_count_wallet_records_by_tag: entities >>> wallet_handle: {:?}, type_: {:?}, query_json: {:?}, tag_name: {:?}", wallet_handle, type_, query_json, tag_name);
+
+    let result = CommandExecutor::instance()
+        .send(Command::NonSecrets(
+            NonSecretsCommand::CountRecordsByTag(
+                wallet_handle,
+                type_,
+                query_json,
+                tag_name,
+                Box::new(move |result| {
+                    let (err, counts_json) = result_to_err_code_1!(result, String::new());
+                    trace!("indy_count_wallet_records_by_tag: counts_json: {:?}", counts_json);
+                    let counts_json = ctypes::string_to_cstring(counts_json);
+                    cb(command_handle, err, counts_json.as_ptr())
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_count_wallet_records_by_tag: <<< res: {:?}", res);
+
+    res
+}
+
+/// Delete all expired non-secret records from the wallet.
+///
+/// #Params
+/// command_handle: command handle to map callback to caller context
+/// wallet_handle: wallet handle (created by open_wallet)
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// count: count of deleted records
+///
+/// #Errors
+/// WalletStorageError if wallet storage doesn't support record expiry
+#[no_mangle]
+pub extern fn indy_purge_expired_wallet_records(command_handle: i32,
+                                                wallet_handle: i32,
+                                                cb: Option<extern fn(command_handle_: i32, err: ErrorCode,
+                                                                     count: usize)>) -> ErrorCode {
+    trace!("indy_purge_expired_wallet_records: >>> wallet_handle: {:?}", wallet_handle);
+
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam3);
+
+    trace!("indy_purge_expired_wallet_records: entities >>> wallet_handle: {:?}", wallet_handle);
+
+    let result = CommandExecutor::instance()
+        .send(Command::NonSecrets(
+            NonSecretsCommand::PurgeExpiredRecords(
+                wallet_handle,
+                Box::new(move |result| {
+                    let (err, count) = result_to_err_code_1!(result, 0);
+                    trace!("indy_purge_expired_wallet_records: count: {:?}", count);
+                    cb(command_handle, err, count)
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_purge_expired_wallet_records: <<< res: {:?}", res);
+
+    res
+}
@@ -4,8 +4,11 @@ use api::ErrorCode;
 use errors::ToErrorCode;
 use commands::{Command, CommandExecutor};
 use commands::pairwise::PairwiseCommand;
+use domain::wallet::Tags;
+use errors::common::CommonError;
 use utils::ctypes;
 
+use serde_json;
 use self::libc::c_char;
 
 
@@ -110,6 +113,9 @@ pub  extern fn indy_create_pairwise(command_handle: i32,
 
 /// Get list of saved pairwise.
 ///
+/// NOTE: This method immediately returns all saved pairwise.
+/// Use <indy_open_pairwise_search> to fetch pairwise by small batches.
+///
 /// #Params
 /// wallet_handle: wallet handler (created by open_wallet).
 /// command_handle: command handle to map callback to user context.
@@ -245,4 +251,367 @@ pub  extern fn indy_set_pairwise_metadata(command_handle: i32,
     trace!("indy_set_pairwise_metadata: <<< res: {:?}", res);
 
     res
-}
\ No newline at end of file
+}
+
+/// Replaces tags of the pairwise stored in the wallet. Tags are used for search by indy_search_pairwise.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// their_did: their DID of the pairwise
+/// tags_json: the tags to replace existing tags of the pairwise with, as json:
+///   {
+///     "tagName1": <str>, // string tag (will be stored encrypted)
+///     "~tagName2": <str>, // string tag (will be stored un-encrypted)
+///   }
+///   If tag name starts with "~" the tag will be stored un-encrypted that will allow
+///   usage of this tag in complex search queries (comparison, predicates)
+///   Encrypted tags can be searched only for exact matching
+///   "~created_at" tag (creation unix time in seconds) and "my_did" tag are set by libindy, they are reserved and kept on replace
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_set_pairwise_tags(command_handle: i32,
+                                     wallet_handle: i32,
+                                     their_did: *const c_char,
+                                     tags_json: *const c_char,
+                                     cb: Option<extern fn(xcommand_handle: i32,
+                                                          err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_set_pairwise_tags: >>> wallet_handle: {:?}, their_did: {:?}, tags_json: {:?}", wallet_handle, their_did, tags_json);
+
+    check_useful_c_str!(their_did, ErrorCode::CommonInvalidParam3);
+    check_useful_json!(tags_json, ErrorCode::CommonInvalidParam4, Tags);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_set_pairwise_tags: entities >>> wallet_handle: {:?}, their_did: {:?}, tags_json: {:?}", wallet_handle, their_did, tags_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Pairwise(PairwiseCommand::SetPairwiseTags(
+            wallet_handle,
+            their_did,
+            tags_json,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_set_pairwise_tags:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_set_pairwise_tags: <<< res: {:?}", res);
+
+    res
+}
+
+/// Searches pairwise stored in the wallet by their tags.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// query_json: MongoDB style query to pairwise tags (see indy_open_wallet_search), for example:
+///  {
+///    "label": "Alice",
+///    "~created_at": { "$gt": "1530000000" },
+///  }
+///  Use "{}" to get all pairwise.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+/// cb:
+/// - xcommand_handle: Command handle to map callback to caller context.
+/// - err: Error code.
+/// - list_pairwise: list of found pairwise as json:
+///   [{
+///     "my_did": string,
+///     "their_did": string,
+///     "metadata": string, (optional)
+///     "tags": {"tagName": "tagValue", ...},
+///   }, ...]
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_search_pairwise(command_handle: i32,
+                                   wallet_handle: i32,
+                                   query_json: *const c_char,
+                                   cb: Option<extern fn(xcommand_handle: i32,
+                                                        err: ErrorCode,
+                                                        list_pairwise: *const c_char)>) -> ErrorCode {
+    trace!("indy_search_pairwise: >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+    check_useful_c_str!(query_json, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_search_pairwise: entities >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Pairwise(PairwiseCommand::SearchPairwise(
+            wallet_handle,
+            query_json,
+            Box::new(move |result| {
+                let (err, list_pairwise) = result_to_err_code_1!(result, String::new());
+                trace!("indy_search_pairwise: list_pairwise: {:?}", list_pairwise);
+                let list_pairwise = ctypes::string_to_cstring(list_pairwise);
+                cb(command_handle, err, list_pairwise.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_search_pairwise: <<< res: {:?}", res);
+
+    res
+}
+
+/// Deletes pairwise from the Wallet.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// their_did: their DID of the pairwise
+/// cascade: also delete their DID of the pairwise with its metadata and endpoint.
+///   My DID and its keys are never deleted, as they can be used beyond the pairwise.
+///   Records are deleted atomically if wallet storage supports batches.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_delete_pairwise(command_handle: i32,
+                                   wallet_handle: i32,
+                                   their_did: *const c_char,
+                                   cascade: bool,
+                                   cb: Option<extern fn(xcommand_handle: i32,
+                                                        err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_delete_pairwise: >>> wallet_handle: {:?}, their_did: {:?}, cascade: {:?}", wallet_handle, their_did, cascade);
+
+    check_useful_c_str!(their_did, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_delete_pairwise: entities >>> wallet_handle: {:?}, their_did: {:?}, cascade: {:?}", wallet_handle, their_did, cascade);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Pairwise(PairwiseCommand::DeletePairwise(
+            wallet_handle,
+            their_did,
+            cascade,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_delete_pairwise:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_delete_pairwise: <<< res: {:?}", res);
+
+    res
+}
+
+/// Re-points pairwise to the new DID of the counterparty (for example after the counterparty rotated its DID).
+/// Metadata and tags of the pairwise are kept.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// their_did: current their DID of the pairwise
+/// new_their_did: new their DID of the pairwise. Should be already stored in the wallet (see indy_store_their_did).
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_update_pairwise_their_did(command_handle: i32,
+                                             wallet_handle: i32,
+                                             their_did: *const c_char,
+                                             new_their_did: *const c_char,
+                                             cb: Option<extern fn(xcommand_handle: i32,
+                                                                  err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_update_pairwise_their_did: >>> wallet_handle: {:?}, their_did: {:?}, new_their_did: {:?}", wallet_handle, their_did, new_their_did);
+
+    check_useful_c_str!(their_did, ErrorCode::CommonInvalidParam3);
+    check_useful_c_str!(new_their_did, ErrorCode::CommonInvalidParam4);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam5);
+
+    trace!("indy_update_pairwise_their_did: entities >>> wallet_handle: {:?}, their_did: {:?}, new_their_did: {:?}", wallet_handle, their_did, new_their_did);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Pairwise(PairwiseCommand::UpdatePairwiseTheirDid(
+            wallet_handle,
+            their_did,
+            new_their_did,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_update_pairwise_their_did:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_update_pairwise_their_did: <<< res: {:?}", res);
+
+    res
+}
+
+/// Opens search for pairwise stored in the Wallet.
+///
+/// Instead of immediately returning of all pairwise (as indy_list_pairwise does)
+/// this call returns search_handle that can be used later
+/// to fetch pairwise by small batches (with indy_fetch_pairwise_search_next).
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// wallet_handle: wallet handler (created by open_wallet).
+/// query_json: (optional) MongoDB style query to pairwise tags (see indy_search_pairwise).
+///   All pairwise are returned if not set.
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// search_handle: Search handle that can be used later to fetch pairwise by small batches (with indy_fetch_pairwise_search_next)
+/// total_count: Total count of pairwise
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_open_pairwise_search(command_handle: i32,
+                                        wallet_handle: i32,
+                                        query_json: *const c_char,
+                                        cb: Option<extern fn(xcommand_handle: i32,
+                                                             err: ErrorCode,
+                                                             search_handle: i32,
+                                                             total_count: usize)>) -> ErrorCode {
+    trace!("indy_open_pairwise_search: >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+    check_useful_opt_c_str!(query_json, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_open_pairwise_search: entities >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Pairwise(PairwiseCommand::OpenPairwiseSearch(
+            wallet_handle,
+            query_json,
+            Box::new(move |result| {
+                let (err, search_handle, total_count) = result_to_err_code_2!(result, 0, 0);
+                trace!("indy_open_pairwise_search: search_handle: {:?}, total_count: {:?}", search_handle, total_count);
+                cb(command_handle, err, search_handle, total_count)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_open_pairwise_search: <<< res: {:?}", res);
+
+    res
+}
+
+/// Fetches next pairwise for search.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// search_handle: Search handle (created by indy_open_pairwise_search)
+/// count: Count of pairwise to fetch
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// list_pairwise: list of pairwise as json (see indy_search_pairwise)
+/// NOTE: The list of length less than the requested count means pairwise search iterator is completed.
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_fetch_pairwise_search_next(command_handle: i32,
+                                              search_handle: i32,
+                                              count: usize,
+                                              cb: Option<extern fn(xcommand_handle: i32,
+                                                                   err: ErrorCode,
+                                                                   list_pairwise: *const c_char)>) -> ErrorCode {
+    trace!("indy_fetch_pairwise_search_next: >>> search_handle: {:?}, count: {:?}", search_handle, count);
+
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_fetch_pairwise_search_next: entities >>> search_handle: {:?}, count: {:?}", search_handle, count);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Pairwise(PairwiseCommand::FetchPairwiseSearchNext(
+            search_handle,
+            count,
+            Box::new(move |result| {
+                let (err, list_pairwise) = result_to_err_code_1!(result, String::new());
+                trace!("indy_fetch_pairwise_search_next: list_pairwise: {:?}", list_pairwise);
+                let list_pairwise = ctypes::string_to_cstring(list_pairwise);
+                cb(command_handle, err, list_pairwise.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_fetch_pairwise_search_next: <<< res: {:?}", res);
+
+    res
+}
+
+/// Closes pairwise search (make search handle invalid)
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// search_handle: Search handle (created by indy_open_pairwise_search)
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Error code
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_close_pairwise_search(command_handle: i32,
+                                         search_handle: i32,
+                                         cb: Option<extern fn(xcommand_handle: i32,
+                                                              err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_close_pairwise_search: >>> search_handle: {:?}", search_handle);
+
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam3);
+
+    trace!("indy_close_pairwise_search: entities >>> search_handle: {:?}", search_handle);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Pairwise(PairwiseCommand::ClosePairwiseSearch(
+            search_handle,
+            Box::new(move |result| {
+                let err = result_to_err_code!(result);
+                trace!("indy_close_pairwise_search:");
+                cb(command_handle, err)
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+
+    trace!("indy_close_pairwise_search: <<< res: {:?}", res);
+
+    res
+}
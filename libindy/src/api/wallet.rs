@@ -3,7 +3,7 @@ extern crate libc;
 use api::ErrorCode;
 use commands::{Command, CommandExecutor};
 use commands::wallet::WalletCommand;
-use domain::wallet::{Config, Credentials, ExportConfig, KeyConfig};
+use domain::wallet::{Config, Credentials, ExportConfig, CheckConfig, KeyConfig};
 use errors::common::CommonError;
 use errors::ToErrorCode;
 use utils::ctypes;
@@ -17,6 +17,7 @@ use self::libc::c_char;
 /// #Params
 /// command_handle: Command handle to map callback to caller context.
 /// type_: Storage type name.
+///        Names of built-in 'default', 'kv' and 'inmem' storage types can't be registered.
 /// create: WalletType create operation handler
 /// open: WalletType open operation handler
 /// close: Wallet close operation handler
@@ -143,6 +144,98 @@ pub extern fn indy_register_wallet_storage(command_handle: i32,
     res
 }
 
+/// Register batch handler for custom wallet storage implementation.
+/// Storage type must be registered with indy_register_wallet_storage before.
+/// Without this handler indy_wallet_batch calls will fail for wallets of this storage type
+/// and libindy changes of several related records (e.g. pairwise deletion) are applied one by one.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// type_: Storage type name.
+/// batch: WalletType batch operation handler
+///
+/// #Returns
+/// Error code
+#[no_mangle]
+pub extern fn indy_register_wallet_storage_batch(command_handle: i32,
+                                                 type_: *const c_char,
+                                                 batch: Option<WalletBatch>,
+                                                 cb: Option<extern fn(xcommand_handle: i32,
+                                                                      err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_register_wallet_storage_batch: >>> command_handle: {:?}, type_: {:?}, cb: {:?}",
+           command_handle, type_, cb);
+
+    check_useful_c_str!(type_, ErrorCode::CommonInvalidParam2);
+    check_useful_c_callback!(batch, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_register_wallet_storage_batch: params type_: {:?}", type_);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Wallet(
+            WalletCommand::RegisterWalletBatch(
+                type_,
+                batch,
+                Box::new(move |result| {
+                    let err = result_to_err_code!(result);
+                    trace!("indy_register_wallet_storage_batch: cb command_handle: {:?}, err: {:?}", command_handle, err);
+                    cb(command_handle, err)
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+    trace!("indy_register_wallet_storage_batch: <<< res: {:?}", res);
+    res
+}
+
+/// Register record version handler for custom wallet storage implementation.
+/// Storage type must be registered with indy_register_wallet_storage before.
+/// Without this handler records of this storage type are returned without versions
+/// and conditional record updates and deletes fail.
+///
+/// Storage supporting versions must strictly increase record version on each change of
+/// record value or tags and handle "check_version" operation of batch handler.
+/// Versions are opaque: only versions of the same record can be compared.
+///
+/// #Params
+/// command_handle: Command handle to map callback to caller context.
+/// type_: Storage type name.
+/// get_record_version: WalletType get record version handler
+///
+/// #Returns
+/// Error code
+#[no_mangle]
+pub extern fn indy_register_wallet_storage_versioning(command_handle: i32,
+                                                      type_: *const c_char,
+                                                      get_record_version: Option<WalletGetRecordVersion>,
+                                                      cb: Option<extern fn(xcommand_handle: i32,
+                                                                           err: ErrorCode)>) -> ErrorCode {
+    trace!("indy_register_wallet_storage_versioning: >>> command_handle: {:?}, type_: {:?}, cb: {:?}",
+           command_handle, type_, cb);
+
+    check_useful_c_str!(type_, ErrorCode::CommonInvalidParam2);
+    check_useful_c_callback!(get_record_version, ErrorCode::CommonInvalidParam3);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_register_wallet_storage_versioning: params type_: {:?}", type_);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Wallet(
+            WalletCommand::RegisterWalletVersioning(
+                type_,
+                get_record_version,
+                Box::new(move |result| {
+                    let err = result_to_err_code!(result);
+                    trace!("indy_register_wallet_storage_versioning: cb command_handle: {:?}, err: {:?}", command_handle, err);
+                    cb(command_handle, err)
+                })
+            )));
+
+    let res = result_to_err_code!(result);
+    trace!("indy_register_wallet_storage_versioning: <<< res: {:?}", res);
+    res
+}
+
 /// Create a new secure wallet.
 ///
 /// #Params
@@ -152,14 +245,16 @@ pub extern fn indy_register_wallet_storage(command_handle: i32,
 ///         Configured storage uses this identifier to lookup exact wallet data placement.
 ///   "storage_type": optional<string>, Type of the wallet storage. Defaults to 'default'.
 ///                  'Default' storage type allows to store wallet data in the local file.
+///                  'kv' storage type allows to store wallet data in the local file of embedded key-value store.
+///                  'inmem' storage type keeps wallet data in the process memory only, data is lost on process exit.
 ///                  Custom storage types can be registered with indy_register_wallet_storage call.
 ///   "storage_config": optional<object>, Storage configuration json. Storage type defines set of supported keys.
 ///                     Can be optional if storage supports default configuration.
-///                     For 'default' storage type configuration is:
+///                     For 'default' and 'kv' storage types configuration is:
 ///   {
 ///     "path": optional<string>, Path to the directory with wallet files.
 ///             Defaults to $HOME/.indy_client/wallets.
-///             Wallet will be stored in the file {path}/{id}/sqlite.db
+///             Wallet will be stored in the file {path}/{id}/sqlite.db ({path}/{id}/kv.db for 'kv' storage type)
 ///   }
 /// }
 /// credentials: Wallet credentials json
@@ -225,15 +320,19 @@ pub extern fn indy_create_wallet(command_handle: i32,
 ///             Configured storage uses this identifier to lookup exact wallet data placement.
 ///       "storage_type": optional<string>, Type of the wallet storage. Defaults to 'default'.
 ///                       'Default' storage type allows to store wallet data in the local file.
+///                       'kv' storage type allows to store wallet data in the local file of embedded key-value store.
+///                       'inmem' storage type keeps wallet data in the process memory only, data is lost on process exit.
 ///                       Custom storage types can be registered with indy_register_wallet_storage call.
 ///       "storage_config": optional<object>, Storage configuration json. Storage type defines set of supported keys.
 ///                         Can be optional if storage supports default configuration.
-///                         For 'default' storage type configuration is:
+///                         For 'default' and 'kv' storage types configuration is:
 ///           {
 ///              "path": optional<string>, Path to the directory with wallet files.
 ///                      Defaults to $HOME/.indy_client/wallets.
-///                      Wallet will be stored in the file {path}/{id}/sqlite.db
+///                      Wallet will be stored in the file {path}/{id}/sqlite.db ({path}/{id}/kv.db for 'kv' storage type)
 ///           }
+///       "cache_size": optional<int>, Max number of decrypted records cached in memory while the wallet is open.
+///                     Cached records are invalidated on update and delete. Cache is disabled by default.
 ///
 ///   }
 /// credentials: Wallet credentials json
@@ -348,6 +447,72 @@ pub extern fn indy_export_wallet(command_handle: i32,
     res
 }
 
+/// Checks integrity of opened wallet
+///
+/// Walks all wallet records and validates decryption of their types, ids, values and tags.
+/// Unreadable records are reported and can be quarantined: such record is replaced with
+/// a record of "Indy::QuarantinedRecord" type which value keeps raw encrypted content
+/// of the original one. So the rest of the wallet can be read and searched.
+/// Tags left without their records are reported too and deleted on quarantine.
+/// Storage errors met while records are read are reported and the check goes on.
+///
+/// #Params:
+/// wallet_handle: wallet handle returned by indy_open_wallet
+/// check_config: JSON containing settings for check operation.
+///   {
+///     "quarantine": optional<bool>, Quarantine unreadable records and delete orphan tags. Defaults to false.
+///   }
+/// cb: Callback that takes command result as parameter.
+///
+/// #Returns
+/// Check report json:
+///   {
+///     "checked": <int>, Number of checked records
+///     "failed": [{
+///         "type": optional<string>, Record type if it can be decrypted
+///         "id": optional<string>, Record id if it can be decrypted
+///         "encrypted_id": <string>, Base64 encoded encrypted record id
+///         "error": <string>, Description of the failure
+///     }],
+///     "quarantined": <int>, Number of quarantined records
+///     "orphan_tags": <int>, Number of tags left without their records
+///     "storage_errors": [<string>], Descriptions of storage errors met while records were read
+///   }
+///
+/// #Errors
+/// Common*
+/// Wallet*
+#[no_mangle]
+pub extern fn indy_check_wallet(command_handle: i32,
+                                wallet_handle: i32,
+                                check_config: *const c_char,
+                                cb: Option<extern fn(xcommand_handle: i32,
+                                                     err: ErrorCode,
+                                                     report_json: *const c_char)>) -> ErrorCode {
+    trace!("indy_check_wallet: >>> wallet_handle: {:?}, check_config: {:?}", wallet_handle, check_config);
+
+    check_useful_json!(check_config, ErrorCode::CommonInvalidParam3, CheckConfig);
+    check_useful_c_callback!(cb, ErrorCode::CommonInvalidParam4);
+
+    trace!("indy_check_wallet: params wallet_handle: {:?}, check_config: {:?}", wallet_handle, check_config);
+
+    let result = CommandExecutor::instance()
+        .send(Command::Wallet(WalletCommand::Check(
+            wallet_handle,
+            check_config,
+            Box::new(move |result| {
+                let (err, report_json) = result_to_err_code_1!(result, String::new());
+                trace!("indy_check_wallet: cb command_handle: {:?} err: {:?}, report_json: {:?}", command_handle, err, report_json);
+                let report_json = ctypes::string_to_cstring(report_json);
+                cb(command_handle, err, report_json.as_ptr())
+            })
+        )));
+
+    let res = result_to_err_code!(result);
+    trace!("indy_check_wallet: <<< res: {:?}", res);
+    res
+}
+
 
 /// Creates a new secure wallet and then imports its content
 /// according to fields provided in import_config
@@ -360,14 +525,16 @@ pub extern fn indy_export_wallet(command_handle: i32,
 ///         Configured storage uses this identifier to lookup exact wallet data placement.
 ///   "storage_type": optional<string>, Type of the wallet storage. Defaults to 'default'.
 ///                  'Default' storage type allows to store wallet data in the local file.
+///                  'kv' storage type allows to store wallet data in the local file of embedded key-value store.
+///                  'inmem' storage type keeps wallet data in the process memory only, data is lost on process exit.
 ///                  Custom storage types can be registered with indy_register_wallet_storage call.
 ///   "storage_config": optional<object>, Storage configuration json. Storage type defines set of supported keys.
 ///                     Can be optional if storage supports default configuration.
-///                     For 'default' storage type configuration is:
+///                     For 'default' and 'kv' storage types configuration is:
 ///   {
 ///     "path": optional<string>, Path to the directory with wallet files.
 ///             Defaults to $HOME/.indy_client/wallets.
-///             Wallet will be stored in the file {path}/{id}/sqlite.db
+///             Wallet will be stored in the file {path}/{id}/sqlite.db ({path}/{id}/kv.db for 'kv' storage type)
 ///   }
 /// }
 /// credentials: Wallet credentials json
@@ -478,14 +645,16 @@ pub extern fn indy_close_wallet(command_handle: i32,
 ///         Configured storage uses this identifier to lookup exact wallet data placement.
 ///   "storage_type": optional<string>, Type of the wallet storage. Defaults to 'default'.
 ///                  'Default' storage type allows to store wallet data in the local file.
+///                  'kv' storage type allows to store wallet data in the local file of embedded key-value store.
+///                  'inmem' storage type keeps wallet data in the process memory only, data is lost on process exit.
 ///                  Custom storage types can be registered with indy_register_wallet_storage call.
 ///   "storage_config": optional<object>, Storage configuration json. Storage type defines set of supported keys.
 ///                     Can be optional if storage supports default configuration.
-///                     For 'default' storage type configuration is:
+///                     For 'default' and 'kv' storage types configuration is:
 ///   {
 ///     "path": optional<string>, Path to the directory with wallet files.
 ///             Defaults to $HOME/.indy_client/wallets.
-///             Wallet will be stored in the file {path}/{id}/sqlite.db
+///             Wallet will be stored in the file {path}/{id}/sqlite.db ({path}/{id}/kv.db for 'kv' storage type)
 ///   }
 /// }
 /// credentials: Wallet credentials json
@@ -845,6 +1014,11 @@ pub type WalletFreeStorageMetadata = extern fn(storage_handle: i32,
 ///      "tagName3": { $gte: 123 },
 ///    },
 ///  }
+///  Note JSON number operand of $gt, $gte, $lt, $lte (for example { $gte: 123 }) means
+///  numeric comparison, string operand means lexical comparison. Numeric operand is passed
+///  as integer or as decimal without exponent. Numeric comparison must match only tag values
+///  like "-12" or "0.5" (optional minus, digits, optional fraction), integers that fit 64-bit
+///  signed integer must be compared exactly, the rest as doubles.
 /// options_json: //TODO: FIXME: Think about replacing by bitmask
 ///  {
 ///    retrieveRecords: (optional, true by default) If false only "counts" will be calculated,
@@ -852,6 +1026,11 @@ pub type WalletFreeStorageMetadata = extern fn(storage_handle: i32,
 ///    retrieveType: (optional, false by default) Retrieve record type,
 ///    retrieveValue: (optional, true by default) Retrieve record value,
 ///    retrieveTags: (optional, true by default) Retrieve record tags,
+///    sortBy: (optional) Name of unencrypted ("~" prefixed) tag to sort records by,
+///            passed to storage as "~" followed by base64 of encrypted tag name,
+///    sortOrder: (optional, "asc" by default) Sort order: "asc" or "desc",
+///    offset: (optional) Number of matching records to skip,
+///    limit: (optional) Max number of records to retrieve,
 ///  }
 /// search_handle_p: pointer to store wallet search handle
 pub type WalletSearchRecords = extern fn(storage_handle: i32,
@@ -899,3 +1078,36 @@ pub type WalletFetchSearchNextRecord = extern fn(storage_handle: i32,
 /// search_handle: wallet search handle (See search_records handler)
 pub type WalletFreeSearch = extern fn(storage_handle: i32,
                                       search_handle: i32) -> ErrorCode;
+
+/// Apply the list of operations to the wallet storage atomically:
+/// either all operations are applied or none of them.
+///
+/// #Params
+/// storage_handle: opened storage handle (See open handler)
+/// operations_json: the list of operations as json array:
+///   [
+///     {"op": "add", "type": string, "id": string, "value": string, "tags": {"tagName1": "tag value 1", ...}},
+///     {"op": "update", "type": string, "id": string, "value": string},
+///     {"op": "add_tags", "type": string, "id": string, "tags": {"tagName1": "tag value 1", ...}},
+///     {"op": "update_tags", "type": string, "id": string, "tags": {"tagName1": "tag value 1", ...}},
+///     {"op": "delete_tags", "type": string, "id": string, "tag_names": ["tagName1", ...]},
+///     {"op": "delete", "type": string, "id": string},
+///     {"op": "check_version", "type": string, "id": string, "version": number},
+///   ]
+///   Types, ids and tags are encoded the same way as for the corresponding single record handlers,
+///   values are base64 encoded bytes of record value.
+///   "check_version" is sent only to storages with registered get record version handler.
+///   It must fail the whole batch with WalletItemVersionMismatch error if record version differs.
+pub type WalletBatch = extern fn(storage_handle: i32,
+                                 operations_json: *const c_char) -> ErrorCode;
+
+/// Get the version of wallet storage record retrieved by get_record or fetch_search_next_record handler.
+///
+/// #Params
+/// storage_handle: opened storage handle (See open handler)
+/// record_handle: retrieved record handle (See get_record handler)
+///
+/// returns: opaque version of the record. Strictly increases on each change of record value or tags.
+pub type WalletGetRecordVersion = extern fn(storage_handle: i32,
+                                            record_handle: i32,
+                                            version_p: *mut u64) -> ErrorCode;
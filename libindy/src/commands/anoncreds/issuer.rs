@@ -95,6 +95,12 @@ pub enum IssuerCommand {
         String, //revocation revoc id
         String, //credential revoc id
         Box<Fn(Result<String, IndyError>) + Send>),
+    RevokeCredentials(
+        i32, // wallet handle
+        i32, // blob storage reader config handle
+        String, //revocation revoc id
+        Vec<String>, //credential revoc ids
+        Box<Fn(Result<String, IndyError>) + Send>),
     /*    RecoverCredential(
             i32, // wallet handle
             i32, // blob storage reader config handle
@@ -164,6 +170,10 @@ impl IssuerCommandExecutor {
                 info!(target: "issuer_command_executor", "RevokeCredential command received");
                 cb(self.revoke_credential(wallet_handle, blob_storage_reader_handle, &rev_reg_id, &cred_revoc_id));
             }
+            IssuerCommand::RevokeCredentials(wallet_handle, blob_storage_reader_handle, rev_reg_id, cred_revoc_ids, cb) => {
+                info!(target: "issuer_command_executor", "RevokeCredentials command received");
+                cb(self.revoke_credentials(wallet_handle, blob_storage_reader_handle, &rev_reg_id, &cred_revoc_ids));
+            }
             /*            IssuerCommand::RecoverCredential(wallet_handle, blob_storage_reader_handle, rev_reg_id, cred_revoc_id, cb) => {
                             info!(target: "issuer_command_executor", "RecoverCredential command received");
                             cb(self.recovery_credential(wallet_handle, blob_storage_reader_handle, &rev_reg_id, &cred_revoc_id));
@@ -569,6 +579,73 @@ impl IssuerCommandExecutor {
         Ok(rev_reg_delta_json)
     }
 
+    fn revoke_credentials(&self,
+                          wallet_handle: i32,
+                          blob_storage_reader_handle: i32,
+                          rev_reg_id: &str,
+                          cred_revoc_ids: &[String]) -> Result<String, IndyError> {
+        debug!("revoke_credentials >>> wallet_handle: {:?}, blob_storage_reader_handle:  {:?}, rev_reg_id: {:?}, cred_revoc_ids: {:?}",
+               wallet_handle, blob_storage_reader_handle, rev_reg_id, secret!(cred_revoc_ids));
+
+        if cred_revoc_ids.is_empty() {
+            return Err(IndyError::CommonError(CommonError::InvalidStructure("Empty list of credential revocation ids".to_string())));
+        }
+
+        let cred_revoc_ids = cred_revoc_ids
+            .iter()
+            .map(|cred_revoc_id| parse_cred_rev_id(cred_revoc_id))
+            .collect::<Result<Vec<u32>, CommonError>>()?;
+
+        let revocation_registry_definition: RevocationRegistryDefinitionV1 =
+            RevocationRegistryDefinitionV1::from(
+                self._wallet_get_rev_reg_def(wallet_handle, &rev_reg_id)?);
+
+        let mut rev_reg: RevocationRegistryV1 =
+            RevocationRegistryV1::from(
+                self._wallet_get_rev_reg(wallet_handle, &rev_reg_id)?);
+
+        let sdk_tails_accessor = SDKTailsAccessor::new(self.blob_storage_service.clone(),
+                                                       blob_storage_reader_handle,
+                                                       &revocation_registry_definition)?;
+
+        let mut rev_reg_info = self._wallet_get_rev_reg_info(wallet_handle, &rev_reg_id)?;
+
+        // All ids are checked against the registry state before the accumulator is touched,
+        // so an invalid id in the list leaves the wallet unchanged.
+        for cred_revoc_id in cred_revoc_ids.iter() {
+            if *cred_revoc_id > revocation_registry_definition.value.max_cred_num + 1 {
+                return Err(IndyError::AnoncredsError(AnoncredsError::InvalidUserRevocId(format!("Revocation id: {:?} not found in RevocationRegistry", cred_revoc_id))));
+            }
+
+            let is_valid = match revocation_registry_definition.value.issuance_type {
+                IssuanceType::ISSUANCE_ON_DEMAND => rev_reg_info.used_ids.remove(cred_revoc_id),
+                IssuanceType::ISSUANCE_BY_DEFAULT => rev_reg_info.used_ids.insert(*cred_revoc_id)
+            };
+
+            if !is_valid {
+                return Err(IndyError::AnoncredsError(AnoncredsError::InvalidUserRevocId(format!("Revocation id: {:?} not found in RevocationRegistry", cred_revoc_id))));
+            }
+        }
+
+        let rev_reg_delta =
+            self.anoncreds_service.issuer.revoke_batch(&mut rev_reg.value, revocation_registry_definition.value.max_cred_num, &cred_revoc_ids, &sdk_tails_accessor)?
+                .ok_or(CommonError::InvalidState("RevocationRegistryDelta not built".to_string()))?;
+
+        let rev_reg_delta = RevocationRegistryDelta::RevocationRegistryDeltaV1(RevocationRegistryDeltaV1 { value: rev_reg_delta });
+
+        let rev_reg_delta_json = serde_json::to_string(&rev_reg_delta)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize RevocationRegistryDelta: {:?}", err)))?;
+
+        let rev_reg = RevocationRegistry::RevocationRegistryV1(rev_reg);
+
+        self.wallet_service.update_indy_object(wallet_handle, &rev_reg_id, &rev_reg)?;
+        self.wallet_service.update_indy_object(wallet_handle, &rev_reg_id, &rev_reg_info)?;
+
+        debug!("revoke_credentials <<< rev_reg_delta_json: {:?}", rev_reg_delta_json);
+
+        Ok(rev_reg_delta_json)
+    }
+
     fn _recovery_credential(&self,
                             wallet_handle: i32,
                             blob_storage_reader_handle: i32,
@@ -8,12 +8,14 @@ use errors::indy::IndyError;
 use errors::anoncreds::AnoncredsError;
 use services::anoncreds::AnoncredsService;
 use services::anoncreds::helpers::parse_cred_rev_id;
+use services::anoncreds::prover::{MASTER_SECRET_ID_TAG, STORED_AT_TAG};
 use services::wallet::{WalletService, WalletSearch, RecordOptions, SearchOptions, WalletRecord};
 use services::crypto::CryptoService;
 use std::rc::Rc;
 use std::cell::RefCell;
 use services::blob_storage::BlobStorageService;
 use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
 use self::indy_crypto::cl::{Witness, RevocationRegistry, new_nonce};
 use super::tails::SDKTailsAccessor;
 
@@ -27,8 +29,10 @@ use domain::anoncreds::revocation_registry_definition::{RevocationRegistryDefini
 use domain::anoncreds::revocation_registry_delta::{RevocationRegistryDelta, RevocationRegistryDeltaV1};
 use domain::anoncreds::proof_request::{ProofRequest, ProofRequestExtraQuery, PredicateInfo, NonRevocedInterval};
 use domain::anoncreds::requested_credential::RequestedCredentials;
+use domain::anoncreds::credential_selection::CredentialSelectionPolicy;
 use domain::anoncreds::revocation_state::RevocationState;
-use domain::anoncreds::master_secret::MasterSecret;
+use domain::anoncreds::master_secret::{MasterSecret, MasterSecretInfo};
+use domain::crypto::export::EncryptedExport;
 use utils::sequence;
 
 pub enum ProverCommand {
@@ -36,6 +40,24 @@ pub enum ProverCommand {
         i32, // wallet handle
         Option<String>, // master secret id
         Box<Fn(Result<String, IndyError>) + Send>),
+    ListMasterSecrets(
+        i32, // wallet handle
+        Box<Fn(Result<String, IndyError>) + Send>),
+    ExportMasterSecret(
+        i32, // wallet handle
+        String, // master secret id
+        String, // passphrase
+        Box<Fn(Result<String, IndyError>) + Send>),
+    ImportMasterSecret(
+        i32, // wallet handle
+        Option<String>, // master secret id
+        EncryptedExport, // exported master secret
+        String, // passphrase
+        Box<Fn(Result<String, IndyError>) + Send>),
+    DeleteMasterSecret(
+        i32, // wallet handle
+        String, // master secret id
+        Box<Fn(Result<(), IndyError>) + Send>),
     CreateCredentialRequest(
         i32, // wallet handle
         String, // prover did
@@ -74,6 +96,11 @@ pub enum ProverCommand {
         i32, // wallet handle
         ProofRequest, // proof request
         Box<Fn(Result<String, IndyError>) + Send>),
+    SelectCredentialsForProofReq(
+        i32, // wallet handle
+        ProofRequest, // proof request
+        Option<CredentialSelectionPolicy>, // selection policy
+        Box<Fn(Result<(String, String), IndyError>) + Send>),
     SearchCredentialsForProofReq(
         i32, // wallet handle
         ProofRequest, // proof request
@@ -161,6 +188,22 @@ impl ProverCommandExecutor {
                 info!(target: "prover_command_executor", "CreateMasterSecret command received");
                 cb(self.create_master_secret(wallet_handle, master_secret_id.as_ref().map(String::as_str)));
             }
+            ProverCommand::ListMasterSecrets(wallet_handle, cb) => {
+                info!(target: "prover_command_executor", "ListMasterSecrets command received");
+                cb(self.list_master_secrets(wallet_handle));
+            }
+            ProverCommand::ExportMasterSecret(wallet_handle, master_secret_id, passphrase, cb) => {
+                info!(target: "prover_command_executor", "ExportMasterSecret command received");
+                cb(self.export_master_secret(wallet_handle, &master_secret_id, &passphrase));
+            }
+            ProverCommand::ImportMasterSecret(wallet_handle, master_secret_id, exported_master_secret, passphrase, cb) => {
+                info!(target: "prover_command_executor", "ImportMasterSecret command received");
+                cb(self.import_master_secret(wallet_handle, master_secret_id.as_ref().map(String::as_str), &exported_master_secret, &passphrase));
+            }
+            ProverCommand::DeleteMasterSecret(wallet_handle, master_secret_id, cb) => {
+                info!(target: "prover_command_executor", "DeleteMasterSecret command received");
+                cb(self.delete_master_secret(wallet_handle, &master_secret_id));
+            }
             ProverCommand::CreateCredentialRequest(wallet_handle, prover_did, credential_offer,
                                                    credential_def, master_secret_name, cb) => {
                 info!(target: "prover_command_executor", "CreateCredentialRequest command received");
@@ -198,6 +241,10 @@ impl ProverCommandExecutor {
                 info!(target: "prover_command_executor", "GetCredentialsForProofReq command received");
                 cb(self.get_credentials_for_proof_req(wallet_handle, &proof_req));
             }
+            ProverCommand::SelectCredentialsForProofReq(wallet_handle, proof_req, selection_policy, cb) => {
+                info!(target: "prover_command_executor", "SelectCredentialsForProofReq command received");
+                cb(self.select_credentials_for_proof_req(wallet_handle, &proof_req, selection_policy.as_ref()));
+            }
             ProverCommand::SearchCredentialsForProofReq(wallet_handle, proof_req, extra_query, cb) => {
                 info!(target: "prover_command_executor", "SearchCredentialsForProofReq command received");
                 cb(self.search_credentials_for_proof_req(wallet_handle, &proof_req, extra_query.as_ref()));
@@ -254,6 +301,102 @@ impl ProverCommandExecutor {
         Ok(master_secret_id)
     }
 
+    fn list_master_secrets(&self, wallet_handle: i32) -> Result<String, IndyError> {
+        debug!("list_master_secrets >>> wallet_handle: {:?}", wallet_handle);
+
+        let mut master_secrets_search =
+            self.wallet_service.search_indy_records::<MasterSecret>(wallet_handle, "{}", &SearchOptions::id_value())?;
+
+        let mut master_secrets_info: Vec<MasterSecretInfo> = Vec::new();
+
+        while let Some(master_secret_record) = master_secrets_search.fetch_next_record()? {
+            let master_secret_id = master_secret_record.get_id();
+
+            master_secrets_info.push(MasterSecretInfo {
+                id: master_secret_id.to_string(),
+                cred_count: self._count_credentials_for_master_secret(wallet_handle, master_secret_id)?
+            });
+        }
+
+        let master_secrets_info_json = serde_json::to_string(&master_secrets_info)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize list of MasterSecretInfo: {:?}", err)))?;
+
+        debug!("list_master_secrets <<< master_secrets_info_json: {:?}", master_secrets_info_json);
+
+        Ok(master_secrets_info_json)
+    }
+
+    fn export_master_secret(&self,
+                            wallet_handle: i32,
+                            master_secret_id: &str,
+                            passphrase: &str) -> Result<String, IndyError> {
+        debug!("export_master_secret >>> wallet_handle: {:?}, master_secret_id: {:?}, passphrase: {:?}", wallet_handle, master_secret_id, secret!(passphrase));
+
+        let master_secret: MasterSecret = self._wallet_get_master_secret(wallet_handle, master_secret_id)?;
+
+        let exported_master_secret = self.crypto_service.encrypt_export(&master_secret.value, passphrase)?;
+
+        let exported_master_secret_json = serde_json::to_string(&exported_master_secret)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize EncryptedExport: {:?}", err)))?;
+
+        debug!("export_master_secret <<< exported_master_secret_json: {:?}", exported_master_secret_json);
+
+        Ok(exported_master_secret_json)
+    }
+
+    fn import_master_secret(&self,
+                            wallet_handle: i32,
+                            master_secret_id: Option<&str>,
+                            exported_master_secret: &EncryptedExport,
+                            passphrase: &str) -> Result<String, IndyError> {
+        debug!("import_master_secret >>> wallet_handle: {:?}, master_secret_id: {:?}, exported_master_secret: {:?}, passphrase: {:?}",
+               wallet_handle, master_secret_id, exported_master_secret, secret!(passphrase));
+
+        let master_secret_id = master_secret_id.map(String::from).unwrap_or(uuid::Uuid::new_v4().to_string());
+
+        if self.wallet_service.record_exists::<MasterSecret>(wallet_handle, &master_secret_id)? {
+            return Err(IndyError::AnoncredsError(
+                AnoncredsError::MasterSecretDuplicateNameError(format!("MasterSecret already exists {}", master_secret_id))));
+        }
+
+        let master_secret = MasterSecret {
+            value: self.crypto_service.decrypt_export(exported_master_secret, passphrase)?
+        };
+
+        self.wallet_service.add_indy_object(wallet_handle, &master_secret_id, &master_secret, &HashMap::new())?;
+
+        debug!("import_master_secret <<< master_secret_id: {:?}", master_secret_id);
+
+        Ok(master_secret_id)
+    }
+
+    fn delete_master_secret(&self,
+                            wallet_handle: i32,
+                            master_secret_id: &str) -> Result<(), IndyError> {
+        debug!("delete_master_secret >>> wallet_handle: {:?}, master_secret_id: {:?}", wallet_handle, master_secret_id);
+
+        let cred_count = self._count_credentials_for_master_secret(wallet_handle, master_secret_id)?;
+
+        if cred_count > 0 {
+            return Err(IndyError::AnoncredsError(
+                AnoncredsError::MasterSecretInUse(format!("MasterSecret {} is referenced by {} stored credentials", master_secret_id, cred_count))));
+        }
+
+        let untagged_cred_count = self._count_credentials_without_master_secret(wallet_handle)?;
+
+        if untagged_cred_count > 0 {
+            return Err(IndyError::AnoncredsError(
+                AnoncredsError::MasterSecretInUse(format!("MasterSecret {} may be referenced by {} credentials stored without master secret id",
+                                                          master_secret_id, untagged_cred_count))));
+        }
+
+        self.wallet_service.delete_indy_record::<MasterSecret>(wallet_handle, master_secret_id)?;
+
+        debug!("delete_master_secret <<<");
+
+        Ok(())
+    }
+
     fn create_credential_request(&self,
                                  wallet_handle: i32,
                                  prover_did: &str,
@@ -322,7 +465,9 @@ impl ProverCommandExecutor {
 
         let out_cred_id = cred_id.map(String::from).unwrap_or(uuid::Uuid::new_v4().to_string());
 
-        let cred_tags = self.anoncreds_service.prover.build_credential_tags(&credential);
+        let mut cred_tags = self.anoncreds_service.prover.build_credential_tags(&credential);
+        cred_tags.insert(MASTER_SECRET_ID_TAG.to_string(), cred_req_metadata.master_secret_name.clone());
+        cred_tags.insert(STORED_AT_TAG.to_string(), ProverCommandExecutor::_now_nanos()?.to_string());
         self.wallet_service.add_indy_object(wallet_handle, &out_cred_id, credential, &cred_tags)?;
 
         debug!("store_credential <<< out_cred_id: {:?}", out_cred_id);
@@ -439,6 +584,74 @@ impl ProverCommandExecutor {
                                      proof_request: &ProofRequest) -> Result<String, IndyError> {
         debug!("get_credentials_for_proof_req >>> wallet_handle: {:?}, proof_request: {:?}", wallet_handle, proof_request);
 
+        let credentials_for_proof_request = self._get_credentials_for_proof_req(wallet_handle, proof_request)?;
+
+        let credentials_for_proof_request_json = serde_json::to_string(&credentials_for_proof_request)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize CredentialsForProofRequest: {:?}", err)))?;
+
+        debug!("get_credentials_for_proof_req <<< credentials_for_proof_request_json: {:?}", credentials_for_proof_request_json);
+
+        Ok(credentials_for_proof_request_json)
+    }
+
+    fn select_credentials_for_proof_req(&self,
+                                        wallet_handle: i32,
+                                        proof_request: &ProofRequest,
+                                        selection_policy: Option<&CredentialSelectionPolicy>) -> Result<(String, String), IndyError> {
+        debug!("select_credentials_for_proof_req >>> wallet_handle: {:?}, proof_request: {:?}, selection_policy: {:?}", wallet_handle, proof_request, selection_policy);
+
+        let credentials_for_proof_request = self._get_credentials_for_proof_req(wallet_handle, proof_request)?;
+
+        let default_policy = CredentialSelectionPolicy::default();
+        let selection_policy = selection_policy.unwrap_or(&default_policy);
+
+        let stored_at = self._get_credentials_stored_at(wallet_handle, &credentials_for_proof_request)?;
+
+        let selection = self.anoncreds_service.prover.select_credentials(&credentials_for_proof_request, selection_policy, &stored_at)?;
+
+        let requested_credentials_json = serde_json::to_string(&selection.requested_credentials)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize RequestedCredentials: {:?}", err)))?;
+
+        let unmet_referents_json = serde_json::to_string(&selection.unmet_referents)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize unmet referents: {:?}", err)))?;
+
+        debug!("select_credentials_for_proof_req <<< requested_credentials_json: {:?}, unmet_referents_json: {:?}", requested_credentials_json, unmet_referents_json);
+
+        Ok((requested_credentials_json, unmet_referents_json))
+    }
+
+    fn _get_credentials_stored_at(&self,
+                                  wallet_handle: i32,
+                                  credentials_for_proof_request: &CredentialsForProofRequest) -> Result<HashMap<String, u64>, IndyError> {
+        let mut stored_at: HashMap<String, u64> = HashMap::new();
+
+        let candidates = credentials_for_proof_request.attrs.values()
+            .chain(credentials_for_proof_request.predicates.values())
+            .flat_map(|candidates| candidates.iter());
+
+        for candidate in candidates {
+            let cred_id = &candidate.cred_info.referent;
+
+            if stored_at.contains_key(cred_id) {
+                continue;
+            }
+
+            let credential_record = self.wallet_service.get_indy_record::<Credential>(wallet_handle, cred_id, &RecordOptions::id_tags())?;
+
+            // Credentials stored before the tag was introduced have no time of storing
+            if let Some(time) = credential_record.get_tags()
+                .and_then(|tags| tags.get(STORED_AT_TAG))
+                .and_then(|time| time.parse::<u64>().ok()) {
+                stored_at.insert(cred_id.clone(), time);
+            }
+        }
+
+        Ok(stored_at)
+    }
+
+    fn _get_credentials_for_proof_req(&self,
+                                      wallet_handle: i32,
+                                      proof_request: &ProofRequest) -> Result<CredentialsForProofRequest, IndyError> {
         let mut credentials_for_proof_request = CredentialsForProofRequest::default();
 
         for (attr_id, requested_attr) in &proof_request.requested_attributes {
@@ -468,12 +681,7 @@ impl ProverCommandExecutor {
             credentials_for_proof_request.predicates.insert(predicate_id.to_string(), credentials_for_predicate);
         }
 
-        let credentials_for_proof_request_json = serde_json::to_string(&credentials_for_proof_request)
-            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize CredentialsForProofRequest: {:?}", err)))?;
-
-        debug!("get_credentials_for_proof_req <<< credentials_for_proof_request_json: {:?}", credentials_for_proof_request_json);
-
-        Ok(credentials_for_proof_request_json)
+        Ok(credentials_for_proof_request)
     }
 
     fn search_credentials_for_proof_req(&self,
@@ -769,5 +977,41 @@ impl ProverCommandExecutor {
     fn _wallet_get_master_secret(&self, wallet_handle: i32, key: &str) -> Result<MasterSecret, WalletError> {
         self.wallet_service.get_indy_object(wallet_handle, &key, &RecordOptions::id_value())
     }
+
+    fn _count_credentials_for_master_secret(&self, wallet_handle: i32, master_secret_id: &str) -> Result<usize, WalletError> {
+        let query_json = json!({ MASTER_SECRET_ID_TAG: master_secret_id }).to_string();
+
+        let credentials_search =
+            self.wallet_service.search_indy_records::<Credential>(wallet_handle, &query_json, &SearchOptions::id_value())?;
+
+        Ok(credentials_search.get_total_count()?.unwrap_or(0))
+    }
+
+    // Credentials stored before the master secret id tag was introduced can belong to any master secret
+    fn _count_credentials_without_master_secret(&self, wallet_handle: i32) -> Result<usize, WalletError> {
+        let mut credentials_search =
+            self.wallet_service.search_indy_records::<Credential>(wallet_handle, "{}", &SearchOptions::id_tags())?;
+
+        let mut count = 0;
+
+        while let Some(credential_record) = credentials_search.fetch_next_record()? {
+            let tagged = credential_record.get_tags()
+                .map(|tags| tags.contains_key(MASTER_SECRET_ID_TAG))
+                .unwrap_or(false);
+
+            if !tagged {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    fn _now_nanos() -> Result<u64, CommonError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot get current time: {:?}", err)))?;
+
+        Ok(now.as_secs() * 1_000_000_000 + now.subsec_nanos() as u64)
+    }
 }
 
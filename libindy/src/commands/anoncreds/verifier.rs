@@ -1,10 +1,12 @@
+extern crate serde_json;
+
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 use domain::anoncreds::schema::{Schema, SchemaV1, schemas_map_to_schemas_v1_map};
 use domain::anoncreds::credential_definition::{CredentialDefinition, CredentialDefinitionV1, cred_defs_map_to_cred_defs_v1_map};
-use domain::anoncreds::proof::Proof;
-use domain::anoncreds::proof_request::ProofRequest;
+use domain::anoncreds::proof::{Proof, VerifyProofOptions};
+use domain::anoncreds::proof_request::{ProofRequest, SchemaAttributeTypes};
 use domain::anoncreds::revocation_registry_definition::{RevocationRegistryDefinition, RevocationRegistryDefinitionV1, rev_reg_defs_map_to_rev_reg_defs_v1_map};
 use domain::anoncreds::revocation_registry::{RevocationRegistry, RevocationRegistryV1, rev_regs_map_to_rev_regs_local_map};
 use errors::common::CommonError;
@@ -19,7 +21,13 @@ pub enum VerifierCommand {
         HashMap<String, CredentialDefinition>, // credential defs
         HashMap<String, RevocationRegistryDefinition>, // rev reg defs
         HashMap<String, HashMap<u64, RevocationRegistry>>, // rev reg entries
-        Box<Fn(Result<bool, IndyError>) + Send>)
+        VerifyProofOptions, // verification options
+        Box<Fn(Result<bool, IndyError>) + Send>),
+    ValidateProofRequest(
+        ProofRequest, // proof request
+        HashMap<String, Schema>, // credential schemas
+        SchemaAttributeTypes, // schema attribute types
+        Box<Fn(Result<String, IndyError>) + Send>)
 }
 
 pub struct VerifierCommandExecutor {
@@ -35,13 +43,18 @@ impl VerifierCommandExecutor {
 
     pub fn execute(&self, command: VerifierCommand) {
         match command {
-            VerifierCommand::VerifyProof(proof_request, proof, schemas, credential_defs, rev_reg_defs, rev_regs, cb) => {
+            VerifierCommand::VerifyProof(proof_request, proof, schemas, credential_defs, rev_reg_defs, rev_regs, options, cb) => {
                 info!(target: "verifier_command_executor", "VerifyProof command received");
                 cb(self.verify_proof(proof_request, proof,
                                      &schemas_map_to_schemas_v1_map(schemas),
                                      &cred_defs_map_to_cred_defs_v1_map(credential_defs),
                                      &rev_reg_defs_map_to_rev_reg_defs_v1_map(rev_reg_defs),
-                                     &rev_regs_map_to_rev_regs_local_map(rev_regs)));
+                                     &rev_regs_map_to_rev_regs_local_map(rev_regs),
+                                     &options));
+            }
+            VerifierCommand::ValidateProofRequest(proof_request, schemas, attr_types, cb) => {
+                info!(target: "verifier_command_executor", "ValidateProofRequest command received");
+                cb(self.validate_proof_request(&proof_request, &schemas_map_to_schemas_v1_map(schemas), &attr_types));
             }
         };
     }
@@ -52,10 +65,11 @@ impl VerifierCommandExecutor {
                     schemas: &HashMap<String, SchemaV1>,
                     cred_defs: &HashMap<String, CredentialDefinitionV1>,
                     rev_reg_defs: &HashMap<String, RevocationRegistryDefinitionV1>,
-                    rev_regs: &HashMap<String, HashMap<u64, RevocationRegistryV1>>) -> Result<bool, IndyError> {
+                    rev_regs: &HashMap<String, HashMap<u64, RevocationRegistryV1>>,
+                    options: &VerifyProofOptions) -> Result<bool, IndyError> {
         debug!("verify_proof >>> proof_req: {:?}, proof: {:?}, schemas: {:?}, cred_defs: {:?},  \
-               rev_reg_defs: {:?}, rev_regs: {:?}",
-               proof_req, proof, schemas, cred_defs, rev_reg_defs, rev_regs);
+               rev_reg_defs: {:?}, rev_regs: {:?}, options: {:?}",
+               proof_req, proof, schemas, cred_defs, rev_reg_defs, rev_regs, options);
 
         let requested_attrs: HashSet<String> =
             proof_req.requested_attributes
@@ -122,10 +136,27 @@ impl VerifierCommandExecutor {
                                                             schemas,
                                                             cred_defs,
                                                             rev_reg_defs,
-                                                            rev_regs)?;
+                                                            rev_regs,
+                                                            options.strict_encoding)?;
 
         debug!("verify_proof <<< result: {:?}", result);
 
         Ok(result)
     }
-}
\ No newline at end of file
+
+    fn validate_proof_request(&self,
+                              proof_req: &ProofRequest,
+                              schemas: &HashMap<String, SchemaV1>,
+                              attr_types: &SchemaAttributeTypes) -> Result<String, IndyError> {
+        debug!("validate_proof_request >>> proof_req: {:?}, schemas: {:?}, attr_types: {:?}", proof_req, schemas, attr_types);
+
+        let problems = self.anoncreds_service.verifier.validate_proof_request(proof_req, schemas, attr_types)?;
+
+        let problems_json = serde_json::to_string(&problems)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize ProofRequestProblems: {:?}", err)))?;
+
+        debug!("validate_proof_request <<< problems_json: {:?}", problems_json);
+
+        Ok(problems_json)
+    }
+}
@@ -5,18 +5,34 @@ use std::collections::HashMap;
 
 use errors::common::CommonError;
 use errors::indy::IndyError;
-use domain::crypto::key::{KeyInfo, Key, KeyMetadata};
+use domain::crypto::key::{KeyInfo, Key, KeyMetadata, KeyWithMeta, RootSeed, ROOT_SEED_ID};
+use domain::wallet::Tags;
 use domain::crypto::combo_box::ComboBox;
+use domain::crypto::pack::*;
+use domain::crypto::jws::{JWSHeader, FlattenedJWS, JWSSerialization, JWSSignOptions, JWSVerifyOptions, JWS_ALG_EDDSA};
+use domain::crypto::did::{Did, TheirDid};
+use domain::crypto::export::{EncryptedExport, KeyExport};
+use domain::crypto::stream::StreamMetadata;
+use errors::wallet::WalletError;
+use utils::crypto::base58;
 use utils::crypto::base64;
-use services::wallet::{WalletService, RecordOptions};
-use services::crypto::CryptoService;
+use utils::sequence;
+use services::wallet::{WalletService, RecordOptions, SearchOptions, CREATED_AT_TAG};
+use services::crypto::{CryptoService, EncryptStream, DecryptStream, parse_stream_header, DEFAULT_CRYPTO_TYPE, ROOT_SEED_BYTES};
 
 use std::rc::Rc;
 use std::str;
 use std::result;
+use std::cell::RefCell;
 
 type Result<T> = result::Result<T, IndyError>;
 
+// JWE fields are base64url encoded without padding (RFC 7515), but padded fields
+// of messages packed by previous versions are accepted too
+fn _decode_jwe_field(field: &str) -> Result<Vec<u8>> {
+    Ok(base64::decode_urlsafe_nopad(field.trim_right_matches('='))?)
+}
+
 pub enum CryptoCommand {
     CreateKey(
         i32, // wallet handle
@@ -60,12 +76,106 @@ pub enum CryptoCommand {
         i32, // wallet handle
         String, // my vk
         Vec<u8>, // msg
-        Box<Fn(Result<Vec<u8>>) + Send>)
+        Box<Fn(Result<Vec<u8>>) + Send>),
+    PackMessage(
+        i32, // wallet handle
+        Vec<u8>, // message
+        Vec<String>, // receiver keys
+        Option<String>, // sender key
+        Box<Fn(Result<Vec<u8>>) + Send>),
+    UnpackMessage(
+        i32, // wallet handle
+        Vec<u8>, // jwe
+        Box<Fn(Result<Vec<u8>>) + Send>),
+    JwsSign(
+        i32, // wallet handle
+        String, // signer (my verkey or my did)
+        Vec<u8>, // payload
+        JWSSignOptions, // options
+        Box<Fn(Result<String>) + Send>),
+    JwsVerify(
+        i32, // wallet handle
+        String, // jws
+        Option<Vec<u8>>, // detached payload
+        JWSVerifyOptions, // options
+        Box<Fn(Result<bool>) + Send>),
+    ExportKey(
+        i32, // wallet handle
+        String, // verkey
+        String, // passphrase
+        Box<Fn(Result<String>) + Send>),
+    ImportKey(
+        i32, // wallet handle
+        EncryptedExport, // exported key
+        String, // passphrase
+        Box<Fn(Result<String>) + Send>),
+    CreateRootSeed(
+        i32, // wallet handle
+        Option<String>, // mnemonic
+        Box<Fn(Result<()>) + Send>),
+    ExportRootSeed(
+        i32, // wallet handle
+        String, // passphrase
+        Box<Fn(Result<String>) + Send>),
+    ImportRootSeed(
+        i32, // wallet handle
+        EncryptedExport, // exported root seed
+        String, // passphrase
+        Box<Fn(Result<()>) + Send>),
+    CreateDerivedKey(
+        i32, // wallet handle
+        String, // derivation path
+        Box<Fn(Result<String>) + Send>),
+    SetKeyTags(
+        i32, // wallet handle
+        String, // verkey
+        Tags, // tags
+        Box<Fn(Result<()>) + Send>),
+    SearchKeys(
+        i32, // wallet handle
+        String, // query json
+        Box<Fn(Result<String>) + Send>),
+    OpenEncryptStream(
+        i32, // wallet handle
+        Vec<String>, // receiver keys
+        Option<String>, // sender key
+        Box<Fn(Result<i32>) + Send>),
+    FeedEncryptStream(
+        i32, // stream handle
+        Vec<u8>, // data
+        Box<Fn(Result<Vec<u8>>) + Send>),
+    FinishEncryptStream(
+        i32, // stream handle
+        Box<Fn(Result<Vec<u8>>) + Send>),
+    CloseEncryptStream(
+        i32, // stream handle
+        Box<Fn(Result<()>) + Send>),
+    OpenDecryptStream(
+        i32, // wallet handle
+        Box<Fn(Result<i32>) + Send>),
+    FeedDecryptStream(
+        i32, // stream handle
+        Vec<u8>, // encrypted data
+        Box<Fn(Result<Vec<u8>>) + Send>),
+    FinishDecryptStream(
+        i32, // stream handle
+        Box<Fn(Result<(String, Vec<u8>)>) + Send>),
+    CloseDecryptStream(
+        i32, // stream handle
+        Box<Fn(Result<()>) + Send>)
+}
+
+enum DecryptStreamState {
+    // Collecting header, content key isn't known yet
+    Header(i32 /* wallet handle */, Vec<u8>),
+    Content(DecryptStream, StreamMetadata),
 }
 
 pub struct CryptoCommandExecutor {
     wallet_service: Rc<WalletService>,
     crypto_service: Rc<CryptoService>,
+    encrypt_streams: RefCell<HashMap<i32, EncryptStream>>,
+    decrypt_streams: RefCell<HashMap<i32, DecryptStreamState>>,
 }
 
 impl CryptoCommandExecutor {
@@ -75,6 +185,8 @@ impl CryptoCommandExecutor {
         CryptoCommandExecutor {
             wallet_service,
             crypto_service,
+            encrypt_streams: RefCell::new(HashMap::new()),
+            decrypt_streams: RefCell::new(HashMap::new()),
         }
     }
 
@@ -116,6 +228,86 @@ impl CryptoCommandExecutor {
                 info!("AnonymousDecrypt command received");
                 cb(self.anonymous_decrypt(wallet_handle, &my_vk, &encrypted_msg));
             }
+            CryptoCommand::PackMessage(wallet_handle, message, receiver_keys, sender_vk, cb) => {
+                info!("PackMessage command received");
+                cb(self.pack_message(wallet_handle, &message, &receiver_keys, sender_vk.as_ref().map(String::as_str)));
+            }
+            CryptoCommand::UnpackMessage(wallet_handle, jwe, cb) => {
+                info!("UnpackMessage command received");
+                cb(self.unpack_message(wallet_handle, &jwe));
+            }
+            CryptoCommand::JwsSign(wallet_handle, signer, payload, options, cb) => {
+                info!("JwsSign command received");
+                cb(self.jws_sign(wallet_handle, &signer, &payload, &options));
+            }
+            CryptoCommand::JwsVerify(wallet_handle, jws, payload, options, cb) => {
+                info!("JwsVerify command received");
+                cb(self.jws_verify(wallet_handle, &jws, payload.as_ref().map(Vec::as_slice), &options));
+            }
+            CryptoCommand::ExportKey(wallet_handle, verkey, passphrase, cb) => {
+                info!("ExportKey command received");
+                cb(self.export_key(wallet_handle, &verkey, &passphrase));
+            }
+            CryptoCommand::ImportKey(wallet_handle, exported_key, passphrase, cb) => {
+                info!("ImportKey command received");
+                cb(self.import_key(wallet_handle, &exported_key, &passphrase));
+            }
+            CryptoCommand::CreateRootSeed(wallet_handle, mnemonic, cb) => {
+                info!("CreateRootSeed command received");
+                cb(self.create_root_seed(wallet_handle, mnemonic.as_ref().map(String::as_str)));
+            }
+            CryptoCommand::ExportRootSeed(wallet_handle, passphrase, cb) => {
+                info!("ExportRootSeed command received");
+                cb(self.export_root_seed(wallet_handle, &passphrase));
+            }
+            CryptoCommand::ImportRootSeed(wallet_handle, exported_root_seed, passphrase, cb) => {
+                info!("ImportRootSeed command received");
+                cb(self.import_root_seed(wallet_handle, &exported_root_seed, &passphrase));
+            }
+            CryptoCommand::CreateDerivedKey(wallet_handle, path, cb) => {
+                info!("CreateDerivedKey command received");
+                cb(self.create_derived_key(wallet_handle, &path));
+            }
+            CryptoCommand::SetKeyTags(wallet_handle, verkey, tags, cb) => {
+                info!("SetKeyTags command received");
+                cb(self.set_key_tags(wallet_handle, &verkey, &tags));
+            }
+            CryptoCommand::SearchKeys(wallet_handle, query_json, cb) => {
+                info!("SearchKeys command received");
+                cb(self.search_keys(wallet_handle, &query_json));
+            }
+            CryptoCommand::OpenEncryptStream(wallet_handle, receiver_keys, sender_vk, cb) => {
+                info!("OpenEncryptStream command received");
+                cb(self.open_encrypt_stream(wallet_handle, &receiver_keys, sender_vk.as_ref().map(String::as_str)));
+            }
+            CryptoCommand::FeedEncryptStream(stream_handle, data, cb) => {
+                info!("FeedEncryptStream command received");
+                cb(self.feed_encrypt_stream(stream_handle, &data));
+            }
+            CryptoCommand::FinishEncryptStream(stream_handle, cb) => {
+                info!("FinishEncryptStream command received");
+                cb(self.finish_encrypt_stream(stream_handle));
+            }
+            CryptoCommand::CloseEncryptStream(stream_handle, cb) => {
+                info!("CloseEncryptStream command received");
+                cb(self.close_encrypt_stream(stream_handle));
+            }
+            CryptoCommand::OpenDecryptStream(wallet_handle, cb) => {
+                info!("OpenDecryptStream command received");
+                cb(self.open_decrypt_stream(wallet_handle));
+            }
+            CryptoCommand::FeedDecryptStream(stream_handle, data, cb) => {
+                info!("FeedDecryptStream command received");
+                cb(self.feed_decrypt_stream(stream_handle, &data));
+            }
+            CryptoCommand::FinishDecryptStream(stream_handle, cb) => {
+                info!("FinishDecryptStream command received");
+                cb(self.finish_decrypt_stream(stream_handle));
+            }
+            CryptoCommand::CloseDecryptStream(stream_handle, cb) => {
+                info!("CloseDecryptStream command received");
+                cb(self.close_decrypt_stream(stream_handle));
+            }
         };
     }
 
@@ -123,7 +315,7 @@ impl CryptoCommandExecutor {
         debug!("create_key >>> wallet_handle: {:?}, key_info: {:?}", wallet_handle, secret!(key_info));
 
         let key = self.crypto_service.create_key(key_info)?;
-        self.wallet_service.add_indy_object(wallet_handle, &key.verkey, &key, &HashMap::new())?;
+        self.wallet_service.add_indy_object(wallet_handle, &key.verkey, &key, &WalletService::creation_tags())?;
 
         let res = key.verkey;
         debug!("create_key <<< res: {:?}", res);
@@ -247,6 +439,356 @@ impl CryptoCommandExecutor {
         Ok(res)
     }
 
+    fn pack_message(&self,
+                    wallet_handle: i32,
+                    message: &[u8],
+                    receiver_keys: &[String],
+                    sender_vk: Option<&str>) -> Result<Vec<u8>> {
+        debug!("pack_message >>> wallet_handle: {:?}, message: {:?}, receiver_keys: {:?}, sender_vk: {:?}", wallet_handle, message, receiver_keys, sender_vk);
+
+        let cek = self.crypto_service.gen_content_key();
+
+        let (alg, recipients) = self._prepare_recipients(wallet_handle, receiver_keys, sender_vk, &cek[..])?;
+
+        let protected = Protected {
+            enc: PROTECTED_HEADER_ENC.to_string(),
+            typ: PROTECTED_HEADER_TYP.to_string(),
+            alg: alg.to_string(),
+            recipients
+        };
+
+        let protected = serde_json::to_string(&protected)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize protected header: {:?}", err)))?;
+
+        let protected = base64::encode_urlsafe_nopad(protected.as_bytes());
+
+        let (ciphertext, iv, tag) = self.crypto_service.encrypt_content(&cek, message, protected.as_bytes());
+
+        let jwe = JWE {
+            protected,
+            iv: base64::encode_urlsafe_nopad(&iv),
+            ciphertext: base64::encode_urlsafe_nopad(&ciphertext),
+            tag: base64::encode_urlsafe_nopad(&tag)
+        };
+
+        let res = serde_json::to_vec(&jwe)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize JWE: {:?}", err)))?;
+
+        debug!("pack_message <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn unpack_message(&self,
+                      wallet_handle: i32,
+                      jwe: &[u8]) -> Result<Vec<u8>> {
+        debug!("unpack_message >>> wallet_handle: {:?}, jwe: {:?}", wallet_handle, jwe);
+
+        let jwe: JWE = serde_json::from_slice(jwe)
+            .map_err(|err| CommonError::InvalidStructure(format!("Can't deserialize JWE: {:?}", err)))?;
+
+        let protected: Protected = serde_json::from_slice(&_decode_jwe_field(&jwe.protected)?)
+            .map_err(|err| CommonError::InvalidStructure(format!("Can't deserialize protected header: {:?}", err)))?;
+
+        if protected.enc != PROTECTED_HEADER_ENC {
+            return Err(IndyError::CommonError(CommonError::InvalidStructure(format!("Unsupported content encryption: {}", protected.enc))));
+        }
+
+        let (cek, recipient_verkey, sender_verkey) = self._unwrap_content_key(wallet_handle, &protected.alg, &protected.recipients)?;
+
+        let message = self.crypto_service.decrypt_content(&cek,
+                                                          &_decode_jwe_field(&jwe.ciphertext)?,
+                                                          &_decode_jwe_field(&jwe.iv)?,
+                                                          &_decode_jwe_field(&jwe.tag)?,
+                                                          jwe.protected.as_bytes())?;
+
+        // Message can be any binary data, so it's returned base64url encoded like other envelope fields
+        let unpack_message = UnpackMessage {
+            message: base64::encode_urlsafe_nopad(&message),
+            recipient_verkey,
+            sender_verkey
+        };
+
+        let res = serde_json::to_vec(&unpack_message)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize unpacked message: {:?}", err)))?;
+
+        debug!("unpack_message <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn _prepare_recipients(&self, wallet_handle: i32, receiver_keys: &[String], sender_vk: Option<&str>, cek: &[u8]) -> Result<(&'static str, Vec<Recipient>)> {
+        if receiver_keys.is_empty() {
+            return Err(IndyError::CommonError(CommonError::InvalidStructure("No receiver keys given".to_string())));
+        }
+
+        for receiver_key in receiver_keys {
+            self.crypto_service.validate_key(receiver_key)?;
+        }
+
+        match sender_vk {
+            Some(sender_vk) => {
+                self.crypto_service.validate_key(sender_vk)?;
+
+                let sender_key: Key = self.wallet_service.get_indy_object(wallet_handle, sender_vk, &RecordOptions::id_value())?;
+
+                Ok((PROTECTED_HEADER_ALG_AUTH, self._prepare_auth_recipients(&sender_key, receiver_keys, cek)?))
+            }
+            None => Ok((PROTECTED_HEADER_ALG_ANON, self._prepare_anon_recipients(receiver_keys, cek)?))
+        }
+    }
+
+    fn _prepare_auth_recipients(&self, sender_key: &Key, receiver_keys: &[String], cek: &[u8]) -> Result<Vec<Recipient>> {
+        let mut recipients: Vec<Recipient> = Vec::new();
+
+        for their_vk in receiver_keys {
+            let (encrypted_key, iv) = self.crypto_service.encrypt(sender_key, their_vk, cek)?;
+
+            // Sender verkey is sealed for every recipient, so it isn't visible to anyone else
+            let sender = self.crypto_service.encrypt_sealed(their_vk, sender_key.verkey.as_bytes())?;
+
+            recipients.push(Recipient {
+                encrypted_key: base64::encode_urlsafe_nopad(&encrypted_key),
+                header: Header {
+                    kid: their_vk.to_string(),
+                    iv: Some(base64::encode_urlsafe_nopad(&iv)),
+                    sender: Some(base64::encode_urlsafe_nopad(&sender))
+                }
+            });
+        }
+
+        Ok(recipients)
+    }
+
+    fn _prepare_anon_recipients(&self, receiver_keys: &[String], cek: &[u8]) -> Result<Vec<Recipient>> {
+        let mut recipients: Vec<Recipient> = Vec::new();
+
+        for their_vk in receiver_keys {
+            let encrypted_key = self.crypto_service.encrypt_sealed(their_vk, cek)?;
+
+            recipients.push(Recipient {
+                encrypted_key: base64::encode_urlsafe_nopad(&encrypted_key),
+                header: Header {
+                    kid: their_vk.to_string(),
+                    iv: None,
+                    sender: None
+                }
+            });
+        }
+
+        Ok(recipients)
+    }
+
+    fn _unwrap_content_key(&self, wallet_handle: i32, alg: &str, recipients: &[Recipient]) -> Result<(Vec<u8>, String, Option<String>)> {
+        let (recipient, my_key) = self._find_my_recipient(wallet_handle, recipients)?;
+
+        let encrypted_key = _decode_jwe_field(&recipient.encrypted_key)?;
+
+        let (cek, sender_verkey) = match alg {
+            PROTECTED_HEADER_ALG_AUTH => {
+                let sender = recipient.header.sender.as_ref()
+                    .ok_or(CommonError::InvalidStructure("Sender is not specified for Authcrypt recipient".to_string()))?;
+
+                let iv = recipient.header.iv.as_ref()
+                    .ok_or(CommonError::InvalidStructure("IV is not specified for Authcrypt recipient".to_string()))?;
+
+                let sender_vk = self.crypto_service.decrypt_sealed(&my_key, &_decode_jwe_field(sender)?)?;
+
+                let sender_vk = String::from_utf8(sender_vk)
+                    .map_err(|err| CommonError::InvalidStructure(format!("Can't decode sender verkey: {:?}", err)))?;
+
+                self.crypto_service.validate_key(&sender_vk)?;
+
+                let cek = self.crypto_service.decrypt(&my_key, &sender_vk, &encrypted_key, &_decode_jwe_field(iv)?)?;
+
+                (cek, Some(sender_vk))
+            }
+            PROTECTED_HEADER_ALG_ANON => (self.crypto_service.decrypt_sealed(&my_key, &encrypted_key)?, None),
+            alg => return Err(IndyError::CommonError(CommonError::InvalidStructure(format!("Unsupported key wrapping algorithm: {}", alg))))
+        };
+
+        Ok((cek, recipient.header.kid, sender_verkey))
+    }
+
+    fn _find_my_recipient(&self, wallet_handle: i32, recipients: &[Recipient]) -> Result<(Recipient, Key)> {
+        for recipient in recipients {
+            if self.wallet_service.record_exists::<Key>(wallet_handle, &recipient.header.kid)? {
+                let my_key: Key = self.wallet_service.get_indy_object(wallet_handle, &recipient.header.kid, &RecordOptions::id_value())?;
+                return Ok((recipient.clone(), my_key));
+            }
+        }
+
+        Err(IndyError::WalletError(WalletError::ItemNotFound))
+    }
+
+    fn jws_sign(&self,
+                wallet_handle: i32,
+                signer: &str,
+                payload: &[u8],
+                options: &JWSSignOptions) -> Result<String> {
+        debug!("jws_sign >>> wallet_handle: {:?}, signer: {:?}, payload: {:?}, options: {:?}", wallet_handle, signer, payload, options);
+
+        let (key, kid) = self._resolve_jws_signer(wallet_handle, signer)?;
+
+        let header = JWSHeader {
+            alg: JWS_ALG_EDDSA.to_string(),
+            kid: Some(kid),
+            typ: options.typ.clone()
+        };
+
+        let header = serde_json::to_string(&header)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize JWS header: {:?}", err)))?;
+
+        let protected = base64::encode_urlsafe_nopad(header.as_bytes());
+        let payload = base64::encode_urlsafe_nopad(payload);
+
+        let signing_input = format!("{}.{}", protected, payload);
+        let signature = self.crypto_service.sign(&key, signing_input.as_bytes())?;
+        let signature = base64::encode_urlsafe_nopad(&signature);
+
+        let payload = if options.detached { None } else { Some(payload) };
+
+        let res = match options.serialization {
+            JWSSerialization::Compact =>
+                format!("{}.{}.{}", protected, payload.unwrap_or_default(), signature),
+            JWSSerialization::Json =>
+                serde_json::to_string(&FlattenedJWS { payload, protected, signature })
+                    .map_err(|err| CommonError::InvalidState(format!("Can't serialize JWS: {:?}", err)))?
+        };
+
+        debug!("jws_sign <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn jws_verify(&self,
+                  wallet_handle: i32,
+                  jws: &str,
+                  detached_payload: Option<&[u8]>,
+                  options: &JWSVerifyOptions) -> Result<bool> {
+        debug!("jws_verify >>> wallet_handle: {:?}, jws: {:?}, detached_payload: {:?}, options: {:?}", wallet_handle, jws, detached_payload, options);
+
+        let jws = self._parse_jws(jws)?;
+
+        let payload = match (jws.payload, detached_payload) {
+            (Some(payload), None) => payload,
+            (None, Some(payload)) => base64::encode_urlsafe_nopad(payload),
+            (Some(_), Some(_)) =>
+                return Err(IndyError::CommonError(CommonError::InvalidStructure("JWS contains payload, but detached payload is also given".to_string()))),
+            (None, None) =>
+                return Err(IndyError::CommonError(CommonError::InvalidStructure("JWS payload is detached, but detached payload isn't given".to_string())))
+        };
+
+        let header: JWSHeader = serde_json::from_slice(&base64::decode_urlsafe_nopad(&jws.protected)?)
+            .map_err(|err| CommonError::InvalidStructure(format!("Can't deserialize JWS header: {:?}", err)))?;
+
+        if header.alg != JWS_ALG_EDDSA {
+            return Err(IndyError::CommonError(CommonError::InvalidStructure(format!("Unsupported JWS algorithm: {}", header.alg))));
+        }
+
+        let kid = header.kid
+            .ok_or(CommonError::InvalidStructure("JWS header doesn't contain kid".to_string()))?;
+
+        let verkey = self._resolve_jws_verkey(wallet_handle, &kid, options.allow_verkey_kid)?;
+
+        self._check_jws_key_type(&verkey)?;
+
+        if let Some(ref signer) = options.signer {
+            if signer != &kid && signer != &verkey {
+                debug!("jws_verify <<< res: false, JWS is signed by {:?}, but expected signer is {:?}", kid, signer);
+                return Ok(false);
+            }
+        }
+
+        let signature = base64::decode_urlsafe_nopad(&jws.signature)?;
+        let signing_input = format!("{}.{}", jws.protected, payload);
+
+        let res = self.crypto_service.verify(&verkey, signing_input.as_bytes(), &signature)?;
+
+        debug!("jws_verify <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn _parse_jws(&self, jws: &str) -> Result<FlattenedJWS> {
+        let jws = jws.trim();
+
+        if jws.starts_with('{') {
+            return serde_json::from_str(jws)
+                .map_err(|err| IndyError::CommonError(CommonError::InvalidStructure(format!("Can't deserialize JWS: {:?}", err))));
+        }
+
+        let parts: Vec<&str> = jws.split('.').collect();
+
+        if parts.len() != 3 {
+            return Err(IndyError::CommonError(CommonError::InvalidStructure("Compact JWS must consist of 3 parts".to_string())));
+        }
+
+        Ok(FlattenedJWS {
+            payload: if parts[1].is_empty() { None } else { Some(parts[1].to_string()) },
+            protected: parts[0].to_string(),
+            signature: parts[2].to_string()
+        })
+    }
+
+    // Signer can be referenced by my DID or by verkey of my key. The signer reference itself is used as kid,
+    // so key doesn't need to belong to any of my DIDs.
+    fn _resolve_jws_signer(&self, wallet_handle: i32, signer: &str) -> Result<(Key, String)> {
+        if self.crypto_service.validate_key(signer).is_ok() && self.wallet_service.record_exists::<Key>(wallet_handle, signer)? {
+            self._check_jws_key_type(signer)?;
+
+            let key: Key = self.wallet_service.get_indy_object(wallet_handle, signer, &RecordOptions::id_value())?;
+
+            return Ok((key, signer.to_string()));
+        }
+
+        self.crypto_service.validate_did(signer)?;
+
+        let my_did: Did = self.wallet_service.get_indy_object(wallet_handle, signer, &RecordOptions::id_value())?;
+
+        self._check_jws_key_type(&my_did.verkey)?;
+
+        let key: Key = self.wallet_service.get_indy_object(wallet_handle, &my_did.verkey, &RecordOptions::id_value())?;
+
+        Ok((key, my_did.did))
+    }
+
+    // kid must be DID known to the wallet (my or their) or verkey of my key. Other verkey is accepted as kid only if caller allows it.
+    fn _resolve_jws_verkey(&self, wallet_handle: i32, kid: &str, allow_verkey_kid: bool) -> Result<String> {
+        if self.crypto_service.validate_did(kid).is_ok() {
+            match self.wallet_service.get_indy_object::<Did>(wallet_handle, kid, &RecordOptions::id_value()) {
+                Ok(did) => return Ok(did.verkey),
+                Err(WalletError::ItemNotFound) => {}
+                Err(err) => return Err(IndyError::from(err))
+            };
+
+            match self.wallet_service.get_indy_object::<TheirDid>(wallet_handle, kid, &RecordOptions::id_value()) {
+                Ok(did) => return Ok(did.verkey),
+                Err(WalletError::ItemNotFound) => {}
+                Err(err) => return Err(IndyError::from(err))
+            };
+        }
+
+        if self.crypto_service.validate_key(kid).is_err() {
+            return Err(IndyError::WalletError(WalletError::ItemNotFound));
+        }
+
+        if !allow_verkey_kid && !self.wallet_service.record_exists::<Key>(wallet_handle, kid)? {
+            return Err(IndyError::WalletError(WalletError::ItemNotFound));
+        }
+
+        Ok(kid.to_string())
+    }
+
+    fn _check_jws_key_type(&self, verkey: &str) -> Result<()> {
+        match verkey.split(':').nth(1) {
+            Some(crypto_type) if crypto_type != DEFAULT_CRYPTO_TYPE =>
+                Err(IndyError::CommonError(CommonError::InvalidStructure(
+                    format!("JWS can be created only with {} keys (EdDSA), but key has crypto type: {}", DEFAULT_CRYPTO_TYPE, crypto_type)))),
+            _ => Ok(())
+        }
+    }
+
     fn set_key_metadata(&self, wallet_handle: i32, verkey: &str, metadata: &str) -> Result<()> {
         debug!("set_key_metadata >>> wallet_handle: {:?}, verkey: {:?}, metadata: {:?}", wallet_handle, verkey, metadata);
 
@@ -276,4 +818,332 @@ impl CryptoCommandExecutor {
 
         Ok(res)
     }
+
+    fn set_key_tags(&self, wallet_handle: i32, verkey: &str, tags: &Tags) -> Result<()> {
+        debug!("set_key_tags >>> wallet_handle: {:?}, verkey: {:?}, tags: {:?}", wallet_handle, verkey, tags);
+
+        self.crypto_service.validate_key(verkey)?;
+
+        self.wallet_service.update_indy_record_user_tags::<Key>(wallet_handle, verkey, tags, &[CREATED_AT_TAG])?;
+
+        debug!("set_key_tags <<<");
+
+        Ok(())
+    }
+
+    fn search_keys(&self, wallet_handle: i32, query_json: &str) -> Result<String> {
+        debug!("search_keys >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+        let mut key_search =
+            self.wallet_service.search_indy_records::<Key>(wallet_handle, query_json, &SearchOptions::id_tags())?;
+
+        let mut keys: Vec<KeyWithMeta> = Vec::new();
+
+        while let Some(key_record) = key_search.fetch_next_record()? {
+            // Record value contains signkey, so only record id (verkey) is returned
+            let verkey = key_record.get_id().to_string();
+
+            let metadata = self.wallet_service.get_indy_opt_object::<KeyMetadata>(wallet_handle, &verkey, &RecordOptions::id_value())?;
+
+            keys.push(KeyWithMeta {
+                verkey,
+                metadata: metadata.map(|m| m.value),
+                tags: key_record.get_tags().cloned().unwrap_or_default()
+            });
+        }
+
+        let res = serde_json::to_string(&keys)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize keys list {}", err)))?;
+
+        debug!("search_keys <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn export_key(&self,
+                  wallet_handle: i32,
+                  verkey: &str,
+                  passphrase: &str) -> Result<String> {
+        debug!("export_key >>> wallet_handle: {:?}, verkey: {:?}, passphrase: {:?}", wallet_handle, verkey, secret!(passphrase));
+
+        self.crypto_service.validate_key(verkey)?;
+
+        let key: Key = self.wallet_service.get_indy_object(wallet_handle, verkey, &RecordOptions::id_value())?;
+
+        let metadata = self.wallet_service.get_indy_opt_object::<KeyMetadata>(wallet_handle, verkey, &RecordOptions::id_value())?
+            .map(|metadata| metadata.value);
+
+        let exported_key = self.crypto_service.encrypt_export(&KeyExport { key, metadata }, passphrase)?;
+
+        let res = serde_json::to_string(&exported_key)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize exported key: {:?}", err)))?;
+
+        debug!("export_key <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn import_key(&self,
+                  wallet_handle: i32,
+                  exported_key: &EncryptedExport,
+                  passphrase: &str) -> Result<String> {
+        debug!("import_key >>> wallet_handle: {:?}, exported_key: {:?}, passphrase: {:?}", wallet_handle, exported_key, secret!(passphrase));
+
+        let key_export: KeyExport = self.crypto_service.decrypt_export(exported_key, passphrase)?;
+
+        self.crypto_service.validate_key_pair(&key_export.key)?;
+
+        if self.wallet_service.record_exists::<Key>(wallet_handle, &key_export.key.verkey)? {
+            return Err(IndyError::WalletError(WalletError::ItemAlreadyExists));
+        }
+
+        // Metadata goes first, so import interrupted halfway can be repeated
+        if let Some(metadata) = key_export.metadata {
+            self.wallet_service.upsert_indy_object(wallet_handle, &key_export.key.verkey, &KeyMetadata { value: metadata })?;
+        }
+
+        self.wallet_service.add_indy_object(wallet_handle, &key_export.key.verkey, &key_export.key, &WalletService::creation_tags())?;
+
+        let res = key_export.key.verkey;
+
+        debug!("import_key <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn create_root_seed(&self,
+                        wallet_handle: i32,
+                        mnemonic: Option<&str>) -> Result<()> {
+        debug!("create_root_seed >>> wallet_handle: {:?}, mnemonic: {:?}", wallet_handle, secret!(&mnemonic));
+
+        let root_seed = self.crypto_service.gen_root_seed(mnemonic)?;
+        let root_seed = RootSeed { value: base58::encode(&root_seed) };
+
+        // Root seed is never replaced, otherwise all derived keys become unrecoverable
+        self.wallet_service.add_indy_object(wallet_handle, ROOT_SEED_ID, &root_seed, &HashMap::new())?;
+
+        debug!("create_root_seed <<<");
+
+        Ok(())
+    }
+
+    fn export_root_seed(&self,
+                        wallet_handle: i32,
+                        passphrase: &str) -> Result<String> {
+        debug!("export_root_seed >>> wallet_handle: {:?}, passphrase: {:?}", wallet_handle, secret!(passphrase));
+
+        let root_seed: RootSeed = self.wallet_service.get_indy_object(wallet_handle, ROOT_SEED_ID, &RecordOptions::id_value())?;
+
+        let exported_root_seed = self.crypto_service.encrypt_export(&root_seed, passphrase)?;
+
+        let res = serde_json::to_string(&exported_root_seed)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize exported root seed: {:?}", err)))?;
+
+        debug!("export_root_seed <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn import_root_seed(&self,
+                        wallet_handle: i32,
+                        exported_root_seed: &EncryptedExport,
+                        passphrase: &str) -> Result<()> {
+        debug!("import_root_seed >>> wallet_handle: {:?}, exported_root_seed: {:?}, passphrase: {:?}", wallet_handle, exported_root_seed, secret!(passphrase));
+
+        let root_seed: RootSeed = self.crypto_service.decrypt_export(exported_root_seed, passphrase)?;
+
+        let root_seed_bytes = base58::decode(&root_seed.value)?;
+
+        if root_seed_bytes.len() != ROOT_SEED_BYTES {
+            return Err(IndyError::CommonError(CommonError::InvalidStructure(format!("Invalid root seed length: {}", root_seed_bytes.len()))));
+        }
+
+        // As on creation, root seed is never replaced
+        self.wallet_service.add_indy_object(wallet_handle, ROOT_SEED_ID, &root_seed, &HashMap::new())?;
+
+        debug!("import_root_seed <<<");
+
+        Ok(())
+    }
+
+    fn create_derived_key(&self,
+                          wallet_handle: i32,
+                          path: &str) -> Result<String> {
+        debug!("create_derived_key >>> wallet_handle: {:?}, path: {:?}", wallet_handle, path);
+
+        let root_seed: RootSeed = self.wallet_service.get_indy_object(wallet_handle, ROOT_SEED_ID, &RecordOptions::id_value())?;
+        let seed = self.crypto_service.derive_seed(&base58::decode(&root_seed.value)?, path)?;
+
+        let key_info = KeyInfo { seed: Some(base64::encode(&seed)), crypto_type: None };
+
+        let key = self.crypto_service.create_key(&key_info)?;
+        self.wallet_service.add_indy_object(wallet_handle, &key.verkey, &key, &WalletService::creation_tags())?;
+
+        let res = key.verkey;
+
+        debug!("create_derived_key <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn open_encrypt_stream(&self,
+                           wallet_handle: i32,
+                           receiver_keys: &[String],
+                           sender_vk: Option<&str>) -> Result<i32> {
+        debug!("open_encrypt_stream >>> wallet_handle: {:?}, receiver_keys: {:?}, sender_vk: {:?}", wallet_handle, receiver_keys, sender_vk);
+
+        let cek = self.crypto_service.gen_content_key();
+
+        let (alg, recipients) = self._prepare_recipients(wallet_handle, receiver_keys, sender_vk, &cek[..])?;
+
+        let header = self.crypto_service.create_stream_header(alg, recipients);
+        let stream = EncryptStream::new(&header, cek)?;
+
+        let stream_handle = sequence::get_next_id();
+        self.encrypt_streams.borrow_mut().insert(stream_handle, stream);
+
+        debug!("open_encrypt_stream <<< stream_handle: {:?}", stream_handle);
+
+        Ok(stream_handle)
+    }
+
+    fn feed_encrypt_stream(&self,
+                           stream_handle: i32,
+                           data: &[u8]) -> Result<Vec<u8>> {
+        debug!("feed_encrypt_stream >>> stream_handle: {:?}, data len: {:?}", stream_handle, data.len());
+
+        let mut encrypt_streams = self.encrypt_streams.borrow_mut();
+        let stream = encrypt_streams.get_mut(&stream_handle)
+            .ok_or(CommonError::InvalidStructure(format!("Unknown EncryptStream handle: {}", stream_handle)))?;
+
+        let res = stream.update(data)?;
+
+        debug!("feed_encrypt_stream <<< res len: {:?}", res.len());
+
+        Ok(res)
+    }
+
+    fn finish_encrypt_stream(&self,
+                             stream_handle: i32) -> Result<Vec<u8>> {
+        debug!("finish_encrypt_stream >>> stream_handle: {:?}", stream_handle);
+
+        let stream = self.encrypt_streams.borrow_mut().remove(&stream_handle)
+            .ok_or(CommonError::InvalidStructure(format!("Unknown EncryptStream handle: {}", stream_handle)))?;
+
+        let res = stream.finish()?;
+
+        debug!("finish_encrypt_stream <<< res len: {:?}", res.len());
+
+        Ok(res)
+    }
+
+    fn close_encrypt_stream(&self, stream_handle: i32) -> Result<()> {
+        debug!("close_encrypt_stream >>> stream_handle: {:?}", stream_handle);
+
+        self.encrypt_streams.borrow_mut().remove(&stream_handle)
+            .ok_or(CommonError::InvalidStructure(format!("Unknown EncryptStream handle: {}", stream_handle)))?;
+
+        debug!("close_encrypt_stream <<<");
+
+        Ok(())
+    }
+
+    fn open_decrypt_stream(&self,
+                           wallet_handle: i32) -> Result<i32> {
+        debug!("open_decrypt_stream >>> wallet_handle: {:?}", wallet_handle);
+
+        let stream_handle = sequence::get_next_id();
+        self.decrypt_streams.borrow_mut().insert(stream_handle, DecryptStreamState::Header(wallet_handle, Vec::new()));
+
+        debug!("open_decrypt_stream <<< stream_handle: {:?}", stream_handle);
+
+        Ok(stream_handle)
+    }
+
+    fn feed_decrypt_stream(&self,
+                           stream_handle: i32,
+                           data: &[u8]) -> Result<Vec<u8>> {
+        debug!("feed_decrypt_stream >>> stream_handle: {:?}, data len: {:?}", stream_handle, data.len());
+
+        // Stream is dropped on any error as it can't be continued consistently
+        let state = self.decrypt_streams.borrow_mut().remove(&stream_handle)
+            .ok_or(CommonError::InvalidStructure(format!("Unknown DecryptStream handle: {}", stream_handle)))?;
+
+        let (state, res) = match state {
+            DecryptStreamState::Header(wallet_handle, mut buffer) => {
+                buffer.extend_from_slice(data);
+
+                match self._start_decrypt_stream(wallet_handle, &buffer)? {
+                    Some((mut stream, metadata)) => {
+                        let res = stream.update(&[])?;
+                        (DecryptStreamState::Content(stream, metadata), res)
+                    }
+                    None => (DecryptStreamState::Header(wallet_handle, buffer), Vec::new())
+                }
+            }
+            DecryptStreamState::Content(mut stream, metadata) => {
+                let res = stream.update(data)?;
+                (DecryptStreamState::Content(stream, metadata), res)
+            }
+        };
+
+        self.decrypt_streams.borrow_mut().insert(stream_handle, state);
+
+        debug!("feed_decrypt_stream <<< res len: {:?}", res.len());
+
+        Ok(res)
+    }
+
+    fn finish_decrypt_stream(&self,
+                             stream_handle: i32) -> Result<(String, Vec<u8>)> {
+        debug!("finish_decrypt_stream >>> stream_handle: {:?}", stream_handle);
+
+        let state = self.decrypt_streams.borrow_mut().remove(&stream_handle)
+            .ok_or(CommonError::InvalidStructure(format!("Unknown DecryptStream handle: {}", stream_handle)))?;
+
+        let (stream, metadata) = match state {
+            DecryptStreamState::Content(stream, metadata) => (stream, metadata),
+            DecryptStreamState::Header(..) =>
+                return Err(IndyError::CommonError(CommonError::InvalidStructure("Stream is truncated: header is incomplete".to_string())))
+        };
+
+        let data = stream.finish()?;
+
+        let metadata = serde_json::to_string(&metadata)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize stream metadata: {:?}", err)))?;
+
+        let res = (metadata, data);
+
+        debug!("finish_decrypt_stream <<< metadata: {:?}, data len: {:?}", res.0, res.1.len());
+
+        Ok(res)
+    }
+
+    fn close_decrypt_stream(&self, stream_handle: i32) -> Result<()> {
+        debug!("close_decrypt_stream >>> stream_handle: {:?}", stream_handle);
+
+        self.decrypt_streams.borrow_mut().remove(&stream_handle)
+            .ok_or(CommonError::InvalidStructure(format!("Unknown DecryptStream handle: {}", stream_handle)))?;
+
+        debug!("close_decrypt_stream <<<");
+
+        Ok(())
+    }
+
+    fn _start_decrypt_stream(&self, wallet_handle: i32, buffer: &[u8]) -> Result<Option<(DecryptStream, StreamMetadata)>> {
+        let header = match parse_stream_header(buffer)? {
+            Some((header, _)) => header,
+            None => return Ok(None)
+        };
+
+        if header.enc != PROTECTED_HEADER_ENC {
+            return Err(IndyError::CommonError(CommonError::InvalidStructure(format!("Unsupported content encryption: {}", header.enc))));
+        }
+
+        let (cek, recipient_verkey, sender_verkey) = self._unwrap_content_key(wallet_handle, &header.alg, &header.recipients)?;
+
+        let (stream, _) = DecryptStream::new(buffer, &cek)?;
+
+        Ok(Some((stream, StreamMetadata { recipient_verkey, sender_verkey })))
+    }
 }
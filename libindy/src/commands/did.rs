@@ -2,12 +2,15 @@ use errors::common::CommonError;
 use errors::did::DidError;
 use errors::wallet::WalletError;
 use errors::indy::IndyError;
-use domain::crypto::key::KeyInfo;
+use errors::ledger::LedgerError;
+use domain::crypto::key::{KeyInfo, Key, KeyMetadata, RootSeed, ROOT_SEED_ID};
+use domain::crypto::export::{EncryptedExport, KeyExport, DidExport};
 use domain::crypto::did::{MyDidInfo, Did, TheirDidInfo, TheirDid, TemporaryDid, DidWithMeta, DidMetadata};
-use domain::ledger::response::Reply;
+use domain::ledger::response::{Reply, Message};
+use domain::wallet::Tags;
 use domain::ledger::nym::{GetNymReplyResult, GetNymResultDataV0};
 use domain::ledger::attrib::{GetAttrReplyResult, AttribData, Endpoint};
-use services::wallet::{WalletService, RecordOptions, SearchOptions};
+use services::wallet::{WalletService, RecordOptions, SearchOptions, CREATED_AT_TAG};
 use services::crypto::CryptoService;
 use services::ledger::LedgerService;
 
@@ -16,12 +19,19 @@ use std::error::Error;
 use std::rc::Rc;
 use std::str;
 use std::cell::RefCell;
+use std::thread;
+use std::time::Duration;
 
 use commands::ledger::LedgerCommand;
 use commands::{Command, CommandExecutor};
 use std::collections::HashMap;
 use utils::sequence;
 use utils::crypto::base58;
+use utils::crypto::base64;
+use utils::crypto::verkey_builder::build_full_verkey;
+
+const ROTATE_KEY_GET_NYM_ATTEMPTS: usize = 5;
+const ROTATE_KEY_GET_NYM_BACKOFF_MS: u64 = 500;
 
 pub enum DidCommand {
     CreateAndStoreMyDid(
@@ -81,6 +91,69 @@ pub enum DidCommand {
         String, // did
         String, // verkey
         Box<Fn(Result<String, IndyError>) + Send>),
+    ExportDid(
+        i32, // wallet handle
+        String, // did
+        String, // passphrase
+        Box<Fn(Result<String, IndyError>) + Send>),
+    ImportDid(
+        i32, // wallet handle
+        EncryptedExport, // exported did
+        String, // passphrase
+        Box<Fn(Result<String, IndyError>) + Send>),
+    CreateAndStoreDerivedMyDid(
+        i32, // wallet handle
+        String, // derivation path
+        Box<Fn(Result<(String, String), IndyError>) + Send>),
+    SetDidTags(
+        i32, // wallet handle
+        String, // my did
+        Tags, // tags
+        Box<Fn(Result<(), IndyError>) + Send>),
+    SearchMyDids(
+        i32, // wallet handle
+        String, // query json
+        Box<Fn(Result<String, IndyError>) + Send>),
+    RotateKey(
+        i32, // pool handle
+        i32, // wallet handle
+        String, // my did
+        KeyInfo, // key info
+        Box<Fn(Result<String, IndyError>) + Send>),
+    VerifyForDid(
+        i32, // pool handle
+        i32, // wallet handle
+        String, // did (my or their)
+        Vec<u8>, // message
+        Vec<u8>, // signature
+        bool, // require current
+        Box<Fn(Result<bool, IndyError>) + Send>),
+    // Internal commands
+    RotateKeyNymAck(
+        i32, // cmd handle
+        i32, // pool handle
+        i32, // wallet handle
+        String, // my did
+        String, // new verkey
+        Result<String, IndyError>, // Nym Result
+    ),
+    // Internal commands
+    RotateKeyGetNymAck(
+        i32, // cmd handle
+        i32, // pool handle
+        i32, // wallet handle
+        String, // my did
+        String, // new verkey
+        usize, // attempts left
+        Result<String, IndyError>, // GetNym Result
+    ),
+    // Internal commands
+    VerifyForDidGetNymAck(
+        i32, // cmd handle
+        i32, // wallet handle
+        String, // did
+        Result<String, IndyError>, // GetNym Result
+    ),
     // Internal commands
     GetNymAck(
         i32, // wallet_handle
@@ -111,6 +184,9 @@ pub struct DidCommandExecutor {
     crypto_service: Rc<CryptoService>,
     ledger_service: Rc<LedgerService>,
     deferred_commands: RefCell<HashMap<i32, DidCommand>>,
+    rotate_key_callbacks: RefCell<HashMap<i32, Box<Fn(Result<String, IndyError>) + Send>>>,
+    rotate_key_unconfirmed_nyms: RefCell<HashMap<i32, IndyError>>,
+    verify_for_did_requests: RefCell<HashMap<i32, (Vec<u8>, Vec<u8>, Box<Fn(Result<bool, IndyError>) + Send>)>>,
 }
 
 impl DidCommandExecutor {
@@ -122,6 +198,9 @@ impl DidCommandExecutor {
             crypto_service,
             ledger_service,
             deferred_commands: RefCell::new(HashMap::new()),
+            rotate_key_callbacks: RefCell::new(HashMap::new()),
+            rotate_key_unconfirmed_nyms: RefCell::new(HashMap::new()),
+            verify_for_did_requests: RefCell::new(HashMap::new()),
         }
     }
 
@@ -179,6 +258,46 @@ impl DidCommandExecutor {
                 info!("AbbreviateVerkey command received");
                 cb(self.abbreviate_verkey(&did, verkey));
             }
+            DidCommand::ExportDid(wallet_handle, did, passphrase, cb) => {
+                info!("ExportDid command received");
+                cb(self.export_did(wallet_handle, &did, &passphrase));
+            }
+            DidCommand::ImportDid(wallet_handle, exported_did, passphrase, cb) => {
+                info!("ImportDid command received");
+                cb(self.import_did(wallet_handle, &exported_did, &passphrase));
+            }
+            DidCommand::CreateAndStoreDerivedMyDid(wallet_handle, path, cb) => {
+                info!("CreateAndStoreDerivedMyDid command received");
+                cb(self.create_and_store_derived_my_did(wallet_handle, &path));
+            }
+            DidCommand::SetDidTags(wallet_handle, did, tags, cb) => {
+                info!("SetDidTags command received");
+                cb(self.set_did_tags(wallet_handle, &did, &tags));
+            }
+            DidCommand::SearchMyDids(wallet_handle, query_json, cb) => {
+                info!("SearchMyDids command received");
+                cb(self.search_my_dids(wallet_handle, &query_json));
+            }
+            DidCommand::RotateKey(pool_handle, wallet_handle, did, key_info, cb) => {
+                info!("RotateKey command received");
+                self.rotate_key(pool_handle, wallet_handle, did, &key_info, cb);
+            }
+            DidCommand::RotateKeyNymAck(cmd_handle, pool_handle, wallet_handle, did, verkey, result) => {
+                info!("RotateKeyNymAck command received");
+                self.rotate_key_nym_ack(cmd_handle, pool_handle, wallet_handle, did, verkey, result);
+            }
+            DidCommand::RotateKeyGetNymAck(cmd_handle, pool_handle, wallet_handle, did, verkey, attempts, result) => {
+                info!("RotateKeyGetNymAck command received");
+                self.rotate_key_get_nym_ack(cmd_handle, pool_handle, wallet_handle, did, verkey, attempts, result);
+            }
+            DidCommand::VerifyForDid(pool_handle, wallet_handle, did, msg, signature, require_current, cb) => {
+                info!("VerifyForDid command received");
+                self.verify_for_did(pool_handle, wallet_handle, did, msg, signature, require_current, cb);
+            }
+            DidCommand::VerifyForDidGetNymAck(cmd_handle, wallet_handle, did, result) => {
+                info!("VerifyForDidGetNymAck command received");
+                self.verify_for_did_get_nym_ack(cmd_handle, wallet_handle, did, result);
+            }
             DidCommand::GetNymAck(wallet_handle, result, deferred_cmd_id) => {
                 info!("GetNymAck command received");
                 self.get_nym_ack(wallet_handle, result, deferred_cmd_id);
@@ -201,8 +320,8 @@ impl DidCommandExecutor {
             return Err(IndyError::DidError(DidError::AlreadyExistsError(did.did)));
         };
 
-        self.wallet_service.add_indy_object(wallet_handle, &did.did, &did, &HashMap::new())?;
-        self.wallet_service.add_indy_object(wallet_handle, &key.verkey, &key, &HashMap::new())?;
+        self.wallet_service.add_indy_object(wallet_handle, &did.did, &did, &WalletService::creation_tags())?;
+        self.wallet_service.add_indy_object(wallet_handle, &key.verkey, &key, &WalletService::creation_tags())?;
 
         let res = (did.did, did.verkey);
 
@@ -211,6 +330,23 @@ impl DidCommandExecutor {
         Ok(res)
     }
 
+    fn create_and_store_derived_my_did(&self,
+                                       wallet_handle: i32,
+                                       path: &str) -> Result<(String, String), IndyError> {
+        debug!("create_and_store_derived_my_did >>> wallet_handle: {:?}, path: {:?}", wallet_handle, path);
+
+        let root_seed: RootSeed = self.wallet_service.get_indy_object(wallet_handle, ROOT_SEED_ID, &RecordOptions::id_value())?;
+        let seed = self.crypto_service.derive_seed(&base58::decode(&root_seed.value)?, path)?;
+
+        let my_did_info = MyDidInfo { did: None, seed: Some(base64::encode(&seed)), crypto_type: None, cid: None };
+
+        let res = self.create_and_store_my_did(wallet_handle, &my_did_info)?;
+
+        debug!("create_and_store_derived_my_did <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
     fn replace_keys_start(&self,
                           wallet_handle: i32,
                           key_info: &KeyInfo,
@@ -224,7 +360,7 @@ impl DidCommandExecutor {
         let temporary_key = self.crypto_service.create_key(&key_info)?;
         let my_temporary_did = TemporaryDid { did: my_did.did, verkey: temporary_key.verkey.clone() };
 
-        self.wallet_service.add_indy_object(wallet_handle, &temporary_key.verkey, &temporary_key, &HashMap::new())?;
+        self.wallet_service.add_indy_object(wallet_handle, &temporary_key.verkey, &temporary_key, &WalletService::creation_tags())?;
         self.wallet_service.add_indy_object(wallet_handle, &my_temporary_did.did, &my_temporary_did, &HashMap::new())?;
 
         let res = my_temporary_did.verkey;
@@ -280,7 +416,8 @@ impl DidCommandExecutor {
         let did_with_meta = DidWithMeta {
             did: did.did,
             verkey: did.verkey,
-            metadata: metadata.map(|m|m.value)
+            metadata: metadata.map(|m|m.value),
+            tags: None
         };
 
         let res = serde_json::to_string(&did_with_meta)
@@ -312,7 +449,8 @@ impl DidCommandExecutor {
             let did_with_meta = DidWithMeta {
                 did: did.did,
                 verkey: did.verkey,
-                metadata: metadata.map(|m|m.value)
+                metadata: metadata.map(|m|m.value),
+                tags: None
             };
 
             dids.push(did_with_meta);
@@ -327,6 +465,57 @@ impl DidCommandExecutor {
         Ok(res)
     }
 
+    fn set_did_tags(&self,
+                    wallet_handle: i32,
+                    my_did: &str,
+                    tags: &Tags) -> Result<(), IndyError> {
+        debug!("set_did_tags >>> wallet_handle: {:?}, my_did: {:?}, tags: {:?}", wallet_handle, my_did, tags);
+
+        self.crypto_service.validate_did(my_did)?;
+
+        self.wallet_service.update_indy_record_user_tags::<Did>(wallet_handle, my_did, tags, &[CREATED_AT_TAG])?;
+
+        debug!("set_did_tags <<<");
+
+        Ok(())
+    }
+
+    fn search_my_dids(&self, wallet_handle: i32, query_json: &str) -> Result<String, IndyError> {
+        debug!("search_my_dids >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+        let mut did_search =
+            self.wallet_service.search_indy_records::<Did>(wallet_handle, query_json, &SearchOptions::id_value_tags())?;
+
+        let mut dids: Vec<DidWithMeta> = Vec::new();
+
+        while let Some(did_record) = did_search.fetch_next_record()? {
+            let did_id = did_record.get_id();
+
+            let did: Did = did_record.get_value()
+                .and_then(|did_json| serde_json::from_str(&did_json).ok())
+                .ok_or(CommonError::InvalidStructure(format!("Cannot deserialize Did: {:?}", did_id)))?;
+
+            let metadata = self.wallet_service.get_indy_opt_object::<DidMetadata>(wallet_handle, &did.did, &RecordOptions::id_value())?;
+
+            let did_with_meta = DidWithMeta {
+                did: did.did,
+                verkey: did.verkey,
+                metadata: metadata.map(|m| m.value),
+                tags: Some(did_record.get_tags().cloned().unwrap_or_default())
+            };
+
+            dids.push(did_with_meta);
+        }
+
+        let res = serde_json::to_string(&dids)
+            .map_err(|err|
+                IndyError::CommonError(CommonError::InvalidState(format!("Can't serialize DIDs list {}", err))))?;
+
+        debug!("search_my_dids <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
     fn key_for_did(&self,
                    pool_handle: i32,
                    wallet_handle: i32,
@@ -502,7 +691,19 @@ impl DidCommandExecutor {
 
         let get_nym_reply = get_nym_reply_result?;
 
-        let get_nym_response: Reply<GetNymReplyResult> = serde_json::from_str(&get_nym_reply)
+        let their_did_info = self._parse_get_nym_reply(&get_nym_reply)?;
+
+        let their_did = self.crypto_service.create_their_did(&their_did_info)?;
+
+        self.wallet_service.add_indy_object(wallet_handle, &their_did.did, &their_did, &HashMap::new())?;
+
+        trace!("_get_nym_ack <<<");
+
+        Ok(())
+    }
+
+    fn _parse_get_nym_reply(&self, get_nym_reply: &str) -> Result<TheirDidInfo, IndyError> {
+        let get_nym_response: Reply<GetNymReplyResult> = serde_json::from_str(get_nym_reply)
             .map_err(map_err_trace!())
             .map_err(|err| CommonError::InvalidState(format!("Invalid GetNymReplyResult json: {:?}", err)))?;
 
@@ -521,13 +722,305 @@ impl DidCommandExecutor {
             GetNymReplyResult::GetNymReplyResultV1(res) => TheirDidInfo::new(res.txn.data.did, res.txn.data.verkey)
         };
 
-        let their_did = self.crypto_service.create_their_did(&their_did_info)?;
+        Ok(their_did_info)
+    }
 
-        self.wallet_service.add_indy_object(wallet_handle, &their_did.did, &their_did, &HashMap::new())?;
+    fn rotate_key(&self,
+                  pool_handle: i32,
+                  wallet_handle: i32,
+                  my_did: String,
+                  key_info: &KeyInfo,
+                  cb: Box<Fn(Result<String, IndyError>) + Send>) {
+        debug!("rotate_key >>> pool_handle: {:?}, wallet_handle: {:?}, my_did: {:?}, key_info: {:?}", pool_handle, wallet_handle, my_did, secret!(key_info));
+
+        let new_verkey = try_cb!(self.replace_keys_start(wallet_handle, key_info, &my_did), cb);
+
+        let nym_request = match self.ledger_service.build_nym_request(&my_did, &my_did, Some(&new_verkey), None, None) {
+            Ok(nym_request) => nym_request,
+            Err(err) => {
+                self._rollback_rotate_key(wallet_handle, &my_did, &new_verkey);
+                return cb(Err(IndyError::from(err)));
+            }
+        };
 
-        trace!("_get_nym_ack <<<");
+        let cmd_handle = sequence::get_next_id();
+        self.rotate_key_callbacks.borrow_mut().insert(cmd_handle, cb);
 
-        Ok(())
+        // Nym is signed by the current key as temporary one isn't applied yet
+        CommandExecutor::instance()
+            .send(Command::Ledger(LedgerCommand::SignAndSubmitRequest(
+                pool_handle,
+                wallet_handle,
+                my_did.clone(),
+                nym_request,
+                Box::new(move |result| {
+                    CommandExecutor::instance()
+                        .send(Command::Did(DidCommand::RotateKeyNymAck(
+                            cmd_handle,
+                            pool_handle,
+                            wallet_handle,
+                            my_did.clone(),
+                            new_verkey.clone(),
+                            result
+                        ))).unwrap();
+                })
+            ))).unwrap();
+
+        debug!("rotate_key <<<");
+    }
+
+    fn rotate_key_nym_ack(&self,
+                          cmd_handle: i32,
+                          pool_handle: i32,
+                          wallet_handle: i32,
+                          my_did: String,
+                          new_verkey: String,
+                          nym_reply_result: Result<String, IndyError>) {
+        debug!("rotate_key_nym_ack >>> cmd_handle: {:?}, pool_handle: {:?}, wallet_handle: {:?}, my_did: {:?}, new_verkey: {:?}, nym_reply_result: {:?}",
+               cmd_handle, pool_handle, wallet_handle, my_did, new_verkey, nym_reply_result);
+
+        let nym_reply_result = nym_reply_result
+            .map(|nym_reply| serde_json::from_str::<Message<serde_json::Value>>(&nym_reply)
+                .map_err(|err| IndyError::CommonError(CommonError::InvalidStructure(
+                    format!("Cannot deserialize transaction Response: {:?}", err)))));
+
+        match nym_reply_result {
+            Ok(Ok(Message::Reject(response))) | Ok(Ok(Message::ReqNACK(response))) => {
+                // Ledger explicitly refused the Nym and still keeps the current key, so temporary one is useless
+                self._rollback_rotate_key(wallet_handle, &my_did, &new_verkey);
+                let err = IndyError::LedgerError(LedgerError::InvalidTransaction(
+                    format!("Transaction has been failed: {:?}", response.reason)));
+                return self._rotate_key_cb(cmd_handle, Err(err));
+            }
+            Ok(Ok(Message::Reply(_))) => {}
+            Ok(Err(err)) | Err(err) => {
+                // Timeout or unexpected reply doesn't mean the Nym isn't written, so ledger is asked before deciding
+                warn!("Nym outcome for {} is unknown, confirming with GetNym: {:?}", my_did, err);
+                self.rotate_key_unconfirmed_nyms.borrow_mut().insert(cmd_handle, err);
+            }
+        }
+
+        let get_nym_request = match self.ledger_service.build_get_nym_request(Some(&my_did), &my_did) {
+            Ok(get_nym_request) => get_nym_request,
+            Err(err) => return self._rotate_key_cb(cmd_handle, Err(IndyError::from(err)))
+        };
+
+        self._submit_rotate_key_get_nym(cmd_handle, pool_handle, wallet_handle, my_did, new_verkey, ROTATE_KEY_GET_NYM_ATTEMPTS, get_nym_request);
+
+        debug!("rotate_key_nym_ack <<<");
+    }
+
+    fn rotate_key_get_nym_ack(&self,
+                              cmd_handle: i32,
+                              pool_handle: i32,
+                              wallet_handle: i32,
+                              my_did: String,
+                              new_verkey: String,
+                              attempts: usize,
+                              get_nym_reply_result: Result<String, IndyError>) {
+        debug!("rotate_key_get_nym_ack >>> cmd_handle: {:?}, pool_handle: {:?}, wallet_handle: {:?}, my_did: {:?}, new_verkey: {:?}, attempts: {:?}, get_nym_reply_result: {:?}",
+               cmd_handle, pool_handle, wallet_handle, my_did, new_verkey, attempts, get_nym_reply_result);
+
+        // Nym is written or may still be written at this point, so temporary key is kept on any error
+        // and rotation can be finished by replace_keys_apply later
+        let ledger_verkey = get_nym_reply_result
+            .and_then(|get_nym_reply| self._parse_get_nym_reply(&get_nym_reply))
+            .and_then(|their_did_info|
+                build_full_verkey(&their_did_info.did, their_did_info.verkey.as_ref().map(String::as_str))
+                    .map_err(IndyError::from));
+
+        let res = match ledger_verkey {
+            Ok(ref ledger_verkey) if *ledger_verkey == new_verkey =>
+                self.replace_keys_apply(wallet_handle, &my_did).map(|_| new_verkey),
+            Ok(_) if attempts > 1 => {
+                // Reply can come from the node that hasn't caught up the written Nym yet
+                match self.ledger_service.build_get_nym_request(Some(&my_did), &my_did) {
+                    Ok(get_nym_request) =>
+                        return self._submit_rotate_key_get_nym(cmd_handle, pool_handle, wallet_handle, my_did, new_verkey, attempts - 1, get_nym_request),
+                    Err(err) => Err(IndyError::from(err))
+                }
+            }
+            Ok(ledger_verkey) =>
+                match self.rotate_key_unconfirmed_nyms.borrow_mut().remove(&cmd_handle) {
+                    Some(nym_err) => Err(nym_err),
+                    None => Err(IndyError::CommonError(CommonError::InvalidState(
+                        format!("Ledger returned unexpected verkey for {}: {}", my_did, ledger_verkey))))
+                },
+            Err(err) => Err(err)
+        };
+
+        self._rotate_key_cb(cmd_handle, res);
+
+        debug!("rotate_key_get_nym_ack <<<");
+    }
+
+    fn _submit_rotate_key_get_nym(&self,
+                                  cmd_handle: i32,
+                                  pool_handle: i32,
+                                  wallet_handle: i32,
+                                  my_did: String,
+                                  new_verkey: String,
+                                  attempts: usize,
+                                  get_nym_request: String) {
+        // Pool accepts read reply only if it has valid state proof or is confirmed by f + 1 nodes
+        let submit = move || {
+            CommandExecutor::instance()
+                .send(Command::Ledger(LedgerCommand::SubmitRequest(
+                    pool_handle,
+                    get_nym_request,
+                    Box::new(move |result| {
+                        CommandExecutor::instance()
+                            .send(Command::Did(DidCommand::RotateKeyGetNymAck(
+                                cmd_handle,
+                                pool_handle,
+                                wallet_handle,
+                                my_did.clone(),
+                                new_verkey.clone(),
+                                attempts,
+                                result
+                            ))).unwrap();
+                    })
+                ))).unwrap();
+        };
+
+        // Retries are delayed exponentially to give lagging nodes time to catch up
+        // without blocking the command thread
+        match ROTATE_KEY_GET_NYM_ATTEMPTS - attempts {
+            0 => submit(),
+            retry => {
+                let delay = Duration::from_millis(ROTATE_KEY_GET_NYM_BACKOFF_MS << (retry - 1));
+                thread::spawn(move || {
+                    thread::sleep(delay);
+                    submit();
+                });
+            }
+        }
+    }
+
+    fn _rollback_rotate_key(&self, wallet_handle: i32, my_did: &str, temporary_verkey: &str) {
+        let _ = self.wallet_service.delete_indy_record::<TemporaryDid>(wallet_handle, my_did)
+            .map_err(|err| warn!("Can't delete temporary did for {}: {:?}", my_did, err));
+        let _ = self.wallet_service.delete_indy_record::<Key>(wallet_handle, temporary_verkey)
+            .map_err(|err| warn!("Can't delete temporary key {}: {:?}", temporary_verkey, err));
+    }
+
+    fn _rotate_key_cb(&self, cmd_handle: i32, result: Result<String, IndyError>) {
+        self.rotate_key_unconfirmed_nyms.borrow_mut().remove(&cmd_handle);
+
+        match self.rotate_key_callbacks.borrow_mut().remove(&cmd_handle) {
+            Some(cb) => cb(result),
+            None => error!("Can't process RotateKey ack for unknown handle {}", cmd_handle)
+        }
+    }
+
+    fn verify_for_did(&self,
+                      pool_handle: i32,
+                      wallet_handle: i32,
+                      did: String,
+                      msg: Vec<u8>,
+                      signature: Vec<u8>,
+                      require_current: bool,
+                      cb: Box<Fn(Result<bool, IndyError>) + Send>) {
+        debug!("verify_for_did >>> pool_handle: {:?}, wallet_handle: {:?}, did: {:?}, msg: {:?}, signature: {:?}, require_current: {:?}",
+               pool_handle, wallet_handle, did, msg, signature, require_current);
+
+        try_cb!(self.crypto_service.validate_did(&did), cb);
+
+        if require_current {
+            // Cached key can be outdated, so the key is always resolved from the ledger
+            let get_nym_request = try_cb!(self.ledger_service.build_get_nym_request(None, &did), cb);
+
+            let cmd_handle = sequence::get_next_id();
+            self.verify_for_did_requests.borrow_mut().insert(cmd_handle, (msg, signature, cb));
+
+            // Pool accepts read reply only if it has valid state proof or is confirmed by f + 1 nodes
+            return CommandExecutor::instance()
+                .send(Command::Ledger(LedgerCommand::SubmitRequest(
+                    pool_handle,
+                    get_nym_request,
+                    Box::new(move |result| {
+                        CommandExecutor::instance()
+                            .send(Command::Did(DidCommand::VerifyForDidGetNymAck(
+                                cmd_handle,
+                                wallet_handle,
+                                did.clone(),
+                                result
+                            ))).unwrap();
+                    })
+                ))).unwrap();
+        }
+
+        // Look to my did
+        let verkey = match self._wallet_get_my_did(wallet_handle, &did) {
+            Ok(my_did) => my_did.verkey,
+            Err(WalletError::ItemNotFound) => {
+                // look to their did
+                let their_did = ensure_their_did!(self,
+                                                  wallet_handle,
+                                                  pool_handle,
+                                                  did,
+                                                  DidCommand::VerifyForDid(
+                                                      pool_handle,
+                                                      wallet_handle,
+                                                      did.clone(),
+                                                      msg,
+                                                      signature,
+                                                      require_current,
+                                                      cb),
+                                                  cb);
+                their_did.verkey
+            }
+            Err(err) => return cb(Err(IndyError::from(err)))
+        };
+
+        let res = self.crypto_service.verify(&verkey, &msg, &signature).map_err(IndyError::from);
+
+        debug!("verify_for_did <<< res: {:?}", res);
+
+        cb(res)
+    }
+
+    fn verify_for_did_get_nym_ack(&self,
+                                  cmd_handle: i32,
+                                  wallet_handle: i32,
+                                  did: String,
+                                  get_nym_reply_result: Result<String, IndyError>) {
+        debug!("verify_for_did_get_nym_ack >>> cmd_handle: {:?}, wallet_handle: {:?}, did: {:?}, get_nym_reply_result: {:?}",
+               cmd_handle, wallet_handle, did, get_nym_reply_result);
+
+        let (msg, signature, cb) = match self.verify_for_did_requests.borrow_mut().remove(&cmd_handle) {
+            Some(request) => request,
+            None => {
+                error!("Can't process VerifyForDid ack for unknown handle {}", cmd_handle);
+                return;
+            }
+        };
+
+        // create_their_did expands abbreviated verkey returned by ledger
+        let their_did = try_cb!(get_nym_reply_result
+            .and_then(|get_nym_reply| self._parse_get_nym_reply(&get_nym_reply))
+            .and_then(|their_did_info| self.crypto_service.create_their_did(&their_did_info).map_err(IndyError::from)), cb);
+
+        if their_did.did != did {
+            return cb(Err(IndyError::CommonError(CommonError::InvalidState(
+                format!("Ledger returned Nym for unexpected DID: {}", their_did.did)))));
+        }
+
+        // Refresh cached key of their did, my did keys are managed by wallet owner only
+        match self.wallet_service.record_exists::<Did>(wallet_handle, &did) {
+            Ok(false) => {
+                let _ = self.wallet_service.upsert_indy_object(wallet_handle, &did, &their_did)
+                    .map_err(|err| warn!("Can't update cached key for {}: {:?}", did, err));
+            }
+            Ok(true) => {}
+            Err(err) => return cb(Err(IndyError::from(err)))
+        }
+
+        let res = self.crypto_service.verify(&their_did.verkey, &msg, &signature).map_err(IndyError::from);
+
+        debug!("verify_for_did_get_nym_ack <<< res: {:?}", res);
+
+        cb(res)
     }
 
     fn get_attrib_ack(&self,
@@ -600,6 +1093,9 @@ impl DidCommandExecutor {
             DidCommand::KeyForDid(_, _, _, cb) => {
                 return cb(Err(err));
             }
+            DidCommand::VerifyForDid(_, _, _, _, _, _, cb) => {
+                return cb(Err(err));
+            }
             DidCommand::GetEndpointForDid(_, _, _, cb) => {
                 return cb(Err(err));
             }
@@ -665,6 +1161,85 @@ impl DidCommandExecutor {
             ))).unwrap();
     }
 
+    fn export_did(&self,
+                  wallet_handle: i32,
+                  did: &str,
+                  passphrase: &str) -> Result<String, IndyError> {
+        debug!("export_did >>> wallet_handle: {:?}, did: {:?}, passphrase: {:?}", wallet_handle, did, secret!(passphrase));
+
+        self.crypto_service.validate_did(did)?;
+
+        let did = self._wallet_get_my_did(wallet_handle, did)?;
+
+        let key: Key = self.wallet_service.get_indy_object(wallet_handle, &did.verkey, &RecordOptions::id_value())?;
+
+        let key_metadata = self.wallet_service.get_indy_opt_object::<KeyMetadata>(wallet_handle, &did.verkey, &RecordOptions::id_value())?
+            .map(|metadata| metadata.value);
+
+        let did_metadata = self.wallet_service.get_indy_opt_object::<DidMetadata>(wallet_handle, &did.did, &RecordOptions::id_value())?
+            .map(|metadata| metadata.value);
+
+        let did_export = DidExport {
+            did,
+            key: KeyExport { key, metadata: key_metadata },
+            metadata: did_metadata
+        };
+
+        let exported_did = self.crypto_service.encrypt_export(&did_export, passphrase)?;
+
+        let res = serde_json::to_string(&exported_did)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize exported DID: {:?}", err)))?;
+
+        debug!("export_did <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn import_did(&self,
+                  wallet_handle: i32,
+                  exported_did: &EncryptedExport,
+                  passphrase: &str) -> Result<String, IndyError> {
+        debug!("import_did >>> wallet_handle: {:?}, exported_did: {:?}, passphrase: {:?}", wallet_handle, exported_did, secret!(passphrase));
+
+        let did_export: DidExport = self.crypto_service.decrypt_export(exported_did, passphrase)?;
+
+        self.crypto_service.validate_did(&did_export.did.did)?;
+        self.crypto_service.validate_key_pair(&did_export.key.key)?;
+
+        if did_export.did.verkey != did_export.key.key.verkey {
+            return Err(IndyError::CommonError(CommonError::InvalidStructure("Exported DID verkey doesn't match exported key".to_string())));
+        }
+
+        if self.wallet_service.record_exists::<Did>(wallet_handle, &did_export.did.did)? {
+            return Err(IndyError::DidError(DidError::AlreadyExistsError(did_export.did.did)));
+        };
+
+        // Records are added one by one, metadata before the record it belongs to and DID last,
+        // so import interrupted halfway can be repeated without leaving DID without its key
+        let key_exists = self.wallet_service.record_exists::<Key>(wallet_handle, &did_export.key.key.verkey)?;
+
+        // The key can be already in the wallet, for example if it was imported separately
+        if !key_exists {
+            if let Some(metadata) = did_export.key.metadata {
+                self.wallet_service.upsert_indy_object(wallet_handle, &did_export.key.key.verkey, &KeyMetadata { value: metadata })?;
+            }
+
+            self.wallet_service.add_indy_object(wallet_handle, &did_export.key.key.verkey, &did_export.key.key, &WalletService::creation_tags())?;
+        }
+
+        if let Some(metadata) = did_export.metadata {
+            self.wallet_service.upsert_indy_object(wallet_handle, &did_export.did.did, &DidMetadata { value: metadata })?;
+        }
+
+        self.wallet_service.add_indy_object(wallet_handle, &did_export.did.did, &did_export.did, &WalletService::creation_tags())?;
+
+        let res = did_export.did.did;
+
+        debug!("import_did <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
     fn _wallet_get_my_did(&self, wallet_handle: i32, my_did: &str) -> Result<Did, WalletError> {
         self.wallet_service.get_indy_object(wallet_handle, &my_did, &RecordOptions::id_value())
     }
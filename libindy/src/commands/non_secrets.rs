@@ -6,9 +6,9 @@ use errors::indy::IndyError;
 use errors::common::CommonError;
 use errors::wallet::WalletError;
 use services::wallet::{WalletService, WalletRecord, WalletSearch, RecordOptions, SearchOptions};
-use domain::wallet::Tags;
+use domain::wallet::{AddRecordOptions, RecordOperation, Tags};
 use std::rc::Rc;
-use std::collections::HashMap;
+use std::collections::{HashMap, BTreeMap};
 use utils::sequence;
 use std::cell::RefCell;
 
@@ -22,17 +22,30 @@ pub enum NonSecretsCommand {
               String, // id
               String, // value
               Option<Tags>, //tags
+              Option<AddRecordOptions>, // options
               Box<Fn(Result<()>) + Send>),
     UpdateRecordValue(i32, // handle
                       String, // type
                       String, // id
                       String, // value
                       Box<Fn(Result<()>) + Send>),
+    UpdateRecordValueIfVersion(i32, // handle
+                               String, // type
+                               String, // id
+                               String, // value
+                               u64, // version
+                               Box<Fn(Result<()>) + Send>),
     UpdateRecordTags(i32, // handle
                      String, // type
                      String, // id
                      Tags, //tags
                      Box<Fn(Result<()>) + Send>),
+    UpdateRecordTagsIfVersion(i32, // handle
+                              String, // type
+                              String, // id
+                              Tags, //tags
+                              u64, // version
+                              Box<Fn(Result<()>) + Send>),
     AddRecordTags(i32, // handle
                   String, // type
                   String, // id
@@ -47,6 +60,14 @@ pub enum NonSecretsCommand {
                  String, // type
                  String, // id
                  Box<Fn(Result<()>) + Send>),
+    DeleteRecordIfVersion(i32, // handle
+                          String, // type
+                          String, // id
+                          u64, // version
+                          Box<Fn(Result<()>) + Send>),
+    Batch(i32, // handle
+          Vec<RecordOperation>, // operations
+          Box<Fn(Result<()>) + Send>),
     GetRecord(i32, // handle
               String, // type
               String, // id
@@ -62,7 +83,18 @@ pub enum NonSecretsCommand {
                            usize, // count
                            Box<Fn(Result<String>) + Send>),
     CloseSearch(i32, // wallet search handle
-                Box<Fn(Result<()>) + Send>)
+                Box<Fn(Result<()>) + Send>),
+    CountRecords(i32, // handle
+                 String, // type
+                 String, // query json
+                 Box<Fn(Result<usize>) + Send>),
+    CountRecordsByTag(i32, // handle
+                      String, // type
+                      String, // query json
+                      String, // tag name
+                      Box<Fn(Result<String>) + Send>),
+    PurgeExpiredRecords(i32, // handle
+                        Box<Fn(Result<usize>) + Send>)
 }
 
 pub struct NonSecretsCommandExecutor {
@@ -80,18 +112,26 @@ impl NonSecretsCommandExecutor {
 
     pub fn execute(&self, command: NonSecretsCommand) {
         match command {
-            NonSecretsCommand::AddRecord(handle, type_, id, value, tags, cb) => {
+            NonSecretsCommand::AddRecord(handle, type_, id, value, tags, options, cb) => {
                 info!(target: "non_secrets_command_executor", "AddRecord command received");
-                cb(self.add_record(handle, &type_, &id, &value, tags.as_ref()));
+                cb(self.add_record(handle, &type_, &id, &value, tags.as_ref(), options.as_ref()));
             }
             NonSecretsCommand::UpdateRecordValue(handle, type_, id, value, cb) => {
                 info!(target: "non_secrets_command_executor", "UpdateRecordValue command received");
                 cb(self.update_record_value(handle, &type_, &id, &value));
             }
+            NonSecretsCommand::UpdateRecordValueIfVersion(handle, type_, id, value, version, cb) => {
+                info!(target: "non_secrets_command_executor", "UpdateRecordValueIfVersion command received");
+                cb(self.update_record_value_if_version(handle, &type_, &id, &value, version));
+            }
             NonSecretsCommand::UpdateRecordTags(handle, type_, id, tags, cb) => {
                 info!(target: "non_secrets_command_executor", "UpdateRecordTags command received");
                 cb(self.update_record_tags(handle, &type_, &id, &tags));
             }
+            NonSecretsCommand::UpdateRecordTagsIfVersion(handle, type_, id, tags, version, cb) => {
+                info!(target: "non_secrets_command_executor", "UpdateRecordTagsIfVersion command received");
+                cb(self.update_record_tags_if_version(handle, &type_, &id, &tags, version));
+            }
             NonSecretsCommand::AddRecordTags(handle, type_, id, tags, cb) => {
                 info!(target: "non_secrets_command_executor", "AddRecordTags command received");
                 cb(self.add_record_tags(handle, &type_, &id, &tags));
@@ -104,6 +144,14 @@ impl NonSecretsCommandExecutor {
                 info!(target: "non_secrets_command_executor", "DeleteRecord command received");
                 cb(self.delete_record(handle, &type_, &id));
             }
+            NonSecretsCommand::DeleteRecordIfVersion(handle, type_, id, version, cb) => {
+                info!(target: "non_secrets_command_executor", "DeleteRecordIfVersion command received");
+                cb(self.delete_record_if_version(handle, &type_, &id, version));
+            }
+            NonSecretsCommand::Batch(handle, operations, cb) => {
+                info!(target: "non_secrets_command_executor", "Batch command received");
+                cb(self.batch(handle, &operations));
+            }
             NonSecretsCommand::GetRecord(handle, type_, id, options_json, cb) => {
                 info!(target: "non_secrets_command_executor", "GetRecord command received");
                 cb(self.get_record(handle, &type_, &id, &options_json));
@@ -120,6 +168,18 @@ impl NonSecretsCommandExecutor {
                 info!(target: "non_secrets_command_executor", "CloseSearch command received");
                 cb(self.close_search(wallet_search_handle));
             }
+            NonSecretsCommand::CountRecords(handle, type_, query_json, cb) => {
+                info!(target: "non_secrets_command_executor", "CountRecords command received");
+                cb(self.count_records(handle, &type_, &query_json));
+            }
+            NonSecretsCommand::CountRecordsByTag(handle, type_, query_json, tag_name, cb) => {
+                info!(target: "non_secrets_command_executor", "CountRecordsByTag command received");
+                cb(self.count_records_by_tag(handle, &type_, &query_json, &tag_name));
+            }
+            NonSecretsCommand::PurgeExpiredRecords(handle, cb) => {
+                info!(target: "non_secrets_command_executor", "PurgeExpiredRecords command received");
+                cb(self.purge_expired_records(handle));
+            }
         };
     }
 
@@ -128,12 +188,18 @@ impl NonSecretsCommandExecutor {
                   type_: &str,
                   id: &str,
                   value: &str,
-                  tags: Option<&Tags>) -> Result<()> {
-        trace!("add_record >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, value: {:?}, tags: {:?}", wallet_handle, type_, id, value, tags);
+                  tags: Option<&Tags>,
+                  options: Option<&AddRecordOptions>) -> Result<()> {
+        trace!("add_record >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, value: {:?}, tags: {:?}, options: {:?}", wallet_handle, type_, id, value, tags, options);
 
         self._check_type(type_)?;
 
-        let res = self.wallet_service.add_record(wallet_handle, type_, id, value, tags.unwrap_or(&Tags::new()))?;
+        let tags = tags.cloned().unwrap_or_default();
+
+        let res = match options.and_then(|options| options.expires_at) {
+            Some(expires_at) => self.wallet_service.add_record_with_expiry(wallet_handle, type_, id, value, &tags, expires_at)?,
+            None => self.wallet_service.add_record(wallet_handle, type_, id, value, &tags)?
+        };
 
         trace!("add_record <<< res: {:?}", res);
 
@@ -156,6 +222,23 @@ impl NonSecretsCommandExecutor {
         Ok(res)
     }
 
+    fn update_record_value_if_version(&self,
+                                      wallet_handle: i32,
+                                      type_: &str,
+                                      id: &str,
+                                      value: &str,
+                                      version: u64) -> Result<()> {
+        trace!("update_record_value_if_version >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, value: {:?}, version: {:?}", wallet_handle, type_, id, value, version);
+
+        self._check_type(type_)?;
+
+        let res = self.wallet_service.update_record_value_if_version(wallet_handle, type_, id, value, version)?;
+
+        trace!("update_record_value_if_version <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
     fn update_record_tags(&self,
                           wallet_handle: i32,
                           type_: &str,
@@ -172,6 +255,23 @@ impl NonSecretsCommandExecutor {
         Ok(res)
     }
 
+    fn update_record_tags_if_version(&self,
+                                     wallet_handle: i32,
+                                     type_: &str,
+                                     id: &str,
+                                     tags: &Tags,
+                                     version: u64) -> Result<()> {
+        trace!("update_record_tags_if_version >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, tags: {:?}, version: {:?}", wallet_handle, type_, id, tags, version);
+
+        self._check_type(type_)?;
+
+        let res = self.wallet_service.update_record_tags_if_version(wallet_handle, type_, id, &tags, version)?;
+
+        trace!("update_record_tags_if_version <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
     fn add_record_tags(&self,
                        wallet_handle: i32,
                        type_: &str,
@@ -222,6 +322,38 @@ impl NonSecretsCommandExecutor {
         Ok(res)
     }
 
+    fn delete_record_if_version(&self,
+                                wallet_handle: i32,
+                                type_: &str,
+                                id: &str,
+                                version: u64) -> Result<()> {
+        trace!("delete_record_if_version >>> wallet_handle: {:?}, type_: {:?}, id: {:?}, version: {:?}", wallet_handle, type_, id, version);
+
+        self._check_type(type_)?;
+
+        let res = self.wallet_service.delete_record_if_version(wallet_handle, type_, id, version)?;
+
+        trace!("delete_record_if_version <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn batch(&self,
+             wallet_handle: i32,
+             operations: &[RecordOperation]) -> Result<()> {
+        trace!("batch >>> wallet_handle: {:?}, operations: {:?}", wallet_handle, operations);
+
+        for operation in operations {
+            self._check_type(operation.type_())?;
+        }
+
+        let res = self.wallet_service.batch(wallet_handle, operations)?;
+
+        trace!("batch <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
     fn get_record(&self,
                   wallet_handle: i32,
                   type_: &str,
@@ -312,6 +444,53 @@ impl NonSecretsCommandExecutor {
         Ok(res)
     }
 
+    fn count_records(&self,
+                     wallet_handle: i32,
+                     type_: &str,
+                     query_json: &str) -> Result<usize> {
+        trace!("count_records >>> wallet_handle: {:?}, type_: {:?}, query_json: {:?}", wallet_handle, type_, query_json);
+
+        self._check_type(type_)?;
+
+        let res = self.wallet_service.count_records(wallet_handle, type_, query_json)?;
+
+        trace!("count_records <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn count_records_by_tag(&self,
+                            wallet_handle: i32,
+                            type_: &str,
+                            query_json: &str,
+                            tag_name: &str) -> Result<String> {
+        trace!("count_records_by_tag >>> wallet_handle: {:?}, type_: {:?}, query_json: {:?}, tag_name: {:?}", wallet_handle, type_, query_json, tag_name);
+
+        self._check_type(type_)?;
+
+        let counts: BTreeMap<String, usize> = self.wallet_service.count_records_by_tag(wallet_handle, type_, query_json, tag_name)?
+            .into_iter()
+            .collect();
+
+        let res = serde_json::to_string(&counts)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize counts: {:?}", err)))?;
+
+        trace!("count_records_by_tag <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn purge_expired_records(&self,
+                             wallet_handle: i32) -> Result<usize> {
+        trace!("purge_expired_records >>> wallet_handle: {:?}", wallet_handle);
+
+        let res = self.wallet_service.purge_expired_records(wallet_handle)?;
+
+        trace!("purge_expired_records <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
     fn _check_type(&self, type_: &str) -> Result<()> {
         if type_.starts_with(WalletService::PREFIX) {
             return Err(IndyError::WalletError(WalletError::AccessFailed(format!("Record of type \"{}\" is not available", type_))));
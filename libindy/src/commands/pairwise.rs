@@ -5,10 +5,16 @@ use std::collections::HashMap;
 
 use errors::common::CommonError;
 use errors::indy::IndyError;
-use services::wallet::{WalletService, RecordOptions};
-use domain::pairwise::{Pairwise, PairwiseInfo};
-use domain::crypto::did::{Did, TheirDid};
-
+use errors::wallet::WalletError;
+use services::wallet::{WalletService, WalletRecord, WalletSearch, RecordOptions, SearchOptions, CREATED_AT_TAG};
+use domain::pairwise::{Pairwise, PairwiseInfo, PairwiseWithTags};
+use domain::wallet::{Tags, RecordOperation};
+use domain::crypto::did::{Did, TheirDid, DidMetadata};
+use domain::ledger::attrib::Endpoint;
+use named_type::NamedType;
+use utils::sequence;
+
+use std::cell::RefCell;
 use std::rc::Rc;
 use std::str;
 
@@ -16,6 +22,8 @@ use std::result;
 
 type Result<T> = result::Result<T, IndyError>;
 
+const MY_DID_TAG: &'static str = "my_did";
+
 pub enum PairwiseCommand {
     PairwiseExists(
         i32, // wallet handle
@@ -38,17 +46,49 @@ pub enum PairwiseCommand {
         i32, // wallet handle
         String, // their_did
         Option<String>, // metadata
+        Box<Fn(Result<()>) + Send>),
+    SetPairwiseTags(
+        i32, // wallet handle
+        String, // their_did
+        Tags, // tags
+        Box<Fn(Result<()>) + Send>),
+    SearchPairwise(
+        i32, // wallet handle
+        String, // query json
+        Box<Fn(Result<String>) + Send>),
+    DeletePairwise(
+        i32, // wallet handle
+        String, // their_did
+        bool, // cascade
+        Box<Fn(Result<()>) + Send>),
+    UpdatePairwiseTheirDid(
+        i32, // wallet handle
+        String, // their_did
+        String, // new their_did
+        Box<Fn(Result<()>) + Send>),
+    OpenPairwiseSearch(
+        i32, // wallet handle
+        Option<String>, // query json
+        Box<Fn(Result<(i32, usize)>) + Send>),
+    FetchPairwiseSearchNext(
+        i32, // search handle
+        usize, // count
+        Box<Fn(Result<String>) + Send>),
+    ClosePairwiseSearch(
+        i32, // search handle
         Box<Fn(Result<()>) + Send>)
 }
 
 pub struct PairwiseCommandExecutor {
-    wallet_service: Rc<WalletService>
+    wallet_service: Rc<WalletService>,
+    searches: RefCell<HashMap<i32, Box<WalletSearch>>>
 }
 
 impl PairwiseCommandExecutor {
     pub fn new(wallet_service: Rc<WalletService>) -> PairwiseCommandExecutor {
         PairwiseCommandExecutor {
-            wallet_service
+            wallet_service,
+            searches: RefCell::new(HashMap::new())
         }
     }
 
@@ -74,6 +114,34 @@ impl PairwiseCommandExecutor {
                 info!(target: "pairwise_command_executor", "SetPairwiseMetadata command received");
                 cb(self.set_pairwise_metadata(wallet_handle, &their_did, metadata.as_ref().map(String::as_str)));
             }
+            PairwiseCommand::SetPairwiseTags(wallet_handle, their_did, tags, cb) => {
+                info!(target: "pairwise_command_executor", "SetPairwiseTags command received");
+                cb(self.set_pairwise_tags(wallet_handle, &their_did, &tags));
+            }
+            PairwiseCommand::SearchPairwise(wallet_handle, query_json, cb) => {
+                info!(target: "pairwise_command_executor", "SearchPairwise command received");
+                cb(self.search_pairwise(wallet_handle, &query_json));
+            }
+            PairwiseCommand::DeletePairwise(wallet_handle, their_did, cascade, cb) => {
+                info!(target: "pairwise_command_executor", "DeletePairwise command received");
+                cb(self.delete_pairwise(wallet_handle, &their_did, cascade));
+            }
+            PairwiseCommand::UpdatePairwiseTheirDid(wallet_handle, their_did, new_their_did, cb) => {
+                info!(target: "pairwise_command_executor", "UpdatePairwiseTheirDid command received");
+                cb(self.update_pairwise_their_did(wallet_handle, &their_did, &new_their_did));
+            }
+            PairwiseCommand::OpenPairwiseSearch(wallet_handle, query_json, cb) => {
+                info!(target: "pairwise_command_executor", "OpenPairwiseSearch command received");
+                cb(self.open_pairwise_search(wallet_handle, query_json.as_ref().map(String::as_str)));
+            }
+            PairwiseCommand::FetchPairwiseSearchNext(search_handle, count, cb) => {
+                info!(target: "pairwise_command_executor", "FetchPairwiseSearchNext command received");
+                cb(self.fetch_pairwise_search_next(search_handle, count));
+            }
+            PairwiseCommand::ClosePairwiseSearch(search_handle, cb) => {
+                info!(target: "pairwise_command_executor", "ClosePairwiseSearch command received");
+                cb(self.close_pairwise_search(search_handle));
+            }
         };
     }
 
@@ -105,7 +173,10 @@ impl PairwiseCommandExecutor {
             metadata: metadata.map(str::to_string)
         };
 
-        self.wallet_service.add_indy_object(wallet_handle, &their_did, &pairwise, &HashMap::new())?;
+        let mut tags = WalletService::creation_tags();
+        tags.insert(MY_DID_TAG.to_string(), my_did.to_string());
+
+        self.wallet_service.add_indy_object(wallet_handle, &their_did, &pairwise, &tags)?;
 
         debug!("create_pairwise <<<");
 
@@ -174,4 +245,192 @@ impl PairwiseCommandExecutor {
 
         Ok(())
     }
+
+    fn set_pairwise_tags(&self,
+                         wallet_handle: i32,
+                         their_did: &str,
+                         tags: &Tags) -> Result<()> {
+        debug!("set_pairwise_tags >>> wallet_handle: {:?}, their_did: {:?}, tags: {:?}", wallet_handle, their_did, tags);
+
+        self.wallet_service.update_indy_record_user_tags::<Pairwise>(wallet_handle, their_did, tags, &[CREATED_AT_TAG, MY_DID_TAG])?;
+
+        debug!("set_pairwise_tags <<<");
+
+        Ok(())
+    }
+
+    fn search_pairwise(&self,
+                       wallet_handle: i32,
+                       query_json: &str) -> Result<String> {
+        debug!("search_pairwise >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+        let mut pairwise_search =
+            self.wallet_service.search_indy_records::<Pairwise>(wallet_handle, query_json, &SearchOptions::id_value_tags())?;
+
+        let mut list_pairwise: Vec<PairwiseWithTags> = Vec::new();
+
+        while let Some(pairwise_record) = pairwise_search.fetch_next_record()? {
+            list_pairwise.push(self._get_pairwise_with_tags(&pairwise_record)?);
+        }
+
+        let res = serde_json::to_string(&list_pairwise)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize pairwise list {:?}", err)))?;
+
+        debug!("search_pairwise <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn delete_pairwise(&self,
+                       wallet_handle: i32,
+                       their_did: &str,
+                       cascade: bool) -> Result<()> {
+        debug!("delete_pairwise >>> wallet_handle: {:?}, their_did: {:?}, cascade: {:?}", wallet_handle, their_did, cascade);
+
+        if cascade {
+            let pairwise: Pairwise =
+                self.wallet_service.get_indy_object(wallet_handle, &their_did, &RecordOptions::id_value())?;
+
+            // My DID and its keys are never deleted here, they can be used beyond the pairwise.
+            // Pairwise goes first, so deletion interrupted halfway leaves their DID records only
+            let mut operations = vec![self.wallet_service.delete_indy_record_operation::<Pairwise>(&their_did)];
+            self._delete_their_did_operations(wallet_handle, &pairwise.their_did, &mut operations)?;
+
+            self.wallet_service.apply_operations(wallet_handle, &operations)?;
+        } else {
+            self.wallet_service.delete_indy_record::<Pairwise>(wallet_handle, &their_did)?;
+        }
+
+        debug!("delete_pairwise <<<");
+
+        Ok(())
+    }
+
+    fn update_pairwise_their_did(&self,
+                                 wallet_handle: i32,
+                                 their_did: &str,
+                                 new_their_did: &str) -> Result<()> {
+        debug!("update_pairwise_their_did >>> wallet_handle: {:?}, their_did: {:?}, new_their_did: {:?}", wallet_handle, their_did, new_their_did);
+
+        let pairwise_record = self.wallet_service.get_indy_record::<Pairwise>(wallet_handle, &their_did, &RecordOptions::id_value_tags())?;
+        let pairwise = self._get_pairwise_with_tags(&pairwise_record)?;
+
+        self.wallet_service.get_indy_record::<TheirDid>(wallet_handle, &new_their_did, &RecordOptions::id())?;
+
+        let mut tags = pairwise.tags;
+        tags.insert(MY_DID_TAG.to_string(), pairwise.my_did.clone());
+
+        let new_pairwise = Pairwise {
+            my_did: pairwise.my_did,
+            their_did: new_their_did.to_string(),
+            metadata: pairwise.metadata
+        };
+
+        // Pairwise is keyed by their DID, so it is re-created with the same metadata and tags.
+        // New pairwise goes first, so update interrupted halfway doesn't lose the pairwise
+        let operations = vec![
+            self.wallet_service.add_indy_object_operation(&new_their_did, &new_pairwise, &tags)?,
+            self.wallet_service.delete_indy_record_operation::<Pairwise>(&their_did)
+        ];
+
+        self.wallet_service.apply_operations(wallet_handle, &operations)?;
+
+        debug!("update_pairwise_their_did <<<");
+
+        Ok(())
+    }
+
+    fn open_pairwise_search(&self,
+                            wallet_handle: i32,
+                            query_json: Option<&str>) -> Result<(i32, usize)> {
+        debug!("open_pairwise_search >>> wallet_handle: {:?}, query_json: {:?}", wallet_handle, query_json);
+
+        let search_options = json!({
+            "retrieveRecords": true,
+            "retrieveTotalCount": true,
+            "retrieveType": false,
+            "retrieveValue": true,
+            "retrieveTags": true
+        }).to_string();
+
+        let pairwise_search =
+            self.wallet_service.search_indy_records::<Pairwise>(wallet_handle, query_json.unwrap_or("{}"), &search_options)?;
+
+        let total_count = pairwise_search.get_total_count()?.unwrap_or(0);
+
+        let handle = sequence::get_next_id();
+
+        self.searches.borrow_mut().insert(handle, Box::new(pairwise_search));
+
+        let res = (handle, total_count);
+
+        debug!("open_pairwise_search <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn fetch_pairwise_search_next(&self,
+                                  search_handle: i32,
+                                  count: usize) -> Result<String> {
+        debug!("fetch_pairwise_search_next >>> search_handle: {:?}, count: {:?}", search_handle, count);
+
+        let mut searches = self.searches.borrow_mut();
+        let search = searches.get_mut(&search_handle)
+            .ok_or(WalletError::InvalidHandle(format!("Unknown PairwiseSearch handle: {}", search_handle)))?;
+
+        let mut list_pairwise: Vec<PairwiseWithTags> = Vec::new();
+
+        for _ in 0..count {
+            match search.fetch_next_record()? {
+                Some(pairwise_record) => list_pairwise.push(self._get_pairwise_with_tags(&pairwise_record)?),
+                None => break
+            }
+        }
+
+        let res = serde_json::to_string(&list_pairwise)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize pairwise list {:?}", err)))?;
+
+        debug!("fetch_pairwise_search_next <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn close_pairwise_search(&self, search_handle: i32) -> Result<()> {
+        debug!("close_pairwise_search >>> search_handle: {:?}", search_handle);
+
+        let res = match self.searches.borrow_mut().remove(&search_handle) {
+            Some(_) => Ok(()),
+            None => Err(WalletError::InvalidHandle(format!("Unknown PairwiseSearch handle: {}", search_handle)))
+        }?;
+
+        debug!("close_pairwise_search <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn _get_pairwise_with_tags(&self, pairwise_record: &WalletRecord) -> Result<PairwiseWithTags> {
+        let pairwise_id = pairwise_record.get_id();
+
+        let pairwise: Pairwise = pairwise_record.get_value()
+            .and_then(|pairwise_json| serde_json::from_str(pairwise_json).ok())
+            .ok_or(CommonError::InvalidStructure(format!("Cannot deserialize Pairwise: {:?}", pairwise_id)))?;
+
+        let tags = pairwise_record.get_tags().cloned().unwrap_or_default();
+
+        Ok(PairwiseWithTags::new(pairwise, tags))
+    }
+
+    fn _delete_their_did_operations(&self, wallet_handle: i32, their_did: &str, operations: &mut Vec<RecordOperation>) -> Result<()> {
+        self._delete_operation_if_exists::<TheirDid>(wallet_handle, their_did, operations)?;
+        self._delete_operation_if_exists::<DidMetadata>(wallet_handle, their_did, operations)?;
+        self._delete_operation_if_exists::<Endpoint>(wallet_handle, their_did, operations)?;
+        Ok(())
+    }
+
+    fn _delete_operation_if_exists<T>(&self, wallet_handle: i32, name: &str, operations: &mut Vec<RecordOperation>) -> Result<()> where T: NamedType {
+        if self.wallet_service.record_exists::<T>(wallet_handle, name)? {
+            operations.push(self.wallet_service.delete_indy_record_operation::<T>(name));
+        }
+        Ok(())
+    }
 }
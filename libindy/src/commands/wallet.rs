@@ -2,12 +2,13 @@ extern crate libc;
 extern crate serde_json;
 extern crate indy_crypto;
 
+use errors::common::CommonError;
 use errors::indy::IndyError;
 use services::wallet::WalletService;
 use services::crypto::CryptoService;
 use api::wallet::*;
 use utils::crypto::{base58, randombytes, chacha20poly1305_ietf};
-use domain::wallet::{KeyConfig, Config, Credentials, ExportConfig};
+use domain::wallet::{KeyConfig, Config, Credentials, ExportConfig, CheckConfig};
 
 use std::rc::Rc;
 use std::result;
@@ -41,6 +42,12 @@ pub enum WalletCommand {
                        WalletFetchSearchNextRecord, // fetch search next record
                        WalletFreeSearch, // free search
                        Box<Fn(Result<()>) + Send>),
+    RegisterWalletBatch(String, // type_
+                        WalletBatch, // batch
+                        Box<Fn(Result<()>) + Send>),
+    RegisterWalletVersioning(String, // type_
+                             WalletGetRecordVersion, // get record version
+                             Box<Fn(Result<()>) + Send>),
     Create(Config, // config
            Credentials, // credentials
            Box<Fn(Result<()>) + Send>),
@@ -61,6 +68,9 @@ pub enum WalletCommand {
            Box<Fn(Result<()>) + Send>),
     GenerateKey(Option<KeyConfig>, // config
                 Box<Fn(Result<String>) + Send>),
+    Check(i32, // wallet_handle
+          CheckConfig, // check config
+          Box<Fn(Result<String>) + Send>),
 }
 
 pub struct WalletCommandExecutor {
@@ -92,6 +102,14 @@ impl WalletCommandExecutor {
                                        free_storage_metadata, search_records, search_all_records, get_search_total_count,
                                        fetch_search_next_record, free_search));
             }
+            WalletCommand::RegisterWalletBatch(type_, batch, cb) => {
+                debug!(target: "wallet_command_executor", "RegisterWalletBatch command received");
+                cb(self._register_batch(&type_, batch));
+            }
+            WalletCommand::RegisterWalletVersioning(type_, get_record_version, cb) => {
+                debug!(target: "wallet_command_executor", "RegisterWalletVersioning command received");
+                cb(self._register_versioning(&type_, get_record_version));
+            }
             WalletCommand::Create(config, credentials, cb) => {
                 debug!(target: "wallet_command_executor", "Create command received");
                 cb(self._create(&config, &credentials));
@@ -120,6 +138,10 @@ impl WalletCommandExecutor {
                 debug!(target: "wallet_command_executor", "DeriveKey command received");
                 cb(self._generate_key(config.as_ref()));
             }
+            WalletCommand::Check(wallet_handle, check_config, cb) => {
+                debug!(target: "wallet_command_executor", "Check command received");
+                cb(self._check(wallet_handle, &check_config));
+            }
         };
     }
 
@@ -164,6 +186,28 @@ impl WalletCommandExecutor {
         Ok(res)
     }
 
+    fn _register_batch(&self,
+                       type_: &str,
+                       batch: WalletBatch) -> Result<()> {
+        trace!("_register_batch >>> type_: {:?}", type_);
+
+        let res = self.wallet_service.register_wallet_storage_batch(type_, batch)?;
+
+        trace!("_register_batch <<< res: {:?}", res);
+        Ok(res)
+    }
+
+    fn _register_versioning(&self,
+                            type_: &str,
+                            get_record_version: WalletGetRecordVersion) -> Result<()> {
+        trace!("_register_versioning >>> type_: {:?}", type_);
+
+        let res = self.wallet_service.register_wallet_storage_versioning(type_, get_record_version)?;
+
+        trace!("_register_versioning <<< res: {:?}", res);
+        Ok(res)
+    }
+
     fn _create(&self,
                config: &Config,
                credentials: &Credentials) -> Result<()> {
@@ -219,6 +263,20 @@ impl WalletCommandExecutor {
         Ok(res)
     }
 
+    fn _check(&self,
+              wallet_handle: i32,
+              check_config: &CheckConfig) -> Result<String> {
+        trace!("_check >>> handle: {:?}, check_config: {:?}", wallet_handle, check_config);
+
+        let report = self.wallet_service.check_wallet(wallet_handle, check_config)?;
+
+        let res = serde_json::to_string(&report)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize wallet check report: {:?}", err)))?;
+
+        trace!("_check <<< res: {:?}", res);
+        Ok(res)
+    }
+
     fn _import(&self,
                config: &Config,
                credentials: &Credentials,
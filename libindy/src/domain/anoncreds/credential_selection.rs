@@ -0,0 +1,32 @@
+use std::collections::{HashMap, HashSet};
+
+use super::requested_credential::RequestedCredentials;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub enum SelectionStrategy {
+    #[serde(rename = "newest")]
+    Newest,
+    #[serde(rename = "fewest_credentials")]
+    FewestCredentials
+}
+
+impl Default for SelectionStrategy {
+    fn default() -> Self {
+        SelectionStrategy::Newest
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct CredentialSelectionPolicy {
+    #[serde(default)]
+    pub strategy: SelectionStrategy,
+    pub issuer_did: Option<String>,
+    #[serde(default)]
+    pub revoked: HashMap<String, HashSet<String>>
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CredentialSelection {
+    pub requested_credentials: RequestedCredentials,
+    pub unmet_referents: Vec<String>
+}
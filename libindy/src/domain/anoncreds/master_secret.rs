@@ -5,4 +5,10 @@ use named_type::NamedType;
 #[derive(Debug, Deserialize, Serialize, NamedType)]
 pub struct MasterSecret {
     pub value: CryptoMasterSecret,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct MasterSecretInfo {
+    pub id: String,
+    pub cred_count: usize
+}
@@ -1,6 +1,7 @@
 pub mod credential;
 pub mod credential_definition;
 pub mod credential_for_proof_request;
+pub mod credential_selection;
 pub mod credential_offer;
 pub mod credential_request;
 pub mod proof;
@@ -47,4 +47,10 @@ pub struct Identifier {
     pub cred_def_id: String,
     pub rev_reg_id: Option<String>,
     pub timestamp: Option<u64>
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct VerifyProofOptions {
+    #[serde(default)]
+    pub strict_encoding: bool
+}
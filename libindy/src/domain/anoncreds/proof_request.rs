@@ -54,4 +54,49 @@ pub struct RequestedAttributeInfo {
 pub struct RequestedPredicateInfo {
     pub predicate_referent: String,
     pub predicate_info: PredicateInfo
-}
\ No newline at end of file
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ProofRequestProblemType {
+    #[serde(rename = "unknown_restriction_key")]
+    UnknownRestrictionKey,
+    #[serde(rename = "malformed_restriction")]
+    MalformedRestriction,
+    #[serde(rename = "invalid_non_revoked_interval")]
+    InvalidNonRevokedInterval,
+    #[serde(rename = "conflicting_non_revoked_interval")]
+    ConflictingNonRevokedInterval,
+    #[serde(rename = "attribute_not_in_schema")]
+    AttributeNotInSchema,
+    #[serde(rename = "predicate_on_non_numeric_attribute")]
+    PredicateOnNonNumericAttribute
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum AttributeType {
+    #[serde(rename = "number")]
+    Number,
+    #[serde(rename = "string")]
+    String
+}
+
+// Schema id -> attribute name -> attribute type
+pub type SchemaAttributeTypes = HashMap<String, HashMap<String, AttributeType>>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ProofRequestProblem {
+    #[serde(rename = "type")]
+    pub type_: ProofRequestProblemType,
+    pub referent: Option<String>,
+    pub message: String
+}
+
+impl ProofRequestProblem {
+    pub fn new(type_: ProofRequestProblemType, referent: Option<&str>, message: String) -> ProofRequestProblem {
+        ProofRequestProblem {
+            type_,
+            referent: referent.map(String::from),
+            message
+        }
+    }
+}
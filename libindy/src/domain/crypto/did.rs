@@ -1,6 +1,7 @@
 extern crate indy_crypto;
 
 use named_type::NamedType;
+use domain::wallet::Tags;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct MyDidInfo {
@@ -49,7 +50,9 @@ pub struct DidMetadata {
 pub struct DidWithMeta {
     pub did: String,
     pub verkey: String,
-    pub metadata: Option<String>
+    pub metadata: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Tags>
 }
 
 #[derive(Serialize, Deserialize, Debug, NamedType)]
@@ -0,0 +1,23 @@
+use super::key::Key;
+use super::did::Did;
+
+/// Passphrase-encrypted export of a single key, DID or master secret.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncryptedExport {
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KeyExport {
+    pub key: Key,
+    pub metadata: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DidExport {
+    pub did: Did,
+    pub key: KeyExport,
+    pub metadata: Option<String>
+}
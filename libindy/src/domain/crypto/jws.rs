@@ -0,0 +1,51 @@
+pub const JWS_ALG_EDDSA: &'static str = "EdDSA";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct JWSHeader {
+    pub alg: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>
+}
+
+/// Flattened JWS JSON Serialization (RFC 7515, 7.2.2)
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct FlattenedJWS {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+    pub protected: String,
+    pub signature: String
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum JWSSerialization {
+    Compact,
+    Json
+}
+
+impl Default for JWSSerialization {
+    fn default() -> Self {
+        JWSSerialization::Compact
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct JWSSignOptions {
+    #[serde(default)]
+    pub serialization: JWSSerialization,
+    #[serde(default)]
+    pub detached: bool,
+    pub typ: Option<String>
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct JWSVerifyOptions {
+    pub signer: Option<String>,
+    #[serde(default)]
+    pub allow_verkey_kid: bool
+}
@@ -1,6 +1,7 @@
 extern crate indy_crypto;
 
 use named_type::NamedType;
+use domain::wallet::Tags;
 
 
 #[derive(Derivative)]
@@ -33,4 +34,22 @@ pub struct KeyInfo {
 #[derive(Serialize, Deserialize, Debug, NamedType)]
 pub struct KeyMetadata {
     pub value: String
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct KeyWithMeta {
+    pub verkey: String,
+    pub metadata: Option<String>,
+    pub tags: Tags
+}
+
+pub const ROOT_SEED_ID: &'static str = "root_seed";
+
+/// Wallet-held root seed used for deterministic derivation of keys and DIDs
+#[derive(Derivative)]
+#[derivative(Debug)]
+#[derive(Serialize, Deserialize, NamedType)]
+pub struct RootSeed {
+    #[derivative(Debug="ignore")]
+    pub value: String
+}
@@ -1,3 +1,7 @@
 pub mod key;
 pub mod did;
 pub mod combo_box;
+pub mod pack;
+pub mod jws;
+pub mod export;
+pub mod stream;
@@ -0,0 +1,17 @@
+use super::pack::Recipient;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct StreamHeader {
+    pub enc: String,
+    pub alg: String,
+    pub recipients: Vec<Recipient>,
+    pub nonce: Vec<u8>,
+    pub chunk_size: usize
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct StreamMetadata {
+    pub recipient_verkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_verkey: Option<String>
+}
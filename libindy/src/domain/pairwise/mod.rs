@@ -1,4 +1,5 @@
 use named_type::NamedType;
+use domain::wallet::Tags;
 
 #[derive(Serialize, Deserialize, NamedType)]
 pub struct Pairwise {
@@ -22,4 +23,24 @@ impl From<Pairwise> for PairwiseInfo {
             metadata: pairwise.metadata
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+pub struct PairwiseWithTags {
+    pub my_did: String,
+    pub their_did: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<String>,
+    pub tags: Tags
+}
+
+impl PairwiseWithTags {
+    pub fn new(pairwise: Pairwise, tags: Tags) -> Self {
+        PairwiseWithTags {
+            my_did: pairwise.my_did,
+            their_did: pairwise.their_did,
+            metadata: pairwise.metadata,
+            tags
+        }
+    }
+}
@@ -8,6 +8,7 @@ pub struct Config {
     pub id: String,
     pub storage_type: Option<String>,
     pub storage_config: Option<Value>,
+    pub cache_size: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -64,4 +65,101 @@ pub struct KeyConfig {
     pub seed: Option<String>
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CheckConfig {
+    #[serde(default)]
+    pub quarantine: bool
+}
+
+#[derive(Debug, Serialize)]
+pub struct WalletCheckReport {
+    pub checked: usize,
+    pub failed: Vec<WalletCheckFailure>,
+    pub quarantined: usize,
+    pub orphan_tags: usize,
+    pub storage_errors: Vec<String>
+}
+
+// Type and id are None if they can't be decrypted, encrypted id is base64 encoded
+#[derive(Debug, Serialize)]
+pub struct WalletCheckFailure {
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub id: Option<String>,
+    pub encrypted_id: String,
+    pub error: String
+}
+
 pub type Tags = HashMap<String, String>;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddRecordOptions {
+    pub expires_at: Option<u64>
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum RecordOperation {
+    Add {
+        #[serde(rename = "type")]
+        type_: String,
+        id: String,
+        value: String,
+        tags: Option<Tags>,
+        expires_at: Option<u64>
+    },
+    Update {
+        #[serde(rename = "type")]
+        type_: String,
+        id: String,
+        value: String
+    },
+    AddTags {
+        #[serde(rename = "type")]
+        type_: String,
+        id: String,
+        tags: Tags
+    },
+    UpdateTags {
+        #[serde(rename = "type")]
+        type_: String,
+        id: String,
+        tags: Tags
+    },
+    DeleteTags {
+        #[serde(rename = "type")]
+        type_: String,
+        id: String,
+        tag_names: Vec<String>
+    },
+    Delete {
+        #[serde(rename = "type")]
+        type_: String,
+        id: String
+    },
+}
+
+impl RecordOperation {
+    pub fn type_(&self) -> &str {
+        match *self {
+            RecordOperation::Add { ref type_, .. } |
+            RecordOperation::Update { ref type_, .. } |
+            RecordOperation::AddTags { ref type_, .. } |
+            RecordOperation::UpdateTags { ref type_, .. } |
+            RecordOperation::DeleteTags { ref type_, .. } |
+            RecordOperation::Delete { ref type_, .. } => type_
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match *self {
+            RecordOperation::Add { ref id, .. } |
+            RecordOperation::Update { ref id, .. } |
+            RecordOperation::AddTags { ref id, .. } |
+            RecordOperation::UpdateTags { ref id, .. } |
+            RecordOperation::DeleteTags { ref id, .. } |
+            RecordOperation::Delete { ref id, .. } => id
+        }
+    }
+}
@@ -19,6 +19,7 @@ pub enum AnoncredsError {
     InvalidUserRevocId(String),
     CredentialRevoked(String),
     CredDefAlreadyExists(String),
+    MasterSecretInUse(String),
     CommonError(CommonError)
 }
 
@@ -31,6 +32,7 @@ impl fmt::Display for AnoncredsError {
             AnoncredsError::InvalidUserRevocId(ref description) => write!(f, "Invalid revocation id: {}", description),
             AnoncredsError::CredentialRevoked(ref description) => write!(f, "Credential revoked: {}", description),
             AnoncredsError::CredDefAlreadyExists(ref description) => write!(f, "Credential definition already exists: {}", description),
+            AnoncredsError::MasterSecretInUse(ref description) => write!(f, "Master secret is in use: {}", description),
             AnoncredsError::CommonError(ref err) => err.fmt(f)
         }
     }
@@ -45,6 +47,7 @@ impl error::Error for AnoncredsError {
             AnoncredsError::InvalidUserRevocId(ref description) => description,
             AnoncredsError::CredentialRevoked(ref description) => description,
             AnoncredsError::CredDefAlreadyExists(ref description) => description,
+            AnoncredsError::MasterSecretInUse(ref description) => description,
             AnoncredsError::CommonError(ref err) => err.description()
         }
     }
@@ -57,6 +60,7 @@ impl error::Error for AnoncredsError {
             AnoncredsError::InvalidUserRevocId(_) => None,
             AnoncredsError::CredentialRevoked(_) => None,
             AnoncredsError::CredDefAlreadyExists(_) => None,
+            AnoncredsError::MasterSecretInUse(_) => None,
             AnoncredsError::CommonError(ref err) => Some(err)
         }
     }
@@ -71,6 +75,7 @@ impl ToErrorCode for AnoncredsError {
             AnoncredsError::InvalidUserRevocId(_) => ErrorCode::AnoncredsInvalidUserRevocId,
             AnoncredsError::CredentialRevoked(_) => ErrorCode::AnoncredsCredentialRevoked,
             AnoncredsError::CredDefAlreadyExists(_) => ErrorCode::AnoncredsCredDefAlreadyExistsError,
+            AnoncredsError::MasterSecretInUse(_) => ErrorCode::AnoncredsMasterSecretInUseError,
             AnoncredsError::CommonError(ref err) => err.to_error_code()
         }
     }
@@ -32,6 +32,7 @@ pub enum WalletError {
     EncryptionError(String),
     ItemNotFound,
     ItemAlreadyExists,
+    ItemVersionMismatch,
     QueryError(String),
 }
 
@@ -53,6 +54,7 @@ impl fmt::Display for WalletError {
             WalletError::EncryptionError(ref description) => write!(f, "Wallet encryption error occurred. Description: {}", description),
             WalletError::ItemNotFound => write!(f, "Item not found"),
             WalletError::ItemAlreadyExists => write!(f, "Item already exists"),
+            WalletError::ItemVersionMismatch => write!(f, "Item version mismatch"),
             WalletError::QueryError(ref description) => write!(f, "{}", description),
         }
     }
@@ -75,6 +77,7 @@ impl error::Error for WalletError {
             WalletError::EncryptionError(ref description) => description,
             WalletError::ItemNotFound => "Item not found",
             WalletError::ItemAlreadyExists => "Item already exists",
+            WalletError::ItemVersionMismatch => "Item version mismatch",
             WalletError::QueryError(ref description) => description,
         }
     }
@@ -95,6 +98,7 @@ impl error::Error for WalletError {
             WalletError::EncryptionError(_) => None,
             WalletError::ItemNotFound => None,
             WalletError::ItemAlreadyExists => None,
+            WalletError::ItemVersionMismatch => None,
             WalletError::QueryError(_) => None,
         }
     }
@@ -117,6 +121,7 @@ impl ToErrorCode for WalletError {
             WalletError::EncryptionError(_) => ErrorCode::WalletEncryptionError,
             WalletError::ItemNotFound => ErrorCode::WalletItemNotFound,
             WalletError::ItemAlreadyExists => ErrorCode::WalletItemAlreadyExists,
+            WalletError::ItemVersionMismatch => ErrorCode::WalletItemVersionMismatch,
             WalletError::QueryError(_) => ErrorCode::WalletQueryError,
         }
     }
@@ -149,6 +154,7 @@ impl From<WalletStorageError> for WalletError {
             WalletStorageError::NotFound => WalletError::NotFound(String::from("Storage not found")),
             WalletStorageError::ItemNotFound => WalletError::ItemNotFound,
             WalletStorageError::ItemAlreadyExists => WalletError::ItemAlreadyExists,
+            WalletStorageError::ItemVersionMismatch => WalletError::ItemVersionMismatch,
             WalletStorageError::PluggedStorageError(code) => WalletError::PluggedWalletError(code),
             _ => WalletError::StorageError(err.description().to_string())
         }
@@ -186,6 +192,8 @@ pub enum WalletStorageError {
     ConfigError,
     ItemNotFound,
     ItemAlreadyExists,
+    ItemVersionMismatch,
+    UnsupportedOperation(String),
     IOError(String),
     PluggedStorageError(ErrorCode),
     CommonError(CommonError),
@@ -239,6 +247,8 @@ impl error::Error for WalletStorageError {
             WalletStorageError::ConfigError => "Storage configuration is invalid",
             WalletStorageError::ItemNotFound => "Item not found",
             WalletStorageError::ItemAlreadyExists => "Item already exists",
+            WalletStorageError::ItemVersionMismatch => "Item version mismatch",
+            WalletStorageError::UnsupportedOperation(ref s) => s,
             WalletStorageError::PluggedStorageError(_err_code) => "Plugged storage error",
             WalletStorageError::IOError(ref s) => s,
             WalletStorageError::CommonError(ref e) => e.description(),
@@ -255,6 +265,8 @@ impl fmt::Display for WalletStorageError {
             WalletStorageError::ConfigError => write!(f, "Storage configuration is invalid"),
             WalletStorageError::ItemNotFound => write!(f, "Item not found"),
             WalletStorageError::ItemAlreadyExists => write!(f, "Item already exists"),
+            WalletStorageError::ItemVersionMismatch => write!(f, "Item version mismatch"),
+            WalletStorageError::UnsupportedOperation(ref s) => write!(f, "Unsupported storage operation: {}", s),
             WalletStorageError::IOError(ref s) => write!(f, "IO error occurred during storage operation: {}", s),
             WalletStorageError::PluggedStorageError(err_code) => write!(f, "Plugged storage error: {}", err_code as i32),
             WalletStorageError::CommonError(ref e) => write!(f, "Common error: {}", e.description()),
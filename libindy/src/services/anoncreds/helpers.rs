@@ -6,6 +6,8 @@ use domain::anoncreds::credential::AttributeValues;
 use domain::anoncreds::proof_request::{AttributeInfo, PredicateInfo};
 
 use self::indy_crypto::cl::{issuer, verifier, CredentialSchema, NonCredentialSchema, MasterSecret, CredentialValues, SubProofRequest};
+use self::indy_crypto::bn::BigNumber;
+use utils::crypto::hash::hash;
 
 
 use std::collections::{HashSet, HashMap};
@@ -14,6 +16,19 @@ pub fn attr_common_view(attr: &str) -> String {
     attr.replace(" ", "").to_lowercase()
 }
 
+/// Standard encoding of credential attribute values: 32-bit integers are encoded as themselves
+/// in normalized form (so "+42" and "0042" are encoded as "42"), any other value as the decimal
+/// representation of its sha256 digest.
+pub fn encode_attribute_value(raw: &str) -> Result<String, CommonError> {
+    if let Ok(value) = raw.parse::<i32>() {
+        return Ok(value.to_string());
+    }
+
+    let digest = hash(raw.as_bytes())?;
+
+    Ok(BigNumber::from_bytes(&digest)?.to_dec()?)
+}
+
 pub fn build_credential_schema(attrs: &HashSet<String>) -> Result<CredentialSchema, CommonError> {
     trace!("build_credential_schema >>> attrs: {:?}", attrs);
 
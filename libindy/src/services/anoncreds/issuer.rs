@@ -165,6 +165,33 @@ impl Issuer {
         Ok(rev_reg_delta)
     }
 
+    pub fn revoke_batch<RTA>(&self,
+                             rev_reg: &mut RevocationRegistry,
+                             max_cred_num: u32,
+                             rev_idxs: &[u32],
+                             rev_tails_accessor: &RTA) -> Result<Option<RevocationRegistryDelta>, AnoncredsError> where RTA: RevocationTailsAccessor {
+        trace!("revoke_batch >>> rev_reg: {:?}, max_cred_num: {:?}, rev_idxs: {:?}", rev_reg, max_cred_num, secret!(&rev_idxs));
+
+        let mut rev_reg_delta: Option<RevocationRegistryDelta> = None;
+
+        // Deltas of single revocations are merged into one delta covering the whole batch
+        for rev_idx in rev_idxs {
+            let other_rev_reg_delta = CryptoIssuer::revoke_credential(rev_reg, max_cred_num, *rev_idx, rev_tails_accessor)?;
+
+            rev_reg_delta = match rev_reg_delta.take() {
+                Some(mut delta) => {
+                    delta.merge(&other_rev_reg_delta)?;
+                    Some(delta)
+                }
+                None => Some(other_rev_reg_delta)
+            };
+        }
+
+        trace!("revoke_batch <<< rev_reg_delta {:?}", rev_reg_delta);
+
+        Ok(rev_reg_delta)
+    }
+
     #[allow(dead_code)]
     pub fn recovery<RTA>(&self,
                          rev_reg: &mut RevocationRegistry,
@@ -11,7 +11,9 @@ use domain::anoncreds::schema::SchemaV1;
 use domain::anoncreds::credential_definition::CredentialDefinitionV1 as CredentialDefinition;
 use domain::anoncreds::revocation_registry_definition::RevocationRegistryDefinitionV1;
 use domain::anoncreds::revocation_state::RevocationState;
-use domain::anoncreds::requested_credential::ProvingCredentialKey;
+use domain::anoncreds::requested_credential::{ProvingCredentialKey, RequestedAttribute};
+use domain::anoncreds::credential_for_proof_request::{CredentialsForProofRequest, RequestedCredential};
+use domain::anoncreds::credential_selection::{CredentialSelectionPolicy, CredentialSelection, SelectionStrategy};
 
 use errors::common::CommonError;
 use errors::anoncreds::AnoncredsError;
@@ -35,6 +37,15 @@ use std::collections::hash_map::Entry;
 
 const ATTRIBUTE_EXISTENCE_MARKER: &'static str = "1";
 
+// Credentials are tagged with the master secret they are bound to
+pub const MASTER_SECRET_ID_TAG: &'static str = "master_secret_id";
+
+// Credentials are tagged with time of storing (in nanoseconds) to select the newest ones
+pub const STORED_AT_TAG: &'static str = "stored_at";
+
+// Tags set by prover for own needs can't be used in restrictions of verifier
+const INTERNAL_TAG_NAMES: [&'static str; 2] = [MASTER_SECRET_ID_TAG, STORED_AT_TAG];
+
 pub struct Prover {}
 
 macro_rules! serde_map {
@@ -311,6 +322,10 @@ impl Prover {
             format!("attr::{}::marker", &attr_common_view(name)) => serde_json::Value::String(ATTRIBUTE_EXISTENCE_MARKER.to_string())
         )));
 
+        if let Some(ref restrictions) = *restrictions {
+            Prover::_check_restriction_tags(restrictions)?;
+        }
+
         match restrictions.as_ref() {
             // Convert old restrictions format to valid wql
             Some(&serde_json::Value::Array(ref array)) => {
@@ -354,6 +369,23 @@ impl Prover {
         Ok(res)
     }
 
+    fn _check_restriction_tags(restriction: &serde_json::Value) -> Result<(), CommonError> {
+        match *restriction {
+            serde_json::Value::Object(ref object) => {
+                for (key, value) in object.iter() {
+                    if INTERNAL_TAG_NAMES.contains(&key.as_str()) {
+                        return Err(CommonError::InvalidStructure(format!("Restriction on internal credential tag: {}", key)));
+                    }
+                    Prover::_check_restriction_tags(value)?;
+                }
+                Ok(())
+            }
+            serde_json::Value::Array(ref array) =>
+                array.iter().map(Prover::_check_restriction_tags).collect(),
+            _ => Ok(())
+        }
+    }
+
     pub fn attribute_satisfy_predicate(&self,
                                        predicate: &PredicateInfo,
                                        attribute_value: &str) -> Result<bool, CommonError> {
@@ -372,6 +404,149 @@ impl Prover {
         res
     }
 
+    pub fn select_credentials(&self,
+                              credentials_for_proof_req: &CredentialsForProofRequest,
+                              policy: &CredentialSelectionPolicy,
+                              stored_at: &HashMap<String, u64>) -> Result<CredentialSelection, CommonError> {
+        trace!("select_credentials >>> credentials_for_proof_req: {:?}, policy: {:?}, stored_at: {:?}", credentials_for_proof_req, policy, stored_at);
+
+        // Candidates are ranked per referent: credentials of preferred issuer go first,
+        // then the most recently stored ones according to stored_at time of credentials.
+        let mut referents: Vec<(bool, &String, Vec<&RequestedCredential>)> = Vec::new();
+
+        for (referent, candidates) in credentials_for_proof_req.attrs.iter() {
+            referents.push((false, referent, Prover::_rank_candidates(candidates, policy, stored_at)));
+        }
+
+        for (referent, candidates) in credentials_for_proof_req.predicates.iter() {
+            referents.push((true, referent, Prover::_rank_candidates(candidates, policy, stored_at)));
+        }
+
+        let mut selected: Vec<Option<&RequestedCredential>> = vec![None; referents.len()];
+
+        match policy.strategy {
+            SelectionStrategy::Newest => {
+                for (idx, &(_, _, ref candidates)) in referents.iter().enumerate() {
+                    selected[idx] = candidates.first().map(|candidate| *candidate);
+                }
+            }
+            SelectionStrategy::FewestCredentials => {
+                let mut unassigned: Vec<usize> = (0..referents.len())
+                    .filter(|idx| !referents[*idx].2.is_empty())
+                    .collect();
+
+                // Greedy set cover: take the credential covering most of unassigned referents,
+                // ties are resolved by candidates rank and then by credential id
+                while !unassigned.is_empty() {
+                    let mut coverage: HashMap<&str, (usize, usize)> = HashMap::new();
+
+                    for idx in unassigned.iter() {
+                        for (rank, candidate) in referents[*idx].2.iter().enumerate() {
+                            let entry = coverage.entry(candidate.cred_info.referent.as_str()).or_insert((0, rank));
+                            entry.0 += 1;
+                            entry.1 = ::std::cmp::min(entry.1, rank);
+                        }
+                    }
+
+                    let best_cred_id = coverage
+                        .iter()
+                        .max_by(|&(cred_id_a, &(count_a, rank_a)), &(cred_id_b, &(count_b, rank_b))|
+                            count_a.cmp(&count_b)
+                                .then(rank_b.cmp(&rank_a))
+                                .then(cred_id_b.cmp(cred_id_a)))
+                        .map(|(cred_id, _)| cred_id.to_string())
+                        .ok_or(CommonError::InvalidState("Credential candidates not found".to_string()))?;
+
+                    unassigned.retain(|idx| {
+                        match referents[*idx].2.iter().find(|candidate| candidate.cred_info.referent == best_cred_id) {
+                            Some(candidate) => {
+                                selected[*idx] = Some(*candidate);
+                                false
+                            }
+                            None => true
+                        }
+                    });
+                }
+            }
+        };
+
+        let mut requested_credentials = RequestedCredentials {
+            self_attested_attributes: HashMap::new(),
+            requested_attributes: HashMap::new(),
+            requested_predicates: HashMap::new()
+        };
+        let mut unmet_referents: Vec<String> = Vec::new();
+
+        for (idx, &(is_predicate, referent, _)) in referents.iter().enumerate() {
+            let candidate = match selected[idx] {
+                Some(candidate) => candidate,
+                None => {
+                    unmet_referents.push(referent.clone());
+                    continue;
+                }
+            };
+
+            let cred_id = candidate.cred_info.referent.clone();
+            let timestamp = Prover::_get_timestamp_for_candidate(candidate);
+
+            if is_predicate {
+                requested_credentials.requested_predicates.insert(referent.clone(), ProvingCredentialKey { cred_id, timestamp });
+            } else {
+                requested_credentials.requested_attributes.insert(referent.clone(), RequestedAttribute { cred_id, timestamp, revealed: true });
+            }
+        }
+
+        unmet_referents.sort();
+
+        let res = CredentialSelection { requested_credentials, unmet_referents };
+
+        trace!("select_credentials <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    fn _rank_candidates<'a>(candidates: &'a Vec<RequestedCredential>,
+                            policy: &CredentialSelectionPolicy,
+                            stored_at: &HashMap<String, u64>) -> Vec<&'a RequestedCredential> {
+        let mut ranked: Vec<&RequestedCredential> = candidates
+            .iter()
+            .filter(|candidate| !Prover::_is_known_revoked(candidate, policy))
+            .collect();
+
+        // Credentials with unknown time of storing are treated as the oldest ones,
+        // ties are resolved by credential id to make selection deterministic
+        let stored_at_of = |candidate: &RequestedCredential| stored_at.get(&candidate.cred_info.referent).cloned().unwrap_or(0);
+        ranked.sort_by(|a, b| stored_at_of(b).cmp(&stored_at_of(a))
+            .then(b.cred_info.referent.cmp(&a.cred_info.referent)));
+
+        if let Some(ref issuer_did) = policy.issuer_did {
+            // sort is stable so order of storing is kept inside of both groups
+            ranked.sort_by_key(|candidate| Prover::_issuer_did_for_candidate(candidate) != issuer_did.as_str());
+        }
+
+        ranked
+    }
+
+    fn _is_known_revoked(candidate: &RequestedCredential, policy: &CredentialSelectionPolicy) -> bool {
+        match (candidate.cred_info.rev_reg_id.as_ref(), candidate.cred_info.cred_rev_id.as_ref()) {
+            (Some(rev_reg_id), Some(cred_rev_id)) =>
+                policy.revoked.get(rev_reg_id).map(|revoked| revoked.contains(cred_rev_id)).unwrap_or(false),
+            _ => false
+        }
+    }
+
+    fn _issuer_did_for_candidate(candidate: &RequestedCredential) -> &str {
+        candidate.cred_info.cred_def_id.split(':').next().unwrap_or("")
+    }
+
+    fn _get_timestamp_for_candidate(candidate: &RequestedCredential) -> Option<u64> {
+        if candidate.cred_info.rev_reg_id.is_some() {
+            candidate.interval.as_ref().and_then(|interval| interval.to)
+        } else {
+            None
+        }
+    }
+
     fn _update_requested_proof(&self, req_attrs_for_credential: Vec<RequestedAttributeInfo>,
                                req_predicates_for_credential: Vec<RequestedPredicateInfo>,
                                proof_req: &ProofRequest,
@@ -769,6 +944,22 @@ mod tests {
 
             assert_eq!(expected_query, _value(&query));
         }
+
+        #[test]
+        fn build_query_works_for_restriction_on_internal_tag() {
+            let ps = Prover::new();
+
+            let restrictions = vec![
+                json!({"master_secret_id": "master_secret"}),
+                json!({"$or": [{"schema_id": SCHEMA_ID}, {"$not": {"stored_at": {"$gt": "0"}}}]}),
+                json!([{"schema_id": SCHEMA_ID}, {"master_secret_id": "master_secret"}]),
+            ];
+
+            for restriction in restrictions {
+                let res = ps.build_query(ATTR_NAME, ATTR_REFERENT, &Some(restriction), &None);
+                assert_match!(Err(CommonError::InvalidStructure(_)), res);
+            }
+        }
     }
 
     mod attribute_satisfy_predicate {
@@ -995,4 +1186,130 @@ mod tests {
             assert_eq!(_attr_values(), res);
         }
     }
+
+    mod select_credentials {
+        use super::*;
+        use domain::anoncreds::credential::CredentialInfo;
+
+        const ISSUER_DID_2: &'static str = "did2";
+
+        fn _candidate(cred_id: &str, issuer_did: &str, rev_reg_id: Option<&str>, interval: Option<NonRevocedInterval>) -> RequestedCredential {
+            RequestedCredential {
+                cred_info: CredentialInfo {
+                    referent: cred_id.to_string(),
+                    attrs: HashMap::new(),
+                    schema_id: SCHEMA_ID.to_string(),
+                    cred_def_id: format!("{}:3:CL:1:TAG", issuer_did),
+                    rev_reg_id: rev_reg_id.map(String::from),
+                    cred_rev_id: rev_reg_id.map(|_| cred_id.to_string()),
+                },
+                interval
+            }
+        }
+
+        fn _credentials_for_proof_req() -> CredentialsForProofRequest {
+            CredentialsForProofRequest {
+                attrs: hashmap!(
+                    "attr1_referent".to_string() => vec![_candidate("1", ISSUER_DID, None, None), _candidate("2", ISSUER_DID_2, None, None)],
+                    "attr2_referent".to_string() => vec![_candidate("3", ISSUER_DID, None, None)],
+                    "attr3_referent".to_string() => vec![]
+                ),
+                predicates: hashmap!(
+                    "predicate1_referent".to_string() => vec![_candidate("3", ISSUER_DID, None, None), _candidate("4", ISSUER_DID, None, None)]
+                )
+            }
+        }
+
+        fn _selected_cred_ids(selection: &CredentialSelection) -> HashMap<String, String> {
+            let mut res: HashMap<String, String> = HashMap::new();
+            for (referent, attr) in selection.requested_credentials.requested_attributes.iter() {
+                res.insert(referent.clone(), attr.cred_id.clone());
+            }
+            for (referent, predicate) in selection.requested_credentials.requested_predicates.iter() {
+                res.insert(referent.clone(), predicate.cred_id.clone());
+            }
+            res
+        }
+
+        #[test]
+        fn select_credentials_works_for_newest() {
+            let ps = Prover::new();
+
+            let stored_at = hashmap!(
+                "1".to_string() => 40,
+                "2".to_string() => 30,
+                "3".to_string() => 20
+            );
+            let selection = ps.select_credentials(&_credentials_for_proof_req(), &CredentialSelectionPolicy::default(), &stored_at).unwrap();
+
+            let expected = hashmap!(
+                "attr1_referent".to_string() => "1".to_string(),
+                "attr2_referent".to_string() => "3".to_string(),
+                "predicate1_referent".to_string() => "3".to_string()
+            );
+            assert_eq!(expected, _selected_cred_ids(&selection));
+            assert_eq!(vec!["attr3_referent".to_string()], selection.unmet_referents);
+        }
+
+        #[test]
+        fn select_credentials_works_for_preferred_issuer() {
+            let ps = Prover::new();
+
+            let policy = CredentialSelectionPolicy { issuer_did: Some(ISSUER_DID.to_string()), ..CredentialSelectionPolicy::default() };
+
+            // credential of other issuer is newer but issuer preference goes first
+            let stored_at = hashmap!("1".to_string() => 10, "2".to_string() => 20);
+            let selection = ps.select_credentials(&_credentials_for_proof_req(), &policy, &stored_at).unwrap();
+
+            assert_eq!("1", _selected_cred_ids(&selection)["attr1_referent"]);
+        }
+
+        #[test]
+        fn select_credentials_works_for_newest_without_stored_at() {
+            let ps = Prover::new();
+
+            let selection = ps.select_credentials(&_credentials_for_proof_req(), &CredentialSelectionPolicy::default(), &HashMap::new()).unwrap();
+
+            assert_eq!("2", _selected_cred_ids(&selection)["attr1_referent"]);
+            assert_eq!("4", _selected_cred_ids(&selection)["predicate1_referent"]);
+        }
+
+        #[test]
+        fn select_credentials_works_for_fewest_credentials() {
+            let ps = Prover::new();
+
+            let policy = CredentialSelectionPolicy { strategy: SelectionStrategy::FewestCredentials, ..CredentialSelectionPolicy::default() };
+            let selection = ps.select_credentials(&_credentials_for_proof_req(), &policy, &HashMap::new()).unwrap();
+
+            let selected = _selected_cred_ids(&selection);
+            assert_eq!("3", selected["attr2_referent"]);
+            assert_eq!("3", selected["predicate1_referent"]);
+            assert_eq!(vec!["attr3_referent".to_string()], selection.unmet_referents);
+        }
+
+        #[test]
+        fn select_credentials_works_for_known_revoked_and_interval() {
+            let ps = Prover::new();
+
+            let interval = Some(NonRevocedInterval { from: None, to: Some(100) });
+
+            let credentials_for_proof_req = CredentialsForProofRequest {
+                attrs: hashmap!(
+                    "attr1_referent".to_string() => vec![_candidate("1", ISSUER_DID, Some(REV_REG_ID), interval.clone()),
+                                                         _candidate("2", ISSUER_DID, Some(REV_REG_ID), interval.clone())]
+                ),
+                predicates: HashMap::new()
+            };
+
+            let policy = CredentialSelectionPolicy {
+                revoked: hashmap!(REV_REG_ID.to_string() => vec!["2".to_string()].into_iter().collect()),
+                ..CredentialSelectionPolicy::default()
+            };
+            let selection = ps.select_credentials(&credentials_for_proof_req, &policy, &HashMap::new()).unwrap();
+
+            let requested_attr = &selection.requested_credentials.requested_attributes["attr1_referent"];
+            assert_eq!("1", requested_attr.cred_id);
+            assert_eq!(Some(100), requested_attr.timestamp);
+        }
+    }
 }
@@ -1,4 +1,5 @@
 extern crate indy_crypto;
+extern crate serde_json;
 
 use std::collections::HashMap;
 use errors::common::CommonError;
@@ -10,8 +11,12 @@ use domain::anoncreds::schema::SchemaV1;
 use domain::anoncreds::credential_definition::CredentialDefinitionV1 as CredentialDefinition;
 use domain::anoncreds::revocation_registry_definition::RevocationRegistryDefinitionV1;
 use domain::anoncreds::proof::{Proof, RequestedProof};
-use domain::anoncreds::proof_request::{ProofRequest, AttributeInfo, PredicateInfo};
+use domain::anoncreds::proof_request::{ProofRequest, AttributeInfo, PredicateInfo, NonRevocedInterval, ProofRequestProblem, ProofRequestProblemType,
+                                        AttributeType, SchemaAttributeTypes};
 use domain::anoncreds::revocation_registry::RevocationRegistryV1;
+use services::wallet::language::{self, Operator, TagName, TargetValue};
+
+const CREDENTIAL_TAG_NAMES: [&'static str; 7] = ["schema_id", "schema_issuer_did", "schema_name", "schema_version", "issuer_did", "cred_def_id", "rev_reg_id"];
 
 pub struct Verifier {}
 
@@ -26,9 +31,15 @@ impl Verifier {
                   schemas: &HashMap<String, SchemaV1>,
                   cred_defs: &HashMap<String, CredentialDefinition>,
                   rev_reg_defs: &HashMap<String, RevocationRegistryDefinitionV1>,
-                  rev_regs: &HashMap<String, HashMap<u64, RevocationRegistryV1>>) -> Result<bool, CommonError> {
-        trace!("verify >>> full_proof: {:?}, proof_req: {:?}, schemas: {:?}, cred_defs: {:?}, rev_reg_defs: {:?} rev_regs: {:?}",
-               full_proof, proof_req, schemas, cred_defs, rev_reg_defs, rev_regs);
+                  rev_regs: &HashMap<String, HashMap<u64, RevocationRegistryV1>>,
+                  strict_encoding: bool) -> Result<bool, CommonError> {
+        trace!("verify >>> full_proof: {:?}, proof_req: {:?}, schemas: {:?}, cred_defs: {:?}, rev_reg_defs: {:?} rev_regs: {:?}, strict_encoding: {:?}",
+               full_proof, proof_req, schemas, cred_defs, rev_reg_defs, rev_regs, strict_encoding);
+
+        if strict_encoding && !Verifier::_check_revealed_attrs_encoding(&full_proof.requested_proof)? {
+            trace!("verify <<< valid: false, revealed raw values do not match encoded values");
+            return Ok(false);
+        }
 
         let mut proof_verifier = CryptoVerifier::new_proof_verifier()?;
         let non_credential_schema = build_non_credential_schema()?;
@@ -76,6 +87,244 @@ impl Verifier {
         Ok(valid)
     }
 
+    pub fn validate_proof_request(&self,
+                                  proof_req: &ProofRequest,
+                                  schemas: &HashMap<String, SchemaV1>,
+                                  attr_types: &SchemaAttributeTypes) -> Result<Vec<ProofRequestProblem>, CommonError> {
+        trace!("validate_proof_request >>> proof_req: {:?}, schemas: {:?}, attr_types: {:?}", proof_req, schemas, attr_types);
+
+        let mut problems: Vec<ProofRequestProblem> = Vec::new();
+
+        if let Some(ref interval) = proof_req.non_revoked {
+            Verifier::_validate_non_revoked_interval(None, interval, &mut problems);
+        }
+
+        for (referent, attr_info) in proof_req.requested_attributes.iter() {
+            Verifier::_validate_requested_item(referent, &attr_info.name, &attr_info.restrictions, &proof_req.non_revoked,
+                                               &attr_info.non_revoked, schemas, &mut problems);
+        }
+
+        for (referent, predicate_info) in proof_req.requested_predicates.iter() {
+            let schema_ids = Verifier::_validate_requested_item(referent, &predicate_info.name, &predicate_info.restrictions, &proof_req.non_revoked,
+                                                                &predicate_info.non_revoked, schemas, &mut problems);
+            Verifier::_validate_predicate_attr_type(referent, &predicate_info.name, &schema_ids, attr_types, &mut problems);
+        }
+
+        trace!("validate_proof_request <<< problems: {:?}", problems);
+
+        Ok(problems)
+    }
+
+    fn _validate_requested_item(referent: &str,
+                                attr_name: &str,
+                                restrictions: &Option<serde_json::Value>,
+                                global_interval: &Option<NonRevocedInterval>,
+                                local_interval: &Option<NonRevocedInterval>,
+                                schemas: &HashMap<String, SchemaV1>,
+                                problems: &mut Vec<ProofRequestProblem>) -> Vec<String> {
+        if let Some(ref interval) = *local_interval {
+            Verifier::_validate_non_revoked_interval(Some(referent), interval, problems);
+
+            if let Some(ref global_interval) = *global_interval {
+                if Verifier::_intervals_are_disjoint(global_interval, interval) {
+                    problems.push(ProofRequestProblem::new(
+                        ProofRequestProblemType::ConflictingNonRevokedInterval, Some(referent),
+                        format!("Interval {:?} does not intersect proof request interval {:?}", interval, global_interval)));
+                }
+            }
+        }
+
+        let query = match Verifier::_parse_restrictions(restrictions) {
+            Ok(query) => query,
+            Err(err) => {
+                problems.push(ProofRequestProblem::new(ProofRequestProblemType::MalformedRestriction, Some(referent), err));
+                return Vec::new();
+            }
+        };
+
+        let mut schema_ids: Vec<String> = Vec::new();
+
+        if let Some(ref query) = query {
+            Verifier::_validate_restriction_keys(referent, query, problems);
+            Verifier::_collect_schema_ids(query, &mut schema_ids);
+        }
+
+        let schemas_to_check: Vec<&SchemaV1> =
+            if schema_ids.is_empty() {
+                schemas.values().collect()
+            } else {
+                schema_ids.iter().filter_map(|schema_id| schemas.get(schema_id)).collect()
+            };
+
+        if !schemas_to_check.is_empty() &&
+            !schemas_to_check.iter().any(|schema| schema.attr_names.iter().any(|name| attr_common_view(name) == attr_common_view(attr_name))) {
+            problems.push(ProofRequestProblem::new(
+                ProofRequestProblemType::AttributeNotInSchema, Some(referent),
+                format!("Attribute \"{}\" not found in schemas {:?}", attr_name,
+                        schemas_to_check.iter().map(|schema| schema.id.as_str()).collect::<Vec<&str>>())));
+        }
+
+        schema_ids
+    }
+
+    // Predicate can be proven only for attributes with integer encoding,
+    // so it is reported only if no schema allowed by restrictions has the attribute as number
+    fn _validate_predicate_attr_type(referent: &str,
+                                     attr_name: &str,
+                                     schema_ids: &[String],
+                                     attr_types: &SchemaAttributeTypes,
+                                     problems: &mut Vec<ProofRequestProblem>) {
+        let attr_schema_types: Vec<(&str, &AttributeType)> = attr_types.iter()
+            .filter(|&(schema_id, _)| schema_ids.is_empty() || schema_ids.contains(schema_id))
+            .filter_map(|(schema_id, types)| types.iter()
+                .find(|&(name, _)| attr_common_view(name) == attr_common_view(attr_name))
+                .map(|(_, type_)| (schema_id.as_str(), type_)))
+            .collect();
+
+        let non_numeric_schema_ids: Vec<&str> = attr_schema_types.iter()
+            .filter(|&&(_, type_)| *type_ == AttributeType::String)
+            .map(|&(schema_id, _)| schema_id)
+            .collect();
+
+        if !non_numeric_schema_ids.is_empty() && non_numeric_schema_ids.len() == attr_schema_types.len() {
+            problems.push(ProofRequestProblem::new(
+                ProofRequestProblemType::PredicateOnNonNumericAttribute, Some(referent),
+                format!("Predicate on attribute \"{}\" which is not numeric in schemas {:?}", attr_name, non_numeric_schema_ids)));
+        }
+    }
+
+    fn _validate_non_revoked_interval(referent: Option<&str>,
+                                      interval: &NonRevocedInterval,
+                                      problems: &mut Vec<ProofRequestProblem>) {
+        if let (Some(from), Some(to)) = (interval.from, interval.to) {
+            if from > to {
+                problems.push(ProofRequestProblem::new(
+                    ProofRequestProblemType::InvalidNonRevokedInterval, referent,
+                    format!("Interval start {} is after interval end {}", from, to)));
+            }
+        }
+    }
+
+    fn _intervals_are_disjoint(first: &NonRevocedInterval, second: &NonRevocedInterval) -> bool {
+        let ends_before = |a: &NonRevocedInterval, b: &NonRevocedInterval|
+            match (a.to, b.from) {
+                (Some(to), Some(from)) => to < from,
+                _ => false
+            };
+
+        ends_before(first, second) || ends_before(second, first)
+    }
+
+    fn _parse_restrictions(restrictions: &Option<serde_json::Value>) -> Result<Option<Operator>, String> {
+        let query = match *restrictions {
+            // Restrictions in old format are converted to wql as it is done by prover
+            Some(serde_json::Value::Array(ref array)) => {
+                let mut sub_queries: Vec<serde_json::Value> = Vec::new();
+
+                for sub_query in array {
+                    let sub_query = sub_query.as_object()
+                        .ok_or("Restriction in array format must contain only objects".to_string())?
+                        .clone()
+                        .into_iter()
+                        .filter(|&(_, ref v)| !v.is_null())
+                        .collect();
+                    sub_queries.push(serde_json::Value::Object(sub_query));
+                }
+
+                json!({"$or": sub_queries})
+            }
+            Some(serde_json::Value::Object(ref object)) => serde_json::Value::Object(object.clone()),
+            None => return Ok(None),
+            _ => return Err("Restriction must be JSON object or array".to_string())
+        };
+
+        language::parse_from_json(&query.to_string())
+            .map(Some)
+            .map_err(|err| err.to_string())
+    }
+
+    fn _validate_restriction_keys(referent: &str,
+                                  query: &Operator,
+                                  problems: &mut Vec<ProofRequestProblem>) {
+        let tag_name = match *query {
+            Operator::And(ref operators) | Operator::Or(ref operators) => {
+                operators.iter().for_each(|operator| Verifier::_validate_restriction_keys(referent, operator, problems));
+                return;
+            }
+            Operator::Not(ref operator) => {
+                Verifier::_validate_restriction_keys(referent, operator, problems);
+                return;
+            }
+            Operator::Eq(ref tag_name, _) |
+            Operator::Neq(ref tag_name, _) |
+            Operator::Gt(ref tag_name, _) |
+            Operator::Gte(ref tag_name, _) |
+            Operator::Lt(ref tag_name, _) |
+            Operator::Lte(ref tag_name, _) |
+            Operator::Like(ref tag_name, _) |
+            Operator::In(ref tag_name, _) => tag_name
+        };
+
+        match *tag_name {
+            TagName::EncryptedTagName(ref name) => {
+                let name = String::from_utf8_lossy(name);
+
+                if !Verifier::_is_credential_tag_name(&name) {
+                    problems.push(ProofRequestProblem::new(
+                        ProofRequestProblemType::UnknownRestrictionKey, Some(referent),
+                        format!("Unknown restriction key \"{}\"", name)));
+                }
+            }
+            TagName::PlainTagName(ref name) => {
+                problems.push(ProofRequestProblem::new(
+                    ProofRequestProblemType::UnknownRestrictionKey, Some(referent),
+                    format!("Unknown restriction key \"~{}\": credentials have no plaintext tags", String::from_utf8_lossy(name))));
+            }
+        }
+    }
+
+    fn _is_credential_tag_name(name: &str) -> bool {
+        if CREDENTIAL_TAG_NAMES.iter().any(|tag_name| *tag_name == name) {
+            return true;
+        }
+
+        let parts: Vec<&str> = name.split("::").collect();
+
+        parts.len() == 3 && parts[0] == "attr" && !parts[1].is_empty() && (parts[2] == "marker" || parts[2] == "value")
+    }
+
+    fn _collect_schema_ids(query: &Operator, schema_ids: &mut Vec<String>) {
+        match *query {
+            Operator::And(ref operators) | Operator::Or(ref operators) =>
+                operators.iter().for_each(|operator| Verifier::_collect_schema_ids(operator, schema_ids)),
+            Operator::Eq(TagName::EncryptedTagName(ref name), TargetValue::Unencrypted(ref value)) if name.as_slice() == b"schema_id" =>
+                schema_ids.push(value.clone()),
+            Operator::In(TagName::EncryptedTagName(ref name), ref values) if name.as_slice() == b"schema_id" =>
+                values.iter().for_each(|value|
+                    if let TargetValue::Unencrypted(ref value) = *value {
+                        schema_ids.push(value.clone())
+                    }),
+            _ => {}
+        }
+    }
+
+    // Every raw value must match the standard encoding, so a prover cannot reveal a raw string
+    // that differs from the proven encoded value. Used in strict encoding mode only.
+    fn _check_revealed_attrs_encoding(requested_proof: &RequestedProof) -> Result<bool, CommonError> {
+        trace!("_check_revealed_attrs_encoding >>> requested_proof: {:?}", requested_proof);
+
+        for (attr_referent, revealed_attr_info) in requested_proof.revealed_attrs.iter() {
+            if encode_attribute_value(&revealed_attr_info.raw)? != revealed_attr_info.encoded {
+                trace!("_check_revealed_attrs_encoding <<< attribute {:?} raw value does not match encoded value", attr_referent);
+                return Ok(false);
+            }
+        }
+
+        trace!("_check_revealed_attrs_encoding <<< true");
+
+        Ok(true)
+    }
+
     fn _get_revealed_attributes_for_credential(sub_proof_index: usize,
                                                requested_proof: &RequestedProof,
                                                proof_req: &ProofRequest) -> Result<Vec<AttributeInfo>, CommonError> {
@@ -114,3 +363,199 @@ impl Verifier {
         Ok(predicates_for_credential)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::anoncreds::proof::RevealedAttributeInfo;
+
+    const SCHEMA_ID: &'static str = "NcYxiDXkpYi6ov5FcYDi1e:2:gvt:1.0";
+
+    fn _schemas() -> HashMap<String, SchemaV1> {
+        let mut schemas = HashMap::new();
+        schemas.insert(SCHEMA_ID.to_string(), SchemaV1 {
+            id: SCHEMA_ID.to_string(),
+            name: "gvt".to_string(),
+            version: "1.0".to_string(),
+            attr_names: vec!["name".to_string(), "age".to_string()].into_iter().collect(),
+            seq_no: None,
+        });
+        schemas
+    }
+
+    fn _attr_types() -> SchemaAttributeTypes {
+        let mut types = HashMap::new();
+        types.insert("name".to_string(), AttributeType::String);
+        types.insert("age".to_string(), AttributeType::Number);
+
+        let mut attr_types = HashMap::new();
+        attr_types.insert(SCHEMA_ID.to_string(), types);
+        attr_types
+    }
+
+    fn _proof_req(proof_req: serde_json::Value) -> ProofRequest {
+        let mut proof_req = proof_req;
+        proof_req["nonce"] = json!("123432421212");
+        proof_req["name"] = json!("proof_req_1");
+        proof_req["version"] = json!("0.1");
+        serde_json::from_value(proof_req).unwrap()
+    }
+
+    fn _problem_types(proof_req: serde_json::Value) -> Vec<ProofRequestProblemType> {
+        Verifier::new().validate_proof_request(&_proof_req(proof_req), &_schemas(), &_attr_types()).unwrap()
+            .into_iter()
+            .map(|problem| problem.type_)
+            .collect()
+    }
+
+    #[test]
+    fn validate_proof_request_works() {
+        let problems = _problem_types(json!({
+            "requested_attributes": {
+                "attr1_referent": {"name": "name", "restrictions": {"schema_id": SCHEMA_ID, "attr::name::value": "Alex"}}
+            },
+            "requested_predicates": {
+                "predicate1_referent": {"name": "age", "p_type": ">=", "p_value": 18, "restrictions": [{"issuer_did": "NcYxiDXkpYi6ov5FcYDi1e", "schema_id": null}]}
+            },
+            "non_revoked": {"from": 10, "to": 100}
+        }));
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn validate_proof_request_works_for_unknown_restriction_key() {
+        let problems = _problem_types(json!({
+            "requested_attributes": {
+                "attr1_referent": {"name": "name", "restrictions": {"$or": [{"issuer": "NcYxiDXkpYi6ov5FcYDi1e"}, {"attr::name": "Alex"}]}}
+            },
+            "requested_predicates": {}
+        }));
+        assert_eq!(vec![ProofRequestProblemType::UnknownRestrictionKey, ProofRequestProblemType::UnknownRestrictionKey], problems);
+    }
+
+    #[test]
+    fn validate_proof_request_works_for_malformed_operator() {
+        let problems = _problem_types(json!({
+            "requested_attributes": {
+                "attr1_referent": {"name": "name", "restrictions": {"schema_name": {"$gt": "gvt"}}},
+                "attr2_referent": {"name": "name", "restrictions": {"schema_name": {"$regex": "gvt"}}},
+                "attr3_referent": {"name": "name", "restrictions": "gvt"}
+            },
+            "requested_predicates": {}
+        }));
+        assert_eq!(vec![ProofRequestProblemType::MalformedRestriction; 3], problems);
+    }
+
+    #[test]
+    fn validate_proof_request_works_for_invalid_non_revoked_interval() {
+        let problems = _problem_types(json!({
+            "requested_attributes": {
+                "attr1_referent": {"name": "name", "non_revoked": {"from": 100, "to": 10}}
+            },
+            "requested_predicates": {}
+        }));
+        assert_eq!(vec![ProofRequestProblemType::InvalidNonRevokedInterval], problems);
+    }
+
+    #[test]
+    fn validate_proof_request_works_for_conflicting_non_revoked_intervals() {
+        let problems = _problem_types(json!({
+            "requested_attributes": {
+                "attr1_referent": {"name": "name", "non_revoked": {"from": 200, "to": 300}},
+                "attr2_referent": {"name": "name", "non_revoked": {"from": 50, "to": 150}}
+            },
+            "requested_predicates": {},
+            "non_revoked": {"from": 10, "to": 100}
+        }));
+        assert_eq!(vec![ProofRequestProblemType::ConflictingNonRevokedInterval], problems);
+    }
+
+    #[test]
+    fn validate_proof_request_works_for_predicate_on_attribute_not_in_schema() {
+        let problems = _problem_types(json!({
+            "requested_attributes": {},
+            "requested_predicates": {
+                "predicate1_referent": {"name": "height", "p_type": ">=", "p_value": 150, "restrictions": {"schema_id": SCHEMA_ID}}
+            }
+        }));
+        assert_eq!(vec![ProofRequestProblemType::AttributeNotInSchema], problems);
+    }
+
+    #[test]
+    fn validate_proof_request_works_for_predicate_on_non_numeric_attribute() {
+        let problems = _problem_types(json!({
+            "requested_attributes": {},
+            "requested_predicates": {
+                "predicate1_referent": {"name": "Name", "p_type": ">=", "p_value": 1, "restrictions": {"schema_id": SCHEMA_ID}},
+                "predicate2_referent": {"name": "name", "p_type": ">=", "p_value": 1},
+                "predicate3_referent": {"name": "age", "p_type": ">=", "p_value": 18}
+            }
+        }));
+        assert_eq!(vec![ProofRequestProblemType::PredicateOnNonNumericAttribute; 2], problems);
+    }
+
+    #[test]
+    fn validate_proof_request_works_for_predicate_on_attribute_numeric_in_other_schema() {
+        let mut types = HashMap::new();
+        types.insert("name".to_string(), AttributeType::Number);
+
+        let mut attr_types = _attr_types();
+        attr_types.insert("other_schema_id".to_string(), types);
+
+        let proof_req = _proof_req(json!({
+            "requested_attributes": {},
+            "requested_predicates": {
+                "predicate1_referent": {"name": "name", "p_type": ">=", "p_value": 1},
+                "predicate2_referent": {"name": "name", "p_type": ">=", "p_value": 1, "restrictions": {"issuer_did": "NcYxiDXkpYi6ov5FcYDi1e"}},
+                "predicate3_referent": {"name": "name", "p_type": ">=", "p_value": 1, "restrictions": {"schema_id": SCHEMA_ID}}
+            }
+        }));
+
+        let problems = Verifier::new().validate_proof_request(&proof_req, &_schemas(), &attr_types).unwrap();
+        assert_eq!(1, problems.len());
+        assert_eq!(ProofRequestProblemType::PredicateOnNonNumericAttribute, problems[0].type_);
+        assert_eq!(Some("predicate3_referent".to_string()), problems[0].referent);
+    }
+
+    #[test]
+    fn validate_proof_request_works_for_restriction_on_unknown_schema() {
+        let problems = _problem_types(json!({
+            "requested_attributes": {},
+            "requested_predicates": {
+                "predicate1_referent": {"name": "height", "p_type": ">=", "p_value": 150, "restrictions": {"schema_id": "other_schema_id"}}
+            }
+        }));
+        assert!(problems.is_empty());
+    }
+
+    fn _requested_proof(raw: &str, encoded: &str) -> RequestedProof {
+        let mut revealed_attrs = HashMap::new();
+        revealed_attrs.insert("attr1_referent".to_string(),
+                              RevealedAttributeInfo { sub_proof_index: 0, raw: raw.to_string(), encoded: encoded.to_string() });
+
+        RequestedProof { revealed_attrs, ..RequestedProof::default() }
+    }
+
+    #[test]
+    fn check_revealed_attrs_encoding_works_for_integer() {
+        assert!(Verifier::_check_revealed_attrs_encoding(&_requested_proof("28", "28")).unwrap());
+        assert!(Verifier::_check_revealed_attrs_encoding(&_requested_proof("-28", "-28")).unwrap());
+        assert!(!Verifier::_check_revealed_attrs_encoding(&_requested_proof("28", "29")).unwrap());
+    }
+
+    #[test]
+    fn check_revealed_attrs_encoding_works_for_string() {
+        let encoded = "5944657099558967239210949258394887428692050081607692519917050011144233115103";
+
+        assert!(Verifier::_check_revealed_attrs_encoding(&_requested_proof("male", encoded)).unwrap());
+        assert!(!Verifier::_check_revealed_attrs_encoding(&_requested_proof("female", encoded)).unwrap());
+    }
+
+    #[test]
+    fn check_revealed_attrs_encoding_works_for_non_canonical_integer() {
+        assert!(Verifier::_check_revealed_attrs_encoding(&_requested_proof("+28", "28")).unwrap());
+        assert!(Verifier::_check_revealed_attrs_encoding(&_requested_proof("0028", "28")).unwrap());
+        assert!(Verifier::_check_revealed_attrs_encoding(&_requested_proof("-0028", "-28")).unwrap());
+        assert!(!Verifier::_check_revealed_attrs_encoding(&_requested_proof("0028", "0028")).unwrap());
+    }
+}
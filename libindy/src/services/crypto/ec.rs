@@ -0,0 +1,140 @@
+use super::CryptoType;
+use utils::crypto::ec;
+use utils::crypto::chacha20poly1305_ietf;
+use utils::crypto::hash::hash;
+use errors::common::CommonError;
+use errors::crypto::CryptoError;
+
+
+/// Crypto type over short Weierstrass curves (secp256k1, NIST P-256).
+///
+/// Signatures are ECDSA over sha256. Encryption uses ECDH shared secret hashed by sha256
+/// as chacha20poly1305_ietf key. Sealed boxes use ephemeral key pair and are serialized
+/// as ephemeral public key || nonce || ciphertext.
+pub struct ECCryptoType {
+    curve: ec::Curve
+}
+
+impl ECCryptoType {
+    pub fn new(curve: ec::Curve) -> ECCryptoType {
+        ECCryptoType { curve }
+    }
+
+    fn _shared_key(&self, sk: &[u8], vk: &[u8], context: &[&[u8]]) -> Result<chacha20poly1305_ietf::Key, CryptoError> {
+        let mut material = ec::ecdh(self.curve, sk, vk)?;
+
+        for item in context {
+            material.extend_from_slice(item);
+        }
+
+        Ok(chacha20poly1305_ietf::Key::from_slice(&hash(&material)?)?)
+    }
+}
+
+impl CryptoType for ECCryptoType {
+    fn encrypt(&self, sk: &[u8], vk: &[u8], doc: &[u8], nonce: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let key = self._shared_key(sk, vk, &[])?;
+        let nonce = chacha20poly1305_ietf::Nonce::from_slice(nonce)?;
+
+        Ok(chacha20poly1305_ietf::encrypt(doc, &key, &nonce))
+    }
+
+    fn decrypt(&self, sk: &[u8], vk: &[u8], doc: &[u8], nonce: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let key = self._shared_key(sk, vk, &[])?;
+        let nonce = chacha20poly1305_ietf::Nonce::from_slice(nonce)?;
+
+        Ok(chacha20poly1305_ietf::decrypt(doc, &key, &nonce)?)
+    }
+
+    fn gen_nonce(&self) -> Vec<u8> {
+        chacha20poly1305_ietf::gen_nonce()[..].to_vec()
+    }
+
+    fn create_key(&self, seed: Option<&[u8]>) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        ec::create_key_pair(self.curve, seed)
+    }
+
+    fn validate_key(&self, vk: &[u8]) -> Result<(), CryptoError> {
+        ec::validate_public_key(self.curve, vk)
+    }
+
+    fn public_key(&self, sk: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        ec::public_key(self.curve, sk)
+    }
+
+    fn sign(&self, sk: &[u8], doc: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        ec::sign(self.curve, sk, doc)
+    }
+
+    fn verify(&self, vk: &[u8], doc: &[u8], signature: &[u8]) -> Result<bool, CryptoError> {
+        ec::verify(self.curve, vk, doc, signature)
+    }
+
+    fn encrypt_sealed(&self, vk: &[u8], doc: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let (epk, esk) = ec::create_key_pair(self.curve, None)?;
+
+        let key = self._shared_key(&esk, vk, &[&epk[..], vk])?;
+        let nonce = chacha20poly1305_ietf::gen_nonce();
+
+        let mut res = epk;
+        res.extend_from_slice(&nonce[..]);
+        res.extend_from_slice(&chacha20poly1305_ietf::encrypt(doc, &key, &nonce));
+
+        Ok(res)
+    }
+
+    fn decrypt_sealed(&self, vk: &[u8], sk: &[u8], doc: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if doc.len() < ec::PUBLICKEYBYTES + chacha20poly1305_ietf::NONCEBYTES + chacha20poly1305_ietf::TAGBYTES {
+            return Err(CryptoError::CommonError(
+                CommonError::InvalidStructure("Sealed message is too short".to_string())));
+        }
+
+        let (epk, doc) = doc.split_at(ec::PUBLICKEYBYTES);
+        let (nonce, doc) = doc.split_at(chacha20poly1305_ietf::NONCEBYTES);
+
+        let key = self._shared_key(sk, epk, &[epk, vk])?;
+        let nonce = chacha20poly1305_ietf::Nonce::from_slice(nonce)?;
+
+        Ok(chacha20poly1305_ietf::decrypt(doc, &key, &nonce)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt_works() {
+        for curve in [ec::Curve::Secp256k1, ec::Curve::P256].iter() {
+            let crypto_type = ECCryptoType::new(*curve);
+
+            let (alice_vk, alice_sk) = crypto_type.create_key(None).unwrap();
+            let (bob_vk, bob_sk) = crypto_type.create_key(None).unwrap();
+
+            let doc = b"Hello World";
+            let nonce = crypto_type.gen_nonce();
+
+            let encrypted = crypto_type.encrypt(&alice_sk, &bob_vk, doc, &nonce).unwrap();
+            let decrypted = crypto_type.decrypt(&bob_sk, &alice_vk, &encrypted, &nonce).unwrap();
+
+            assert_eq!(doc.to_vec(), decrypted);
+        }
+    }
+
+    #[test]
+    fn encrypt_decrypt_sealed_works() {
+        for curve in [ec::Curve::Secp256k1, ec::Curve::P256].iter() {
+            let crypto_type = ECCryptoType::new(*curve);
+
+            let (vk, sk) = crypto_type.create_key(None).unwrap();
+            let (other_vk, other_sk) = crypto_type.create_key(None).unwrap();
+
+            let doc = b"Hello World";
+
+            let encrypted = crypto_type.encrypt_sealed(&vk, doc).unwrap();
+
+            assert_eq!(doc.to_vec(), crypto_type.decrypt_sealed(&vk, &sk, &encrypted).unwrap());
+            assert!(crypto_type.decrypt_sealed(&other_vk, &other_sk, &encrypted).is_err());
+        }
+    }
+}
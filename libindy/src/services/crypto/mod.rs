@@ -1,33 +1,62 @@
 mod ed25519;
+#[cfg(feature = "ec_openssl")]
+mod ec;
+mod stream;
 
 use self::ed25519::ED25519CryptoType;
+#[cfg(feature = "ec_openssl")]
+use self::ec::ECCryptoType;
+
+pub use self::stream::{EncryptStream, DecryptStream, parse_header as parse_stream_header};
 
 use errors::common::CommonError;
 use errors::crypto::CryptoError;
 use domain::crypto::key::{Key, KeyInfo};
 use domain::crypto::did::{Did, MyDidInfo, TheirDidInfo, TheirDid};
 use domain::crypto::combo_box::ComboBox;
+use domain::crypto::export::EncryptedExport;
+use domain::crypto::pack::{Recipient, PROTECTED_HEADER_ENC};
+use domain::crypto::stream::StreamHeader;
+use domain::wallet::KeyDerivationMethod;
 use utils::crypto::base58;
 use utils::crypto::base64;
 use utils::crypto::verkey_builder::build_full_verkey;
 use utils::crypto::ed25519_sign;
-use utils::crypto::ed25519_box;
+use utils::crypto::chacha20poly1305_ietf;
+use utils::crypto::pwhash_argon2i13;
+use utils::crypto::hmacsha256;
+use utils::crypto::hash::hash;
+use utils::crypto::randombytes::randombytes;
+#[cfg(feature = "ec_openssl")]
+use utils::crypto::ec::Curve;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
 
 use std::collections::HashMap;
 use std::str;
 
 pub const DEFAULT_CRYPTO_TYPE: &'static str = "ed25519";
+pub const SECP256K1_CRYPTO_TYPE: &'static str = "secp256k1";
+pub const SECP256R1_CRYPTO_TYPE: &'static str = "secp256r1";
+
+pub const ROOT_SEED_BYTES: usize = 32;
+const ROOT_SEED_DOMAIN: &'static str = "indy root seed";
+const HARDENED_OFFSET: u32 = 0x8000_0000;
 
+// Key material is passed as raw bytes, every crypto type checks and parses it on its own
 trait CryptoType {
-    fn encrypt(&self, sk: &ed25519_sign::SecretKey, vk: &ed25519_sign::PublicKey, doc: &[u8], nonce: &ed25519_box::Nonce) -> Result<Vec<u8>, CryptoError>;
-    fn decrypt(&self, sk: &ed25519_sign::SecretKey, vk: &ed25519_sign::PublicKey, doc: &[u8], nonce: &ed25519_box::Nonce) -> Result<Vec<u8>, CryptoError>;
-    fn gen_nonce(&self) -> ed25519_box::Nonce;
-    fn create_key(&self, seed: Option<&ed25519_sign::Seed>) -> Result<(ed25519_sign::PublicKey, ed25519_sign::SecretKey), CryptoError>;
-    fn validate_key(&self, _vk: &ed25519_sign::PublicKey) -> Result<(), CryptoError>;
-    fn sign(&self, sk: &ed25519_sign::SecretKey, doc: &[u8]) -> Result<ed25519_sign::Signature, CryptoError>;
-    fn verify(&self, vk: &ed25519_sign::PublicKey, doc: &[u8], signature: &ed25519_sign::Signature) -> Result<bool, CryptoError>;
-    fn encrypt_sealed(&self, vk: &ed25519_sign::PublicKey, doc: &[u8]) -> Result<Vec<u8>, CryptoError>;
-    fn decrypt_sealed(&self, vk: &ed25519_sign::PublicKey, sk: &ed25519_sign::SecretKey, doc: &[u8]) -> Result<Vec<u8>, CryptoError>;
+    fn encrypt(&self, sk: &[u8], vk: &[u8], doc: &[u8], nonce: &[u8]) -> Result<Vec<u8>, CryptoError>;
+    fn decrypt(&self, sk: &[u8], vk: &[u8], doc: &[u8], nonce: &[u8]) -> Result<Vec<u8>, CryptoError>;
+    fn gen_nonce(&self) -> Vec<u8>;
+    fn create_key(&self, seed: Option<&[u8]>) -> Result<(Vec<u8>, Vec<u8>), CryptoError>;
+    fn validate_key(&self, vk: &[u8]) -> Result<(), CryptoError>;
+    fn public_key(&self, sk: &[u8]) -> Result<Vec<u8>, CryptoError>;
+    fn sign(&self, sk: &[u8], doc: &[u8]) -> Result<Vec<u8>, CryptoError>;
+    fn verify(&self, vk: &[u8], doc: &[u8], signature: &[u8]) -> Result<bool, CryptoError>;
+    fn encrypt_sealed(&self, vk: &[u8], doc: &[u8]) -> Result<Vec<u8>, CryptoError>;
+    fn decrypt_sealed(&self, vk: &[u8], sk: &[u8], doc: &[u8]) -> Result<Vec<u8>, CryptoError>;
 }
 
 pub struct CryptoService {
@@ -38,6 +67,11 @@ impl CryptoService {
     pub fn new() -> CryptoService {
         let mut crypto_types: HashMap<&str, Box<CryptoType>> = HashMap::new();
         crypto_types.insert(DEFAULT_CRYPTO_TYPE, Box::new(ED25519CryptoType::new()));
+        #[cfg(feature = "ec_openssl")]
+        {
+            crypto_types.insert(SECP256K1_CRYPTO_TYPE, Box::new(ECCryptoType::new(Curve::Secp256k1)));
+            crypto_types.insert(SECP256R1_CRYPTO_TYPE, Box::new(ECCryptoType::new(Curve::P256)));
+        }
 
         CryptoService {
             crypto_types
@@ -61,7 +95,7 @@ impl CryptoService {
         let crypto_type = self.crypto_types.get(crypto_type_name).unwrap();
 
         let seed = self.convert_seed(key_info.seed.as_ref().map(String::as_ref))?;
-        let (vk, sk) = crypto_type.create_key(seed.as_ref())?;
+        let (vk, sk) = crypto_type.create_key(seed.as_ref().map(|seed| &seed[..]))?;
         let mut vk = base58::encode(&vk[..]);
         let sk = base58::encode(&sk[..]);
 
@@ -94,7 +128,7 @@ impl CryptoService {
         let crypto_type = self.crypto_types.get(crypto_type_name).unwrap();
 
         let seed = self.convert_seed(my_did_info.seed.as_ref().map(String::as_ref))?;
-        let (vk, sk) = crypto_type.create_key(seed.as_ref())?;
+        let (vk, sk) = crypto_type.create_key(seed.as_ref().map(|seed| &seed[..]))?;
         let did = match my_did_info.did {
             Some(ref did) => {
                 self.validate_did(did)?;
@@ -156,8 +190,8 @@ impl CryptoService {
 
         let crypto_type = self.crypto_types.get(crypto_type_name).unwrap();
 
-        let my_sk = ed25519_sign::SecretKey::from_slice(&base58::decode(my_key.signkey.as_str())?)?;
-        let signature = crypto_type.sign(&my_sk, doc)?[..].to_vec();
+        let my_sk = base58::decode(my_key.signkey.as_str())?;
+        let signature = crypto_type.sign(&my_sk, doc)?;
 
         trace!("sign <<< signature: {:?}", signature);
 
@@ -181,10 +215,9 @@ impl CryptoService {
 
         let crypto_type = self.crypto_types.get(crypto_type_name).unwrap();
 
-        let their_vk = ed25519_sign::PublicKey::from_slice(&base58::decode(&their_vk)?)?;
-        let signature = ed25519_sign::Signature::from_slice(&signature)?;
+        let their_vk = base58::decode(&their_vk)?;
 
-        let valid = crypto_type.verify(&their_vk, msg, &signature)?;
+        let valid = crypto_type.verify(&their_vk, msg, signature)?;
 
         trace!("verify <<< valid: {:?}", valid);
 
@@ -238,12 +271,11 @@ impl CryptoService {
 
         let crypto_type = self.crypto_types.get(&crypto_type_name).unwrap();
 
-        let my_sk = ed25519_sign::SecretKey::from_slice(&base58::decode(my_key.signkey.as_str())?)?;
-        let their_vk = ed25519_sign::PublicKey::from_slice(&base58::decode(their_vk)?)?;
+        let my_sk = base58::decode(my_key.signkey.as_str())?;
+        let their_vk = base58::decode(their_vk)?;
         let nonce = crypto_type.gen_nonce();
 
         let encrypted_doc = crypto_type.encrypt(&my_sk, &their_vk, doc, &nonce)?;
-        let nonce = nonce[..].to_vec();
 
         trace!("encrypt <<< encrypted_doc: {:?}, nonce: {:?}", encrypted_doc, nonce);
 
@@ -282,11 +314,10 @@ impl CryptoService {
 
         let crypto_type = self.crypto_types.get(crypto_type_name).unwrap();
 
-        let my_sk = ed25519_sign::SecretKey::from_slice(&base58::decode(&my_key.signkey)?)?;
-        let their_vk = ed25519_sign::PublicKey::from_slice(&base58::decode(their_vk)?)?;
-        let nonce = ed25519_box::Nonce::from_slice(&nonce)?;
+        let my_sk = base58::decode(&my_key.signkey)?;
+        let their_vk = base58::decode(their_vk)?;
 
-        let decrypted_doc = crypto_type.decrypt(&my_sk, &their_vk, &doc, &nonce)?;
+        let decrypted_doc = crypto_type.decrypt(&my_sk, &their_vk, &doc, nonce)?;
 
         trace!("decrypt <<< decrypted_doc: {:?}", decrypted_doc);
 
@@ -309,7 +340,7 @@ impl CryptoService {
 
         let crypto_type = self.crypto_types.get(crypto_type_name).unwrap();
 
-        let their_vk = ed25519_sign::PublicKey::from_slice(&base58::decode(their_vk)?)?;
+        let their_vk = base58::decode(their_vk)?;
 
         let encrypted_doc = crypto_type.encrypt_sealed(&their_vk, doc)?;
 
@@ -335,8 +366,8 @@ impl CryptoService {
 
         let crypto_type = self.crypto_types.get(crypto_type_name).unwrap();
 
-        let my_vk = ed25519_sign::PublicKey::from_slice(&base58::decode(my_vk)?)?;
-        let my_sk = ed25519_sign::SecretKey::from_slice(&base58::decode(my_key.signkey.as_str())?)?;
+        let my_vk = base58::decode(my_vk)?;
+        let my_sk = base58::decode(my_key.signkey.as_str())?;
 
         let decrypted_doc = crypto_type.decrypt_sealed(&my_vk, &my_sk, doc)?;
 
@@ -345,6 +376,161 @@ impl CryptoService {
         Ok(decrypted_doc)
     }
 
+    pub fn gen_content_key(&self) -> chacha20poly1305_ietf::Key {
+        chacha20poly1305_ietf::gen_key()
+    }
+
+    pub fn encrypt_content(&self, cek: &chacha20poly1305_ietf::Key, doc: &[u8], aad: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        trace!("encrypt_content >>> doc: {:?}, aad: {:?}", doc, aad);
+
+        let iv = chacha20poly1305_ietf::gen_nonce();
+        let (ciphertext, tag) = chacha20poly1305_ietf::encrypt_detached(doc, aad, cek, &iv);
+        let iv = iv[..].to_vec();
+
+        trace!("encrypt_content <<< ciphertext: {:?}, iv: {:?}, tag: {:?}", ciphertext, iv, tag);
+
+        (ciphertext, iv, tag)
+    }
+
+    pub fn decrypt_content(&self, cek: &[u8], ciphertext: &[u8], iv: &[u8], tag: &[u8], aad: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        trace!("decrypt_content >>> ciphertext: {:?}, iv: {:?}, tag: {:?}, aad: {:?}", ciphertext, iv, tag, aad);
+
+        let cek = chacha20poly1305_ietf::Key::from_slice(cek)?;
+        let iv = chacha20poly1305_ietf::Nonce::from_slice(iv)?;
+
+        let doc = chacha20poly1305_ietf::decrypt_detached(ciphertext, tag, aad, &cek, &iv)?;
+
+        trace!("decrypt_content <<< doc: {:?}", doc);
+
+        Ok(doc)
+    }
+
+    pub fn create_stream_header(&self, alg: &str, recipients: Vec<Recipient>) -> StreamHeader {
+        StreamHeader {
+            enc: PROTECTED_HEADER_ENC.to_string(),
+            alg: alg.to_string(),
+            recipients,
+            nonce: chacha20poly1305_ietf::gen_nonce()[..].to_vec(),
+            chunk_size: stream::CHUNK_SIZE
+        }
+    }
+
+    pub fn encrypt_export<T>(&self, content: &T, passphrase: &str) -> Result<EncryptedExport, CryptoError> where T: Serialize {
+        trace!("encrypt_export >>> passphrase: {:?}", secret!(&passphrase));
+
+        let content = serde_json::to_vec(content)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize export content: {:?}", err)))?;
+
+        let salt = pwhash_argon2i13::gen_salt();
+        let key = chacha20poly1305_ietf::derive_key(passphrase, &salt, &KeyDerivationMethod::ARGON2I_MOD)?;
+        let (ciphertext, nonce) = chacha20poly1305_ietf::gen_nonce_and_encrypt(&content, &key);
+
+        let res = EncryptedExport {
+            salt: base64::encode(&salt[..]),
+            nonce: base64::encode(&nonce[..]),
+            ciphertext: base64::encode(&ciphertext)
+        };
+
+        trace!("encrypt_export <<< res: {:?}", res);
+
+        Ok(res)
+    }
+
+    pub fn decrypt_export<T>(&self, export: &EncryptedExport, passphrase: &str) -> Result<T, CryptoError> where T: DeserializeOwned {
+        trace!("decrypt_export >>> export: {:?}, passphrase: {:?}", export, secret!(&passphrase));
+
+        let salt = pwhash_argon2i13::Salt::from_slice(&base64::decode(&export.salt)?)
+            .map_err(|err| CommonError::InvalidStructure(format!("Invalid salt: {:?}", err)))?;
+
+        let nonce = chacha20poly1305_ietf::Nonce::from_slice(&base64::decode(&export.nonce)?)
+            .map_err(|err| CommonError::InvalidStructure(format!("Invalid nonce: {:?}", err)))?;
+
+        let key = chacha20poly1305_ietf::derive_key(passphrase, &salt, &KeyDerivationMethod::ARGON2I_MOD)?;
+
+        let content = chacha20poly1305_ietf::decrypt(&base64::decode(&export.ciphertext)?, &key, &nonce)?;
+
+        let res: T = serde_json::from_slice(&content)
+            .map_err(|err| CommonError::InvalidStructure(format!("Cannot deserialize export content: {:?}", err)))?;
+
+        trace!("decrypt_export <<<");
+
+        Ok(res)
+    }
+
+    /// Generates root seed for deterministic key derivation. If mnemonic (or any other
+    /// backed-up secret phrase) is given, root seed is stretched from it by argon2i, so it can be recovered later.
+    pub fn gen_root_seed(&self, mnemonic: Option<&str>) -> Result<Vec<u8>, CryptoError> {
+        trace!("gen_root_seed >>> mnemonic: {:?}", secret!(&mnemonic));
+
+        let res = match mnemonic {
+            Some(mnemonic) => {
+                // Salt is fixed, otherwise root seed can't be recovered from the mnemonic only
+                let salt = pwhash_argon2i13::Salt::from_slice(&hash(ROOT_SEED_DOMAIN.as_bytes())?[..pwhash_argon2i13::SALTBYTES])?;
+
+                let mut root_seed = vec![0u8; ROOT_SEED_BYTES];
+                pwhash_argon2i13::pwhash(&mut root_seed, mnemonic.as_bytes(), &salt, &KeyDerivationMethod::ARGON2I_MOD)?;
+                root_seed
+            }
+            None => randombytes(ROOT_SEED_BYTES)
+        };
+
+        trace!("gen_root_seed <<<");
+
+        Ok(res)
+    }
+
+    /// Derives ed25519 seed from root seed by path like "m/0/5".
+    ///
+    /// Every path segment is hardened index (trailing ' is allowed, but not required):
+    /// seed_i+1 = HMAC-SHA256(key = seed_i, data = big-endian u32 (index + 2^31)).
+    pub fn derive_seed(&self, root_seed: &[u8], path: &str) -> Result<Vec<u8>, CryptoError> {
+        trace!("derive_seed >>> path: {:?}", path);
+
+        let indexes = CryptoService::_parse_derivation_path(path)?;
+
+        let mut seed = root_seed.to_vec();
+
+        for index in indexes {
+            let key = hmacsha256::Key::from_slice(&seed)?;
+            let data = [(index >> 24) as u8, (index >> 16) as u8, (index >> 8) as u8, index as u8];
+            seed = hmacsha256::authenticate(&data, &key)[..].to_vec();
+        }
+
+        trace!("derive_seed <<<");
+
+        Ok(seed)
+    }
+
+    fn _parse_derivation_path(path: &str) -> Result<Vec<u32>, CryptoError> {
+        let invalid_path = || CryptoError::CommonError(CommonError::InvalidStructure(format!("Invalid derivation path: {}", path)));
+
+        let mut segments = path.split('/');
+
+        if segments.next() != Some("m") {
+            return Err(invalid_path());
+        }
+
+        let mut indexes: Vec<u32> = Vec::new();
+
+        for segment in segments {
+            let segment = segment.trim_right_matches('\'');
+
+            let index = segment.parse::<u32>().map_err(|_| invalid_path())?;
+
+            if index >= HARDENED_OFFSET {
+                return Err(invalid_path());
+            }
+
+            indexes.push(index + HARDENED_OFFSET);
+        }
+
+        if indexes.is_empty() {
+            return Err(invalid_path());
+        }
+
+        Ok(indexes)
+    }
+
     pub fn convert_seed(&self, seed: Option<&str>) -> Result<Option<ed25519_sign::Seed>, CryptoError> {
         trace!("convert_seed >>> seed: {:?}", seed);
 
@@ -385,8 +571,7 @@ impl CryptoService {
         if vk.starts_with('~') {
             base58::decode(&vk[1..])?; // TODO: proper validate abbreviated verkey
         } else {
-            let vk = ed25519_sign::PublicKey::from_slice(&base58::decode(vk)?)?;
-            crypto_type.validate_key(&vk)?;
+            crypto_type.validate_key(&base58::decode(vk)?)?;
         };
 
         trace!("validate_key <<<");
@@ -394,6 +579,32 @@ impl CryptoService {
         Ok(())
     }
 
+    /// Checks that verkey of the key is derived from its signkey.
+    pub fn validate_key_pair(&self, key: &Key) -> Result<(), CryptoError> {
+        trace!("validate_key_pair >>> key: {:?}", key);
+
+        self.validate_key(&key.verkey)?;
+
+        let (vk, crypto_type_name) = if key.verkey.contains(':') {
+            let splits: Vec<&str> = key.verkey.split(':').collect();
+            (splits[0], splits[1])
+        } else {
+            (key.verkey.as_str(), DEFAULT_CRYPTO_TYPE)
+        };
+
+        let crypto_type = self.crypto_types.get(crypto_type_name).unwrap();
+
+        let derived_vk = crypto_type.public_key(&base58::decode(&key.signkey)?)?;
+
+        if base58::decode(vk)? != derived_vk {
+            return Err(CryptoError::CommonError(CommonError::InvalidStructure("Verkey doesn't match signkey".to_string())));
+        }
+
+        trace!("validate_key_pair <<<");
+
+        Ok(())
+    }
+
     pub fn validate_did(&self, did: &str) -> Result<(), CryptoError> {
         trace!("validate_did >>> did: {:?}", did);
 
@@ -653,4 +864,181 @@ mod tests {
         let decrypted_message = service.decrypt_sealed(&key, &encrypted_message).unwrap();
         assert_eq!(msg, decrypted_message.as_slice());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn encrypt_decrypt_content_works() {
+        let service = CryptoService::new();
+        let msg = "some message".as_bytes();
+        let aad = "protected".as_bytes();
+        let cek = service.gen_content_key();
+        let (ciphertext, iv, tag) = service.encrypt_content(&cek, msg, aad);
+        let decrypted_message = service.decrypt_content(&cek[..], &ciphertext, &iv, &tag, aad).unwrap();
+        assert_eq!(msg, decrypted_message.as_slice());
+    }
+
+    #[test]
+    fn decrypt_content_works_for_other_aad() {
+        let service = CryptoService::new();
+        let msg = "some message".as_bytes();
+        let cek = service.gen_content_key();
+        let (ciphertext, iv, tag) = service.encrypt_content(&cek, msg, "protected".as_bytes());
+        let res = service.decrypt_content(&cek[..], &ciphertext, &iv, &tag, "other".as_bytes());
+        assert!(res.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "ec_openssl")]
+    fn create_key_works_for_ec_crypto_types() {
+        let service = CryptoService::new();
+
+        for crypto_type in [SECP256K1_CRYPTO_TYPE, SECP256R1_CRYPTO_TYPE].iter() {
+            let key_info = KeyInfo { seed: Some("00000000000000000000000000000My1".to_string()), crypto_type: Some(crypto_type.to_string()) };
+
+            let key1 = service.create_key(&key_info).unwrap();
+            let key2 = service.create_key(&key_info).unwrap();
+
+            assert!(key1.verkey.ends_with(&format!(":{}", crypto_type)));
+            assert_eq!(key1.verkey, key2.verkey);
+            service.validate_key(&key1.verkey).unwrap();
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ec_openssl")]
+    fn sign_verify_works_for_ec_crypto_types() {
+        let service = CryptoService::new();
+        let message = r#"message"#;
+
+        for crypto_type in [SECP256K1_CRYPTO_TYPE, SECP256R1_CRYPTO_TYPE].iter() {
+            let key_info = KeyInfo { seed: None, crypto_type: Some(crypto_type.to_string()) };
+            let my_key = service.create_key(&key_info).unwrap();
+
+            let signature = service.sign(&my_key, message.as_bytes()).unwrap();
+
+            assert!(service.verify(&my_key.verkey, message.as_bytes(), &signature).unwrap());
+            assert!(!service.verify(&my_key.verkey, "other message".as_bytes(), &signature).unwrap());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ec_openssl")]
+    fn encrypt_decrypt_works_for_ec_crypto_types() {
+        let service = CryptoService::new();
+        let msg = "some message".as_bytes();
+
+        for crypto_type in [SECP256K1_CRYPTO_TYPE, SECP256R1_CRYPTO_TYPE].iter() {
+            let key_info = KeyInfo { seed: None, crypto_type: Some(crypto_type.to_string()) };
+            let my_key = service.create_key(&key_info).unwrap();
+            let their_key = service.create_key(&key_info).unwrap();
+
+            let (encrypted_message, nonce) = service.encrypt(&my_key, &their_key.verkey, msg).unwrap();
+            let decrypted_message = service.decrypt(&their_key, &my_key.verkey, &encrypted_message, &nonce).unwrap();
+            assert_eq!(msg, decrypted_message.as_slice());
+
+            let encrypted_message = service.encrypt_sealed(&their_key.verkey, msg).unwrap();
+            let decrypted_message = service.decrypt_sealed(&their_key, &encrypted_message).unwrap();
+            assert_eq!(msg, decrypted_message.as_slice());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ec_openssl")]
+    fn encrypt_works_for_incompatible_crypto_types() {
+        let service = CryptoService::new();
+        let msg = "some message".as_bytes();
+
+        let my_key = service.create_key(&KeyInfo { seed: None, crypto_type: Some(SECP256K1_CRYPTO_TYPE.to_string()) }).unwrap();
+        let their_key = service.create_key(&KeyInfo { seed: None, crypto_type: Some(SECP256R1_CRYPTO_TYPE.to_string()) }).unwrap();
+
+        assert!(service.encrypt(&my_key, &their_key.verkey, msg).is_err());
+    }
+
+    #[test]
+    fn validate_key_pair_works() {
+        let service = CryptoService::new();
+        let key = service.create_key(&KeyInfo { seed: None, crypto_type: None }).unwrap();
+        service.validate_key_pair(&key).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "ec_openssl")]
+    fn validate_key_pair_works_for_ec_crypto_types() {
+        let service = CryptoService::new();
+
+        for crypto_type in [SECP256K1_CRYPTO_TYPE, SECP256R1_CRYPTO_TYPE].iter() {
+            let key = service.create_key(&KeyInfo { seed: None, crypto_type: Some(crypto_type.to_string()) }).unwrap();
+            service.validate_key_pair(&key).unwrap();
+        }
+    }
+
+    #[test]
+    fn validate_key_pair_works_for_other_signkey() {
+        let service = CryptoService::new();
+        let key = service.create_key(&KeyInfo { seed: None, crypto_type: None }).unwrap();
+        let other_key = service.create_key(&KeyInfo { seed: None, crypto_type: None }).unwrap();
+
+        let res = service.validate_key_pair(&Key::new(key.verkey, other_key.signkey));
+        assert_match!(Err(CryptoError::CommonError(CommonError::InvalidStructure(_))), res);
+    }
+
+    #[test]
+    fn encrypt_decrypt_export_works() {
+        let service = CryptoService::new();
+        let key = service.create_key(&KeyInfo { seed: None, crypto_type: None }).unwrap();
+
+        let export = service.encrypt_export(&key, "passphrase").unwrap();
+        let imported: Key = service.decrypt_export(&export, "passphrase").unwrap();
+
+        assert_eq!(key.verkey, imported.verkey);
+        assert_eq!(key.signkey, imported.signkey);
+    }
+
+    #[test]
+    fn decrypt_export_works_for_wrong_passphrase() {
+        let service = CryptoService::new();
+        let key = service.create_key(&KeyInfo { seed: None, crypto_type: None }).unwrap();
+
+        let export = service.encrypt_export(&key, "passphrase").unwrap();
+        let res: Result<Key, CryptoError> = service.decrypt_export(&export, "other passphrase");
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn gen_root_seed_works_for_mnemonic() {
+        let service = CryptoService::new();
+
+        let seed1 = service.gen_root_seed(Some("some backed up mnemonic")).unwrap();
+        let seed2 = service.gen_root_seed(Some("some backed up mnemonic")).unwrap();
+        let seed3 = service.gen_root_seed(Some("other mnemonic")).unwrap();
+
+        assert_eq!(ROOT_SEED_BYTES, seed1.len());
+        assert_eq!(seed1, seed2);
+        assert_ne!(seed1, seed3);
+    }
+
+    #[test]
+    fn derive_seed_works() {
+        let service = CryptoService::new();
+        let root_seed = service.gen_root_seed(None).unwrap();
+
+        let seed1 = service.derive_seed(&root_seed, "m/0/1").unwrap();
+        let seed2 = service.derive_seed(&root_seed, "m/0'/1'").unwrap();
+        let seed3 = service.derive_seed(&root_seed, "m/0/2").unwrap();
+
+        assert_eq!(ed25519_sign::SEEDBYTES, seed1.len());
+        assert_eq!(seed1, seed2);
+        assert_ne!(seed1, seed3);
+    }
+
+    #[test]
+    fn derive_seed_works_for_invalid_path() {
+        let service = CryptoService::new();
+        let root_seed = service.gen_root_seed(None).unwrap();
+
+        assert!(service.derive_seed(&root_seed, "0/1").is_err());
+        assert!(service.derive_seed(&root_seed, "m").is_err());
+        assert!(service.derive_seed(&root_seed, "m/a").is_err());
+        assert!(service.derive_seed(&root_seed, "m/2147483648").is_err());
+    }
+}
@@ -0,0 +1,298 @@
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
+use rmp_serde;
+
+use std::io::Write;
+use std::mem;
+
+use domain::crypto::stream::StreamHeader;
+use errors::common::CommonError;
+use errors::crypto::CryptoError;
+use utils::crypto::chacha20poly1305_ietf;
+use utils::crypto::hash::hash;
+
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+const MAX_CHUNK_SIZE: usize = 1024 * 1024;
+const MAX_HEADER_SIZE: usize = 1024 * 1024;
+const LEN_BYTES: usize = 4;
+
+// Stream layout is similar to wallet export:
+//   header len (u32 LE) | header (msgpack) | encrypted chunks
+// Encrypted content starts with header hash, then data frames follow:
+//   frame len (u32 LE) | frame data
+// Zero length frame marks the end of stream, so truncated stream can be detected.
+pub struct EncryptStream {
+    writer: chacha20poly1305_ietf::Writer<Vec<u8>>
+}
+
+impl EncryptStream {
+    pub fn new(header: &StreamHeader, cek: chacha20poly1305_ietf::Key) -> Result<EncryptStream, CryptoError> {
+        let nonce = chacha20poly1305_ietf::Nonce::from_slice(&header.nonce)?;
+
+        let header = rmp_serde::to_vec(header)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize stream header: {:?}", err)))?;
+
+        // Write plain
+        let mut output = Vec::with_capacity(LEN_BYTES + header.len());
+        output.write_u32::<LittleEndian>(header.len() as u32).map_err(CommonError::from)?;
+        output.write_all(&header).map_err(CommonError::from)?;
+
+        // Write encrypted
+        let mut writer = chacha20poly1305_ietf::Writer::new(output, cek, nonce, CHUNK_SIZE);
+        writer.write_all(&hash(&header)?).map_err(CommonError::from)?;
+
+        Ok(EncryptStream { writer })
+    }
+
+    pub fn update(&mut self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        // Empty frame is reserved for the end of stream
+        if !data.is_empty() {
+            self.writer.write_u32::<LittleEndian>(data.len() as u32).map_err(CommonError::from)?;
+            self.writer.write_all(data).map_err(CommonError::from)?;
+        }
+
+        Ok(mem::replace(self.writer.get_mut(), Vec::new()))
+    }
+
+    pub fn finish(mut self) -> Result<Vec<u8>, CryptoError> {
+        self.writer.write_u32::<LittleEndian>(0).map_err(CommonError::from)?;
+        self.writer.flush().map_err(CommonError::from)?;
+
+        Ok(self.writer.into_inner())
+    }
+}
+
+// Reads stream header if enough data is collected. Returns header and its length with prefix.
+pub fn parse_header(data: &[u8]) -> Result<Option<(StreamHeader, usize)>, CryptoError> {
+    if data.len() < LEN_BYTES {
+        return Ok(None);
+    }
+
+    let header_len = LittleEndian::read_u32(&data[..LEN_BYTES]) as usize;
+
+    if header_len == 0 || header_len > MAX_HEADER_SIZE {
+        return Err(CryptoError::CommonError(CommonError::InvalidStructure(format!("Invalid stream header length: {}", header_len))));
+    }
+
+    if data.len() < LEN_BYTES + header_len {
+        return Ok(None);
+    }
+
+    let header: StreamHeader = rmp_serde::from_slice(&data[LEN_BYTES..LEN_BYTES + header_len])
+        .map_err(|err| CommonError::InvalidStructure(format!("Can't deserialize stream header: {:?}", err)))?;
+
+    if header.chunk_size == 0 || header.chunk_size > MAX_CHUNK_SIZE {
+        return Err(CryptoError::CommonError(CommonError::InvalidStructure(format!("Invalid stream chunk size: {}", header.chunk_size))));
+    }
+
+    Ok(Some((header, LEN_BYTES + header_len)))
+}
+
+pub struct DecryptStream {
+    key: chacha20poly1305_ietf::Key,
+    nonce: chacha20poly1305_ietf::Nonce,
+    chunk_size: usize,
+    encrypted: Vec<u8>,
+    decrypted: Vec<u8>,
+    header_hash: Option<Vec<u8>>,
+    frame_left: usize,
+    ended: bool
+}
+
+impl DecryptStream {
+    // `data` is the beginning of the stream and must contain the whole header
+    pub fn new(data: &[u8], cek: &[u8]) -> Result<(DecryptStream, StreamHeader), CryptoError> {
+        let (header, header_end) = parse_header(data)?
+            .ok_or(CommonError::InvalidStructure("Stream header is incomplete".to_string()))?;
+
+        let stream = DecryptStream {
+            key: chacha20poly1305_ietf::Key::from_slice(cek)?,
+            nonce: chacha20poly1305_ietf::Nonce::from_slice(&header.nonce)?,
+            chunk_size: header.chunk_size,
+            encrypted: data[header_end..].to_vec(),
+            decrypted: Vec::new(),
+            header_hash: Some(hash(&data[LEN_BYTES..header_end])?),
+            frame_left: 0,
+            ended: false
+        };
+
+        Ok((stream, header))
+    }
+
+    pub fn update(&mut self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.encrypted.extend_from_slice(data);
+
+        let encrypted_chunk_size = self.chunk_size + chacha20poly1305_ietf::TAGBYTES;
+        let mut chunk_start = 0;
+
+        while self.encrypted.len() >= chunk_start + encrypted_chunk_size {
+            self._decrypt_chunk(chunk_start, chunk_start + encrypted_chunk_size)?;
+            chunk_start += encrypted_chunk_size;
+        }
+
+        if chunk_start > 0 {
+            self.encrypted.drain(..chunk_start);
+        }
+
+        self._read_frames()
+    }
+
+    pub fn finish(mut self) -> Result<Vec<u8>, CryptoError> {
+        // The rest is the last incomplete chunk
+        if !self.encrypted.is_empty() {
+            let end = self.encrypted.len();
+            self._decrypt_chunk(0, end)?;
+        }
+
+        let res = self._read_frames()?;
+
+        if !self.ended {
+            return Err(CryptoError::CommonError(CommonError::InvalidStructure("Stream is truncated".to_string())));
+        }
+
+        Ok(res)
+    }
+
+    fn _decrypt_chunk(&mut self, start: usize, end: usize) -> Result<(), CryptoError> {
+        let chunk = chacha20poly1305_ietf::decrypt(&self.encrypted[start..end], &self.key, &self.nonce)?;
+        self.nonce.increment();
+        self.decrypted.extend(chunk);
+        Ok(())
+    }
+
+    fn _read_frames(&mut self) -> Result<Vec<u8>, CryptoError> {
+        let mut res = Vec::new();
+        let mut pos = 0;
+
+        if let Some(header_hash) = self.header_hash.take() {
+            if self.decrypted.len() < header_hash.len() {
+                self.header_hash = Some(header_hash);
+                return Ok(res);
+            }
+
+            if self.decrypted[..header_hash.len()] != header_hash[..] {
+                return Err(CryptoError::CommonError(CommonError::InvalidStructure("Stream header hash mismatch".to_string())));
+            }
+
+            pos = header_hash.len();
+        }
+
+        while pos < self.decrypted.len() {
+            if self.ended {
+                return Err(CryptoError::CommonError(CommonError::InvalidStructure("Unexpected data after the end of stream".to_string())));
+            }
+
+            if self.frame_left > 0 {
+                let to_copy = ::std::cmp::min(self.frame_left, self.decrypted.len() - pos);
+                res.extend_from_slice(&self.decrypted[pos..pos + to_copy]);
+                self.frame_left -= to_copy;
+                pos += to_copy;
+                continue;
+            }
+
+            if self.decrypted.len() - pos < LEN_BYTES {
+                break;
+            }
+
+            match LittleEndian::read_u32(&self.decrypted[pos..pos + LEN_BYTES]) {
+                0 => self.ended = true,
+                frame_len => self.frame_left = frame_len as usize
+            }
+
+            pos += LEN_BYTES;
+        }
+
+        self.decrypted.drain(..pos);
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::crypto::randombytes::randombytes;
+
+    fn _header() -> StreamHeader {
+        StreamHeader {
+            enc: "chacha20poly1305_ietf".to_string(),
+            alg: "Anoncrypt".to_string(),
+            recipients: Vec::new(),
+            nonce: chacha20poly1305_ietf::gen_nonce()[..].to_vec(),
+            chunk_size: CHUNK_SIZE
+        }
+    }
+
+    fn _encrypt(parts: &[&[u8]]) -> (Vec<u8>, chacha20poly1305_ietf::Key) {
+        let cek = chacha20poly1305_ietf::gen_key();
+        let mut stream = EncryptStream::new(&_header(), cek.clone()).unwrap();
+
+        let mut encrypted = Vec::new();
+        for part in parts {
+            encrypted.extend(stream.update(part).unwrap());
+        }
+        encrypted.extend(stream.finish().unwrap());
+
+        (encrypted, cek)
+    }
+
+    fn _decrypt(encrypted: &[u8], cek: &chacha20poly1305_ietf::Key, feed_size: usize) -> Result<Vec<u8>, CryptoError> {
+        let (_, header_end) = parse_header(encrypted)?.unwrap();
+        let (mut stream, _) = DecryptStream::new(&encrypted[..header_end], &cek[..])?;
+
+        let mut decrypted = Vec::new();
+        for part in encrypted[header_end..].chunks(feed_size) {
+            decrypted.extend(stream.update(part)?);
+        }
+        decrypted.extend(stream.finish()?);
+
+        Ok(decrypted)
+    }
+
+    #[test]
+    fn encrypt_decrypt_stream_works() {
+        let data = randombytes(3 * CHUNK_SIZE + 100);
+
+        let (encrypted, cek) = _encrypt(&[&data[..10], &data[10..CHUNK_SIZE + 7], &data[CHUNK_SIZE + 7..]]);
+
+        for feed_size in [1000, CHUNK_SIZE, CHUNK_SIZE + chacha20poly1305_ietf::TAGBYTES, encrypted.len()].iter() {
+            assert_eq!(data, _decrypt(&encrypted, &cek, *feed_size).unwrap());
+        }
+    }
+
+    #[test]
+    fn encrypt_decrypt_stream_works_for_empty_data() {
+        let (encrypted, cek) = _encrypt(&[]);
+        assert!(_decrypt(&encrypted, &cek, 100).unwrap().is_empty());
+    }
+
+    #[test]
+    fn decrypt_stream_fails_for_truncated_stream() {
+        let data = randombytes(2 * CHUNK_SIZE);
+        let (encrypted, cek) = _encrypt(&[&data]);
+
+        let (_, header_end) = parse_header(&encrypted).unwrap().unwrap();
+        let truncated = &encrypted[..header_end + CHUNK_SIZE + chacha20poly1305_ietf::TAGBYTES];
+
+        assert!(_decrypt(truncated, &cek, 1000).is_err());
+    }
+
+    #[test]
+    fn decrypt_stream_fails_for_modified_chunk() {
+        let data = randombytes(100);
+        let (mut encrypted, cek) = _encrypt(&[&data]);
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+
+        assert!(_decrypt(&encrypted, &cek, 1000).is_err());
+    }
+
+    #[test]
+    fn decrypt_stream_fails_for_other_key() {
+        let data = randombytes(100);
+        let (encrypted, _) = _encrypt(&[&data]);
+
+        assert!(_decrypt(&encrypted, &chacha20poly1305_ietf::gen_key(), 1000).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, HashMap};
+
+use domain::wallet::Tags;
+use utils::crypto::memzero::memzero;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct CachedRecord {
+    pub value: String,
+    pub tags: Tags,
+    pub version: Option<u64>,
+    pub expires_at: Option<u64>,
+}
+
+// Records can keep secrets (keys, master secrets), so decrypted content is wiped
+// when record is evicted or removed from the cache and when its copies are dropped
+impl Drop for CachedRecord {
+    fn drop(&mut self) {
+        _memzero_string(&mut self.value);
+
+        for (mut name, mut value) in self.tags.drain() {
+            _memzero_string(&mut name);
+            _memzero_string(&mut value);
+        }
+    }
+}
+
+fn _memzero_string(s: &mut String) {
+    // zero bytes keep the string valid UTF-8
+    unsafe { memzero(s.as_bytes_mut()) }
+}
+
+// Bounded cache of decrypted records with least recently used eviction.
+// Records are identified by plaintext type and name.
+pub(super) struct RecordCache {
+    capacity: usize,
+    counter: u64,
+    entries: HashMap<(String, String), (u64, CachedRecord)>,
+    usage: BTreeMap<u64, (String, String)>,
+}
+
+impl RecordCache {
+    pub fn new(capacity: usize) -> RecordCache {
+        RecordCache {
+            capacity,
+            counter: 0,
+            entries: HashMap::new(),
+            usage: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, type_: &str, name: &str) -> Option<CachedRecord> {
+        let key = (type_.to_string(), name.to_string());
+        let counter = self._next_counter();
+
+        let entry = self.entries.get_mut(&key)?;
+
+        self.usage.remove(&entry.0);
+        self.usage.insert(counter, key);
+        entry.0 = counter;
+
+        Some(entry.1.clone())
+    }
+
+    pub fn insert(&mut self, type_: &str, name: &str, record: CachedRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.remove(type_, name);
+
+        if self.entries.len() >= self.capacity {
+            let least_used = self.usage.keys().next().cloned();
+
+            if let Some(least_used) = least_used {
+                if let Some(key) = self.usage.remove(&least_used) {
+                    self.entries.remove(&key);
+                }
+            }
+        }
+
+        let key = (type_.to_string(), name.to_string());
+        let counter = self._next_counter();
+
+        self.usage.insert(counter, key.clone());
+        self.entries.insert(key, (counter, record));
+    }
+
+    pub fn remove(&mut self, type_: &str, name: &str) {
+        if let Some((counter, _)) = self.entries.remove(&(type_.to_string(), name.to_string())) {
+            self.usage.remove(&counter);
+        }
+    }
+
+    fn _next_counter(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_cache_get_works() {
+        let mut cache = RecordCache::new(2);
+        cache.insert("type", "id1", _record("value1"));
+
+        assert_eq!(Some(_record("value1")), cache.get("type", "id1"));
+        assert_eq!(None, cache.get("type", "id2"));
+        assert_eq!(None, cache.get("other_type", "id1"));
+    }
+
+    #[test]
+    fn record_cache_insert_works_for_replace() {
+        let mut cache = RecordCache::new(2);
+        cache.insert("type", "id1", _record("value1"));
+        cache.insert("type", "id1", _record("value2"));
+
+        assert_eq!(Some(_record("value2")), cache.get("type", "id1"));
+        assert_eq!(1, cache.entries.len());
+        assert_eq!(1, cache.usage.len());
+    }
+
+    #[test]
+    fn record_cache_insert_works_for_eviction() {
+        let mut cache = RecordCache::new(2);
+        cache.insert("type", "id1", _record("value1"));
+        cache.insert("type", "id2", _record("value2"));
+
+        // id1 becomes recently used, so id2 is evicted
+        cache.get("type", "id1").unwrap();
+        cache.insert("type", "id3", _record("value3"));
+
+        assert_eq!(Some(_record("value1")), cache.get("type", "id1"));
+        assert_eq!(None, cache.get("type", "id2"));
+        assert_eq!(Some(_record("value3")), cache.get("type", "id3"));
+    }
+
+    #[test]
+    fn record_cache_insert_works_for_zero_capacity() {
+        let mut cache = RecordCache::new(0);
+        cache.insert("type", "id1", _record("value1"));
+
+        assert_eq!(None, cache.get("type", "id1"));
+    }
+
+    #[test]
+    fn record_cache_remove_works() {
+        let mut cache = RecordCache::new(2);
+        cache.insert("type", "id1", _record("value1"));
+        cache.remove("type", "id1");
+        cache.remove("type", "id2");
+
+        assert_eq!(None, cache.get("type", "id1"));
+        assert!(cache.usage.is_empty());
+    }
+
+    #[test]
+    fn memzero_string_works() {
+        let mut value = "secret".to_string();
+        _memzero_string(&mut value);
+
+        assert_eq!("\0\0\0\0\0\0", value);
+    }
+
+    fn _record(value: &str) -> CachedRecord {
+        let mut tags = Tags::new();
+        tags.insert("tag".to_string(), "tag_value".to_string());
+
+        CachedRecord {
+            value: value.to_string(),
+            tags,
+            version: Some(1),
+            expires_at: None,
+        }
+    }
+}
@@ -7,6 +7,7 @@ use super::{Keys, WalletRecord};
 use super::storage::{Tag, TagName, StorageRecord};
 use domain::wallet::KeyDerivationMethod;
 
+use errors::common::CommonError;
 use errors::wallet::WalletError;
 
 pub(super) fn gen_master_key_salt() -> Result<pwhash_argon2i13::Salt, WalletError> {
@@ -94,6 +95,10 @@ pub(super) fn decrypt(data: &[u8], key: &chacha20poly1305_ietf::Key, nonce: &cha
 }
 
 pub(super) fn decrypt_merged(joined_data: &[u8], key: &chacha20poly1305_ietf::Key) -> Result<Vec<u8>, WalletError> {
+    if joined_data.len() < chacha20poly1305_ietf::NONCEBYTES {
+        return Err(WalletError::CommonError(CommonError::InvalidStructure("Encrypted data is too short".to_string())));
+    }
+
     let nonce = chacha20poly1305_ietf::Nonce::from_slice(&joined_data[..chacha20poly1305_ietf::NONCEBYTES]).unwrap(); // We can safety unwrap here
     let data = &joined_data[chacha20poly1305_ietf::NONCEBYTES..];
     let res = decrypt(data, key, &nonce)?;
@@ -153,7 +158,7 @@ pub(super) fn decrypt_storage_record(record: &StorageRecord, keys: &Keys) -> Res
     };
 
     let decrypted_tags = decrypt_tags(&record.tags, &keys.tag_name_key, &keys.tag_value_key)?;
-    Ok(WalletRecord::new(decrypted_name, decrypted_type, decrypted_value, decrypted_tags))
+    Ok(WalletRecord::new(decrypted_name, decrypted_type, decrypted_value, decrypted_tags, record.version))
 }
 
 
@@ -70,7 +70,7 @@ pub(super) fn export(wallet: &Wallet, writer: &mut Write, passphrase: &str, vers
 
     let mut records = wallet.get_all()?;
 
-    while let Some(WalletRecord { type_, id, value, tags }) = records.next()? {
+    while let Some(WalletRecord { type_, id, value, tags, .. }) = records.next()? {
         let record = Record {
             type_: type_.ok_or(CommonError::InvalidState("No type fetched for exported record".to_string()))?,
             id,
@@ -380,7 +380,7 @@ mod tests {
 
         let storage = storage_type.open_storage(id, None, None).unwrap();
 
-        Wallet::new(id.to_string(), storage, Rc::new(keys))
+        Wallet::new(id.to_string(), storage, Rc::new(keys), None)
     }
 
     fn _wallet1() -> Wallet {
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::string;
 
 use serde_json;
@@ -39,6 +40,8 @@ impl string::ToString for TagName {
 pub enum TargetValue {
     Unencrypted(String),
     Encrypted(Vec<u8>),
+    // JSON number used for numeric comparison of nonencrypted tag values
+    Numeric(String),
 }
 
 impl From<String> for TargetValue {
@@ -47,11 +50,63 @@ impl From<String> for TargetValue {
     }
 }
 
+/// Number in tag value or numeric operand: optional minus, digits and optional fraction
+/// ("-12", "0.5"). Exponent, leading plus or dot are not numbers.
+/// Integers that fit i64 are compared exactly, anything else is compared as f64.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Number {
+    Integer(i64),
+    Real(f64),
+}
+
+/// Digits of i64::MAX, integers with more digits or greater value are compared as f64
+pub const I64_MAX_DIGITS: &'static str = "9223372036854775807";
+
+impl Number {
+    pub fn parse(value: &str) -> Option<Number> {
+        let unsigned = if value.starts_with('-') { &value[1..] } else { value };
+
+        let mut parts = unsigned.splitn(2, '.');
+        let integer_part = parts.next().unwrap_or("");
+
+        if !_is_digits(integer_part) {
+            return None;
+        }
+
+        match parts.next() {
+            None if unsigned.len() < I64_MAX_DIGITS.len() || (unsigned.len() == I64_MAX_DIGITS.len() && unsigned <= I64_MAX_DIGITS) =>
+                value.parse().ok().map(Number::Integer),
+            None => value.parse().ok().map(Number::Real),
+            Some(fraction_part) if _is_digits(fraction_part) => value.parse().ok().map(Number::Real),
+            Some(_) => None
+        }
+    }
+
+    pub fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        match (*self, *other) {
+            (Number::Integer(a), Number::Integer(b)) => Some(a.cmp(&b)),
+            (a, b) => a.as_f64().partial_cmp(&b.as_f64())
+        }
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Number::Integer(i) => i as f64,
+            Number::Real(f) => f
+        }
+    }
+}
+
+fn _is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
 impl string::ToString for TargetValue {
     fn to_string(&self) -> String {
         match *self {
             TargetValue::Unencrypted(ref s) => format!(r#""{}""#, s),
             TargetValue::Encrypted(ref v) => format!(r#""{}""#, base64::encode(v)),
+            TargetValue::Numeric(ref s) => s.clone(),
         }
     }
 }
@@ -233,38 +288,22 @@ fn parse_single_operator(operator_name: String, key: String, value: serde_json::
     match (&*operator_name, value) {
         ("$neq", serde_json::Value::String(s)) => Ok(Operator::Neq(TagName::from(key)?, TargetValue::from(s))),
         ("$neq", _) => Err(WalletQueryError::ValueErr("$neq must be used with string".to_string())),
-        ("$gt", serde_json::Value::String(s)) => {
-            let target_name = TagName::from(key)?;
-            match target_name {
-                TagName::PlainTagName(_) => Ok(Operator::Gt(target_name, TargetValue::from(s))),
-                TagName::EncryptedTagName(_) => Err(WalletQueryError::StructureErr("$gt must be used only for nonencrypted tag".to_string()))
-            }
+        ("$gt", value) => {
+            let (target_name, target_value) = parse_comparison_operands("$gt", key, value)?;
+            Ok(Operator::Gt(target_name, target_value))
         }
-        ("$gt", _) => Err(WalletQueryError::ValueErr("$gt must be used with string".to_string())),
-        ("$gte", serde_json::Value::String(s)) => {
-            let target_name = TagName::from(key)?;
-            match target_name {
-                TagName::PlainTagName(_) => Ok(Operator::Gte(target_name, TargetValue::from(s))),
-                TagName::EncryptedTagName(_) => Err(WalletQueryError::StructureErr("$gte must be used only for nonencrypted tag".to_string()))
-            }
+        ("$gte", value) => {
+            let (target_name, target_value) = parse_comparison_operands("$gte", key, value)?;
+            Ok(Operator::Gte(target_name, target_value))
         }
-        ("$gte", _) => Err(WalletQueryError::ValueErr("$gte must be used with string".to_string())),
-        ("$lt", serde_json::Value::String(s)) => {
-            let target_name = TagName::from(key)?;
-            match target_name {
-                TagName::PlainTagName(_) => Ok(Operator::Lt(target_name, TargetValue::from(s))),
-                TagName::EncryptedTagName(_) => Err(WalletQueryError::StructureErr("$lt must be used only for nonencrypted tag".to_string()))
-            }
+        ("$lt", value) => {
+            let (target_name, target_value) = parse_comparison_operands("$lt", key, value)?;
+            Ok(Operator::Lt(target_name, target_value))
         }
-        ("$lt", _) => Err(WalletQueryError::ValueErr("$lt must be used with string".to_string())),
-        ("$lte", serde_json::Value::String(s)) => {
-            let target_name = TagName::from(key)?;
-            match target_name {
-                TagName::PlainTagName(_) => Ok(Operator::Lte(target_name, TargetValue::from(s))),
-                TagName::EncryptedTagName(_) => Err(WalletQueryError::StructureErr("$lte must be used only for nonencrypted tag".to_string()))
-            }
+        ("$lte", value) => {
+            let (target_name, target_value) = parse_comparison_operands("$lte", key, value)?;
+            Ok(Operator::Lte(target_name, target_value))
         }
-        ("$lte", _) => Err(WalletQueryError::ValueErr("$lte must be used with string".to_string())),
         ("$like", serde_json::Value::String(s)) => {
             let target_name = TagName::from(key)?;
             match target_name {
@@ -291,6 +330,23 @@ fn parse_single_operator(operator_name: String, key: String, value: serde_json::
     }
 }
 
+// Comparison operators accept either string (compared lexically) or JSON number (compared numerically)
+fn parse_comparison_operands(operator_name: &str, key: String, value: serde_json::Value) -> Result<(TagName, TargetValue), WalletQueryError> {
+    let target_value = match value {
+        serde_json::Value::String(s) => TargetValue::from(s),
+        // Floats are written without exponent to match Number format
+        serde_json::Value::Number(ref n) if n.is_f64() => TargetValue::Numeric(format!("{}", n.as_f64().unwrap_or(0.0))),
+        serde_json::Value::Number(n) => TargetValue::Numeric(n.to_string()),
+        _ => return Err(WalletQueryError::ValueErr(format!("{} must be used with string or number", operator_name)))
+    };
+
+    let target_name = TagName::from(key)?;
+    match target_name {
+        TagName::PlainTagName(_) => Ok((target_name, target_value)),
+        TagName::EncryptedTagName(_) => Err(WalletQueryError::StructureErr(format!("{} must be used only for nonencrypted tag", operator_name)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     extern crate rand;
@@ -484,6 +540,101 @@ mod tests {
         assert_eq!(query, expected);
     }
 
+    #[test]
+    fn test_simple_operator_gt_numeric_parse() {
+        let name1 = _random_string(10);
+
+        let json = format!(r#"{{"~{}":{{"$gt":10}}}}"#, name1);
+
+        let query = parse_from_json(&json).unwrap();
+
+        let expected = Operator::Gt(
+            TagName::PlainTagName(name1.to_vec()),
+            TargetValue::Numeric("10".to_string())
+        );
+
+        assert_eq!(query, expected);
+    }
+
+    #[test]
+    fn test_simple_operator_lte_numeric_float_parse() {
+        let name1 = _random_string(10);
+
+        let json = format!(r#"{{"~{}":{{"$lte":-1.5}}}}"#, name1);
+
+        let query = parse_from_json(&json).unwrap();
+
+        let expected = Operator::Lte(
+            TagName::PlainTagName(name1.to_vec()),
+            TargetValue::Numeric("-1.5".to_string())
+        );
+
+        assert_eq!(query, expected);
+    }
+
+    #[test]
+    fn test_simple_operator_gt_numeric_exponent_parse() {
+        let name1 = _random_string(10);
+
+        let json = format!(r#"{{"~{}":{{"$gt":1e3}}}}"#, name1);
+
+        let query = parse_from_json(&json).unwrap();
+
+        let expected = Operator::Gt(
+            TagName::PlainTagName(name1.to_vec()),
+            TargetValue::Numeric("1000".to_string())
+        );
+
+        assert_eq!(query, expected);
+    }
+
+    #[test]
+    fn test_number_parse() {
+        assert_eq!(Some(Number::Integer(10)), Number::parse("10"));
+        assert_eq!(Some(Number::Integer(-12)), Number::parse("-012"));
+        assert_eq!(Some(Number::Real(-1.5)), Number::parse("-1.5"));
+        assert_eq!(Some(Number::Integer(9223372036854775807)), Number::parse("9223372036854775807"));
+        assert_eq!(Some(Number::Real(9223372036854775808.0)), Number::parse("9223372036854775808"));
+        assert_eq!(None, Number::parse("1-2"));
+        assert_eq!(None, Number::parse("1.2.3"));
+        assert_eq!(None, Number::parse("1e3"));
+        assert_eq!(None, Number::parse("+1"));
+        assert_eq!(None, Number::parse(".5"));
+        assert_eq!(None, Number::parse("5."));
+        assert_eq!(None, Number::parse("-"));
+        assert_eq!(None, Number::parse(""));
+        assert_eq!(None, Number::parse("10 apples"));
+    }
+
+    #[test]
+    fn test_number_cmp() {
+        assert_eq!(Some(Ordering::Less), Number::Integer(9007199254740992).partial_cmp(&Number::Integer(9007199254740993)));
+        assert_eq!(Some(Ordering::Greater), Number::Integer(10).partial_cmp(&Number::Integer(9)));
+        assert_eq!(Some(Ordering::Less), Number::Real(1.5).partial_cmp(&Number::Integer(2)));
+    }
+
+    #[test]
+    fn test_simple_operator_gt_numeric_encrypted_parse() {
+        let name1 = _random_string(10);
+
+        let json = format!(r#"{{"{}":{{"$gt":10}}}}"#, name1);
+
+        let res = parse_from_json(&json);
+
+        assert_match!(Err(WalletQueryError::StructureErr(_)), res);
+    }
+
+    #[test]
+    fn test_simple_operator_eq_numeric_parse() {
+        let name1 = _random_string(10);
+
+        let json = format!(r#"{{"~{}":10}}"#, name1);
+
+        let res = parse_from_json(&json);
+
+        assert_match!(Err(WalletQueryError::StructureErr(_)), res);
+    }
+
     #[test]
     fn test_simple_operator_gte_plaintext_parse() {
         let name1 = _random_string(10);
@@ -2311,6 +2462,22 @@ mod tests {
         assert_eq!(json, expected);
     }
 
+    #[test]
+    fn test_simple_operator_gt_numeric_to_string() {
+        let name1 = _random_vector(10);
+
+        let query = Operator::Gt(
+            TagName::PlainTagName(name1.clone()),
+            TargetValue::Numeric("10".to_string())
+        );
+
+        let json = query.to_string();
+
+        let expected = format!(r#"{{"~{}":{{"$gt":10}}}}"#, base64::encode(&name1));
+
+        assert_eq!(json, expected);
+    }
+
     #[test]
     fn test_simple_operator_gte_plaintext_to_string() {
         let name1 = _random_vector(10);
@@ -2,7 +2,8 @@ mod storage;
 mod encryption;
 mod query_encryption;
 mod iterator;
-mod language;
+mod cache;
+pub mod language;
 mod export_import;
 mod wallet;
 
@@ -13,9 +14,10 @@ use std::fs;
 use std::path::PathBuf;
 use named_type::NamedType;
 use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use api::wallet::*;
-use domain::wallet::{Config, Credentials, ExportConfig, Metadata, MetadataArgon, MetadataRaw, KeyDerivationMethod, Tags};
+use domain::wallet::{Config, Credentials, ExportConfig, CheckConfig, WalletCheckReport, Metadata, MetadataArgon, MetadataRaw, KeyDerivationMethod, RecordOperation, Tags};
 use errors::wallet::WalletError;
 use errors::common::CommonError;
 use utils::sequence;
@@ -23,9 +25,15 @@ use utils::sequence;
 use self::export_import::{export, import};
 use self::storage::WalletStorageType;
 use self::storage::default::SQLiteStorageType;
+use self::storage::inmem::InmemStorageType;
+use self::storage::kv::KVStorageType;
 use self::storage::plugged::PluggedStorageType;
 use self::wallet::{Wallet, Keys};
 
+/// Plain tag libindy sets on DIDs, keys and pairwise with the creation unix time in seconds.
+/// It is kept when tags are replaced, so records can always be searched by creation time.
+pub const CREATED_AT_TAG: &'static str = "~created_at";
+
 pub struct WalletService {
     storage_types: RefCell<HashMap<String, Box<WalletStorageType>>>,
     wallets: RefCell<HashMap<i32, Box<Wallet>>>
@@ -36,6 +44,8 @@ impl WalletService {
         let storage_types = {
             let mut map: HashMap<String, Box<WalletStorageType>> = HashMap::new();
             map.insert("default".to_string(), Box::new(SQLiteStorageType::new()));
+            map.insert("kv".to_string(), Box::new(KVStorageType::new()));
+            map.insert("inmem".to_string(), Box::new(InmemStorageType::new()));
             RefCell::new(map)
         };
 
@@ -95,6 +105,36 @@ impl WalletService {
         Ok(())
     }
 
+    pub fn register_wallet_storage_batch(&self, type_: &str, batch: WalletBatch) -> Result<(), WalletError> {
+        trace!("register_wallet_storage_batch >>> type_: {:?}", type_);
+
+        let storage_types = self.storage_types.borrow();
+
+        let storage_type = storage_types
+            .get(type_)
+            .ok_or(WalletError::UnknownType(type_.to_string()))?;
+
+        storage_type.set_batch_handler(batch)?;
+
+        trace!("register_wallet_storage_batch <<<");
+        Ok(())
+    }
+
+    pub fn register_wallet_storage_versioning(&self, type_: &str, get_record_version: WalletGetRecordVersion) -> Result<(), WalletError> {
+        trace!("register_wallet_storage_versioning >>> type_: {:?}", type_);
+
+        let storage_types = self.storage_types.borrow();
+
+        let storage_type = storage_types
+            .get(type_)
+            .ok_or(WalletError::UnknownType(type_.to_string()))?;
+
+        storage_type.set_version_handler(get_record_version)?;
+
+        trace!("register_wallet_storage_versioning <<<");
+        Ok(())
+    }
+
     pub fn create_wallet(&self,
                          config: &Config,
                          credentials: &Credentials) -> Result<(), WalletError> {
@@ -237,7 +277,7 @@ impl WalletService {
             storage.set_storage_metadata(&metadata)?;
         }
 
-        let wallet = Wallet::new(config.id.clone(), storage, Rc::new(keys));
+        let wallet = Wallet::new(config.id.clone(), storage, Rc::new(keys), config.cache_size);
 
         let wallet_handle = sequence::get_next_id();
         let mut wallets = self.wallets.borrow_mut();
@@ -266,6 +306,13 @@ impl WalletService {
         }
     }
 
+    pub fn add_record_with_expiry(&self, wallet_handle: i32, type_: &str, name: &str, value: &str, tags: &Tags, expires_at: u64) -> Result<(), WalletError> {
+        match self.wallets.borrow_mut().get_mut(&wallet_handle) {
+            Some(wallet) => wallet.add_with_expiry(type_, name, value, tags, expires_at),
+            None => Err(WalletError::InvalidHandle(wallet_handle.to_string()))
+        }
+    }
+
     pub fn add_indy_object<T>(&self, wallet_handle: i32, name: &str, object: &T, tags: &Tags)
                               -> Result<String, WalletError> where T: ::serde::Serialize + Sized, T: NamedType {
         let type_ = T::short_type_name();
@@ -283,6 +330,13 @@ impl WalletService {
         }
     }
 
+    pub fn update_record_value_if_version(&self, wallet_handle: i32, type_: &str, name: &str, value: &str, version: u64) -> Result<(), WalletError> {
+        match self.wallets.borrow().get(&wallet_handle) {
+            Some(wallet) => wallet.update_if_version(type_, name, value, version),
+            None => Err(WalletError::InvalidHandle(wallet_handle.to_string()))
+        }
+    }
+
     pub fn update_indy_object<T>(&self, wallet_handle: i32, name: &str, object: &T) -> Result<String, WalletError> where T: ::serde::Serialize + Sized, T: NamedType {
         let type_ = T::short_type_name();
         match self.wallets.borrow().get(&wallet_handle) {
@@ -311,6 +365,45 @@ impl WalletService {
         }
     }
 
+    pub fn update_record_tags_if_version(&self, wallet_handle: i32, type_: &str, name: &str, tags: &Tags, version: u64) -> Result<(), WalletError> {
+        match self.wallets.borrow().get(&wallet_handle) {
+            Some(wallet) => wallet.update_tags_if_version(type_, name, tags, version),
+            None => Err(WalletError::InvalidHandle(wallet_handle.to_string()))
+        }
+    }
+
+    pub fn update_indy_record_tags<T>(&self, wallet_handle: i32, name: &str, tags: &Tags) -> Result<(), WalletError> where T: NamedType {
+        self.update_record_tags(wallet_handle, &self.add_prefix(T::short_type_name()), name, tags)
+    }
+
+    pub fn update_indy_record_user_tags<T>(&self, wallet_handle: i32, name: &str, tags: &Tags, reserved_tags: &[&str]) -> Result<(), WalletError> where T: NamedType {
+        if let Some(tag) = reserved_tags.iter().find(|tag| tags.contains_key(**tag)) {
+            return Err(WalletError::CommonError(CommonError::InvalidStructure(format!("Tag {} is reserved", tag))));
+        }
+
+        let record = self.get_indy_record::<T>(wallet_handle, name, &RecordOptions::id_tags())?;
+
+        let mut tags = tags.clone();
+
+        if let Some(record_tags) = record.get_tags() {
+            for tag in reserved_tags {
+                if let Some(value) = record_tags.get(*tag) {
+                    tags.insert(tag.to_string(), value.clone());
+                }
+            }
+        }
+
+        self.update_indy_record_tags::<T>(wallet_handle, name, &tags)
+    }
+
+    pub fn creation_tags() -> Tags {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or(0);
+
+        let mut tags = Tags::new();
+        tags.insert(CREATED_AT_TAG.to_string(), now.to_string());
+        tags
+    }
+
     pub fn delete_record_tags(&self, wallet_handle: i32, type_: &str, name: &str, tag_names: &[&str]) -> Result<(), WalletError> {
         match self.wallets.borrow().get(&wallet_handle) {
             Some(wallet) => wallet.delete_tags(type_, name, tag_names),
@@ -325,10 +418,32 @@ impl WalletService {
         }
     }
 
+    pub fn delete_record_if_version(&self, wallet_handle: i32, type_: &str, name: &str, version: u64) -> Result<(), WalletError> {
+        match self.wallets.borrow().get(&wallet_handle) {
+            Some(wallet) => wallet.delete_if_version(type_, name, version),
+            None => Err(WalletError::InvalidHandle(wallet_handle.to_string()))
+        }
+    }
+
     pub fn delete_indy_record<T>(&self, wallet_handle: i32, name: &str) -> Result<(), WalletError> where T: NamedType {
         self.delete_record(wallet_handle, &self.add_prefix(T::short_type_name()), name)
     }
 
+    pub fn batch(&self, wallet_handle: i32, operations: &[RecordOperation]) -> Result<(), WalletError> {
+        match self.wallets.borrow().get(&wallet_handle) {
+            Some(wallet) => wallet.batch(operations),
+            None => Err(WalletError::InvalidHandle(wallet_handle.to_string()))
+        }
+    }
+
+    // Atomic if wallet storage supports batches, otherwise operations are applied one by one
+    pub fn apply_operations(&self, wallet_handle: i32, operations: &[RecordOperation]) -> Result<(), WalletError> {
+        match self.wallets.borrow().get(&wallet_handle) {
+            Some(wallet) => wallet.apply_operations(operations),
+            None => Err(WalletError::InvalidHandle(wallet_handle.to_string()))
+        }
+    }
+
     pub fn get_record(&self, wallet_handle: i32, type_: &str, name: &str, options_json: &str) -> Result<WalletRecord, WalletError> {
         match self.wallets.borrow().get(&wallet_handle) {
             Some(wallet) => wallet.get(type_, name, options_json),
@@ -374,6 +489,27 @@ impl WalletService {
         }
     }
 
+    pub fn count_records(&self, wallet_handle: i32, type_: &str, query_json: &str) -> Result<usize, WalletError> {
+        match self.wallets.borrow().get(&wallet_handle) {
+            Some(wallet) => wallet.count(type_, query_json),
+            None => Err(WalletError::InvalidHandle(wallet_handle.to_string()))
+        }
+    }
+
+    pub fn count_records_by_tag(&self, wallet_handle: i32, type_: &str, query_json: &str, tag_name: &str) -> Result<Vec<(String, usize)>, WalletError> {
+        match self.wallets.borrow().get(&wallet_handle) {
+            Some(wallet) => wallet.count_by_tag(type_, query_json, tag_name),
+            None => Err(WalletError::InvalidHandle(wallet_handle.to_string()))
+        }
+    }
+
+    pub fn purge_expired_records(&self, wallet_handle: i32) -> Result<usize, WalletError> {
+        match self.wallets.borrow().get(&wallet_handle) {
+            Some(wallet) => wallet.purge_expired(),
+            None => Err(WalletError::InvalidHandle(wallet_handle.to_string()))
+        }
+    }
+
     pub fn search_indy_records<T>(&self, wallet_handle: i32, query_json: &str, options_json: &str) -> Result<WalletSearch, WalletError> where T: NamedType {
         self.search_records(wallet_handle, &self.add_prefix(T::short_type_name()), query_json, options_json)
     }
@@ -396,6 +532,20 @@ impl WalletService {
         }
     }
 
+    pub fn add_indy_object_operation<T>(&self, name: &str, object: &T, tags: &Tags) -> Result<RecordOperation, WalletError>
+        where T: ::serde::Serialize + Sized, T: NamedType {
+        let type_ = T::short_type_name();
+        let object_json = serde_json::to_string(object)
+            .map_err(map_err_trace!())
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize {:?}: {:?}", type_, err)))?;
+
+        Ok(RecordOperation::Add { type_: self.add_prefix(type_), id: name.to_string(), value: object_json, tags: Some(tags.clone()), expires_at: None })
+    }
+
+    pub fn delete_indy_record_operation<T>(&self, name: &str) -> RecordOperation where T: NamedType {
+        RecordOperation::Delete { type_: self.add_prefix(T::short_type_name()), id: name.to_string() }
+    }
+
     pub fn record_exists<T>(&self, wallet_handle: i32, name: &str) -> Result<bool, WalletError> where T: NamedType {
         match self.wallets.borrow().get(&wallet_handle) {
             Some(wallet) =>
@@ -444,6 +594,20 @@ impl WalletService {
         res
     }
 
+    pub fn check_wallet(&self, wallet_handle: i32, check_config: &CheckConfig) -> Result<WalletCheckReport, WalletError> {
+        trace!("check_wallet >>> wallet_handle: {:?}, check_config: {:?}", wallet_handle, check_config);
+
+        let wallets = self.wallets.borrow();
+        let wallet = wallets
+            .get(&wallet_handle)
+            .ok_or(WalletError::InvalidHandle(wallet_handle.to_string()))?;
+
+        let res = wallet.check(check_config.quarantine)?;
+
+        trace!("check_wallet <<< res: {:?}", res);
+        Ok(res)
+    }
+
     pub fn import_wallet(&self,
                          config: &Config,
                          credentials: &Credentials,
@@ -540,7 +704,10 @@ pub struct WalletRecord {
     type_: Option<String>,
     id: String,
     value: Option<String>,
-    tags: Option<Tags>
+    tags: Option<Tags>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<u64>
 }
 
 impl Ord for WalletRecord {
@@ -556,12 +723,13 @@ impl PartialOrd for WalletRecord {
 }
 
 impl WalletRecord {
-    pub fn new(name: String, type_: Option<String>, value: Option<String>, tags: Option<Tags>) -> WalletRecord {
+    pub fn new(name: String, type_: Option<String>, value: Option<String>, tags: Option<Tags>, version: Option<u64>) -> WalletRecord {
         WalletRecord {
             id: name,
             type_,
             value,
             tags,
+            version,
         }
     }
 
@@ -578,7 +746,6 @@ impl WalletRecord {
         self.value.as_ref().map(String::as_str)
     }
 
-    #[allow(dead_code)]
     pub fn get_tags(&self) -> Option<&Tags> {
         self.tags.as_ref()
     }
@@ -596,7 +763,9 @@ pub struct RecordOptions {
     #[serde(default = "default_true")]
     retrieve_value: bool,
     #[serde(default = "default_false")]
-    retrieve_tags: bool
+    retrieve_tags: bool,
+    #[serde(default = "default_false")]
+    retrieve_version: bool
 }
 
 impl RecordOptions {
@@ -604,7 +773,8 @@ impl RecordOptions {
         let options = RecordOptions {
             retrieve_type: false,
             retrieve_value: false,
-            retrieve_tags: false
+            retrieve_tags: false,
+            retrieve_version: false
         };
 
         serde_json::to_string(&options).unwrap()
@@ -614,7 +784,41 @@ impl RecordOptions {
         let options = RecordOptions {
             retrieve_type: false,
             retrieve_value: true,
-            retrieve_tags: false
+            retrieve_tags: false,
+            retrieve_version: false
+        };
+
+        serde_json::to_string(&options).unwrap()
+    }
+
+    pub fn id_tags() -> String {
+        let options = RecordOptions {
+            retrieve_type: false,
+            retrieve_value: false,
+            retrieve_tags: true,
+            retrieve_version: false
+        };
+
+        serde_json::to_string(&options).unwrap()
+    }
+
+    pub fn id_value_tags() -> String {
+        let options = RecordOptions {
+            retrieve_type: false,
+            retrieve_value: true,
+            retrieve_tags: true,
+            retrieve_version: false
+        };
+
+        serde_json::to_string(&options).unwrap()
+    }
+
+    pub fn id_value_tags_version() -> String {
+        let options = RecordOptions {
+            retrieve_type: false,
+            retrieve_value: true,
+            retrieve_tags: true,
+            retrieve_version: true
         };
 
         serde_json::to_string(&options).unwrap()
@@ -627,6 +831,7 @@ impl Default for RecordOptions {
             retrieve_type: false,
             retrieve_value: true,
             retrieve_tags: false,
+            retrieve_version: false,
         }
     }
 }
@@ -657,7 +862,33 @@ pub struct SearchOptions {
     #[serde(default = "default_true")]
     retrieve_value: bool,
     #[serde(default = "default_false")]
-    retrieve_tags: bool
+    retrieve_tags: bool,
+    #[serde(default = "default_false")]
+    retrieve_version: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_by: Option<String>,
+    #[serde(default)]
+    sort_order: SortOrder,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc
+}
+
+impl Default for SortOrder {
+    fn default() -> SortOrder {
+        SortOrder::Asc
+    }
 }
 
 impl SearchOptions {
@@ -667,7 +898,34 @@ impl SearchOptions {
             retrieve_total_count: true,
             retrieve_type: true,
             retrieve_value: true,
-            retrieve_tags: false
+            retrieve_tags: false,
+            ..SearchOptions::default()
+        };
+
+        serde_json::to_string(&options).unwrap()
+    }
+
+    pub fn id_tags() -> String {
+        let options = SearchOptions {
+            retrieve_records: true,
+            retrieve_total_count: false,
+            retrieve_type: false,
+            retrieve_value: false,
+            retrieve_tags: true,
+            ..SearchOptions::default()
+        };
+
+        serde_json::to_string(&options).unwrap()
+    }
+
+    pub fn id_value_tags() -> String {
+        let options = SearchOptions {
+            retrieve_records: true,
+            retrieve_total_count: false,
+            retrieve_type: false,
+            retrieve_value: true,
+            retrieve_tags: true,
+            ..SearchOptions::default()
         };
 
         serde_json::to_string(&options).unwrap()
@@ -682,6 +940,11 @@ impl Default for SearchOptions {
             retrieve_type: false,
             retrieve_value: true,
             retrieve_tags: false,
+            retrieve_version: false,
+            sort_by: None,
+            sort_order: SortOrder::Asc,
+            offset: None,
+            limit: None,
         }
     }
 }
@@ -712,6 +975,37 @@ mod tests {
         _register_inmem_wallet(&wallet_service);
     }
 
+    #[test]
+    fn wallet_service_register_type_works_for_twice() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        _register_inmem_wallet(&wallet_service);
+
+        let res = _register_inmem_storage(&wallet_service, "plugged_inmem");
+        assert_match!(Err(WalletError::TypeAlreadyRegistered(_)), res);
+    }
+
+    #[test]
+    fn wallet_service_register_type_works_for_default() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+
+        let res = _register_inmem_storage(&wallet_service, "default");
+        assert_match!(Err(WalletError::TypeAlreadyRegistered(_)), res);
+    }
+
+    #[test]
+    fn wallet_service_register_type_works_for_builtin_inmem() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+
+        let res = _register_inmem_storage(&wallet_service, "inmem");
+        assert_match!(Err(WalletError::TypeAlreadyRegistered(_)), res);
+    }
+
     #[test]
     fn wallet_service_create_wallet_works() {
         _cleanup();
@@ -929,6 +1223,62 @@ mod tests {
         wallet_service.open_wallet(&_config_inmem(), &_credentials()).unwrap();
     }
 
+    #[test]
+    fn wallet_service_open_works_for_builtin_inmem() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        wallet_service.create_wallet(&_config_builtin_inmem(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config_builtin_inmem(), &_credentials()).unwrap();
+
+        let mut tags = HashMap::new();
+        tags.insert("tag".to_string(), "value".to_string());
+        wallet_service.add_record(wallet_handle, "type", "key1", "value1", &tags).unwrap();
+        wallet_service.close_wallet(wallet_handle).unwrap();
+
+        let wallet_handle = wallet_service.open_wallet(&_config_builtin_inmem(), &_credentials()).unwrap();
+
+        let mut search = wallet_service.search_records(wallet_handle, "type", r#"{"tag": "value"}"#, &_fetch_options(true, true, true)).unwrap();
+
+        let record = search.fetch_next_record().unwrap().unwrap();
+        assert_eq!("value1", record.get_value().unwrap());
+        assert!(search.fetch_next_record().unwrap().is_none());
+
+        wallet_service.close_wallet(wallet_handle).unwrap();
+        wallet_service.delete_wallet(&_config_builtin_inmem(), &_credentials()).unwrap();
+
+        let res = wallet_service.open_wallet(&_config_builtin_inmem(), &_credentials());
+        assert_match!(Err(WalletError::NotFound(_)), res);
+    }
+
+    #[test]
+    fn wallet_service_open_works_for_kv() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        wallet_service.create_wallet(&_config_kv(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config_kv(), &_credentials()).unwrap();
+
+        let mut tags = HashMap::new();
+        tags.insert("~tag".to_string(), "value".to_string());
+        wallet_service.add_record(wallet_handle, "type", "key1", "value1", &tags).unwrap();
+        wallet_service.add_record(wallet_handle, "type", "key2", "value2", &HashMap::new()).unwrap();
+        wallet_service.close_wallet(wallet_handle).unwrap();
+
+        let wallet_handle = wallet_service.open_wallet(&_config_kv(), &_credentials()).unwrap();
+
+        let mut search = wallet_service.search_records(wallet_handle, "type", r#"{"~tag": "value"}"#, &_fetch_options(true, true, true)).unwrap();
+
+        let record = search.fetch_next_record().unwrap().unwrap();
+        assert_eq!("value1", record.get_value().unwrap());
+        assert_eq!(tags, record.get_tags().unwrap().clone());
+
+        assert!(search.fetch_next_record().unwrap().is_none());
+
+        wallet_service.close_wallet(wallet_handle).unwrap();
+        wallet_service.delete_wallet(&_config_kv(), &_credentials()).unwrap();
+    }
+
 //    #[test]
 //    fn wallet_service_open_wallet_without_master_key_in_credentials_returns_error() {
 //        _cleanup();
@@ -1201,6 +1551,143 @@ mod tests {
         assert_eq!(new_value, record.get_value().unwrap());
     }
 
+    #[test]
+    fn wallet_service_update_if_version() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        wallet_service.create_wallet(&_config(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config(), &_credentials()).unwrap();
+
+        _check_update_if_version(&wallet_service, wallet_handle);
+    }
+
+    #[test]
+    fn wallet_service_update_if_version_for_plugged() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        _register_inmem_wallet(&wallet_service);
+        wallet_service.register_wallet_storage_batch("plugged_inmem", InmemWallet::batch).unwrap();
+        wallet_service.register_wallet_storage_versioning("plugged_inmem", InmemWallet::get_record_version).unwrap();
+
+        wallet_service.create_wallet(&_config_inmem(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config_inmem(), &_credentials()).unwrap();
+
+        _check_update_if_version(&wallet_service, wallet_handle);
+    }
+
+    #[test]
+    fn wallet_service_update_if_version_for_plugged_without_versioning() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        _register_inmem_wallet(&wallet_service);
+        wallet_service.register_wallet_storage_batch("plugged_inmem", InmemWallet::batch).unwrap();
+
+        wallet_service.create_wallet(&_config_inmem(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config_inmem(), &_credentials()).unwrap();
+
+        wallet_service.add_record(wallet_handle, "type", "name", "value", &HashMap::new()).unwrap();
+
+        let record = wallet_service.get_record(wallet_handle, "type", "name", &RecordOptions::id_value_tags_version()).unwrap();
+        assert_eq!(None, record.version);
+
+        let res = wallet_service.update_record_value_if_version(wallet_handle, "type", "name", "new_value", 1);
+        assert_match!(Err(WalletError::CommonError(CommonError::InvalidState(_))), res);
+    }
+
+    #[test]
+    fn wallet_service_update_if_version_works_for_invalid_handle() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+
+        let res = wallet_service.update_record_value_if_version(1, "type", "name", "value", 1);
+        assert_match!(Err(WalletError::InvalidHandle(_)), res);
+    }
+
+    #[test]
+    fn wallet_service_add_record_with_expiry_works() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        wallet_service.create_wallet(&_config(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config(), &_credentials()).unwrap();
+
+        wallet_service.add_record_with_expiry(wallet_handle, "type", "expired", "value", &HashMap::new(), 1).unwrap();
+        wallet_service.add_record_with_expiry(wallet_handle, "type", "actual", "value", &HashMap::new(), u32::max_value() as u64).unwrap();
+
+        let res = wallet_service.get_record(wallet_handle, "type", "expired", &_fetch_options(false, true, false));
+        assert_match!(Err(WalletError::ItemNotFound), res);
+
+        let record = wallet_service.get_record(wallet_handle, "type", "actual", &_fetch_options(false, true, false)).unwrap();
+        assert_eq!("value", record.get_value().unwrap());
+
+        assert_eq!(1, wallet_service.purge_expired_records(wallet_handle).unwrap());
+        assert_eq!(0, wallet_service.purge_expired_records(wallet_handle).unwrap());
+    }
+
+    #[test]
+    fn wallet_service_add_record_with_expiry_works_for_plugged() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        _register_inmem_wallet(&wallet_service);
+
+        wallet_service.create_wallet(&_config_inmem(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config_inmem(), &_credentials()).unwrap();
+
+        let res = wallet_service.add_record_with_expiry(wallet_handle, "type", "name", "value", &HashMap::new(), 1);
+        assert_match!(Err(WalletError::StorageError(_)), res);
+
+        let res = wallet_service.purge_expired_records(wallet_handle);
+        assert_match!(Err(WalletError::StorageError(_)), res);
+    }
+
+    #[test]
+    fn wallet_service_add_record_with_expiry_works_for_invalid_handle() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+
+        let res = wallet_service.add_record_with_expiry(1, "type", "name", "value", &HashMap::new(), 1);
+        assert_match!(Err(WalletError::InvalidHandle(_)), res);
+
+        let res = wallet_service.purge_expired_records(1);
+        assert_match!(Err(WalletError::InvalidHandle(_)), res);
+    }
+
+    fn _check_update_if_version(wallet_service: &WalletService, wallet_handle: i32) {
+        let type_ = "type";
+        let name = "name";
+
+        wallet_service.add_record(wallet_handle, type_, name, "value", &HashMap::new()).unwrap();
+        let record = wallet_service.get_record(wallet_handle, type_, name, &RecordOptions::id_value_tags_version()).unwrap();
+        assert_eq!(Some(1), record.version);
+
+        wallet_service.update_record_value_if_version(wallet_handle, type_, name, "new_value", 1).unwrap();
+
+        let res = wallet_service.update_record_value_if_version(wallet_handle, type_, name, "stale_value", 1);
+        assert_match!(Err(WalletError::ItemVersionMismatch), res);
+
+        let res = wallet_service.update_record_tags_if_version(wallet_handle, type_, name, &HashMap::new(), 1);
+        assert_match!(Err(WalletError::ItemVersionMismatch), res);
+
+        let res = wallet_service.delete_record_if_version(wallet_handle, type_, name, 1);
+        assert_match!(Err(WalletError::ItemVersionMismatch), res);
+
+        let record = wallet_service.get_record(wallet_handle, type_, name, &RecordOptions::id_value_tags_version()).unwrap();
+        assert_eq!("new_value", record.get_value().unwrap());
+        assert_eq!(Some(2), record.version);
+
+        wallet_service.update_record_tags_if_version(wallet_handle, type_, name, &HashMap::new(), 2).unwrap();
+        wallet_service.delete_record_if_version(wallet_handle, type_, name, 3).unwrap();
+
+        let res = wallet_service.get_record(wallet_handle, type_, name, &_fetch_options(false, true, false));
+        assert_match!(Err(WalletError::ItemNotFound), res);
+    }
+
     /**
      * Delete tests
     */
@@ -1412,6 +1899,27 @@ mod tests {
         assert_eq!(expected_tags, retrieved_tags);
     }
 
+    #[test]
+    fn wallet_service_get_record_works_for_cache() {
+        _cleanup();
+
+        let config = Config { cache_size: Some(10), .._config() };
+
+        let wallet_service = WalletService::new();
+        wallet_service.create_wallet(&config, &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&config, &_credentials()).unwrap();
+
+        wallet_service.add_record(wallet_handle, "type", "key1", "value1", &HashMap::new()).unwrap();
+
+        let record = wallet_service.get_record(wallet_handle, "type", "key1", &_fetch_options(false, true, false)).unwrap();
+        assert_eq!("value1", record.get_value().unwrap());
+
+        wallet_service.update_record_value(wallet_handle, "type", "key1", "value2").unwrap();
+
+        let record = wallet_service.get_record(wallet_handle, "type", "key1", &_fetch_options(false, true, false)).unwrap();
+        assert_eq!("value2", record.get_value().unwrap());
+    }
+
     #[test]
     fn wallet_service_search_records_works() {
         _cleanup();
@@ -1433,6 +1941,46 @@ mod tests {
         assert!(search.fetch_next_record().unwrap().is_none());
     }
 
+    #[test]
+    fn wallet_service_count_records_works() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        wallet_service.create_wallet(&_config(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config(), &_credentials()).unwrap();
+
+        wallet_service.add_record(wallet_handle, "type", "key1", "value1", &HashMap::new()).unwrap();
+        wallet_service.add_record(wallet_handle, "type", "key2", "value2", &HashMap::new()).unwrap();
+        wallet_service.add_record(wallet_handle, "type3", "key3", "value3", &HashMap::new()).unwrap();
+
+        assert_eq!(2, wallet_service.count_records(wallet_handle, "type", "{}").unwrap());
+        assert_eq!(1, wallet_service.count_records(wallet_handle, "type3", "{}").unwrap());
+        assert_eq!(0, wallet_service.count_records(wallet_handle, "type4", "{}").unwrap());
+    }
+
+    #[test]
+    fn wallet_service_count_records_by_tag_works_for_plugged_wallet() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        _register_inmem_wallet(&wallet_service);
+
+        wallet_service.create_wallet(&_config_inmem(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config_inmem(), &_credentials()).unwrap();
+
+        let mut tags = HashMap::new();
+        tags.insert("~status".to_string(), "active".to_string());
+
+        wallet_service.add_record(wallet_handle, "type", "key1", "value1", &tags).unwrap();
+        wallet_service.add_record(wallet_handle, "type", "key2", "value2", &tags).unwrap();
+        wallet_service.add_record(wallet_handle, "type", "key3", "value3", &HashMap::new()).unwrap();
+
+        assert_eq!(3, wallet_service.count_records(wallet_handle, "type", "{}").unwrap());
+
+        let counts = wallet_service.count_records_by_tag(wallet_handle, "type", "{}", "~status").unwrap();
+        assert_eq!(vec![("active".to_string(), 2)], counts);
+    }
+
     #[test]
     fn wallet_service_search_records_works_for_plugged_wallet() {
         _cleanup();
@@ -1555,6 +2103,32 @@ mod tests {
         assert_eq!("value1", record.get_value().unwrap());
     }
 
+    #[test]
+    fn wallet_service_check_wallet_works() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+        wallet_service.create_wallet(&_config(), &_credentials()).unwrap();
+        let wallet_handle = wallet_service.open_wallet(&_config(), &_credentials()).unwrap();
+
+        wallet_service.add_record(wallet_handle, "type", "key1", "value1", &HashMap::new()).unwrap();
+
+        let report = wallet_service.check_wallet(wallet_handle, &CheckConfig { quarantine: true }).unwrap();
+        assert_eq!(1, report.checked);
+        assert!(report.failed.is_empty());
+        assert_eq!(0, report.quarantined);
+    }
+
+    #[test]
+    fn wallet_service_check_wallet_works_for_invalid_handle() {
+        _cleanup();
+
+        let wallet_service = WalletService::new();
+
+        let res = wallet_service.check_wallet(1, &CheckConfig { quarantine: false });
+        assert_match!(Err(WalletError::InvalidHandle(_)), res);
+    }
+
     #[test]
     fn wallet_service_export_wallet_when_empty() {
         _cleanup();
@@ -1803,6 +2377,7 @@ mod tests {
             id: "w1".to_string(),
             storage_type: None,
             storage_config: None,
+            cache_size: None,
         }
     }
 
@@ -1811,14 +2386,34 @@ mod tests {
             id: "w1".to_string(),
             storage_type: Some("default".to_string()),
             storage_config: None,
+            cache_size: None,
         }
     }
 
     fn _config_inmem() -> Config {
         Config {
             id: "w1".to_string(),
-            storage_type: Some("inmem".to_string()),
+            storage_type: Some("plugged_inmem".to_string()),
             storage_config: None,
+            cache_size: None,
+        }
+    }
+
+    fn _config_builtin_inmem() -> Config {
+        Config {
+            id: "w1".to_string(),
+            storage_type: Some("plugged_inmem".to_string()),
+            storage_config: None,
+            cache_size: None,
+        }
+    }
+
+    fn _config_kv() -> Config {
+        Config {
+            id: "w1".to_string(),
+            storage_type: Some("kv".to_string()),
+            storage_config: None,
+            cache_size: None,
         }
     }
 
@@ -1827,6 +2422,7 @@ mod tests {
             id: "w1".to_string(),
             storage_type: Some("unknown".to_string()),
             storage_config: None,
+            cache_size: None,
         }
     }
 
@@ -1966,9 +2562,13 @@ mod tests {
     }
 
     fn _register_inmem_wallet(wallet_service: &WalletService) {
+        _register_inmem_storage(wallet_service, "plugged_inmem").unwrap();
+    }
+
+    fn _register_inmem_storage(wallet_service: &WalletService, type_: &str) -> Result<(), WalletError> {
         wallet_service
             .register_wallet_storage(
-                "inmem",
+                type_,
                 InmemWallet::create,
                 InmemWallet::open,
                 InmemWallet::close,
@@ -1994,6 +2594,5 @@ mod tests {
                 InmemWallet::fetch_search_next_record,
                 InmemWallet::free_search
             )
-            .unwrap();
     }
 }
@@ -69,6 +69,7 @@ fn encrypt_operator(op: Operator, keys: &Keys) -> Result<Operator, WalletQueryEr
 // Encrypts a single tag name, tag value pair.
 // If the tag name is EncryptedTagName enum variant, encrypts both the tag name and the tag value
 // If the tag name is PlainTagName enum variant, encrypts only the tag name
+// Numeric values are allowed only for PlainTagName and are kept as is
 fn encrypt_name_value(name: &TagName, value: TargetValue, keys: &Keys) -> Result<(TagName, TargetValue), WalletQueryError> {
     match (name, value) {
         (&TagName::EncryptedTagName(ref name), TargetValue::Unencrypted(ref s)) => {
@@ -80,6 +81,10 @@ fn encrypt_name_value(name: &TagName, value: TargetValue, keys: &Keys) -> Result
             let encrypted_tag_name = encrypt_as_searchable(&name[..], &keys.tag_name_key, &keys.tags_hmac_key);
             Ok((TagName::PlainTagName(encrypted_tag_name), TargetValue::Unencrypted(s.clone())))
         },
+        (&TagName::PlainTagName(ref name), TargetValue::Numeric(ref s)) => {
+            let encrypted_tag_name = encrypt_as_searchable(&name[..], &keys.tag_name_key, &keys.tags_hmac_key);
+            Ok((TagName::PlainTagName(encrypted_tag_name), TargetValue::Numeric(s.clone())))
+        },
         _ => Err(WalletQueryError::StructureErr("Reached invalid combination of tag name and value while encrypting query".to_string()))
     }
 }
\ No newline at end of file
@@ -14,23 +14,29 @@ use self::owning_ref::OwningHandle;
 use std::rc::Rc;
 
 use utils::environment;
-use errors::wallet::WalletStorageError;
+use utils::crypto::base64;
+use errors::wallet::{WalletStorageError, WalletQueryError};
 use errors::common::CommonError;
 use services::wallet::language;
 
-use super::{StorageIterator, WalletStorageType, WalletStorage, StorageRecord, EncryptedValue, Tag, TagName};
+use super::{StorageIterator, WalletStorageType, WalletStorage, StorageRecord, StorageOperation, EncryptedValue, Tag, TagName};
 use super::super::{RecordOptions, SearchOptions};
 
 
 const _SQLITE_DB: &str = "sqlite.db";
 const _PLAIN_TAGS_QUERY: &str = "SELECT name, value from tags_plaintext where item_id = ?";
 const _ENCRYPTED_TAGS_QUERY: &str = "SELECT name, value from tags_encrypted where item_id = ?";
+const _EXPIRED: &str = "expires_at <= CAST(strftime('%s', 'now') AS INTEGER)";
+const _ORPHAN_TAG: &str = "item_id NOT IN (SELECT id FROM items)";
+const _SCHEMA_VERSION: i32 = 1;
 const _CREATE_SCHEMA: &str = "
     PRAGMA locking_mode=EXCLUSIVE;
     PRAGMA foreign_keys=ON;
 
     BEGIN EXCLUSIVE TRANSACTION;
 
+    PRAGMA user_version=1;
+
     /*** Keys Table ***/
 
     CREATE TABLE metadata (
@@ -47,10 +53,23 @@ const _CREATE_SCHEMA: &str = "
         name NOT NULL,
         value NOT NULL,
         key NOT NULL,
+        version INTEGER NOT NULL DEFAULT 1,
+        expires_at INTEGER,
         PRIMARY KEY(id)
     );
 
     CREATE UNIQUE INDEX ux_items_type_name ON items(type, name);
+    CREATE INDEX ix_items_expires_at ON items(expires_at);
+
+    /*** Version Counter Table ***/
+
+    CREATE TABLE version_counter(
+        id INTEGER NOT NULL,
+        value INTEGER NOT NULL,
+        PRIMARY KEY(id)
+    );
+
+    INSERT INTO version_counter(id, value) VALUES (1, 0);
 
     /*** Encrypted Tags Table ***/
 
@@ -202,7 +221,13 @@ impl StorageIterator for SQLiteStorageIterator {
                 } else {
                     None
                 };
-                Ok(Some(StorageRecord::new(name, value, type_, tags)))
+                let version = if self.options.retrieve_version {
+                    let version: i64 = row.get(5);
+                    Some(version as u64)
+                } else {
+                    None
+                };
+                Ok(Some(StorageRecord::new(name, value, type_, tags, version, None)))
             }
             Some(Err(err)) => Err(WalletStorageError::from(err)),
             None => Ok(None)
@@ -243,6 +268,53 @@ impl SQLiteStorageType {
         path.push(_SQLITE_DB);
         path
     }
+
+    // Wallets created before record versions and expiry were introduced miss the related columns
+    // and the version counter. Schema version is kept in user_version, so migration runs once.
+    fn _upgrade_schema(conn: &rusqlite::Connection) -> Result<(), WalletStorageError> {
+        let schema_version: i32 = conn.query_row("PRAGMA user_version", &[], |row| row.get(0))?;
+
+        if schema_version >= _SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        let tx: transaction::Transaction = transaction::Transaction::new(conn, rusqlite::TransactionBehavior::Exclusive)?;
+
+        let mut has_version = false;
+        let mut has_expires_at = false;
+
+        {
+            let mut stmt = tx.prepare("PRAGMA table_info(items)")?;
+            let mut rows = stmt.query(&[])?;
+
+            while let Some(row) = rows.next() {
+                let name: String = row?.get(1);
+                has_version |= name == "version";
+                has_expires_at |= name == "expires_at";
+            }
+        }
+
+        if !has_version {
+            tx.execute("ALTER TABLE items ADD COLUMN version INTEGER NOT NULL DEFAULT 1", &[])?;
+        }
+
+        if !has_expires_at {
+            tx.execute_batch("ALTER TABLE items ADD COLUMN expires_at INTEGER;
+                              CREATE INDEX ix_items_expires_at ON items(expires_at);")?;
+        }
+
+        tx.execute_batch("CREATE TABLE IF NOT EXISTS version_counter(
+                              id INTEGER NOT NULL,
+                              value INTEGER NOT NULL,
+                              PRIMARY KEY(id)
+                          );
+                          INSERT OR IGNORE INTO version_counter(id, value) SELECT 1, IFNULL(MAX(version), 0) FROM items;")?;
+
+        tx.execute(&format!("PRAGMA user_version={}", _SCHEMA_VERSION), &[])?;
+        tx.commit()?;
+
+        Ok(())
+    }
 }
 
 
@@ -281,11 +353,11 @@ impl WalletStorage for SQLiteStorage {
         } else {
             serde_json::from_str(options)?
         };
-        let res: Result<(i64, Vec<u8>, Vec<u8>), rusqlite::Error> = self.conn.query_row(
-            "SELECT id, value, key FROM items where type = ?1 AND name = ?2",
+        let res: Result<(i64, Vec<u8>, Vec<u8>, i64, Option<i64>), rusqlite::Error> = self.conn.query_row(
+            &format!("SELECT id, value, key, version, expires_at FROM items where type = ?1 AND name = ?2 AND {}", query::NOT_EXPIRED),
             &[&type_.to_vec(), &id.to_vec()],
             |row| {
-                (row.get(0), row.get(1), row.get(2))
+                (row.get(0), row.get(1), row.get(2), row.get(3), row.get(4))
             }
         );
         let item = match res {
@@ -318,8 +390,10 @@ impl WalletStorage for SQLiteStorage {
             }
             Some(tags)
         } else { None };
+        let version = if options.retrieve_version { Some(item.3 as u64) } else { None };
+        let expires_at = item.4.map(|expires_at| expires_at as u64);
 
-        Ok(StorageRecord::new(id.to_vec(), value, type_.map(|val| val.to_vec()), tags))
+        Ok(StorageRecord::new(id.to_vec(), value, type_.map(|val| val.to_vec()), tags, version, expires_at))
     }
 
     ///
@@ -353,125 +427,89 @@ impl WalletStorage for SQLiteStorage {
     ///
     fn add(&self, type_: &[u8], id: &[u8], value: &EncryptedValue, tags: &[Tag]) -> Result<(), WalletStorageError> {
         let tx: transaction::Transaction = transaction::Transaction::new(&self.conn, rusqlite::TransactionBehavior::Deferred)?;
-        let res = tx.prepare_cached("INSERT INTO items (type, name, value, key) VALUES (?1, ?2, ?3, ?4)")?
-            .insert(&[&type_.to_vec(), &id.to_vec(), &value.data, &value.key]);
+        SQLiteStorage::_add(&tx, type_, id, value, tags, None)?;
+        tx.commit()?;
 
-        let id = match res {
-            Ok(entity) => entity,
-            Err(rusqlite::Error::SqliteFailure(_, _)) => return Err(WalletStorageError::ItemAlreadyExists),
-            Err(err) => return Err(WalletStorageError::from(err))
-        };
+        Ok(())
+    }
 
-        if !tags.is_empty() {
-            let mut stmt_e = tx.prepare_cached("INSERT INTO tags_encrypted (item_id, name, value) VALUES (?1, ?2, ?3)")?;
-            let mut stmt_p = tx.prepare_cached("INSERT INTO tags_plaintext (item_id, name, value) VALUES (?1, ?2, ?3)")?;
+    ///
+    /// inserts value and tags into storage the same way as add does.
+    /// Record is hidden from reads after expires_at unix time (in seconds)
+    /// and is deleted by the next purge_expired call.
+    ///
+    fn add_with_expiry(&self, type_: &[u8], id: &[u8], value: &EncryptedValue, tags: &[Tag], expires_at: u64) -> Result<(), WalletStorageError> {
+        let tx: transaction::Transaction = transaction::Transaction::new(&self.conn, rusqlite::TransactionBehavior::Deferred)?;
+        SQLiteStorage::_add(&tx, type_, id, value, tags, Some(expires_at))?;
+        tx.commit()?;
 
-            for tag in tags {
-                match tag {
-                    &Tag::Encrypted(ref tag_name, ref tag_data) => stmt_e.execute(&[&id, tag_name, tag_data])?,
-                    &Tag::PlainText(ref tag_name, ref tag_data) => stmt_p.execute(&[&id, tag_name, tag_data])?
-                };
-            }
-        }
+        Ok(())
+    }
+
+    ///
+    /// deletes all expired records with their tags.
+    /// Returns count of deleted records.
+    ///
+    fn purge_expired(&self) -> Result<usize, WalletStorageError> {
+        let tx: transaction::Transaction = transaction::Transaction::new(&self.conn, rusqlite::TransactionBehavior::Deferred)?;
+
+        // Foreign keys aren't enforced for opened storage, so tags are deleted explicitly
+        tx.execute(&format!("DELETE FROM tags_encrypted WHERE item_id IN (SELECT id FROM items WHERE {})", _EXPIRED), &[])?;
+        tx.execute(&format!("DELETE FROM tags_plaintext WHERE item_id IN (SELECT id FROM items WHERE {})", _EXPIRED), &[])?;
+        let count = tx.execute(&format!("DELETE FROM items WHERE {}", _EXPIRED), &[])?;
 
         tx.commit()?;
 
-        Ok(())
+        Ok(count as usize)
     }
 
-    fn update(&self, type_: &[u8], id: &[u8], value: &EncryptedValue) -> Result<(), WalletStorageError> {
-        let res = self.conn.prepare_cached("UPDATE items SET value = ?1, key = ?2 WHERE type = ?3 AND name = ?4")?
-            .execute(&[&value.data, &value.key, &type_.to_vec(), &id.to_vec()]);
+    fn count_orphan_tags(&self) -> Result<usize, WalletStorageError> {
+        let count: i64 = self.conn.query_row(
+            &format!("SELECT (SELECT COUNT(*) FROM tags_encrypted WHERE {0}) + (SELECT COUNT(*) FROM tags_plaintext WHERE {0})", _ORPHAN_TAG),
+            &[],
+            |row| row.get(0))?;
 
-        match res {
-            Ok(1) => Ok(()),
-            Ok(0) => Err(WalletStorageError::ItemNotFound),
-            Ok(count) => Err(WalletStorageError::CommonError(CommonError::InvalidState(format!("SQLite returned update row count: {}", count)))),
-            Err(err) => Err(WalletStorageError::from(err)),
-        }
+        Ok(count as usize)
     }
 
-    fn add_tags(&self, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
+    fn delete_orphan_tags(&self) -> Result<usize, WalletStorageError> {
         let tx: transaction::Transaction = transaction::Transaction::new(&self.conn, rusqlite::TransactionBehavior::Deferred)?;
 
-        let res = tx.prepare_cached("SELECT id FROM items WHERE type = ?1 AND name = ?2")?
-            .query_row(&[&type_.to_vec(), &id.to_vec()], |row| row.get(0));
+        let count = tx.execute(&format!("DELETE FROM tags_encrypted WHERE {}", _ORPHAN_TAG), &[])? +
+            tx.execute(&format!("DELETE FROM tags_plaintext WHERE {}", _ORPHAN_TAG), &[])?;
 
-        let item_id: i64 = match res {
-            Err(rusqlite::Error::QueryReturnedNoRows) => return Err(WalletStorageError::ItemNotFound),
-            Err(err) => return Err(WalletStorageError::from(err)),
-            Ok(id) => id
-        };
+        tx.commit()?;
 
-        if !tags.is_empty() {
-            let mut enc_tag_insert_stmt = tx.prepare_cached("INSERT OR REPLACE INTO tags_encrypted (item_id, name, value) VALUES (?1, ?2, ?3)")?;
-            let mut plain_tag_insert_stmt = tx.prepare_cached("INSERT OR REPLACE INTO tags_plaintext (item_id, name, value) VALUES (?1, ?2, ?3)")?;
+        Ok(count as usize)
+    }
 
-            for tag in tags {
-                match tag {
-                    &Tag::Encrypted(ref tag_name, ref tag_data) => enc_tag_insert_stmt.execute(&[&item_id, tag_name, tag_data])?,
-                    &Tag::PlainText(ref tag_name, ref tag_data) => plain_tag_insert_stmt.execute(&[&item_id, tag_name, tag_data])?
-                };
-            }
-        }
+    fn update(&self, type_: &[u8], id: &[u8], value: &EncryptedValue) -> Result<(), WalletStorageError> {
+        let tx: transaction::Transaction = transaction::Transaction::new(&self.conn, rusqlite::TransactionBehavior::Deferred)?;
+        SQLiteStorage::_update(&tx, type_, id, value)?;
         tx.commit()?;
 
         Ok(())
     }
 
-    fn update_tags(&self, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
+    fn add_tags(&self, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
         let tx: transaction::Transaction = transaction::Transaction::new(&self.conn, rusqlite::TransactionBehavior::Deferred)?;
+        SQLiteStorage::_add_tags(&tx, type_, id, tags)?;
+        tx.commit()?;
 
-        let res = tx.prepare_cached("SELECT id FROM items WHERE type = ?1 AND name = ?2")?
-            .query_row(&[&type_.to_vec(), &id.to_vec()], |row| row.get(0));
-
-        let item_id: i64 = match res {
-            Err(rusqlite::Error::QueryReturnedNoRows) => return Err(WalletStorageError::ItemNotFound),
-            Err(err) => return Err(WalletStorageError::from(err)),
-            Ok(id) => id
-        };
-
-        tx.execute("DELETE FROM tags_encrypted WHERE item_id = ?1", &[&item_id])?;
-        tx.execute("DELETE FROM tags_plaintext WHERE item_id = ?1", &[&item_id])?;
-
-        if !tags.is_empty() {
-            let mut enc_tag_insert_stmt = tx.prepare_cached("INSERT INTO tags_encrypted (item_id, name, value) VALUES (?1, ?2, ?3)")?;
-            let mut plain_tag_insert_stmt = tx.prepare_cached("INSERT INTO tags_plaintext (item_id, name, value) VALUES (?1, ?2, ?3)")?;
+        Ok(())
+    }
 
-            for tag in tags {
-                match tag {
-                    &Tag::Encrypted(ref tag_name, ref tag_data) => enc_tag_insert_stmt.execute(&[&item_id, tag_name, tag_data])?,
-                    &Tag::PlainText(ref tag_name, ref tag_data) => plain_tag_insert_stmt.execute(&[&item_id, tag_name, tag_data])?
-                };
-            }
-        }
+    fn update_tags(&self, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
+        let tx: transaction::Transaction = transaction::Transaction::new(&self.conn, rusqlite::TransactionBehavior::Deferred)?;
+        SQLiteStorage::_update_tags(&tx, type_, id, tags)?;
         tx.commit()?;
 
         Ok(())
     }
 
     fn delete_tags(&self, type_: &[u8], id: &[u8], tag_names: &[TagName]) -> Result<(), WalletStorageError> {
-        let res = self.conn.prepare_cached("SELECT id FROM items WHERE type =?1 AND name = ?2")?
-            .query_row(&[&type_.to_vec(), &id.to_vec()], |row| row.get(0));
-
-        let item_id: i64 = match res {
-            Err(rusqlite::Error::QueryReturnedNoRows) => return Err(WalletStorageError::ItemNotFound),
-            Err(err) => return Err(WalletStorageError::from(err)),
-            Ok(id) => id
-        };
-
         let tx: transaction::Transaction = transaction::Transaction::new(&self.conn, rusqlite::TransactionBehavior::Deferred)?;
-        {
-            let mut enc_tag_delete_stmt = tx.prepare_cached("DELETE FROM tags_encrypted WHERE item_id = ?1 AND name = ?2")?;
-            let mut plain_tag_delete_stmt = tx.prepare_cached("DELETE FROM tags_plaintext WHERE item_id = ?1 AND name = ?2")?;
-
-            for tag_name in tag_names {
-                match tag_name {
-                    &TagName::OfEncrypted(ref tag_name) => enc_tag_delete_stmt.execute(&[&item_id, tag_name])?,
-                    &TagName::OfPlain(ref tag_name) => plain_tag_delete_stmt.execute(&[&item_id, tag_name])?,
-                };
-            }
-        }
+        SQLiteStorage::_delete_tags(&tx, type_, id, tag_names)?;
         tx.commit()?;
 
         Ok(())
@@ -504,15 +542,31 @@ impl WalletStorage for SQLiteStorage {
     ///  * `IOError("IO error during storage operation:...")` - Failed connection or SQL query
     ///
     fn delete(&self, type_: &[u8], id: &[u8]) -> Result<(), WalletStorageError> {
-        let row_count = self.conn.execute(
-            "DELETE FROM items where type = ?1 AND name = ?2",
-            &[&type_.to_vec(), &id.to_vec()]
-        )?;
-        if row_count == 1 {
-            Ok(())
-        } else {
-            Err(WalletStorageError::ItemNotFound)
+        SQLiteStorage::_delete(&self.conn, type_, id)
+    }
+
+    ///
+    /// Applies all operations in a single transaction.
+    /// If any of operations fails the whole batch is rolled back.
+    ///
+    fn batch(&self, operations: &[StorageOperation]) -> Result<(), WalletStorageError> {
+        let tx: transaction::Transaction = transaction::Transaction::new(&self.conn, rusqlite::TransactionBehavior::Deferred)?;
+
+        for operation in operations {
+            match operation {
+                &StorageOperation::Add(ref type_, ref id, ref value, ref tags, expires_at) => SQLiteStorage::_add(&tx, type_, id, value, tags, expires_at)?,
+                &StorageOperation::Update(ref type_, ref id, ref value) => SQLiteStorage::_update(&tx, type_, id, value)?,
+                &StorageOperation::AddTags(ref type_, ref id, ref tags) => SQLiteStorage::_add_tags(&tx, type_, id, tags)?,
+                &StorageOperation::UpdateTags(ref type_, ref id, ref tags) => SQLiteStorage::_update_tags(&tx, type_, id, tags)?,
+                &StorageOperation::DeleteTags(ref type_, ref id, ref tag_names) => SQLiteStorage::_delete_tags(&tx, type_, id, tag_names)?,
+                &StorageOperation::Delete(ref type_, ref id) => SQLiteStorage::_delete(&tx, type_, id)?,
+                &StorageOperation::CheckVersion(ref type_, ref id, version) => SQLiteStorage::_check_version(&tx, type_, id, version)?,
+            }
         }
+
+        tx.commit()?;
+
+        Ok(())
     }
 
     fn get_storage_metadata(&self) -> Result<Vec<u8>, WalletStorageError> {
@@ -539,11 +593,12 @@ impl WalletStorage for SQLiteStorage {
     }
 
     fn get_all(&self) -> Result<Box<StorageIterator>, WalletStorageError> {
-        let statement = self._prepare_statement("SELECT id, name, value, key, type FROM items;")?;
+        let statement = self._prepare_statement(&format!("SELECT id, name, value, key, type, version FROM items WHERE {};", query::NOT_EXPIRED))?;
         let fetch_options = RecordOptions {
             retrieve_type: true,
             retrieve_value: true,
             retrieve_tags: true,
+            retrieve_version: false,
         };
         let tag_retriever = Some(TagRetriever::new_owned(self.conn.clone())?);
 
@@ -578,9 +633,20 @@ impl WalletStorage for SQLiteStorage {
                 retrieve_value: search_options.retrieve_value,
                 retrieve_tags: search_options.retrieve_tags,
                 retrieve_type: search_options.retrieve_type,
+                retrieve_version: search_options.retrieve_version,
             };
 
-            let (query_string, query_arguments) = query::wql_to_sql(&type_, query, options)?;
+            let sort_tag_name = match search_options.sort_by {
+                Some(ref sort_by) if sort_by.starts_with('~') => Some(base64::decode(&sort_by[1..])?),
+                Some(_) => return Err(WalletStorageError::QueryError(WalletQueryError::StructureErr("sortBy must be used only for nonencrypted tag".to_string()))),
+                None => None
+            };
+
+            let (query_string, query_arguments) = query::wql_to_sql(&type_,
+                                                                     query,
+                                                                     sort_tag_name.as_ref().map(|name| (name, search_options.sort_order)),
+                                                                     search_options.offset,
+                                                                     search_options.limit)?;
 
             let statement = self._prepare_statement(&query_string)?;
             let tag_retriever = if fetch_options.retrieve_tags {
@@ -596,6 +662,35 @@ impl WalletStorage for SQLiteStorage {
         }
     }
 
+    fn count(&self, type_: &[u8], query: &language::Operator) -> Result<usize, WalletStorageError> {
+        let type_ = type_.to_vec();
+
+        let (query_string, query_arguments) = query::wql_to_sql_count(&type_, query)?;
+
+        let count: i64 = self.conn.query_row(&query_string, &query_arguments, |row| row.get(0))?;
+
+        Ok(count as usize)
+    }
+
+    fn count_by_tag(&self, type_: &[u8], query: &language::Operator, tag_name: &[u8]) -> Result<Vec<(String, usize)>, WalletStorageError> {
+        let type_ = type_.to_vec();
+        let tag_name = tag_name.to_vec();
+
+        let (query_string, query_arguments) = query::wql_to_sql_count_by_tag(&type_, query, &tag_name)?;
+
+        let mut stmt = self.conn.prepare(&query_string)?;
+        let mut rows = stmt.query(&query_arguments)?;
+
+        let mut counts = Vec::new();
+        while let Some(row) = rows.next() {
+            let row = row?;
+            let count: i64 = row.get(1);
+            counts.push((row.get(0), count as usize));
+        }
+
+        Ok(counts)
+    }
+
     fn close(&mut self) -> Result<(), WalletStorageError> {
         Ok(())
     }
@@ -609,6 +704,187 @@ impl SQLiteStorage {
             unsafe { (*conn).prepare(sql) }.map(Box::new).map_err(WalletStorageError::from)
         })
     }
+
+    fn _add(conn: &rusqlite::Connection, type_: &[u8], id: &[u8], value: &EncryptedValue, tags: &[Tag], expires_at: Option<u64>) -> Result<(), WalletStorageError> {
+        // Unix time out of INTEGER range is never reached, so it is stored as the latest one
+        let expires_at = expires_at.map(|expires_at| std::cmp::min(expires_at, i64::max_value() as u64) as i64);
+        let version = SQLiteStorage::_next_version(conn)?;
+
+        let id = match SQLiteStorage::_insert_item(conn, type_, id, value, version, expires_at) {
+            // Expired record isn't purged yet, but its name is free for the new one
+            Err(WalletStorageError::ItemAlreadyExists) if SQLiteStorage::_delete_expired(conn, type_, id)? =>
+                SQLiteStorage::_insert_item(conn, type_, id, value, version, expires_at)?,
+            res => res?
+        };
+
+        if !tags.is_empty() {
+            let mut stmt_e = conn.prepare_cached("INSERT INTO tags_encrypted (item_id, name, value) VALUES (?1, ?2, ?3)")?;
+            let mut stmt_p = conn.prepare_cached("INSERT INTO tags_plaintext (item_id, name, value) VALUES (?1, ?2, ?3)")?;
+
+            for tag in tags {
+                match tag {
+                    &Tag::Encrypted(ref tag_name, ref tag_data) => stmt_e.execute(&[&id, tag_name, tag_data])?,
+                    &Tag::PlainText(ref tag_name, ref tag_data) => stmt_p.execute(&[&id, tag_name, tag_data])?
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    fn _insert_item(conn: &rusqlite::Connection, type_: &[u8], id: &[u8], value: &EncryptedValue, version: i64, expires_at: Option<i64>) -> Result<i64, WalletStorageError> {
+        let res = conn.prepare_cached("INSERT INTO items (type, name, value, key, version, expires_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?
+            .insert(&[&type_.to_vec(), &id.to_vec(), &value.data, &value.key, &version, &expires_at]);
+
+        match res {
+            Ok(item_id) => Ok(item_id),
+            Err(rusqlite::Error::SqliteFailure(_, _)) => Err(WalletStorageError::ItemAlreadyExists),
+            Err(err) => Err(WalletStorageError::from(err))
+        }
+    }
+
+    // Returns false if there is no expired item with such type and name
+    fn _delete_expired(conn: &rusqlite::Connection, type_: &[u8], id: &[u8]) -> Result<bool, WalletStorageError> {
+        let res: Result<i64, rusqlite::Error> = conn.prepare_cached(&format!("SELECT id FROM items WHERE type = ?1 AND name = ?2 AND {}", _EXPIRED))?
+            .query_row(&[&type_.to_vec(), &id.to_vec()], |row| row.get(0));
+
+        let item_id = match res {
+            Ok(item_id) => item_id,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(false),
+            Err(err) => return Err(WalletStorageError::from(err))
+        };
+
+        conn.prepare_cached("DELETE FROM tags_encrypted WHERE item_id = ?1")?.execute(&[&item_id])?;
+        conn.prepare_cached("DELETE FROM tags_plaintext WHERE item_id = ?1")?.execute(&[&item_id])?;
+        conn.prepare_cached("DELETE FROM items WHERE id = ?1")?.execute(&[&item_id])?;
+
+        Ok(true)
+    }
+
+    fn _update(conn: &rusqlite::Connection, type_: &[u8], id: &[u8], value: &EncryptedValue) -> Result<(), WalletStorageError> {
+        let version = SQLiteStorage::_next_version(conn)?;
+
+        let res = conn.prepare_cached(&format!("UPDATE items SET value = ?1, key = ?2, version = ?3 WHERE type = ?4 AND name = ?5 AND {}", query::NOT_EXPIRED))?
+            .execute(&[&value.data, &value.key, &version, &type_.to_vec(), &id.to_vec()]);
+
+        match res {
+            Ok(1) => Ok(()),
+            Ok(0) => Err(WalletStorageError::ItemNotFound),
+            Ok(count) => Err(WalletStorageError::CommonError(CommonError::InvalidState(format!("SQLite returned update row count: {}", count)))),
+            Err(err) => Err(WalletStorageError::from(err)),
+        }
+    }
+
+    fn _get_item_id(conn: &rusqlite::Connection, type_: &[u8], id: &[u8]) -> Result<i64, WalletStorageError> {
+        let res = conn.prepare_cached(&format!("SELECT id FROM items WHERE type = ?1 AND name = ?2 AND {}", query::NOT_EXPIRED))?
+            .query_row(&[&type_.to_vec(), &id.to_vec()], |row| row.get(0));
+
+        match res {
+            Err(rusqlite::Error::QueryReturnedNoRows) => Err(WalletStorageError::ItemNotFound),
+            Err(err) => Err(WalletStorageError::from(err)),
+            Ok(id) => Ok(id)
+        }
+    }
+
+    // Versions are taken from the storage wide counter, so re-added record never repeats
+    // a version of the deleted one. Must be called inside of transaction.
+    fn _next_version(conn: &rusqlite::Connection) -> Result<i64, WalletStorageError> {
+        conn.prepare_cached("UPDATE version_counter SET value = value + 1 WHERE id = 1")?
+            .execute(&[])?;
+        let version = conn.prepare_cached("SELECT value FROM version_counter WHERE id = 1")?
+            .query_row(&[], |row| row.get(0))?;
+        Ok(version)
+    }
+
+    // Any change of record value or tags makes a new record version
+    fn _increment_version(conn: &rusqlite::Connection, item_id: i64) -> Result<(), WalletStorageError> {
+        let version = SQLiteStorage::_next_version(conn)?;
+        conn.prepare_cached("UPDATE items SET version = ?1 WHERE id = ?2")?
+            .execute(&[&version, &item_id])?;
+        Ok(())
+    }
+
+    fn _check_version(conn: &rusqlite::Connection, type_: &[u8], id: &[u8], version: u64) -> Result<(), WalletStorageError> {
+        let res: Result<i64, rusqlite::Error> = conn.prepare_cached(&format!("SELECT version FROM items WHERE type = ?1 AND name = ?2 AND {}", query::NOT_EXPIRED))?
+            .query_row(&[&type_.to_vec(), &id.to_vec()], |row| row.get(0));
+
+        match res {
+            Ok(stored_version) if stored_version as u64 == version => Ok(()),
+            Ok(_) => Err(WalletStorageError::ItemVersionMismatch),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Err(WalletStorageError::ItemNotFound),
+            Err(err) => Err(WalletStorageError::from(err))
+        }
+    }
+
+    fn _add_tags(conn: &rusqlite::Connection, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
+        let item_id = SQLiteStorage::_get_item_id(conn, type_, id)?;
+        SQLiteStorage::_increment_version(conn, item_id)?;
+
+        if !tags.is_empty() {
+            let mut enc_tag_insert_stmt = conn.prepare_cached("INSERT OR REPLACE INTO tags_encrypted (item_id, name, value) VALUES (?1, ?2, ?3)")?;
+            let mut plain_tag_insert_stmt = conn.prepare_cached("INSERT OR REPLACE INTO tags_plaintext (item_id, name, value) VALUES (?1, ?2, ?3)")?;
+
+            for tag in tags {
+                match tag {
+                    &Tag::Encrypted(ref tag_name, ref tag_data) => enc_tag_insert_stmt.execute(&[&item_id, tag_name, tag_data])?,
+                    &Tag::PlainText(ref tag_name, ref tag_data) => plain_tag_insert_stmt.execute(&[&item_id, tag_name, tag_data])?
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    fn _update_tags(conn: &rusqlite::Connection, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
+        let item_id = SQLiteStorage::_get_item_id(conn, type_, id)?;
+        SQLiteStorage::_increment_version(conn, item_id)?;
+
+        conn.execute("DELETE FROM tags_encrypted WHERE item_id = ?1", &[&item_id])?;
+        conn.execute("DELETE FROM tags_plaintext WHERE item_id = ?1", &[&item_id])?;
+
+        if !tags.is_empty() {
+            let mut enc_tag_insert_stmt = conn.prepare_cached("INSERT INTO tags_encrypted (item_id, name, value) VALUES (?1, ?2, ?3)")?;
+            let mut plain_tag_insert_stmt = conn.prepare_cached("INSERT INTO tags_plaintext (item_id, name, value) VALUES (?1, ?2, ?3)")?;
+
+            for tag in tags {
+                match tag {
+                    &Tag::Encrypted(ref tag_name, ref tag_data) => enc_tag_insert_stmt.execute(&[&item_id, tag_name, tag_data])?,
+                    &Tag::PlainText(ref tag_name, ref tag_data) => plain_tag_insert_stmt.execute(&[&item_id, tag_name, tag_data])?
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    fn _delete_tags(conn: &rusqlite::Connection, type_: &[u8], id: &[u8], tag_names: &[TagName]) -> Result<(), WalletStorageError> {
+        let item_id = SQLiteStorage::_get_item_id(conn, type_, id)?;
+        SQLiteStorage::_increment_version(conn, item_id)?;
+
+        let mut enc_tag_delete_stmt = conn.prepare_cached("DELETE FROM tags_encrypted WHERE item_id = ?1 AND name = ?2")?;
+        let mut plain_tag_delete_stmt = conn.prepare_cached("DELETE FROM tags_plaintext WHERE item_id = ?1 AND name = ?2")?;
+
+        for tag_name in tag_names {
+            match tag_name {
+                &TagName::OfEncrypted(ref tag_name) => enc_tag_delete_stmt.execute(&[&item_id, tag_name])?,
+                &TagName::OfPlain(ref tag_name) => plain_tag_delete_stmt.execute(&[&item_id, tag_name])?,
+            };
+        }
+
+        Ok(())
+    }
+
+    fn _delete(conn: &rusqlite::Connection, type_: &[u8], id: &[u8]) -> Result<(), WalletStorageError> {
+        let row_count = conn.execute(
+            &format!("DELETE FROM items where type = ?1 AND name = ?2 AND {}", query::NOT_EXPIRED),
+            &[&type_.to_vec(), &id.to_vec()]
+        )?;
+        if row_count == 1 {
+            Ok(())
+        } else {
+            Err(WalletStorageError::ItemNotFound)
+        }
+    }
 }
 
 
@@ -770,6 +1046,8 @@ impl WalletStorageType for SQLiteStorageType {
             conn.execute("PRAGMA synchronous = FULL", &[])?;
         }
 
+        SQLiteStorageType::_upgrade_schema(&conn)?;
+
         Ok(Box::new(SQLiteStorage { conn: Rc::new(conn) }))
     }
 }
@@ -1252,6 +1530,336 @@ mod tests {
         assert_match!(Err(WalletStorageError::ItemNotFound), res);
     }
 
+    #[test]
+    fn sqlite_storage_batch_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        storage.batch(&[
+            StorageOperation::Add(_type1(), _id2(), _value2(), _tags(), None),
+            StorageOperation::Update(_type1(), _id1(), _value2()),
+            StorageOperation::UpdateTags(_type1(), _id1(), _new_tags()),
+            StorageOperation::DeleteTags(_type1(), _id2(), vec![TagName::OfEncrypted(vec![1, 5, 8])]),
+        ]).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_new_tags()));
+
+        let record = storage.get(&_type1(), &_id2(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(record.tags.unwrap(), vec![Tag::PlainText(vec![1, 5, 8, 1], "Plain value".to_string())]);
+
+        storage.batch(&[StorageOperation::Delete(_type1(), _id1())]).unwrap();
+
+        let res = storage.get(&_type1(), &_id1(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn sqlite_storage_batch_works_for_rollback() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.batch(&[
+            StorageOperation::Add(_type1(), _id2(), _value2(), _tags(), None),
+            StorageOperation::Update(_type1(), _id1(), _value2()),
+            StorageOperation::AddTags(_type2(), _id1(), _new_tags()),
+        ]);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+        let res = storage.get(&_type1(), &_id2(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn sqlite_storage_batch_works_for_already_existing() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.batch(&[
+            StorageOperation::Add(_type1(), _id2(), _value2(), _tags(), None),
+            StorageOperation::Add(_type1(), _id1(), _value2(), _tags(), None),
+        ]);
+        assert_match!(Err(WalletStorageError::ItemAlreadyExists), res);
+
+        let res = storage.get(&_type1(), &_id2(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn sqlite_storage_version_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveVersion": true}"##).unwrap();
+        assert_eq!(Some(1), record.version);
+
+        storage.update(&_type1(), &_id1(), &_value2()).unwrap();
+        storage.add_tags(&_type1(), &_id1(), &_new_tags()).unwrap();
+        storage.delete_tags(&_type1(), &_id1(), &[TagName::OfEncrypted(vec![1, 5, 8])]).unwrap();
+        storage.update_tags(&_type1(), &_id1(), &_tags()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveVersion": true}"##).unwrap();
+        assert_eq!(Some(5), record.version);
+
+        let record = storage.get(&_type1(), &_id1(), r##"{}"##).unwrap();
+        assert_eq!(None, record.version);
+    }
+
+    #[test]
+    fn sqlite_storage_version_works_for_readd() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        storage.delete(&_type1(), &_id1()).unwrap();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.batch(&[StorageOperation::CheckVersion(_type1(), _id1(), 1)]);
+        assert_match!(Err(WalletStorageError::ItemVersionMismatch), res);
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveVersion": true}"##).unwrap();
+        assert_eq!(Some(2), record.version);
+    }
+
+    #[test]
+    fn sqlite_storage_batch_works_for_check_version() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        storage.batch(&[
+            StorageOperation::CheckVersion(_type1(), _id1(), 1),
+            StorageOperation::Update(_type1(), _id1(), _value2()),
+        ]).unwrap();
+
+        let res = storage.batch(&[
+            StorageOperation::CheckVersion(_type1(), _id1(), 1),
+            StorageOperation::Update(_type1(), _id1(), _value1()),
+        ]);
+        assert_match!(Err(WalletStorageError::ItemVersionMismatch), res);
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveValue": true, "retrieveVersion": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(Some(2), record.version);
+
+        let res = storage.batch(&[StorageOperation::CheckVersion(_type1(), _id2(), 1)]);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn sqlite_storage_count_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id2(), &_value2(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id(3), &_value(3), &_new_tags()).unwrap();
+        storage.add(&_type2(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let count = storage.count(&_type1(), &language::Operator::And(vec![])).unwrap();
+        assert_eq!(3, count);
+
+        let query = language::Operator::Eq(language::TagName::EncryptedTagName(vec![1, 5, 8]), language::TargetValue::Encrypted(vec![3, 5, 6]));
+        let count = storage.count(&_type1(), &query).unwrap();
+        assert_eq!(2, count);
+    }
+
+    #[test]
+    fn sqlite_storage_count_by_tag_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id2(), &_value2(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id(3), &_value(3), &[Tag::PlainText(vec![1, 5, 8, 1], "Other value".to_string())]).unwrap();
+        storage.add(&_type1(), &_id(4), &_value(4), &_new_tags()).unwrap();
+
+        let counts = storage.count_by_tag(&_type1(), &language::Operator::And(vec![]), &[1, 5, 8, 1]).unwrap();
+        assert_eq!(vec![("Other value".to_string(), 1), ("Plain value".to_string(), 2)], counts);
+
+        let query = language::Operator::Eq(language::TagName::EncryptedTagName(vec![1, 5, 8]), language::TargetValue::Encrypted(vec![3, 5, 6]));
+        let counts = storage.count_by_tag(&_type1(), &query, &[1, 5, 8, 1]).unwrap();
+        assert_eq!(vec![("Plain value".to_string(), 2)], counts);
+
+        let counts = storage.count_by_tag(&_type2(), &language::Operator::And(vec![]), &[1, 5, 8, 1]).unwrap();
+        assert!(counts.is_empty());
+    }
+
+    #[test]
+    fn sqlite_storage_add_with_expiry_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add_with_expiry(&_type1(), &_id1(), &_value1(), &_tags(), 1).unwrap();
+        storage.add_with_expiry(&_type1(), &_id2(), &_value2(), &_tags(), u32::max_value() as u64).unwrap();
+
+        let res = storage.get(&_type1(), &_id1(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        let record = storage.get(&_type1(), &_id2(), r##"{"retrieveValue": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(Some(u32::max_value() as u64), record.expires_at);
+
+        let count = storage.count(&_type1(), &language::Operator::And(vec![])).unwrap();
+        assert_eq!(1, count);
+
+        let mut iterator = storage.search(&_type1(), &language::Operator::And(vec![]), None).unwrap();
+        assert_eq!(_id2(), iterator.next().unwrap().unwrap().id);
+        assert!(iterator.next().unwrap().is_none());
+
+        let mut iterator = storage.get_all().unwrap();
+        assert_eq!(_id2(), iterator.next().unwrap().unwrap().id);
+        assert!(iterator.next().unwrap().is_none());
+
+        let res = storage.update(&_type1(), &_id1(), &_value2());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        let res = storage.delete(&_type1(), &_id1());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn sqlite_storage_add_with_expiry_works_for_max_time() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add_with_expiry(&_type1(), &_id1(), &_value1(), &_tags(), u64::max_value()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveValue": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+
+        assert_eq!(0, storage.purge_expired().unwrap());
+    }
+
+    #[test]
+    fn sqlite_storage_add_works_for_expired() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add_with_expiry(&_type1(), &_id1(), &_value1(), &_tags(), 1).unwrap();
+        storage.add(&_type1(), &_id1(), &_value2(), &_new_tags()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_new_tags()));
+        assert_eq!(None, record.expires_at);
+
+        let res = storage.add(&_type1(), &_id1(), &_value1(), &_tags());
+        assert_match!(Err(WalletStorageError::ItemAlreadyExists), res);
+
+        assert_eq!(0, storage.count_orphan_tags().unwrap());
+    }
+
+    #[test]
+    fn sqlite_storage_batch_works_for_expiry() {
+        _cleanup();
+
+        let storage = _storage();
+
+        storage.batch(&[
+            StorageOperation::Add(_type1(), _id1(), _value1(), _tags(), Some(1)),
+            StorageOperation::Add(_type1(), _id2(), _value2(), _tags(), Some(u32::max_value() as u64)),
+        ]).unwrap();
+
+        let res = storage.get(&_type1(), &_id1(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        let record = storage.get(&_type1(), &_id2(), r##"{}"##).unwrap();
+        assert_eq!(Some(u32::max_value() as u64), record.expires_at);
+
+        assert_eq!(1, storage.purge_expired().unwrap());
+    }
+
+    #[test]
+    fn sqlite_storage_purge_expired_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add_with_expiry(&_type1(), &_id1(), &_value1(), &_tags(), 1).unwrap();
+        storage.add_with_expiry(&_type2(), &_id1(), &_value1(), &_tags(), 1).unwrap();
+        storage.add_with_expiry(&_type1(), &_id2(), &_value2(), &_tags(), u32::max_value() as u64).unwrap();
+        storage.add(&_type2(), &_id2(), &_value2(), &_tags()).unwrap();
+
+        assert_eq!(2, storage.purge_expired().unwrap());
+        assert_eq!(0, storage.purge_expired().unwrap());
+        assert_eq!(0, storage.count_orphan_tags().unwrap());
+
+        storage.get(&_type1(), &_id2(), r##"{}"##).unwrap();
+        storage.get(&_type2(), &_id2(), r##"{}"##).unwrap();
+    }
+
+    #[test]
+    fn sqlite_storage_orphan_tags_works() {
+        _cleanup();
+
+        {
+            let storage = _storage();
+            storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+            storage.add(&_type1(), &_id2(), &_value2(), &_tags()).unwrap();
+            assert_eq!(0, storage.count_orphan_tags().unwrap());
+        }
+
+        {
+            // foreign keys aren't enforced by default, so tags of deleted item are kept
+            let conn = rusqlite::Connection::open(SQLiteStorageType::_db_path(_wallet_id(), None)).unwrap();
+            conn.execute("DELETE FROM items WHERE name = ?1", &[&_id1()]).unwrap();
+        }
+
+        let storage = SQLiteStorageType::new().open_storage(_wallet_id(), None, None).unwrap();
+        assert_eq!(2, storage.count_orphan_tags().unwrap());
+
+        assert_eq!(2, storage.delete_orphan_tags().unwrap());
+        assert_eq!(0, storage.count_orphan_tags().unwrap());
+
+        let record = storage.get(&_type1(), &_id2(), r##"{"retrieveTags": true}"##).unwrap();
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+    }
+
+    #[test]
+    fn sqlite_storage_open_works_for_upgrade() {
+        _cleanup();
+
+        {
+            let storage = _storage();
+            storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        }
+
+        {
+            // wallet created before version counter was introduced
+            let conn = rusqlite::Connection::open(SQLiteStorageType::_db_path(_wallet_id(), None)).unwrap();
+            conn.execute_batch("DROP TABLE version_counter;
+                                UPDATE items SET version = 7;
+                                PRAGMA user_version=0;").unwrap();
+        }
+
+        {
+            let storage = SQLiteStorageType::new().open_storage(_wallet_id(), None, None).unwrap();
+            storage.add(&_type1(), &_id2(), &_value2(), &_tags()).unwrap();
+
+            let record = storage.get(&_type1(), &_id2(), r##"{"retrieveVersion": true}"##).unwrap();
+            assert_eq!(Some(8), record.version);
+        }
+
+        let conn = rusqlite::Connection::open(SQLiteStorageType::_db_path(_wallet_id(), None)).unwrap();
+        let schema_version: i32 = conn.query_row("PRAGMA user_version", &[], |row| row.get(0)).unwrap();
+        assert_eq!(_SCHEMA_VERSION, schema_version);
+    }
+
     fn _cleanup() {
         test::cleanup_storage()
     }
@@ -1,21 +1,52 @@
 use rusqlite::types::ToSql;
 
 use errors::wallet::WalletQueryError;
-use services::wallet::language::{Operator,TagName,TargetValue};
+use services::wallet::SortOrder;
+use services::wallet::language::{Operator,TagName,TargetValue,Number,I64_MAX_DIGITS};
+
+
+// Expired records are hidden from all reads until purged
+pub const NOT_EXPIRED: &str = "(expires_at IS NULL OR expires_at > CAST(strftime('%s', 'now') AS INTEGER))";
 
 
 // Translates Wallet Query Language to SQL
 // WQL input is provided as a reference to a top level Operator
+// Records can be sorted by plaintext tag value, records without this tag go last
 // Result is a tuple of query string and query arguments
-pub fn wql_to_sql<'a>(class: &'a Vec<u8>, op: &'a Operator, _options: Option<&str>) -> Result<(String, Vec<&'a ToSql>), WalletQueryError> {
+pub fn wql_to_sql<'a>(class: &'a Vec<u8>, op: &'a Operator, sort: Option<(&'a Vec<u8>, SortOrder)>,
+                      offset: Option<usize>, limit: Option<usize>) -> Result<(String, Vec<&'a ToSql>), WalletQueryError> {
     let mut arguments: Vec<&ToSql> = Vec::new();
+    let mut query_string = "SELECT i.id, i.name, i.value, i.key, i.type, i.version FROM items as i".to_string();
+
+    if let Some((tag_name, _)) = sort {
+        arguments.push(tag_name);
+        query_string.push_str(" LEFT JOIN tags_plaintext as s ON s.item_id = i.id AND s.name = ?");
+    }
+
     arguments.push(class);
+    query_string.push_str(" WHERE i.type = ? AND ");
+    query_string.push_str(NOT_EXPIRED);
+
     let clause_string = operator_to_sql(op, &mut arguments)?;
-    let mut query_string = "SELECT i.id, i.name, i.value, i.key, i.type FROM items as i WHERE i.type = ?".to_string();
     if !clause_string.is_empty() {
         query_string.push_str(" AND ");
         query_string.push_str(&clause_string);
     }
+
+    match sort {
+        Some((_, SortOrder::Asc)) => query_string.push_str(" ORDER BY s.value IS NULL, s.value ASC, i.id ASC"),
+        Some((_, SortOrder::Desc)) => query_string.push_str(" ORDER BY s.value IS NULL, s.value DESC, i.id ASC"),
+        None => {}
+    }
+
+    // SQLite requires LIMIT clause for OFFSET, negative limit means no limit
+    match (limit, offset) {
+        (Some(limit), Some(offset)) => query_string.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset)),
+        (Some(limit), None) => query_string.push_str(&format!(" LIMIT {}", limit)),
+        (None, Some(offset)) => query_string.push_str(&format!(" LIMIT -1 OFFSET {}", offset)),
+        (None, None) => {}
+    }
+
     Ok((query_string, arguments))
 }
 
@@ -24,11 +55,26 @@ pub fn wql_to_sql_count<'a>(class: &'a Vec<u8>, op: &'a Operator) -> Result<(Str
     let mut arguments: Vec<&ToSql> = Vec::new();
     arguments.push(class);
     let clause_string = operator_to_sql(op, &mut arguments)?;
-    let mut query_string = "SELECT count(*) FROM items as i WHERE i.type = ?".to_string();
+    let mut query_string = format!("SELECT count(*) FROM items as i WHERE i.type = ? AND {}", NOT_EXPIRED);
+    if !clause_string.is_empty() {
+        query_string.push_str(" AND ");
+        query_string.push_str(&clause_string);
+    }
+    Ok((query_string, arguments))
+}
+
+
+pub fn wql_to_sql_count_by_tag<'a>(class: &'a Vec<u8>, op: &'a Operator, tag_name: &'a Vec<u8>) -> Result<(String, Vec<&'a ToSql>), WalletQueryError> {
+    let mut arguments: Vec<&ToSql> = Vec::new();
+    arguments.push(tag_name);
+    arguments.push(class);
+    let clause_string = operator_to_sql(op, &mut arguments)?;
+    let mut query_string = format!("SELECT t.value, count(*) FROM items as i JOIN tags_plaintext as t ON t.item_id = i.id AND t.name = ? WHERE i.type = ? AND {}", NOT_EXPIRED);
     if !clause_string.is_empty() {
         query_string.push_str(" AND ");
         query_string.push_str(&clause_string);
     }
+    query_string.push_str(" GROUP BY t.value ORDER BY t.value");
     Ok((query_string, arguments))
 }
 
@@ -91,6 +137,10 @@ fn gt_to_sql<'a>(name: &'a TagName, value: &'a TargetValue, arguments: &mut Vec<
             arguments.push(queried_value);
             Ok("(i.id in (SELECT item_id FROM tags_plaintext WHERE name = ? AND value > ?))".to_string())
         },
+        (&TagName::PlainTagName(ref queried_name), &TargetValue::Numeric(ref queried_value)) => {
+            arguments.push(queried_name);
+            Ok(format!("(i.id in (SELECT item_id FROM tags_plaintext WHERE name = ? AND {}))", numeric_comparison_sql(">", queried_value)?))
+        },
         _ => Err(WalletQueryError::StructureErr("Invalid combination of tag name and value for $gt operator".to_string()))
     }
 }
@@ -103,6 +153,10 @@ fn gte_to_sql<'a>(name: &'a TagName, value: &'a TargetValue, arguments: &mut Vec
             arguments.push(queried_value);
             Ok("(i.id in (SELECT item_id FROM tags_plaintext WHERE name = ? AND value >= ?))".to_string())
         },
+        (&TagName::PlainTagName(ref queried_name), &TargetValue::Numeric(ref queried_value)) => {
+            arguments.push(queried_name);
+            Ok(format!("(i.id in (SELECT item_id FROM tags_plaintext WHERE name = ? AND {}))", numeric_comparison_sql(">=", queried_value)?))
+        },
         _ => Err(WalletQueryError::StructureErr("Invalid combination of tag name and value for $gte operator".to_string()))
     }
 }
@@ -115,6 +169,10 @@ fn lt_to_sql<'a>(name: &'a TagName, value: &'a TargetValue, arguments: &mut Vec<
             arguments.push(queried_value);
             Ok("(i.id in (SELECT item_id FROM tags_plaintext WHERE name = ? AND value < ?))".to_string())
         },
+        (&TagName::PlainTagName(ref queried_name), &TargetValue::Numeric(ref queried_value)) => {
+            arguments.push(queried_name);
+            Ok(format!("(i.id in (SELECT item_id FROM tags_plaintext WHERE name = ? AND {}))", numeric_comparison_sql("<", queried_value)?))
+        },
         _ => Err(WalletQueryError::StructureErr("Invalid combination of tag name and value for $lte operator".to_string()))
     }
 }
@@ -127,11 +185,38 @@ fn lte_to_sql<'a>(name: &'a TagName, value: &'a TargetValue, arguments: &mut Vec
             arguments.push(queried_value);
             Ok("(i.id in (SELECT item_id FROM tags_plaintext WHERE name = ? AND value <= ?))".to_string())
         },
+        (&TagName::PlainTagName(ref queried_name), &TargetValue::Numeric(ref queried_value)) => {
+            arguments.push(queried_name);
+            Ok(format!("(i.id in (SELECT item_id FROM tags_plaintext WHERE name = ? AND {}))", numeric_comparison_sql("<=", queried_value)?))
+        },
         _ => Err(WalletQueryError::StructureErr("Invalid combination of tag name and value for $lte operator".to_string()))
     }
 }
 
 
+// Numeric comparison of plaintext tag values with the same semantics as language::Number.
+// Values that aren't numbers never match. Operand is validated number, so it is inlined
+fn numeric_comparison_sql(comparison: &str, queried_value: &str) -> Result<String, WalletQueryError> {
+    let unsigned = "(CASE WHEN value GLOB '-*' THEN substr(value, 2) ELSE value END)";
+    let is_integer = format!("({u} <> '' AND {u} NOT GLOB '*[^0-9]*')", u = unsigned);
+    let is_fraction = format!("({u} GLOB '[0-9]*.[0-9]*' AND {u} NOT GLOB '*.*.*' AND {u} NOT GLOB '*[^0-9.]*')", u = unsigned);
+    let fits_i64 = format!("(length({u}) < {len} OR (length({u}) = {len} AND {u} <= '{max}'))",
+                           u = unsigned, len = I64_MAX_DIGITS.len(), max = I64_MAX_DIGITS);
+
+    match Number::parse(queried_value) {
+        Some(Number::Integer(queried_value)) =>
+            Ok(format!("(({int} AND {fits} AND CAST(value AS INTEGER) {cmp} {int_value}) OR \
+                         ((({int} AND NOT {fits}) OR {frac}) AND CAST(value AS REAL) {cmp} {real_value:?}))",
+                       int = is_integer, frac = is_fraction, fits = fits_i64, cmp = comparison,
+                       int_value = queried_value, real_value = queried_value as f64)),
+        Some(Number::Real(queried_value)) =>
+            Ok(format!("(({int} OR {frac}) AND CAST(value AS REAL) {cmp} {real_value:?})",
+                       int = is_integer, frac = is_fraction, cmp = comparison, real_value = queried_value)),
+        None => Err(WalletQueryError::ValueErr(format!("Invalid number: {}", queried_value)))
+    }
+}
+
+
 fn like_to_sql<'a>(name: &'a TagName, value: &'a TargetValue, arguments: &mut Vec<&'a ToSql>) -> Result<String, WalletQueryError> {
     match (name, value) {
         (&TagName::PlainTagName(ref queried_name), &TargetValue::Unencrypted(ref queried_value)) => {
@@ -237,6 +322,68 @@ mod tests {
         ]);
         let query = Operator::Or(vec![condition_1, condition_2]);
         let class = vec![100,100,100];
-        let (_query, _arguments) = wql_to_sql(&class, &query, None).unwrap();
+        let (_query, _arguments) = wql_to_sql(&class, &query, None, None, None).unwrap();
+    }
+
+    #[test]
+    fn sort_and_paging() {
+        let query = Operator::Eq(TagName::PlainTagName(vec![7,8,9]), TargetValue::Unencrypted("spam".to_string()));
+        let class = vec![100,100,100];
+        let sort_tag = vec![1,2,3];
+
+        let (query_string, arguments) = wql_to_sql(&class, &query, Some((&sort_tag, SortOrder::Desc)), Some(10), Some(5)).unwrap();
+        assert!(query_string.contains("LEFT JOIN tags_plaintext as s ON s.item_id = i.id AND s.name = ? WHERE i.type = ?"));
+        assert!(query_string.ends_with("ORDER BY s.value IS NULL, s.value DESC, i.id ASC LIMIT 5 OFFSET 10"));
+        assert_eq!(4, arguments.len());
+
+        let (query_string, _) = wql_to_sql(&class, &query, None, Some(10), None).unwrap();
+        assert!(query_string.ends_with("LIMIT -1 OFFSET 10"));
+    }
+
+    #[test]
+    fn numeric_comparison() {
+        let query = Operator::Gt(TagName::PlainTagName(vec![7,8,9]), TargetValue::Numeric("10".to_string()));
+        let class = vec![100,100,100];
+
+        let (query_string, arguments) = wql_to_sql(&class, &query, None, None, None).unwrap();
+        assert!(query_string.contains("CAST(value AS INTEGER) > 10"));
+        assert!(query_string.contains("CAST(value AS REAL) > 10.0"));
+        assert_eq!(2, arguments.len());
+
+        let query = Operator::Lte(TagName::PlainTagName(vec![7,8,9]), TargetValue::Numeric("-1.5".to_string()));
+        let (query_string, _) = wql_to_sql(&class, &query, None, None, None).unwrap();
+        assert!(query_string.contains("CAST(value AS REAL) <= -1.5"));
+        assert!(!query_string.contains("CAST(value AS INTEGER)"));
+
+        let query = Operator::Gt(TagName::PlainTagName(vec![7,8,9]), TargetValue::Numeric("1e3".to_string()));
+        assert!(wql_to_sql(&class, &query, None, None, None).is_err());
+
+        let query = Operator::Eq(TagName::PlainTagName(vec![7,8,9]), TargetValue::Numeric("10".to_string()));
+        assert!(wql_to_sql(&class, &query, None, None, None).is_err());
+    }
+
+    #[test]
+    fn not_expired() {
+        let query = Operator::And(vec![]);
+        let class = vec![100,100,100];
+
+        let (query_string, arguments) = wql_to_sql(&class, &query, None, None, None).unwrap();
+        assert!(query_string.ends_with(&format!("WHERE i.type = ? AND {}", NOT_EXPIRED)));
+        assert_eq!(1, arguments.len());
+
+        let (query_string, _) = wql_to_sql_count(&class, &query).unwrap();
+        assert!(query_string.contains(NOT_EXPIRED));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn count_by_tag() {
+        let query = Operator::Eq(TagName::EncryptedTagName(vec![1,2,3]), TargetValue::Encrypted(vec![4,5,6]));
+        let class = vec![100,100,100];
+        let tag_name = vec![7,8,9];
+
+        let (query_string, arguments) = wql_to_sql_count_by_tag(&class, &query, &tag_name).unwrap();
+        assert!(query_string.starts_with("SELECT t.value, count(*) FROM items as i JOIN tags_plaintext as t ON t.item_id = i.id AND t.name = ? WHERE i.type = ? AND (expires_at IS NULL"));
+        assert!(query_string.ends_with(" GROUP BY t.value ORDER BY t.value"));
+        assert_eq!(4, arguments.len());
+    }
+}
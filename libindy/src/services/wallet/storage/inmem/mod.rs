@@ -0,0 +1,263 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use errors::wallet::WalletStorageError;
+
+use super::{WalletStorageType, WalletStorage};
+use super::kv::{KVStorage, _metadata_changes};
+use super::kv::store::Store;
+
+
+// Keeps wallets in memory only. Data of each wallet id lives in a separate store
+// and survives close and open until the wallet is deleted or the process ends.
+pub struct InmemStorageType {
+    stores: RefCell<HashMap<String, Rc<RefCell<Store>>>>,
+}
+
+
+impl InmemStorageType {
+    pub fn new() -> InmemStorageType {
+        InmemStorageType {
+            stores: RefCell::new(HashMap::new())
+        }
+    }
+}
+
+
+impl WalletStorageType for InmemStorageType {
+    ///
+    /// Deletes the in-memory storage with the provided id.
+    ///
+    fn delete_storage(&self, id: &str, _config: Option<&str>, _credentials: Option<&str>) -> Result<(), WalletStorageError> {
+        self.stores.borrow_mut()
+            .remove(id)
+            .map(|_| ())
+            .ok_or(WalletStorageError::NotFound)
+    }
+
+    ///
+    /// Creates the in-memory storage with the provided id and stores metadata in it.
+    ///
+    fn create_storage(&self, id: &str, _config: Option<&str>, _credentials: Option<&str>, metadata: &[u8]) -> Result<(), WalletStorageError> {
+        let mut stores = self.stores.borrow_mut();
+
+        if stores.contains_key(id) {
+            return Err(WalletStorageError::AlreadyExists);
+        }
+
+        stores.insert(id.to_string(), Rc::new(RefCell::new(Store::in_memory(_metadata_changes(metadata)))));
+
+        Ok(())
+    }
+
+    ///
+    /// Opens the in-memory storage with the provided id.
+    ///
+    fn open_storage(&self, id: &str, _config: Option<&str>, _credentials: Option<&str>) -> Result<Box<WalletStorage>, WalletStorageError> {
+        let store = self.stores.borrow()
+            .get(id)
+            .cloned()
+            .ok_or(WalletStorageError::NotFound)?;
+
+        Ok(Box::new(KVStorage::new(store)))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{Tag, TagName, StorageOperation};
+    use services::wallet::language;
+    use services::wallet::wallet::EncryptedValue;
+
+    #[test]
+    fn inmem_storage_type_create_works() {
+        let storage_type = InmemStorageType::new();
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+    }
+
+    #[test]
+    fn inmem_storage_type_create_works_for_twice() {
+        let storage_type = InmemStorageType::new();
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+
+        let res = storage_type.create_storage(_wallet_id(), None, None, &_metadata());
+        assert_match!(Err(WalletStorageError::AlreadyExists), res);
+    }
+
+    #[test]
+    fn inmem_storage_get_storage_metadata_works() {
+        let storage_type = InmemStorageType::new();
+        let storage = _storage(&storage_type);
+
+        assert_eq!(storage.get_storage_metadata().unwrap(), _metadata());
+    }
+
+    #[test]
+    fn inmem_storage_type_delete_works() {
+        let storage_type = InmemStorageType::new();
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+
+        storage_type.delete_storage(_wallet_id(), None, None).unwrap();
+
+        let res = storage_type.open_storage(_wallet_id(), None, None);
+        assert_match!(Err(WalletStorageError::NotFound), res);
+    }
+
+    #[test]
+    fn inmem_storage_type_delete_works_for_non_existing() {
+        let storage_type = InmemStorageType::new();
+
+        let res = storage_type.delete_storage("unknown", None, None);
+        assert_match!(Err(WalletStorageError::NotFound), res);
+    }
+
+    #[test]
+    fn inmem_storage_type_open_works_for_not_created() {
+        let storage_type = InmemStorageType::new();
+
+        let res = storage_type.open_storage("unknown", None, None);
+        assert_match!(Err(WalletStorageError::NotFound), res);
+    }
+
+    #[test]
+    fn inmem_storage_set_get_works_for_reopen() {
+        let storage_type = InmemStorageType::new();
+
+        {
+            let storage = _storage(&storage_type);
+            storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        }
+
+        let storage = storage_type.open_storage(_wallet_id(), None, None).unwrap();
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+    }
+
+    #[test]
+    fn inmem_storage_works_for_isolation() {
+        let storage_type = InmemStorageType::new();
+
+        let storage = _storage(&storage_type);
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        storage_type.create_storage("w2", None, None, &_metadata()).unwrap();
+        let other_storage = storage_type.open_storage("w2", None, None).unwrap();
+
+        let res = other_storage.get(&_type1(), &_id1(), "{}");
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        other_storage.add(&_type1(), &_id1(), &_value2(), &[]).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveValue": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+    }
+
+    #[test]
+    fn inmem_storage_search_works() {
+        let storage_type = InmemStorageType::new();
+
+        let storage = _storage(&storage_type);
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id2(), &_value2(), &[Tag::PlainText(vec![1, 5, 8, 1], "Other value".to_string())]).unwrap();
+        storage.add(&_type2(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let query = language::Operator::In(language::TagName::PlainTagName(vec![1, 5, 8, 1]),
+                                           vec![language::TargetValue::Unencrypted("Plain value".to_string()),
+                                                language::TargetValue::Unencrypted("Other value".to_string())]);
+
+        let mut iterator = storage.search(&_type1(), &query, Some(r##"{"retrieveTotalCount": true}"##)).unwrap();
+        assert_eq!(Some(2), iterator.get_total_count().unwrap());
+        assert_eq!(_id1(), iterator.next().unwrap().unwrap().id);
+        assert_eq!(_id2(), iterator.next().unwrap().unwrap().id);
+        assert!(iterator.next().unwrap().is_none());
+
+        let query = language::Operator::Eq(language::TagName::EncryptedTagName(vec![1, 5, 8]), language::TargetValue::Encrypted(vec![3, 5, 6]));
+        assert_eq!(1, storage.count(&_type1(), &query).unwrap());
+    }
+
+    #[test]
+    fn inmem_storage_batch_works_for_rollback() {
+        let storage_type = InmemStorageType::new();
+
+        let storage = _storage(&storage_type);
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.batch(&[
+            StorageOperation::Add(_type1(), _id2(), _value2(), _tags(), None),
+            StorageOperation::DeleteTags(_type1(), _id1(), vec![TagName::OfEncrypted(vec![1, 5, 8])]),
+            StorageOperation::Delete(_type2(), _id1()),
+        ]);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveTags": true}"##).unwrap();
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+        let res = storage.get(&_type1(), &_id2(), "{}");
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    fn _wallet_id() -> &'static str {
+        "w1"
+    }
+
+    fn _storage(storage_type: &InmemStorageType) -> Box<WalletStorage> {
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+        storage_type.open_storage(_wallet_id(), None, None).unwrap()
+    }
+
+    fn _metadata() -> Vec<u8> {
+        vec![1, 2, 3, 4, 5, 6, 7, 8]
+    }
+
+    fn _type(i: u8) -> Vec<u8> {
+        vec![i, 1 + i, 2 + i]
+    }
+
+    fn _type1() -> Vec<u8> {
+        _type(1)
+    }
+
+    fn _type2() -> Vec<u8> {
+        _type(2)
+    }
+
+    fn _id(i: u8) -> Vec<u8> {
+        vec![3 + i, 4 + i, 5 + i]
+    }
+
+    fn _id1() -> Vec<u8> {
+        _id(1)
+    }
+
+    fn _id2() -> Vec<u8> {
+        _id(2)
+    }
+
+    fn _value(i: u8) -> EncryptedValue {
+        EncryptedValue { data: vec![6 + i, 7 + i, 8 + i], key: vec![9 + i, 10 + i, 11 + i] }
+    }
+
+    fn _value1() -> EncryptedValue {
+        _value(1)
+    }
+
+    fn _value2() -> EncryptedValue {
+        _value(2)
+    }
+
+    fn _tags() -> Vec<Tag> {
+        vec![
+            Tag::Encrypted(vec![1, 5, 8], vec![3, 5, 6]),
+            Tag::PlainText(vec![1, 5, 8, 1], "Plain value".to_string())
+        ]
+    }
+
+    fn _sort(mut v: Vec<Tag>) -> Vec<Tag> {
+        v.sort();
+        v
+    }
+}
@@ -0,0 +1,1783 @@
+mod query;
+pub(super) mod store;
+
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+use rmp_serde;
+use serde_json;
+
+use std;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::vec;
+
+use utils::environment;
+use utils::crypto::base64;
+use errors::wallet::{WalletStorageError, WalletQueryError};
+use errors::common::CommonError;
+use services::wallet::language;
+
+use self::store::{Store, Changes};
+use super::{StorageIterator, WalletStorageType, WalletStorage, StorageRecord, StorageOperation, EncryptedValue, Tag, TagName};
+use super::super::{RecordOptions, SearchOptions, SortOrder};
+
+
+const _KV_DB: &str = "kv.db";
+
+// Keys layout. Each part of the key is prefixed with its length, so prefixes never overlap:
+// metadata:        "m"
+// version counter: "v"                                -> last assigned record version
+// item:            "i" | type | id                  -> serialized Item
+// plaintext tag:   "p" | type | name | value | id   -> empty
+// encrypted tag:   "e" | type | name | value | id   -> empty
+const _METADATA_KEY: &[u8] = b"m";
+const _VERSION_COUNTER_KEY: &[u8] = b"v";
+const _ITEM_PREFIX: u8 = b'i';
+const _PLAIN_TAG_PREFIX: u8 = b'p';
+const _ENCRYPTED_TAG_PREFIX: u8 = b'e';
+
+
+fn _push_part(key: &mut Vec<u8>, part: &[u8]) {
+    key.write_u32::<BigEndian>(part.len() as u32).unwrap();
+    key.extend_from_slice(part);
+}
+
+// Splits length prefixed part from the beginning of the key
+fn _read_part(key: &[u8]) -> Option<(&[u8], &[u8])> {
+    if key.len() < 4 {
+        return None;
+    }
+
+    let len = BigEndian::read_u32(&key[..4]) as usize;
+
+    if key.len() < 4 + len {
+        return None;
+    }
+
+    Some((&key[4..4 + len], &key[4 + len..]))
+}
+
+fn _items_prefix(type_: &[u8]) -> Vec<u8> {
+    let mut key = vec![_ITEM_PREFIX];
+    _push_part(&mut key, type_);
+    key
+}
+
+fn _item_key(type_: &[u8], id: &[u8]) -> Vec<u8> {
+    let mut key = _items_prefix(type_);
+    _push_part(&mut key, id);
+    key
+}
+
+// Splits item key to type and id
+fn _read_item_key(key: &[u8]) -> Result<(&[u8], &[u8]), WalletStorageError> {
+    let (type_, rest) = _read_part(&key[1..])
+        .ok_or(WalletStorageError::IOError("Invalid item key".to_string()))?;
+    let (id, _) = _read_part(rest)
+        .ok_or(WalletStorageError::IOError("Invalid item key".to_string()))?;
+
+    Ok((type_, id))
+}
+
+fn _tag_name_prefix(tag_prefix: u8, type_: &[u8], name: &[u8]) -> Vec<u8> {
+    let mut key = vec![tag_prefix];
+    _push_part(&mut key, type_);
+    _push_part(&mut key, name);
+    key
+}
+
+fn _tag_value_prefix(tag_prefix: u8, type_: &[u8], name: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut key = _tag_name_prefix(tag_prefix, type_, name);
+    _push_part(&mut key, value);
+    key
+}
+
+fn _tag_key(type_: &[u8], id: &[u8], tag: &Tag) -> Vec<u8> {
+    let mut key = match *tag {
+        Tag::Encrypted(ref name, ref value) => _tag_value_prefix(_ENCRYPTED_TAG_PREFIX, type_, name, value),
+        Tag::PlainText(ref name, ref value) => _tag_value_prefix(_PLAIN_TAG_PREFIX, type_, name, value.as_bytes()),
+    };
+    _push_part(&mut key, id);
+    key
+}
+
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Item {
+    value: Vec<u8>,
+    key: Vec<u8>,
+    encrypted_tags: Vec<(Vec<u8>, Vec<u8>)>,
+    plain_tags: Vec<(Vec<u8>, String)>,
+    // Taken from the storage wide counter on any change of value or tags
+    #[serde(default = "_initial_version")]
+    version: u64,
+    // Unix time in seconds since which the item is hidden from reads
+    #[serde(default)]
+    expires_at: Option<u64>,
+}
+
+fn _initial_version() -> u64 { 1 }
+
+fn _now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or(0)
+}
+
+impl Item {
+    fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.map(|expires_at| expires_at <= now).unwrap_or(false)
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        self.encrypted_tags.iter()
+            .map(|&(ref name, ref value)| Tag::Encrypted(name.clone(), value.clone()))
+            .chain(self.plain_tags.iter().map(|&(ref name, ref value)| Tag::PlainText(name.clone(), value.clone())))
+            .collect()
+    }
+
+    fn plain_tag(&self, name: &[u8]) -> Option<&String> {
+        self.plain_tags.iter()
+            .find(|&&(ref tag_name, _)| tag_name.as_slice() == name)
+            .map(|&(_, ref value)| value)
+    }
+
+    // Replaces tag with the same name, returns replaced tag
+    fn set_tag(&mut self, tag: &Tag) -> Option<Tag> {
+        let replaced = self.remove_tag(&match *tag {
+            Tag::Encrypted(ref name, _) => TagName::OfEncrypted(name.clone()),
+            Tag::PlainText(ref name, _) => TagName::OfPlain(name.clone()),
+        });
+
+        match *tag {
+            Tag::Encrypted(ref name, ref value) => self.encrypted_tags.push((name.clone(), value.clone())),
+            Tag::PlainText(ref name, ref value) => self.plain_tags.push((name.clone(), value.clone())),
+        }
+
+        replaced
+    }
+
+    fn remove_tag(&mut self, tag_name: &TagName) -> Option<Tag> {
+        match *tag_name {
+            TagName::OfEncrypted(ref name) => {
+                let pos = self.encrypted_tags.iter().position(|&(ref tag_name, _)| tag_name == name)?;
+                let (name, value) = self.encrypted_tags.remove(pos);
+                Some(Tag::Encrypted(name, value))
+            }
+            TagName::OfPlain(ref name) => {
+                let pos = self.plain_tags.iter().position(|&(ref tag_name, _)| tag_name == name)?;
+                let (name, value) = self.plain_tags.remove(pos);
+                Some(Tag::PlainText(name, value))
+            }
+        }
+    }
+
+    fn to_record(&self, type_: &[u8], id: &[u8], options: &RecordOptions) -> StorageRecord {
+        StorageRecord::new(id.to_vec(),
+                           if options.retrieve_value { Some(EncryptedValue::new(self.value.clone(), self.key.clone())) } else { None },
+                           if options.retrieve_type { Some(type_.to_vec()) } else { None },
+                           if options.retrieve_tags { Some(self.tags()) } else { None },
+                           if options.retrieve_version { Some(self.version) } else { None },
+                           self.expires_at)
+    }
+}
+
+
+// Collects changes of several operations so they can be written to the store at once.
+// Reads see changes made by previous operations of the same transaction.
+struct Transaction<'a> {
+    store: &'a Store,
+    changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    now: u64,
+}
+
+impl<'a> Transaction<'a> {
+    fn new(store: &'a Store) -> Transaction<'a> {
+        Transaction {
+            store,
+            changes: BTreeMap::new(),
+            now: _now(),
+        }
+    }
+
+    fn get_item(&self, type_: &[u8], id: &[u8]) -> Result<Option<Item>, WalletStorageError> {
+        Ok(self.get_stored_item(type_, id)?.filter(|item| !item.is_expired(self.now)))
+    }
+
+    // Expired item is still stored until it is purged or replaced
+    fn get_stored_item(&self, type_: &[u8], id: &[u8]) -> Result<Option<Item>, WalletStorageError> {
+        let key = _item_key(type_, id);
+
+        let value = match self.changes.get(&key) {
+            Some(value) => value.as_ref(),
+            None => self.store.get(&key)
+        };
+
+        match value {
+            Some(value) => Ok(Some(_deserialize_item(value)?)),
+            None => Ok(None)
+        }
+    }
+
+    fn put_item(&mut self, type_: &[u8], id: &[u8], item: &Item) -> Result<(), WalletStorageError> {
+        let item = rmp_serde::to_vec(item)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize item: {:?}", err)))?;
+
+        self.changes.insert(_item_key(type_, id), Some(item));
+        Ok(())
+    }
+
+    // Versions are taken from the storage wide counter, so re-added record never repeats
+    // a version of the deleted one
+    fn next_version(&mut self) -> Result<u64, WalletStorageError> {
+        let version = match self.changes.get(_VERSION_COUNTER_KEY) {
+            Some(&Some(ref value)) => _deserialize_version(value)?,
+            _ => _version_counter(self.store)?
+        } + 1;
+
+        let value = rmp_serde::to_vec(&version)
+            .map_err(|err| CommonError::InvalidState(format!("Can't serialize version: {:?}", err)))?;
+
+        self.changes.insert(_VERSION_COUNTER_KEY.to_vec(), Some(value));
+        Ok(version)
+    }
+
+    fn put_tag(&mut self, type_: &[u8], id: &[u8], tag: &Tag) {
+        self.changes.insert(_tag_key(type_, id, tag), Some(Vec::new()));
+    }
+
+    fn remove_tag(&mut self, type_: &[u8], id: &[u8], tag: &Tag) {
+        self.changes.insert(_tag_key(type_, id, tag), None);
+    }
+
+    fn add(&mut self, type_: &[u8], id: &[u8], value: &EncryptedValue, tags: &[Tag], expires_at: Option<u64>) -> Result<(), WalletStorageError> {
+        if let Some(item) = self.get_stored_item(type_, id)? {
+            if !item.is_expired(self.now) {
+                return Err(WalletStorageError::ItemAlreadyExists);
+            }
+
+            // Expired record isn't purged yet, but its name is free for the new one
+            for tag in item.tags() {
+                self.remove_tag(type_, id, &tag);
+            }
+        }
+
+        let mut item = Item {
+            value: value.data.clone(),
+            key: value.key.clone(),
+            encrypted_tags: Vec::new(),
+            plain_tags: Vec::new(),
+            version: self.next_version()?,
+            expires_at,
+        };
+
+        for tag in tags {
+            if let Some(replaced) = item.set_tag(tag) {
+                self.remove_tag(type_, id, &replaced);
+            }
+            self.put_tag(type_, id, tag);
+        }
+
+        self.put_item(type_, id, &item)
+    }
+
+    fn update(&mut self, type_: &[u8], id: &[u8], value: &EncryptedValue) -> Result<(), WalletStorageError> {
+        let mut item = self.get_item(type_, id)?.ok_or(WalletStorageError::ItemNotFound)?;
+
+        item.value = value.data.clone();
+        item.key = value.key.clone();
+        item.version = self.next_version()?;
+
+        self.put_item(type_, id, &item)
+    }
+
+    fn add_tags(&mut self, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
+        let mut item = self.get_item(type_, id)?.ok_or(WalletStorageError::ItemNotFound)?;
+        item.version = self.next_version()?;
+
+        for tag in tags {
+            if let Some(replaced) = item.set_tag(tag) {
+                self.remove_tag(type_, id, &replaced);
+            }
+            self.put_tag(type_, id, tag);
+        }
+
+        self.put_item(type_, id, &item)
+    }
+
+    fn update_tags(&mut self, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
+        let mut item = self.get_item(type_, id)?.ok_or(WalletStorageError::ItemNotFound)?;
+        item.version = self.next_version()?;
+
+        for tag in item.tags() {
+            self.remove_tag(type_, id, &tag);
+        }
+
+        item.encrypted_tags.clear();
+        item.plain_tags.clear();
+
+        for tag in tags {
+            if let Some(replaced) = item.set_tag(tag) {
+                self.remove_tag(type_, id, &replaced);
+            }
+            self.put_tag(type_, id, tag);
+        }
+
+        self.put_item(type_, id, &item)
+    }
+
+    fn delete_tags(&mut self, type_: &[u8], id: &[u8], tag_names: &[TagName]) -> Result<(), WalletStorageError> {
+        let mut item = self.get_item(type_, id)?.ok_or(WalletStorageError::ItemNotFound)?;
+        item.version = self.next_version()?;
+
+        for tag_name in tag_names {
+            if let Some(removed) = item.remove_tag(tag_name) {
+                self.remove_tag(type_, id, &removed);
+            }
+        }
+
+        self.put_item(type_, id, &item)
+    }
+
+    fn delete(&mut self, type_: &[u8], id: &[u8]) -> Result<(), WalletStorageError> {
+        let item = self.get_item(type_, id)?.ok_or(WalletStorageError::ItemNotFound)?;
+
+        for tag in item.tags() {
+            self.remove_tag(type_, id, &tag);
+        }
+
+        self.changes.insert(_item_key(type_, id), None);
+        Ok(())
+    }
+
+    fn check_version(&self, type_: &[u8], id: &[u8], version: u64) -> Result<(), WalletStorageError> {
+        let item = self.get_item(type_, id)?.ok_or(WalletStorageError::ItemNotFound)?;
+
+        if item.version != version {
+            return Err(WalletStorageError::ItemVersionMismatch);
+        }
+
+        Ok(())
+    }
+
+    fn into_changes(self) -> Changes {
+        self.changes.into_iter().collect()
+    }
+}
+
+
+// Tag keys which don't belong to tags of stored items
+fn _orphan_tag_keys(store: &Store) -> Result<Vec<Vec<u8>>, WalletStorageError> {
+    let mut tag_keys = BTreeSet::new();
+
+    for (key, value) in store.scan_prefix(&[_ITEM_PREFIX]) {
+        let (type_, id) = _read_item_key(key)?;
+
+        for tag in _deserialize_item(value)?.tags() {
+            tag_keys.insert(_tag_key(type_, id, &tag));
+        }
+    }
+
+    let mut orphan_keys = Vec::new();
+
+    for tag_prefix in &[[_ENCRYPTED_TAG_PREFIX], [_PLAIN_TAG_PREFIX]] {
+        orphan_keys.extend(store.scan_prefix(tag_prefix)
+            .map(|(key, _)| key)
+            .filter(|key| !tag_keys.contains(*key))
+            .cloned());
+    }
+
+    Ok(orphan_keys)
+}
+
+// Stores created before the counter was introduced start it from the latest record version
+fn _version_counter(store: &Store) -> Result<u64, WalletStorageError> {
+    if let Some(value) = store.get(_VERSION_COUNTER_KEY) {
+        return _deserialize_version(value);
+    }
+
+    let mut version = 0;
+
+    for (_, value) in store.scan_prefix(&[_ITEM_PREFIX]) {
+        version = std::cmp::max(version, _deserialize_item(value)?.version);
+    }
+
+    Ok(version)
+}
+
+// Changes to initialize new store with storage metadata
+pub(super) fn _metadata_changes(metadata: &[u8]) -> Changes {
+    vec![(_METADATA_KEY.to_vec(), Some(metadata.to_vec()))]
+}
+
+
+fn _deserialize_item(value: &[u8]) -> Result<Item, WalletStorageError> {
+    rmp_serde::from_slice(value)
+        .map_err(|err| WalletStorageError::IOError(format!("Can't deserialize item: {:?}", err)))
+}
+
+fn _deserialize_version(value: &[u8]) -> Result<u64, WalletStorageError> {
+    rmp_serde::from_slice(value)
+        .map_err(|err| WalletStorageError::IOError(format!("Can't deserialize version: {:?}", err)))
+}
+
+
+struct KVStorageIterator {
+    records: vec::IntoIter<StorageRecord>,
+    total_count: Option<usize>,
+}
+
+impl KVStorageIterator {
+    fn new(records: Vec<StorageRecord>, total_count: Option<usize>) -> KVStorageIterator {
+        KVStorageIterator {
+            records: records.into_iter(),
+            total_count,
+        }
+    }
+}
+
+impl StorageIterator for KVStorageIterator {
+    fn next(&mut self) -> Result<Option<StorageRecord>, WalletStorageError> {
+        Ok(self.records.next())
+    }
+
+    fn get_total_count(&self) -> Result<Option<usize>, WalletStorageError> {
+        Ok(self.total_count)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Config {
+    path: Option<String>,
+}
+
+// Store is shared, so in-memory storages keep data between opens
+pub(super) struct KVStorage {
+    store: Rc<RefCell<Store>>,
+}
+
+pub struct KVStorageType {}
+
+
+impl KVStorageType {
+    pub fn new() -> KVStorageType {
+        KVStorageType {}
+    }
+
+    fn _db_path(id: &str, config: Option<&Config>) -> std::path::PathBuf {
+        let mut path = match config {
+            Some(Config { path: Some(ref path) }) => std::path::PathBuf::from(path),
+            _ => environment::wallet_home_path()
+        };
+
+        path.push(id);
+        path.push(_KV_DB);
+        path
+    }
+
+    fn _parse_config(config: Option<&str>) -> Result<Option<Config>, WalletStorageError> {
+        let config = config
+            .map(serde_json::from_str::<Config>)
+            .map_or(Ok(None), |v| v.map(Some))
+            .map_err(|err| CommonError::InvalidStructure(format!("Cannot deserialize config: {:?}", err)))?;
+
+        Ok(config)
+    }
+}
+
+
+impl KVStorage {
+    pub(super) fn new(store: Rc<RefCell<Store>>) -> KVStorage {
+        KVStorage { store }
+    }
+
+    // Applies changes collected by the closure at once
+    fn _transaction<F>(&self, f: F) -> Result<(), WalletStorageError> where F: FnOnce(&mut Transaction) -> Result<(), WalletStorageError> {
+        let changes = {
+            let store = self.store.borrow();
+            let mut tx = Transaction::new(&store);
+            f(&mut tx)?;
+            tx.into_changes()
+        };
+
+        self.store.borrow_mut().write(changes)
+    }
+
+    fn _get_item(store: &Store, type_: &[u8], id: &[u8]) -> Result<Item, WalletStorageError> {
+        match store.get(&_item_key(type_, id)) {
+            Some(value) => {
+                let item = _deserialize_item(value)?;
+
+                if item.is_expired(_now()) {
+                    return Err(WalletStorageError::ItemNotFound);
+                }
+
+                Ok(item)
+            }
+            None => Err(WalletStorageError::ItemNotFound)
+        }
+    }
+
+    // Ids of matching items which aren't expired
+    fn _find_ids(store: &Store, type_: &[u8], query: &language::Operator) -> Result<BTreeSet<Vec<u8>>, WalletStorageError> {
+        let now = _now();
+        let mut ids = BTreeSet::new();
+
+        for id in query::find_ids(store, type_, query)? {
+            if let Some(value) = store.get(&_item_key(type_, &id)) {
+                if !_deserialize_item(value)?.is_expired(now) {
+                    ids.insert(id);
+                }
+            }
+        }
+
+        Ok(ids)
+    }
+}
+
+
+impl WalletStorage for KVStorage {
+    fn get(&self, type_: &[u8], id: &[u8], options: &str) -> Result<StorageRecord, WalletStorageError> {
+        let options: RecordOptions = if options == "{}" {
+            RecordOptions::default()
+        } else {
+            serde_json::from_str(options)?
+        };
+
+        let store = self.store.borrow();
+        let item = KVStorage::_get_item(&store, type_, id)?;
+
+        Ok(item.to_record(type_, id, &options))
+    }
+
+    fn add(&self, type_: &[u8], id: &[u8], value: &EncryptedValue, tags: &[Tag]) -> Result<(), WalletStorageError> {
+        self._transaction(|tx| tx.add(type_, id, value, tags, None))
+    }
+
+    fn add_with_expiry(&self, type_: &[u8], id: &[u8], value: &EncryptedValue, tags: &[Tag], expires_at: u64) -> Result<(), WalletStorageError> {
+        self._transaction(|tx| tx.add(type_, id, value, tags, Some(expires_at)))
+    }
+
+    fn purge_expired(&self) -> Result<usize, WalletStorageError> {
+        let now = _now();
+        let mut changes = Changes::new();
+        let mut count = 0;
+
+        for (key, value) in self.store.borrow().scan_prefix(&[_ITEM_PREFIX]) {
+            let item = _deserialize_item(value)?;
+
+            if !item.is_expired(now) {
+                continue;
+            }
+
+            let (type_, id) = _read_item_key(key)?;
+
+            for tag in item.tags() {
+                changes.push((_tag_key(type_, id, &tag), None));
+            }
+
+            changes.push((key.clone(), None));
+            count += 1;
+        }
+
+        if !changes.is_empty() {
+            self.store.borrow_mut().write(changes)?;
+        }
+
+        Ok(count)
+    }
+
+    fn update(&self, type_: &[u8], id: &[u8], value: &EncryptedValue) -> Result<(), WalletStorageError> {
+        self._transaction(|tx| tx.update(type_, id, value))
+    }
+
+    fn add_tags(&self, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
+        self._transaction(|tx| tx.add_tags(type_, id, tags))
+    }
+
+    fn update_tags(&self, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError> {
+        self._transaction(|tx| tx.update_tags(type_, id, tags))
+    }
+
+    fn delete_tags(&self, type_: &[u8], id: &[u8], tag_names: &[TagName]) -> Result<(), WalletStorageError> {
+        self._transaction(|tx| tx.delete_tags(type_, id, tag_names))
+    }
+
+    fn delete(&self, type_: &[u8], id: &[u8]) -> Result<(), WalletStorageError> {
+        self._transaction(|tx| tx.delete(type_, id))
+    }
+
+    ///
+    /// Collects changes of all operations and writes them as single log frame.
+    /// If any of operations fails nothing is written.
+    ///
+    fn batch(&self, operations: &[StorageOperation]) -> Result<(), WalletStorageError> {
+        self._transaction(|tx| {
+            for operation in operations {
+                match operation {
+                    &StorageOperation::Add(ref type_, ref id, ref value, ref tags, expires_at) => tx.add(type_, id, value, tags, expires_at)?,
+                    &StorageOperation::Update(ref type_, ref id, ref value) => tx.update(type_, id, value)?,
+                    &StorageOperation::AddTags(ref type_, ref id, ref tags) => tx.add_tags(type_, id, tags)?,
+                    &StorageOperation::UpdateTags(ref type_, ref id, ref tags) => tx.update_tags(type_, id, tags)?,
+                    &StorageOperation::DeleteTags(ref type_, ref id, ref tag_names) => tx.delete_tags(type_, id, tag_names)?,
+                    &StorageOperation::Delete(ref type_, ref id) => tx.delete(type_, id)?,
+                    &StorageOperation::CheckVersion(ref type_, ref id, version) => tx.check_version(type_, id, version)?,
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn get_storage_metadata(&self) -> Result<Vec<u8>, WalletStorageError> {
+        self.store.borrow()
+            .get(_METADATA_KEY)
+            .cloned()
+            .ok_or(WalletStorageError::ItemNotFound)
+    }
+
+    fn set_storage_metadata(&self, metadata: &[u8]) -> Result<(), WalletStorageError> {
+        self.store.borrow_mut().write(_metadata_changes(metadata))
+    }
+
+    fn get_all(&self) -> Result<Box<StorageIterator>, WalletStorageError> {
+        let store = self.store.borrow();
+
+        let fetch_options = RecordOptions {
+            retrieve_type: true,
+            retrieve_value: true,
+            retrieve_tags: true,
+            retrieve_version: false,
+        };
+
+        let now = _now();
+        let mut records = Vec::new();
+
+        for (key, value) in store.scan_prefix(&[_ITEM_PREFIX]) {
+            let (type_, id) = _read_item_key(key)?;
+            let item = _deserialize_item(value)?;
+
+            if !item.is_expired(now) {
+                records.push(item.to_record(type_, id, &fetch_options));
+            }
+        }
+
+        Ok(Box::new(KVStorageIterator::new(records, None)))
+    }
+
+    fn search(&self, type_: &[u8], query: &language::Operator, options: Option<&str>) -> Result<Box<StorageIterator>, WalletStorageError> {
+        let search_options = match options {
+            None => SearchOptions::default(),
+            Some(option_str) => serde_json::from_str(option_str)?
+        };
+
+        let store = self.store.borrow();
+
+        let ids = KVStorage::_find_ids(&store, type_, query)?;
+
+        let total_count = if search_options.retrieve_total_count { Some(ids.len()) } else { None };
+
+        if !search_options.retrieve_records {
+            return Ok(Box::new(KVStorageIterator::new(Vec::new(), total_count)));
+        }
+
+        let fetch_options = RecordOptions {
+            retrieve_value: search_options.retrieve_value,
+            retrieve_tags: search_options.retrieve_tags,
+            retrieve_type: search_options.retrieve_type,
+            retrieve_version: search_options.retrieve_version,
+        };
+
+        let mut items = Vec::with_capacity(ids.len());
+        for id in ids {
+            let item = KVStorage::_get_item(&store, type_, &id)?;
+            items.push((id, item));
+        }
+
+        // records without sort tag go last, records with equal values are ordered by id
+        if let Some(ref sort_by) = search_options.sort_by {
+            let sort_tag_name = match sort_by.starts_with('~') {
+                true => base64::decode(&sort_by[1..])?,
+                false => return Err(WalletStorageError::QueryError(WalletQueryError::StructureErr("sortBy must be used only for nonencrypted tag".to_string())))
+            };
+
+            items.sort_by(|&(ref id_1, ref item_1), &(ref id_2, ref item_2)| {
+                let ordering = match (item_1.plain_tag(&sort_tag_name), item_2.plain_tag(&sort_tag_name)) {
+                    (Some(value_1), Some(value_2)) => match search_options.sort_order {
+                        SortOrder::Asc => value_1.cmp(value_2),
+                        SortOrder::Desc => value_2.cmp(value_1),
+                    },
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                };
+                ordering.then_with(|| id_1.cmp(id_2))
+            });
+        }
+
+        let records = items.into_iter()
+            .skip(search_options.offset.unwrap_or(0))
+            .take(search_options.limit.unwrap_or(std::usize::MAX))
+            .map(|(id, item)| item.to_record(type_, &id, &fetch_options))
+            .collect();
+
+        Ok(Box::new(KVStorageIterator::new(records, total_count)))
+    }
+
+    fn count(&self, type_: &[u8], query: &language::Operator) -> Result<usize, WalletStorageError> {
+        let store = self.store.borrow();
+        let ids = KVStorage::_find_ids(&store, type_, query)?;
+        Ok(ids.len())
+    }
+
+    fn count_by_tag(&self, type_: &[u8], query: &language::Operator, tag_name: &[u8]) -> Result<Vec<(String, usize)>, WalletStorageError> {
+        let store = self.store.borrow();
+        let ids = KVStorage::_find_ids(&store, type_, query)?;
+
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for id in ids {
+            let item = KVStorage::_get_item(&store, type_, &id)?;
+
+            if let Some(value) = item.plain_tag(tag_name) {
+                *counts.entry(value.clone()).or_insert(0) += 1;
+            }
+        }
+
+        Ok(counts.into_iter().collect())
+    }
+
+    fn count_orphan_tags(&self) -> Result<usize, WalletStorageError> {
+        Ok(_orphan_tag_keys(&self.store.borrow())?.len())
+    }
+
+    fn delete_orphan_tags(&self) -> Result<usize, WalletStorageError> {
+        let orphan_keys = _orphan_tag_keys(&self.store.borrow())?;
+        let count = orphan_keys.len();
+
+        self.store.borrow_mut().write(orphan_keys.into_iter().map(|key| (key, None)).collect())?;
+
+        Ok(count)
+    }
+
+    fn close(&mut self) -> Result<(), WalletStorageError> {
+        Ok(())
+    }
+}
+
+
+impl WalletStorageType for KVStorageType {
+    ///
+    /// Deletes the key-value storage file with the provided id from the path specified in the
+    /// config.
+    ///
+    fn delete_storage(&self, id: &str, config: Option<&str>, _credentials: Option<&str>) -> Result<(), WalletStorageError> {
+        let config = KVStorageType::_parse_config(config)?;
+
+        let db_file_path = KVStorageType::_db_path(id, config.as_ref());
+
+        if db_file_path.exists() {
+            std::fs::remove_dir_all(db_file_path.parent().unwrap())?;
+            Ok(())
+        } else {
+            Err(WalletStorageError::NotFound)
+        }
+    }
+
+    ///
+    /// Creates the key-value storage file with the provided id in the path specified in the config
+    /// and stores metadata in it.
+    ///
+    fn create_storage(&self, id: &str, config: Option<&str>, _credentials: Option<&str>, metadata: &[u8]) -> Result<(), WalletStorageError> {
+        let config = KVStorageType::_parse_config(config)?;
+
+        let db_path = KVStorageType::_db_path(id, config.as_ref());
+
+        if db_path.exists() {
+            return Err(WalletStorageError::AlreadyExists);
+        }
+
+        fs::DirBuilder::new()
+            .recursive(true)
+            .create(db_path.parent().unwrap())?;
+
+        if let Err(err) = Store::create(&db_path, _metadata_changes(metadata)) {
+            if db_path.exists() {
+                std::fs::remove_file(db_path)?;
+            }
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Loads the key-value storage file with the provided id located in the path specified in
+    /// the config.
+    ///
+    fn open_storage(&self, id: &str, config: Option<&str>, _credentials: Option<&str>) -> Result<Box<WalletStorage>, WalletStorageError> {
+        let config = KVStorageType::_parse_config(config)?;
+
+        let db_file_path = KVStorageType::_db_path(id, config.as_ref());
+
+        if !db_file_path.exists() {
+            return Err(WalletStorageError::NotFound);
+        }
+
+        Ok(Box::new(KVStorage::new(Rc::new(RefCell::new(Store::open(&db_file_path)?)))))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::Tag;
+
+    use utils::test;
+
+    #[test]
+    fn kv_storage_type_create_works() {
+        _cleanup();
+
+        let storage_type = KVStorageType::new();
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+    }
+
+    #[test]
+    fn kv_storage_type_create_works_for_custom_path() {
+        _cleanup();
+
+        let config = json!({
+            "path": _custom_path()
+        }).to_string();
+
+        let storage_type = KVStorageType::new();
+        storage_type.create_storage(_wallet_id(), Some(&config), None, &_metadata()).unwrap();
+    }
+
+    #[test]
+    fn kv_storage_type_create_works_for_twice() {
+        _cleanup();
+
+        let storage_type = KVStorageType::new();
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+
+        let res = storage_type.create_storage(_wallet_id(), None, None, &_metadata());
+        assert_match!(Err(WalletStorageError::AlreadyExists), res);
+    }
+
+    #[test]
+    fn kv_storage_get_storage_metadata_works() {
+        _cleanup();
+
+        let storage = _storage();
+        let metadata = storage.get_storage_metadata().unwrap();
+
+        assert_eq!(metadata, _metadata());
+    }
+
+    #[test]
+    fn kv_storage_type_delete_works() {
+        _cleanup();
+
+        let storage_type = KVStorageType::new();
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+
+        storage_type.delete_storage(_wallet_id(), None, None).unwrap();
+    }
+
+
+    #[test]
+    fn kv_storage_type_delete_works_for_non_existing() {
+        _cleanup();
+
+        let storage_type = KVStorageType::new();
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+
+        let res = storage_type.delete_storage("unknown", None, None);
+        assert_match!(Err(WalletStorageError::NotFound), res);
+
+        storage_type.delete_storage(_wallet_id(), None, None).unwrap();
+    }
+
+    #[test]
+    fn kv_storage_type_open_works() {
+        _cleanup();
+        _storage();
+    }
+
+    #[test]
+    fn kv_storage_type_open_works_for_custom() {
+        _cleanup();
+        _storage_custom();
+    }
+
+    #[test]
+    fn kv_storage_type_open_works_for_not_created() {
+        _cleanup();
+
+        let storage_type = KVStorageType::new();
+
+        let res = storage_type.open_storage("unknown", Some("{}"), Some("{}"));
+        assert_match!(Err(WalletStorageError::NotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_add_works_for_is_802() {
+        _cleanup();
+
+        let storage = _storage();
+
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.add(&_type1(), &_id1(), &_value1(), &_tags());
+        assert_match!(Err(WalletStorageError::ItemAlreadyExists), res);
+
+        let res = storage.add(&_type1(), &_id1(), &_value1(), &_tags());
+        assert_match!(Err(WalletStorageError::ItemAlreadyExists), res);
+    }
+
+    #[test]
+    fn kv_storage_set_get_works() {
+        _cleanup();
+
+        let storage = _storage();
+
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+    }
+
+    #[test]
+    fn kv_storage_set_get_works_for_custom() {
+        _cleanup();
+
+        let storage = _storage_custom();
+
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+    }
+
+    #[test]
+    fn kv_storage_set_get_works_for_twice() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.add(&_type1(), &_id1(), &_value2(), &_tags());
+        assert_match!(Err(WalletStorageError::ItemAlreadyExists), res);
+    }
+
+    #[test]
+    fn kv_storage_set_get_works_for_reopen() {
+        _cleanup();
+
+        {
+            _storage().add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        }
+
+        let storage_type = KVStorageType::new();
+        let storage = storage_type.open_storage(_wallet_id(), Some("{}"), Some("{}")).unwrap();
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+    }
+
+    #[test]
+    fn kv_storage_get_works_for_wrong_key() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.get(&_type1(), &_id2(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res)
+    }
+
+    #[test]
+    fn kv_storage_delete_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+        storage.delete(&_type1(), &_id1()).unwrap();
+        let res = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_delete_works_for_non_existing() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.delete(&_type1(), &_id2());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+
+    #[test]
+    fn kv_storage_get_all_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        storage.add(&_type2(), &_id2(), &_value2(), &_tags()).unwrap();
+
+        let mut storage_iterator = storage.get_all().unwrap();
+
+        let record = storage_iterator.next().unwrap().unwrap();
+        assert_eq!(record.type_.unwrap(), _type1());
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+        let record = storage_iterator.next().unwrap().unwrap();
+        assert_eq!(record.type_.unwrap(), _type2());
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+        let record = storage_iterator.next().unwrap();
+        assert!(record.is_none());
+    }
+
+    #[test]
+    fn kv_storage_get_all_works_for_empty() {
+        _cleanup();
+
+        let storage = _storage();
+        let mut storage_iterator = storage.get_all().unwrap();
+
+        let record = storage_iterator.next().unwrap();
+        assert!(record.is_none());
+    }
+
+    #[test]
+    fn kv_storage_update_works() {
+        _cleanup();
+
+        let storage = _storage();
+
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+
+        storage.update(&_type1(), &_id1(), &_value2()).unwrap();
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+    }
+
+    #[test]
+    fn kv_storage_update_works_for_non_existing_id() {
+        _cleanup();
+
+        let storage = _storage();
+
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+
+        let res = storage.update(&_type1(), &_id2(), &_value2());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res)
+    }
+
+    #[test]
+    fn kv_storage_update_works_for_non_existing_type() {
+        _cleanup();
+
+        let storage = _storage();
+
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+
+        let res = storage.update(&_type2(), &_id1(), &_value2());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res)
+    }
+
+    #[test]
+    fn kv_storage_add_tags_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        storage.add_tags(&_type1(), &_id1(), &_new_tags()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+
+        let expected_tags = {
+            let mut tags = _tags();
+            tags.extend(_new_tags());
+            _sort(tags)
+        };
+
+        assert_eq!(_sort(record.tags.unwrap()), expected_tags);
+    }
+
+    #[test]
+    fn kv_storage_add_tags_works_for_non_existing_id() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.add_tags(&_type1(), &_id2(), &_new_tags());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res)
+    }
+
+    #[test]
+    fn kv_storage_add_tags_works_for_non_existing_type() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.add_tags(&_type2(), &_id1(), &_new_tags());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res)
+    }
+
+    #[test]
+    fn kv_storage_add_tags_works_for_already_existing() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let tags_with_existing = {
+            let mut tags = _tags();
+            tags.extend(_new_tags());
+            tags
+        };
+
+        storage.add_tags(&_type1(), &_id1(), &tags_with_existing).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+
+        let expected_tags = {
+            let mut tags = _tags();
+            tags.extend(_new_tags());
+            _sort(tags)
+        };
+
+        assert_eq!(_sort(record.tags.unwrap()), expected_tags);
+    }
+
+    #[test]
+    fn kv_storage_update_tags_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        storage.update_tags(&_type1(), &_id1(), &_new_tags()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_new_tags()));
+    }
+
+    #[test]
+    fn kv_storage_update_tags_works_for_non_existing_id() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.update_tags(&_type1(), &_id2(), &_new_tags());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_update_tags_works_for_non_existing_type() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.update_tags(&_type1(), &_id2(), &_new_tags());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_update_tags_works_for_already_existing() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let tags_with_existing = {
+            let mut tags = _tags();
+            tags.extend(_new_tags());
+            tags
+        };
+
+        storage.update_tags(&_type1(), &_id1(), &tags_with_existing).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+
+        let expected_tags = {
+            let mut tags = _tags();
+            tags.extend(_new_tags());
+            _sort(tags)
+        };
+
+        assert_eq!(_sort(record.tags.unwrap()), expected_tags);
+    }
+
+    #[test]
+    fn kv_storage_delete_tags_works() {
+        _cleanup();
+
+        let storage = _storage();
+
+        let tag_name1 = vec![0, 0, 0];
+        let tag_name2 = vec![1, 1, 1];
+        let tag_name3 = vec![2, 2, 2];
+        let tag1 = Tag::Encrypted(tag_name1.clone(), vec![0, 0, 0]);
+        let tag2 = Tag::PlainText(tag_name2.clone(), "tag_value_2".to_string());
+        let tag3 = Tag::Encrypted(tag_name3.clone(), vec![2, 2, 2]);
+        let tags = vec![tag1.clone(), tag2.clone(), tag3.clone()];
+
+        storage.add(&_type1(), &_id1(), &_value1(), &tags).unwrap();
+
+        let tag_names = vec![TagName::OfEncrypted(tag_name1.clone()), TagName::OfPlain(tag_name2.clone())];
+        storage.delete_tags(&_type1(), &_id1(), &tag_names).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.tags.unwrap(), vec![tag3]);
+    }
+
+    #[test]
+    fn kv_storage_delete_tags_works_for_non_existing_type() {
+        _cleanup();
+
+        let storage = _storage();
+
+        let tag_name1 = vec![0, 0, 0];
+        let tag_name2 = vec![1, 1, 1];
+        let tag_name3 = vec![2, 2, 2];
+        let tag1 = Tag::Encrypted(tag_name1.clone(), vec![0, 0, 0]);
+        let tag2 = Tag::PlainText(tag_name2.clone(), "tag_value_2".to_string());
+        let tag3 = Tag::Encrypted(tag_name3.clone(), vec![2, 2, 2]);
+        let tags = vec![tag1.clone(), tag2.clone(), tag3.clone()];
+
+        storage.add(&_type1(), &_id1(), &_value1(), &tags).unwrap();
+
+        let tag_names = vec![TagName::OfEncrypted(tag_name1.clone()), TagName::OfPlain(tag_name2.clone())];
+        let res = storage.delete_tags(&_type2(), &_id1(), &tag_names);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_delete_tags_works_for_non_existing_id() {
+        _cleanup();
+
+        let storage = _storage();
+
+        let tag_name1 = vec![0, 0, 0];
+        let tag_name2 = vec![1, 1, 1];
+        let tag_name3 = vec![2, 2, 2];
+        let tag1 = Tag::Encrypted(tag_name1.clone(), vec![0, 0, 0]);
+        let tag2 = Tag::PlainText(tag_name2.clone(), "tag_value_2".to_string());
+        let tag3 = Tag::Encrypted(tag_name3.clone(), vec![2, 2, 2]);
+        let tags = vec![tag1.clone(), tag2.clone(), tag3.clone()];
+
+        storage.add(&_type1(), &_id1(), &_value1(), &tags).unwrap();
+
+        let tag_names = vec![TagName::OfEncrypted(tag_name1.clone()), TagName::OfPlain(tag_name2.clone())];
+        let res = storage.delete_tags(&_type1(), &_id2(), &tag_names);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_batch_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        storage.batch(&[
+            StorageOperation::Add(_type1(), _id2(), _value2(), _tags(), None),
+            StorageOperation::Update(_type1(), _id1(), _value2()),
+            StorageOperation::UpdateTags(_type1(), _id1(), _new_tags()),
+            StorageOperation::DeleteTags(_type1(), _id2(), vec![TagName::OfEncrypted(vec![1, 5, 8])]),
+        ]).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_new_tags()));
+
+        let record = storage.get(&_type1(), &_id2(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(record.tags.unwrap(), vec![Tag::PlainText(vec![1, 5, 8, 1], "Plain value".to_string())]);
+
+        storage.batch(&[StorageOperation::Delete(_type1(), _id1())]).unwrap();
+
+        let res = storage.get(&_type1(), &_id1(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_batch_works_for_rollback() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.batch(&[
+            StorageOperation::Add(_type1(), _id2(), _value2(), _tags(), None),
+            StorageOperation::Update(_type1(), _id1(), _value2()),
+            StorageOperation::AddTags(_type2(), _id1(), _new_tags()),
+        ]);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveType": false, "retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+
+        let res = storage.get(&_type1(), &_id2(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_batch_works_for_already_existing() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.batch(&[
+            StorageOperation::Add(_type1(), _id2(), _value2(), _tags(), None),
+            StorageOperation::Add(_type1(), _id1(), _value2(), _tags(), None),
+        ]);
+        assert_match!(Err(WalletStorageError::ItemAlreadyExists), res);
+
+        let res = storage.get(&_type1(), &_id2(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_version_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveVersion": true}"##).unwrap();
+        assert_eq!(Some(1), record.version);
+
+        storage.update(&_type1(), &_id1(), &_value2()).unwrap();
+        storage.add_tags(&_type1(), &_id1(), &_new_tags()).unwrap();
+        storage.delete_tags(&_type1(), &_id1(), &[TagName::OfEncrypted(vec![1, 5, 8])]).unwrap();
+        storage.update_tags(&_type1(), &_id1(), &_tags()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveVersion": true}"##).unwrap();
+        assert_eq!(Some(5), record.version);
+
+        let record = storage.get(&_type1(), &_id1(), r##"{}"##).unwrap();
+        assert_eq!(None, record.version);
+    }
+
+    #[test]
+    fn kv_storage_version_works_for_readd() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        storage.delete(&_type1(), &_id1()).unwrap();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let res = storage.batch(&[StorageOperation::CheckVersion(_type1(), _id1(), 1)]);
+        assert_match!(Err(WalletStorageError::ItemVersionMismatch), res);
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveVersion": true}"##).unwrap();
+        assert_eq!(Some(2), record.version);
+    }
+
+    #[test]
+    fn kv_storage_batch_works_for_check_version() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        storage.batch(&[
+            StorageOperation::CheckVersion(_type1(), _id1(), 1),
+            StorageOperation::Update(_type1(), _id1(), _value2()),
+        ]).unwrap();
+
+        let res = storage.batch(&[
+            StorageOperation::CheckVersion(_type1(), _id1(), 1),
+            StorageOperation::Update(_type1(), _id1(), _value1()),
+        ]);
+        assert_match!(Err(WalletStorageError::ItemVersionMismatch), res);
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveValue": true, "retrieveVersion": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(Some(2), record.version);
+
+        let res = storage.batch(&[StorageOperation::CheckVersion(_type1(), _id2(), 1)]);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_count_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id2(), &_value2(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id(3), &_value(3), &_new_tags()).unwrap();
+        storage.add(&_type2(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let count = storage.count(&_type1(), &language::Operator::And(vec![])).unwrap();
+        assert_eq!(3, count);
+
+        let query = language::Operator::Eq(language::TagName::EncryptedTagName(vec![1, 5, 8]), language::TargetValue::Encrypted(vec![3, 5, 6]));
+        let count = storage.count(&_type1(), &query).unwrap();
+        assert_eq!(2, count);
+    }
+
+    #[test]
+    fn kv_storage_count_by_tag_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id2(), &_value2(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id(3), &_value(3), &[Tag::PlainText(vec![1, 5, 8, 1], "Other value".to_string())]).unwrap();
+        storage.add(&_type1(), &_id(4), &_value(4), &_new_tags()).unwrap();
+
+        let counts = storage.count_by_tag(&_type1(), &language::Operator::And(vec![]), &[1, 5, 8, 1]).unwrap();
+        assert_eq!(vec![("Other value".to_string(), 1), ("Plain value".to_string(), 2)], counts);
+
+        let query = language::Operator::Eq(language::TagName::EncryptedTagName(vec![1, 5, 8]), language::TargetValue::Encrypted(vec![3, 5, 6]));
+        let counts = storage.count_by_tag(&_type1(), &query, &[1, 5, 8, 1]).unwrap();
+        assert_eq!(vec![("Plain value".to_string(), 2)], counts);
+
+        let counts = storage.count_by_tag(&_type2(), &language::Operator::And(vec![]), &[1, 5, 8, 1]).unwrap();
+        assert!(counts.is_empty());
+    }
+
+    #[test]
+    fn kv_storage_add_with_expiry_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add_with_expiry(&_type1(), &_id1(), &_value1(), &_tags(), 1).unwrap();
+        storage.add_with_expiry(&_type1(), &_id2(), &_value2(), &_tags(), u32::max_value() as u64).unwrap();
+
+        let res = storage.get(&_type1(), &_id1(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        let record = storage.get(&_type1(), &_id2(), r##"{"retrieveValue": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(Some(u32::max_value() as u64), record.expires_at);
+
+        let count = storage.count(&_type1(), &language::Operator::And(vec![])).unwrap();
+        assert_eq!(1, count);
+
+        let mut iterator = storage.search(&_type1(), &language::Operator::And(vec![]), None).unwrap();
+        assert_eq!(_id2(), iterator.next().unwrap().unwrap().id);
+        assert!(iterator.next().unwrap().is_none());
+
+        let mut iterator = storage.get_all().unwrap();
+        assert_eq!(_id2(), iterator.next().unwrap().unwrap().id);
+        assert!(iterator.next().unwrap().is_none());
+
+        let res = storage.update(&_type1(), &_id1(), &_value2());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        let res = storage.delete(&_type1(), &_id1());
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn kv_storage_add_with_expiry_works_for_max_time() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add_with_expiry(&_type1(), &_id1(), &_value1(), &_tags(), u64::max_value()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveValue": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+
+        assert_eq!(0, storage.purge_expired().unwrap());
+    }
+
+    #[test]
+    fn kv_storage_add_works_for_expired() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add_with_expiry(&_type1(), &_id1(), &_value1(), &_tags(), 1).unwrap();
+        storage.add(&_type1(), &_id1(), &_value2(), &_new_tags()).unwrap();
+
+        let record = storage.get(&_type1(), &_id1(), r##"{"retrieveValue": true, "retrieveTags": true}"##).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_new_tags()));
+        assert_eq!(None, record.expires_at);
+
+        let res = storage.add(&_type1(), &_id1(), &_value1(), &_tags());
+        assert_match!(Err(WalletStorageError::ItemAlreadyExists), res);
+
+        assert_eq!(0, storage.count_orphan_tags().unwrap());
+    }
+
+    #[test]
+    fn kv_storage_batch_works_for_expiry() {
+        _cleanup();
+
+        let storage = _storage();
+
+        storage.batch(&[
+            StorageOperation::Add(_type1(), _id1(), _value1(), _tags(), Some(1)),
+            StorageOperation::Add(_type1(), _id2(), _value2(), _tags(), Some(u32::max_value() as u64)),
+        ]).unwrap();
+
+        let res = storage.get(&_type1(), &_id1(), r##"{}"##);
+        assert_match!(Err(WalletStorageError::ItemNotFound), res);
+
+        let record = storage.get(&_type1(), &_id2(), r##"{}"##).unwrap();
+        assert_eq!(Some(u32::max_value() as u64), record.expires_at);
+
+        assert_eq!(1, storage.purge_expired().unwrap());
+    }
+
+    #[test]
+    fn kv_storage_purge_expired_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add_with_expiry(&_type1(), &_id1(), &_value1(), &_tags(), 1).unwrap();
+        storage.add_with_expiry(&_type2(), &_id1(), &_value1(), &_tags(), 1).unwrap();
+        storage.add_with_expiry(&_type1(), &_id2(), &_value2(), &_tags(), u32::max_value() as u64).unwrap();
+        storage.add(&_type2(), &_id2(), &_value2(), &_tags()).unwrap();
+
+        assert_eq!(2, storage.purge_expired().unwrap());
+        assert_eq!(0, storage.purge_expired().unwrap());
+        assert_eq!(0, storage.count_orphan_tags().unwrap());
+
+        storage.get(&_type1(), &_id2(), r##"{}"##).unwrap();
+        storage.get(&_type2(), &_id2(), r##"{}"##).unwrap();
+    }
+
+    #[test]
+    fn kv_storage_search_works() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+        storage.add(&_type1(), &_id2(), &_value2(), &_new_tags()).unwrap();
+        storage.add(&_type2(), &_id1(), &_value1(), &_tags()).unwrap();
+
+        let query = language::Operator::Or(vec![
+            language::Operator::Eq(language::TagName::EncryptedTagName(vec![1, 5, 8]), language::TargetValue::Encrypted(vec![3, 5, 6])),
+            language::Operator::Like(language::TagName::PlainTagName(vec![1, 1, 1]), language::TargetValue::Unencrypted("%value%".to_string())),
+        ]);
+
+        let mut iterator = storage.search(&_type1(), &query, Some(r##"{"retrieveTotalCount": true}"##)).unwrap();
+        assert_eq!(Some(2), iterator.get_total_count().unwrap());
+
+        let mut ids = Vec::new();
+        while let Some(record) = iterator.next().unwrap() {
+            ids.push(record.id);
+        }
+        assert_eq!(vec![_id1(), _id2()], ids);
+
+        let query = language::Operator::Not(Box::new(
+            language::Operator::Eq(language::TagName::PlainTagName(vec![1, 5, 8, 1]), language::TargetValue::Unencrypted("Plain value".to_string()))
+        ));
+
+        let mut iterator = storage.search(&_type1(), &query, None).unwrap();
+        assert_eq!(_id2(), iterator.next().unwrap().unwrap().id);
+        assert!(iterator.next().unwrap().is_none());
+    }
+
+    #[test]
+    fn kv_storage_search_works_for_sort_and_offset() {
+        _cleanup();
+
+        let storage = _storage();
+        storage.add(&_type1(), &_id1(), &_value1(), &[Tag::PlainText(vec![1, 5, 8, 1], "b".to_string())]).unwrap();
+        storage.add(&_type1(), &_id2(), &_value2(), &[Tag::PlainText(vec![1, 5, 8, 1], "a".to_string())]).unwrap();
+        storage.add(&_type1(), &_id(3), &_value(3), &[]).unwrap();
+
+        let sort_by = format!("~{}", base64::encode(&[1, 5, 8, 1]));
+
+        let options = json!({"sortBy": sort_by, "sortOrder": "desc", "offset": 1}).to_string();
+        let mut iterator = storage.search(&_type1(), &language::Operator::And(vec![]), Some(&options)).unwrap();
+
+        let mut ids = Vec::new();
+        while let Some(record) = iterator.next().unwrap() {
+            ids.push(record.id);
+        }
+        assert_eq!(vec![_id2(), _id(3)], ids);
+
+        let options = json!({"sortBy": base64::encode(&[1, 5, 8])}).to_string();
+        let res = storage.search(&_type1(), &language::Operator::And(vec![]), Some(&options));
+        assert_match!(Err(WalletStorageError::QueryError(_)), res);
+    }
+
+    #[test]
+    fn kv_storage_search_works_after_reopen() {
+        _cleanup();
+
+        {
+            let storage = _storage();
+            storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+            storage.add(&_type1(), &_id2(), &_value2(), &_tags()).unwrap();
+            storage.delete(&_type1(), &_id1()).unwrap();
+        }
+
+        let storage = KVStorageType::new().open_storage(_wallet_id(), None, None).unwrap();
+
+        let query = language::Operator::Eq(language::TagName::EncryptedTagName(vec![1, 5, 8]), language::TargetValue::Encrypted(vec![3, 5, 6]));
+        let mut iterator = storage.search(&_type1(), &query, None).unwrap();
+        assert_eq!(_id2(), iterator.next().unwrap().unwrap().id);
+        assert!(iterator.next().unwrap().is_none());
+    }
+
+    #[test]
+    fn kv_storage_orphan_tags_works() {
+        _cleanup();
+
+        let storage_type = KVStorageType::new();
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+
+        {
+            let storage = storage_type.open_storage(_wallet_id(), None, None).unwrap();
+            storage.add(&_type1(), &_id1(), &_value1(), &_tags()).unwrap();
+            storage.add(&_type1(), &_id(2), &_value(2), &_tags()).unwrap();
+            assert_eq!(0, storage.count_orphan_tags().unwrap());
+        }
+
+        {
+            let mut store = Store::open(&KVStorageType::_db_path(_wallet_id(), None)).unwrap();
+            store.write(vec![(_item_key(&_type1(), &_id1()), None)]).unwrap();
+        }
+
+        let storage = storage_type.open_storage(_wallet_id(), None, None).unwrap();
+        assert_eq!(2, storage.count_orphan_tags().unwrap());
+
+        assert_eq!(2, storage.delete_orphan_tags().unwrap());
+        assert_eq!(0, storage.count_orphan_tags().unwrap());
+
+        let record = storage.get(&_type1(), &_id(2), r##"{"retrieveTags": true}"##).unwrap();
+        assert_eq!(_sort(record.tags.unwrap()), _sort(_tags()));
+    }
+
+    fn _cleanup() {
+        test::cleanup_storage()
+    }
+
+    fn _wallet_id() -> &'static str {
+        "w1"
+    }
+
+    fn _storage() -> Box<WalletStorage> {
+        let storage_type = KVStorageType::new();
+        storage_type.create_storage(_wallet_id(), None, None, &_metadata()).unwrap();
+        storage_type.open_storage(_wallet_id(), None, None).unwrap()
+    }
+
+    fn _storage_custom() -> Box<WalletStorage> {
+        let storage_type = KVStorageType::new();
+
+        let config = json!({
+            "path": _custom_path()
+        }).to_string();
+
+        storage_type.create_storage(_wallet_id(), Some(&config), None, &_metadata()).unwrap();
+        storage_type.open_storage(_wallet_id(), Some(&config), None).unwrap()
+    }
+
+    fn _metadata() -> Vec<u8> {
+        return vec![
+            1, 2, 3, 4, 5, 6, 7, 8,
+            1, 2, 3, 4, 5, 6, 7, 8,
+            1, 2, 3, 4, 5, 6, 7, 8,
+            1, 2, 3, 4, 5, 6, 7, 8,
+            1, 2, 3, 4, 5, 6, 7, 8,
+            1, 2, 3, 4, 5, 6, 7, 8,
+            1, 2, 3, 4, 5, 6, 7, 8,
+            1, 2, 3, 4, 5, 6, 7, 8
+        ];
+    }
+
+    fn _type(i: u8) -> Vec<u8> {
+        vec![i, 1 + i, 2 + i]
+    }
+
+    fn _type1() -> Vec<u8> {
+        _type(1)
+    }
+
+    fn _type2() -> Vec<u8> {
+        _type(2)
+    }
+
+    fn _id(i: u8) -> Vec<u8> {
+        vec![3 + i, 4 + i, 5 + i]
+    }
+
+    fn _id1() -> Vec<u8> {
+        _id(1)
+    }
+
+    fn _id2() -> Vec<u8> {
+        _id(2)
+    }
+
+    fn _value(i: u8) -> EncryptedValue {
+        EncryptedValue { data: vec![6 + i, 7 + i, 8 + i], key: vec![9 + i, 10 + i, 11 + i] }
+    }
+
+    fn _value1() -> EncryptedValue {
+        _value(1)
+    }
+
+    fn _value2() -> EncryptedValue {
+        _value(2)
+    }
+
+    fn _tags() -> Vec<Tag> {
+        let mut tags: Vec<Tag> = Vec::new();
+        tags.push(Tag::Encrypted(vec![1, 5, 8], vec![3, 5, 6]));
+        tags.push(Tag::PlainText(vec![1, 5, 8, 1], "Plain value".to_string()));
+        tags
+    }
+
+    fn _new_tags() -> Vec<Tag> {
+        vec![
+            Tag::Encrypted(vec![1, 1, 1], vec![2, 2, 2]),
+            Tag::PlainText(vec![1, 1, 1], String::from("tag_value_3"))
+        ]
+    }
+
+    fn _sort(mut v: Vec<Tag>) -> Vec<Tag> {
+        v.sort();
+        v
+    }
+
+    fn _custom_path() -> String {
+        let mut path = environment::tmp_path();
+        path.push("custom_wallet_path");
+        path.to_str().unwrap().to_owned()
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeSet;
+use std::str;
+
+use errors::wallet::WalletQueryError;
+use services::wallet::language::{Operator, TagName, TargetValue, Number};
+
+use super::store::Store;
+use super::{_items_prefix, _tag_name_prefix, _tag_value_prefix, _read_part, _ENCRYPTED_TAG_PREFIX, _PLAIN_TAG_PREFIX};
+
+
+// Evaluates Wallet Query Language against tag indexes of the store
+// WQL input is provided as a reference to a top level Operator
+// Result is a set of ids of matching items ordered by id
+pub fn find_ids(store: &Store, type_: &[u8], op: &Operator) -> Result<BTreeSet<Vec<u8>>, WalletQueryError> {
+    match *op {
+        Operator::Eq(ref tag_name, ref target_value) => eq_ids(store, type_, tag_name, target_value),
+        Operator::Neq(ref tag_name, ref target_value) => neq_ids(store, type_, tag_name, target_value),
+        Operator::Gt(ref tag_name, ref target_value) => compare_ids(store, type_, tag_name, target_value, "$gt", &|ordering| ordering > 0),
+        Operator::Gte(ref tag_name, ref target_value) => compare_ids(store, type_, tag_name, target_value, "$gte", &|ordering| ordering >= 0),
+        Operator::Lt(ref tag_name, ref target_value) => compare_ids(store, type_, tag_name, target_value, "$lt", &|ordering| ordering < 0),
+        Operator::Lte(ref tag_name, ref target_value) => compare_ids(store, type_, tag_name, target_value, "$lte", &|ordering| ordering <= 0),
+        Operator::Like(ref tag_name, ref target_value) => like_ids(store, type_, tag_name, target_value),
+        Operator::In(ref tag_name, ref target_values) => in_ids(store, type_, tag_name, target_values),
+        Operator::And(ref suboperators) => and_ids(store, type_, suboperators),
+        Operator::Or(ref suboperators) => or_ids(store, type_, suboperators),
+        Operator::Not(ref suboperator) => not_ids(store, type_, suboperator),
+    }
+}
+
+
+pub fn all_ids(store: &Store, type_: &[u8]) -> BTreeSet<Vec<u8>> {
+    let prefix = _items_prefix(type_);
+
+    store.scan_prefix(&prefix)
+        .filter_map(|(key, _)| _read_part(&key[prefix.len()..]).map(|(id, _)| id.to_vec()))
+        .collect()
+}
+
+
+fn eq_ids(store: &Store, type_: &[u8], name: &TagName, value: &TargetValue) -> Result<BTreeSet<Vec<u8>>, WalletQueryError> {
+    match (name, value) {
+        (&TagName::PlainTagName(ref queried_name), &TargetValue::Unencrypted(ref queried_value)) =>
+            Ok(_value_ids(store, _PLAIN_TAG_PREFIX, type_, queried_name, queried_value.as_bytes())),
+        (&TagName::EncryptedTagName(ref queried_name), &TargetValue::Encrypted(ref queried_value)) =>
+            Ok(_value_ids(store, _ENCRYPTED_TAG_PREFIX, type_, queried_name, queried_value)),
+        _ => Err(WalletQueryError::StructureErr("Invalid combination of tag name and value for equality operator".to_string()))
+    }
+}
+
+
+fn neq_ids(store: &Store, type_: &[u8], name: &TagName, value: &TargetValue) -> Result<BTreeSet<Vec<u8>>, WalletQueryError> {
+    match (name, value) {
+        (&TagName::PlainTagName(ref queried_name), &TargetValue::Unencrypted(ref queried_value)) =>
+            Ok(_filter_ids(store, _PLAIN_TAG_PREFIX, type_, queried_name, &|value| value != queried_value.as_bytes())),
+        (&TagName::EncryptedTagName(ref queried_name), &TargetValue::Encrypted(ref queried_value)) =>
+            Ok(_filter_ids(store, _ENCRYPTED_TAG_PREFIX, type_, queried_name, &|value| value != &queried_value[..])),
+        _ => Err(WalletQueryError::StructureErr("Invalid combination of tag name and value for inequality operator".to_string()))
+    }
+}
+
+
+// Strings are compared bytewise as SQLite does, numbers are compared numerically
+fn compare_ids(store: &Store, type_: &[u8], name: &TagName, value: &TargetValue, operator_name: &str,
+               predicate: &Fn(i8) -> bool) -> Result<BTreeSet<Vec<u8>>, WalletQueryError> {
+    match (name, value) {
+        (&TagName::PlainTagName(ref queried_name), &TargetValue::Unencrypted(ref queried_value)) =>
+            Ok(_filter_ids(store, _PLAIN_TAG_PREFIX, type_, queried_name,
+                           &|value| predicate(_ordering_to_i8(value.cmp(queried_value.as_bytes()))))),
+        (&TagName::PlainTagName(ref queried_name), &TargetValue::Numeric(ref queried_value)) => {
+            let queried_value = Number::parse(queried_value)
+                .ok_or(WalletQueryError::ValueErr(format!("{} must be used with number", operator_name)))?;
+
+            Ok(_filter_ids(store, _PLAIN_TAG_PREFIX, type_, queried_name, &|value| {
+                match str::from_utf8(value).ok().and_then(Number::parse) {
+                    Some(value) => value.partial_cmp(&queried_value).map(_ordering_to_i8).map_or(false, |ordering| predicate(ordering)),
+                    None => false
+                }
+            }))
+        }
+        _ => Err(WalletQueryError::StructureErr(format!("Invalid combination of tag name and value for {} operator", operator_name)))
+    }
+}
+
+
+fn like_ids(store: &Store, type_: &[u8], name: &TagName, value: &TargetValue) -> Result<BTreeSet<Vec<u8>>, WalletQueryError> {
+    match (name, value) {
+        (&TagName::PlainTagName(ref queried_name), &TargetValue::Unencrypted(ref queried_value)) => {
+            let pattern: Vec<char> = queried_value.chars().collect();
+
+            Ok(_filter_ids(store, _PLAIN_TAG_PREFIX, type_, queried_name, &|value| {
+                match str::from_utf8(value) {
+                    Ok(value) => _like(&value.chars().collect::<Vec<char>>(), &pattern),
+                    Err(_) => false
+                }
+            }))
+        }
+        _ => Err(WalletQueryError::StructureErr("Invalid combination of tag name and value for $like operator".to_string()))
+    }
+}
+
+
+fn in_ids(store: &Store, type_: &[u8], name: &TagName, values: &Vec<TargetValue>) -> Result<BTreeSet<Vec<u8>>, WalletQueryError> {
+    let mut ids = BTreeSet::new();
+
+    for value in values {
+        match (name, value) {
+            (&TagName::PlainTagName(_), &TargetValue::Unencrypted(_)) | (&TagName::EncryptedTagName(_), &TargetValue::Encrypted(_)) =>
+                ids.extend(eq_ids(store, type_, name, value)?),
+            (&TagName::PlainTagName(_), _) =>
+                return Err(WalletQueryError::StructureErr("Encrypted tag value in $in for nonencrypted tag name".to_string())),
+            (&TagName::EncryptedTagName(_), _) =>
+                return Err(WalletQueryError::StructureErr("Unencrypted tag value in $in for encrypted tag name".to_string())),
+        }
+    }
+
+    Ok(ids)
+}
+
+
+fn and_ids(store: &Store, type_: &[u8], suboperators: &[Operator]) -> Result<BTreeSet<Vec<u8>>, WalletQueryError> {
+    let mut ids: Option<BTreeSet<Vec<u8>>> = None;
+
+    for suboperator in suboperators {
+        let subids = find_ids(store, type_, suboperator)?;
+
+        ids = Some(match ids {
+            Some(ids) => ids.intersection(&subids).cloned().collect(),
+            None => subids
+        });
+    }
+
+    Ok(ids.unwrap_or_else(|| all_ids(store, type_)))
+}
+
+
+// Empty $or doesn't restrict records as in the default storage
+fn or_ids(store: &Store, type_: &[u8], suboperators: &[Operator]) -> Result<BTreeSet<Vec<u8>>, WalletQueryError> {
+    if suboperators.is_empty() {
+        return Ok(all_ids(store, type_));
+    }
+
+    let mut ids = BTreeSet::new();
+
+    for suboperator in suboperators {
+        ids.extend(find_ids(store, type_, suboperator)?);
+    }
+
+    Ok(ids)
+}
+
+
+fn not_ids(store: &Store, type_: &[u8], suboperator: &Operator) -> Result<BTreeSet<Vec<u8>>, WalletQueryError> {
+    let subids = find_ids(store, type_, suboperator)?;
+
+    Ok(all_ids(store, type_).difference(&subids).cloned().collect())
+}
+
+
+fn _value_ids(store: &Store, tag_prefix: u8, type_: &[u8], name: &[u8], value: &[u8]) -> BTreeSet<Vec<u8>> {
+    let prefix = _tag_value_prefix(tag_prefix, type_, name, value);
+
+    store.scan_prefix(&prefix)
+        .filter_map(|(key, _)| _read_part(&key[prefix.len()..]).map(|(id, _)| id.to_vec()))
+        .collect()
+}
+
+
+// Scans all values of the tag and returns ids of items which tag value satisfies predicate
+fn _filter_ids(store: &Store, tag_prefix: u8, type_: &[u8], name: &[u8], predicate: &Fn(&[u8]) -> bool) -> BTreeSet<Vec<u8>> {
+    let prefix = _tag_name_prefix(tag_prefix, type_, name);
+
+    store.scan_prefix(&prefix)
+        .filter_map(|(key, _)| {
+            let (value, rest) = _read_part(&key[prefix.len()..])?;
+            let (id, _) = _read_part(rest)?;
+
+            if predicate(value) { Some(id.to_vec()) } else { None }
+        })
+        .collect()
+}
+
+
+fn _ordering_to_i8(ordering: ::std::cmp::Ordering) -> i8 {
+    ordering as i8
+}
+
+
+// SQL LIKE: "%" matches any sequence, "_" matches single character, ASCII letters are case insensitive
+//
+// Greedy two-pointer matching: on mismatch returns to the last "%" and lets it consume one more character.
+// Earlier "%" never needs to be revisited, so it takes O(value * pattern) steps at worst.
+fn _like(value: &[char], pattern: &[char]) -> bool {
+    let (mut v, mut p) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while v < value.len() {
+        match pattern.get(p) {
+            Some(&'%') => {
+                p += 1;
+                backtrack = Some((v, p));
+                continue;
+            }
+            Some(&'_') => {
+                v += 1;
+                p += 1;
+                continue;
+            }
+            Some(c) if value[v].eq_ignore_ascii_case(c) => {
+                v += 1;
+                p += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match backtrack {
+            Some((percent_v, percent_p)) => {
+                v = percent_v + 1;
+                p = percent_p;
+                backtrack = Some((v, p));
+            }
+            None => return false
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '%')
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn _chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn like_works() {
+        assert!(_like(&_chars("prefix_str2"), &_chars("%str%")));
+        assert!(_like(&_chars("STR1"), &_chars("str_")));
+        assert!(_like(&_chars(""), &_chars("%")));
+        assert!(!_like(&_chars("str"), &_chars("str_")));
+        assert!(!_like(&_chars("astr"), &_chars("str%")));
+        assert!(_like(&_chars("abcbcd"), &_chars("a%bcd")));
+        assert!(_like(&_chars("abc"), &_chars("%%a%_c%")));
+        assert!(!_like(&_chars("abc"), &_chars("a%b")));
+    }
+
+    #[test]
+    fn like_works_for_many_wildcards() {
+        let value = _chars(&"a".repeat(1000));
+        let pattern = _chars(&format!("{}b", "%a".repeat(50)));
+
+        assert!(!_like(&value, &pattern));
+    }
+}
@@ -0,0 +1,491 @@
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use rmp_serde;
+
+use std::collections::BTreeMap;
+use std::collections::btree_map;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use errors::common::CommonError;
+use errors::wallet::WalletStorageError;
+
+// Changes written to the log at once: key with new value or None for removed key
+pub type Changes = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+// Log is rewritten on open if it contains more than this number of stale entries
+const _COMPACTION_THRESHOLD: usize = 1000;
+
+// Log is rewritten on write once it grows to twice its size after the last compaction,
+// but not before it reaches this size
+const _COMPACTION_MIN_LEN: u64 = 1024 * 1024;
+
+// Embedded file based key-value store.
+//
+// All pairs are kept in memory as ordered map. Each write appends a single frame
+// to the log file: frame length as u32 (big endian) followed by msgpack serialized changes.
+// Frame is written and synced before changes become visible, so write is either applied
+// completely or not applied at all. Incomplete tail frame left by crash is dropped on open.
+// Store created without file keeps pairs in memory only.
+//
+// Store holds exclusive lock on the sibling lock file while it is alive, so the same log
+// can't be opened twice. Lock file is used instead of the log itself as compaction replaces the log.
+pub struct Store {
+    file: Option<File>,
+    path: Option<PathBuf>,
+    _lock: Option<File>,
+    len: u64,
+    compaction_len: u64,
+    data: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Store {
+    pub fn create(path: &Path, changes: Changes) -> Result<Store, WalletStorageError> {
+        let lock = _lock(path)?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create_new(true)
+            .open(path)?;
+
+        let mut store = Store {
+            file: Some(file),
+            path: Some(path.to_path_buf()),
+            _lock: Some(lock),
+            len: 0,
+            compaction_len: _COMPACTION_MIN_LEN,
+            data: BTreeMap::new(),
+        };
+
+        store.write(changes)?;
+
+        Ok(store)
+    }
+
+    pub fn open(path: &Path) -> Result<Store, WalletStorageError> {
+        let lock = _lock(path)?;
+
+        let mut data = BTreeMap::new();
+        let mut entries: usize = 0;
+
+        {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .append(true)
+                .open(path)?;
+
+            let mut len: u64 = 0;
+
+            {
+                let mut reader = BufReader::new(&mut file);
+
+                while let Some(frame) = _read_frame(&mut reader)? {
+                    let changes: Changes = rmp_serde::from_slice(&frame)
+                        .map_err(|err| WalletStorageError::IOError(format!("Key-value storage log is corrupted: {:?}", err)))?;
+
+                    len += 4 + frame.len() as u64;
+                    entries += changes.len();
+                    _apply(&mut data, changes);
+                }
+            }
+
+            // drop incomplete frame left by interrupted write
+            if file.metadata()?.len() != len {
+                warn!("Dropping incomplete tail of key-value storage log: {:?}", path);
+                file.set_len(len)?;
+                file.sync_all()?;
+            }
+        }
+
+        if entries > data.len() + _COMPACTION_THRESHOLD {
+            _compact(path, &data)?;
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(path)?;
+
+        let len = file.metadata()?.len();
+
+        Ok(Store {
+            file: Some(file),
+            path: Some(path.to_path_buf()),
+            _lock: Some(lock),
+            len,
+            compaction_len: _compaction_len(len),
+            data,
+        })
+    }
+
+    pub fn in_memory(changes: Changes) -> Store {
+        let mut data = BTreeMap::new();
+        _apply(&mut data, changes);
+
+        Store {
+            file: None,
+            path: None,
+            _lock: None,
+            len: 0,
+            compaction_len: 0,
+            data,
+        }
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<&Vec<u8>> {
+        self.data.get(key)
+    }
+
+    // Iterates over pairs which keys start with prefix in keys order
+    pub fn scan_prefix<'a>(&'a self, prefix: &'a [u8]) -> PrefixIter<'a> {
+        PrefixIter {
+            range: self.data.range(prefix.to_vec()..),
+            prefix,
+        }
+    }
+
+    pub fn write(&mut self, changes: Changes) -> Result<(), WalletStorageError> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(ref mut file) = self.file {
+            let frame = rmp_serde::to_vec(&changes)
+                .map_err(|err| CommonError::InvalidState(format!("Can't serialize key-value storage changes: {:?}", err)))?;
+
+            let res = _write_frame(file, &frame)
+                .and_then(|_| file.sync_data());
+
+            if let Err(err) = res {
+                // cut partially written frame
+                file.set_len(self.len)?;
+                return Err(WalletStorageError::from(err));
+            }
+
+            self.len += 4 + frame.len() as u64;
+        }
+
+        _apply(&mut self.data, changes);
+
+        if self.file.is_some() && self.len >= self.compaction_len {
+            self._compact()?;
+        }
+
+        Ok(())
+    }
+
+    // Rewrites log of the opened store and continues appending to the rewritten one
+    fn _compact(&mut self) -> Result<(), WalletStorageError> {
+        let path = match self.path {
+            Some(ref path) => path.clone(),
+            None => return Ok(())
+        };
+
+        _compact(&path, &self.data)?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&path)?;
+
+        self.len = file.metadata()?.len();
+        self.compaction_len = _compaction_len(self.len);
+        self.file = Some(file);
+
+        Ok(())
+    }
+}
+
+pub struct PrefixIter<'a> {
+    range: btree_map::Range<'a, Vec<u8>, Vec<u8>>,
+    prefix: &'a [u8],
+}
+
+impl<'a> Iterator for PrefixIter<'a> {
+    type Item = (&'a Vec<u8>, &'a Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.range.next() {
+            Some((key, value)) if key.starts_with(self.prefix) => Some((key, value)),
+            _ => None
+        }
+    }
+}
+
+fn _compaction_len(len: u64) -> u64 {
+    ::std::cmp::max(_COMPACTION_MIN_LEN, 2 * len)
+}
+
+fn _lock_path(path: &Path) -> PathBuf {
+    let mut lock_path = path.to_path_buf();
+    lock_path.set_extension("lock");
+    lock_path
+}
+
+// Opens lock file and takes exclusive lock on it without waiting
+#[cfg(unix)]
+fn _lock(path: &Path) -> Result<File, WalletStorageError> {
+    use libc;
+    use std::os::unix::io::AsRawFd;
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .open(_lock_path(path))?;
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let err = io::Error::last_os_error();
+
+        return Err(match err.kind() {
+            io::ErrorKind::WouldBlock => WalletStorageError::IOError(format!("Key-value storage is already opened: {:?}", path)),
+            _ => WalletStorageError::from(err)
+        });
+    }
+
+    Ok(file)
+}
+
+// Opens lock file without sharing, so other handles can't be opened until it is closed
+#[cfg(windows)]
+fn _lock(path: &Path) -> Result<File, WalletStorageError> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    // ERROR_SHARING_VIOLATION
+    const SHARING_VIOLATION: i32 = 32;
+
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .share_mode(0)
+        .open(_lock_path(path))
+        .map_err(|err| match err.raw_os_error() {
+            Some(SHARING_VIOLATION) => WalletStorageError::IOError(format!("Key-value storage is already opened: {:?}", path)),
+            _ => WalletStorageError::from(err)
+        })
+}
+
+// Rewrites log as single frame with actual pairs only
+fn _compact(path: &Path, data: &BTreeMap<Vec<u8>, Vec<u8>>) -> Result<(), WalletStorageError> {
+    let changes: Changes = data.iter()
+        .map(|(key, value)| (key.clone(), Some(value.clone())))
+        .collect();
+
+    let frame = rmp_serde::to_vec(&changes)
+        .map_err(|err| CommonError::InvalidState(format!("Can't serialize key-value storage changes: {:?}", err)))?;
+
+    let mut tmp_path = path.to_path_buf();
+    tmp_path.set_extension("tmp");
+
+    {
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        _write_frame(&mut writer, &frame)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+    }
+
+    fs::rename(&tmp_path, path)?;
+    _sync_dir(path)?;
+
+    Ok(())
+}
+
+// Rename survives power loss only after directory entry is synced too
+#[cfg(unix)]
+fn _sync_dir(path: &Path) -> Result<(), WalletStorageError> {
+    let dir = path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+// Directories can't be opened as files on Windows, NTFS journals renames itself
+#[cfg(windows)]
+fn _sync_dir(_path: &Path) -> Result<(), WalletStorageError> {
+    Ok(())
+}
+
+fn _apply(data: &mut BTreeMap<Vec<u8>, Vec<u8>>, changes: Changes) {
+    for (key, value) in changes {
+        match value {
+            Some(value) => data.insert(key, value),
+            None => data.remove(&key)
+        };
+    }
+}
+
+fn _write_frame<W: Write>(writer: &mut W, frame: &[u8]) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(4 + frame.len());
+    buf.write_u32::<BigEndian>(frame.len() as u32)?;
+    buf.extend_from_slice(frame);
+    writer.write_all(&buf)
+}
+
+// Returns None at the end of log or if the last frame is incomplete
+fn _read_frame<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>, WalletStorageError> {
+    let frame_len = match reader.read_u32::<BigEndian>() {
+        Ok(frame_len) => frame_len as usize,
+        Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(WalletStorageError::from(err))
+    };
+
+    let mut frame = vec![0u8; frame_len];
+
+    match reader.read_exact(&mut frame) {
+        Ok(()) => Ok(Some(frame)),
+        Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(err) => Err(WalletStorageError::from(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    use utils::environment;
+
+    #[test]
+    fn store_write_and_reopen_works() {
+        let path = _path("store_write_and_reopen_works");
+
+        {
+            let mut store = Store::create(&path, vec![(b"k1".to_vec(), Some(b"v1".to_vec()))]).unwrap();
+            store.write(vec![(b"k2".to_vec(), Some(b"v2".to_vec())), (b"k1".to_vec(), None)]).unwrap();
+
+            assert!(store.get(b"k1").is_none());
+            assert_eq!(&b"v2".to_vec(), store.get(b"k2").unwrap());
+        }
+
+        let store = Store::open(&path).unwrap();
+        assert!(store.get(b"k1").is_none());
+        assert_eq!(&b"v2".to_vec(), store.get(b"k2").unwrap());
+    }
+
+    #[test]
+    fn store_open_works_for_incomplete_frame() {
+        let path = _path("store_open_works_for_incomplete_frame");
+
+        {
+            Store::create(&path, vec![(b"k1".to_vec(), Some(b"v1".to_vec()))]).unwrap();
+        }
+
+        {
+            let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+            file.write_u32::<BigEndian>(100).unwrap();
+            file.write_all(b"partial").unwrap();
+        }
+
+        let mut store = Store::open(&path).unwrap();
+        assert_eq!(&b"v1".to_vec(), store.get(b"k1").unwrap());
+
+        store.write(vec![(b"k2".to_vec(), Some(b"v2".to_vec()))]).unwrap();
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(&b"v1".to_vec(), store.get(b"k1").unwrap());
+        assert_eq!(&b"v2".to_vec(), store.get(b"k2").unwrap());
+    }
+
+    #[test]
+    fn store_scan_prefix_works() {
+        let path = _path("store_scan_prefix_works");
+
+        let store = Store::create(&path, vec![
+            (b"a1".to_vec(), Some(b"1".to_vec())),
+            (b"b1".to_vec(), Some(b"2".to_vec())),
+            (b"b2".to_vec(), Some(b"3".to_vec())),
+            (b"c1".to_vec(), Some(b"4".to_vec())),
+        ]).unwrap();
+
+        let keys: Vec<Vec<u8>> = store.scan_prefix(b"b").map(|(key, _)| key.clone()).collect();
+        assert_eq!(vec![b"b1".to_vec(), b"b2".to_vec()], keys);
+
+        assert_eq!(0, store.scan_prefix(b"d").count());
+    }
+
+    #[test]
+    fn store_compaction_works() {
+        let path = _path("store_compaction_works");
+
+        {
+            let mut store = Store::create(&path, vec![(b"k".to_vec(), Some(b"v".to_vec()))]).unwrap();
+
+            for i in 0..(_COMPACTION_THRESHOLD + 1) {
+                store.write(vec![(b"tmp".to_vec(), Some(vec![(i % 256) as u8]))]).unwrap();
+            }
+            store.write(vec![(b"tmp".to_vec(), None)]).unwrap();
+        }
+
+        let len_before = fs::metadata(&path).unwrap().len();
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(&b"v".to_vec(), store.get(b"k").unwrap());
+        assert!(store.get(b"tmp").is_none());
+
+        assert!(fs::metadata(&path).unwrap().len() < len_before);
+    }
+
+    #[test]
+    fn store_compaction_works_on_write() {
+        let path = _path("store_compaction_works_on_write");
+
+        let mut store = Store::create(&path, vec![(b"k".to_vec(), Some(b"v".to_vec()))]).unwrap();
+
+        let value = vec![0u8; 64 * 1024];
+        let mut written: u64 = 0;
+
+        while written < 2 * _COMPACTION_MIN_LEN {
+            store.write(vec![(b"tmp".to_vec(), Some(value.clone()))]).unwrap();
+            written += value.len() as u64;
+        }
+
+        assert!(fs::metadata(&path).unwrap().len() < _COMPACTION_MIN_LEN);
+
+        store.write(vec![(b"k2".to_vec(), Some(b"v2".to_vec()))]).unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(&b"v".to_vec(), store.get(b"k").unwrap());
+        assert_eq!(&b"v2".to_vec(), store.get(b"k2").unwrap());
+        assert_eq!(&value, store.get(b"tmp").unwrap());
+    }
+
+    #[test]
+    fn store_open_works_for_already_opened() {
+        let path = _path("store_open_works_for_already_opened");
+
+        let _store = Store::create(&path, vec![(b"k".to_vec(), Some(b"v".to_vec()))]).unwrap();
+
+        let res = Store::open(&path);
+        assert_match!(Err(WalletStorageError::IOError(_)), res);
+    }
+
+    #[test]
+    fn store_open_works_after_close() {
+        let path = _path("store_open_works_after_close");
+
+        {
+            Store::create(&path, vec![(b"k".to_vec(), Some(b"v".to_vec()))]).unwrap();
+        }
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(&b"v".to_vec(), store.get(b"k").unwrap());
+    }
+
+    fn _path(name: &str) -> PathBuf {
+        let mut path = environment::tmp_path();
+        fs::create_dir_all(&path).unwrap();
+        path.push(name);
+
+        if path.exists() {
+            fs::remove_file(&path).unwrap();
+        }
+
+        path
+    }
+}
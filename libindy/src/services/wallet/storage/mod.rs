@@ -1,6 +1,9 @@
 pub mod default;
+pub mod inmem;
+pub mod kv;
 pub mod plugged;
 
+use api::wallet::{WalletBatch, WalletGetRecordVersion};
 use errors::wallet::WalletStorageError;
 use services::wallet::language;
 use services::wallet::wallet::EncryptedValue;
@@ -23,19 +26,37 @@ pub struct StorageRecord {
     pub value: Option<EncryptedValue>,
     pub type_: Option<Vec<u8>>,
     pub tags: Option<Vec<Tag>>,
+    pub version: Option<u64>,
+    // Unix time in seconds, reported by get only so wallet cache can drop expired records
+    pub expires_at: Option<u64>,
 }
 
 impl StorageRecord {
-    fn new(id: Vec<u8>, value: Option<EncryptedValue>, type_: Option<Vec<u8>>, tags: Option<Vec<Tag>>) -> Self {
+    fn new(id: Vec<u8>, value: Option<EncryptedValue>, type_: Option<Vec<u8>>, tags: Option<Vec<Tag>>, version: Option<u64>, expires_at: Option<u64>) -> Self {
         Self {
             id,
             value,
             type_,
             tags,
+            version,
+            expires_at,
         }
     }
 }
 
+#[derive(Debug)]
+pub enum StorageOperation {
+    // Optional unix time in seconds the record expires at
+    Add(Vec<u8>, Vec<u8>, EncryptedValue, Vec<Tag>, Option<u64>),
+    Update(Vec<u8>, Vec<u8>, EncryptedValue),
+    AddTags(Vec<u8>, Vec<u8>, Vec<Tag>),
+    UpdateTags(Vec<u8>, Vec<u8>, Vec<Tag>),
+    DeleteTags(Vec<u8>, Vec<u8>, Vec<TagName>),
+    Delete(Vec<u8>, Vec<u8>),
+    // Fails with ItemVersionMismatch if record version differs, so following operations apply only to the expected record state
+    CheckVersion(Vec<u8>, Vec<u8>, u64),
+}
+
 pub trait StorageIterator {
     fn next(&mut self) -> Result<Option<StorageRecord>, WalletStorageError>;
     fn get_total_count(&self) -> Result<Option<usize>, WalletStorageError>;
@@ -49,15 +70,55 @@ pub trait WalletStorage {
     fn update_tags(&self, type_: &[u8], id: &[u8], tags: &[Tag]) -> Result<(), WalletStorageError>;
     fn delete_tags(&self, type_: &[u8], id: &[u8], tag_names: &[TagName]) -> Result<(), WalletStorageError>;
     fn delete(&self, type_: &[u8], id: &[u8]) -> Result<(), WalletStorageError>;
+    fn batch(&self, operations: &[StorageOperation]) -> Result<(), WalletStorageError>;
     fn get_storage_metadata(&self) -> Result<Vec<u8>, WalletStorageError>;
     fn set_storage_metadata(&self, metadata: &[u8]) -> Result<(), WalletStorageError>;
     fn get_all(&self) -> Result<Box<StorageIterator>, WalletStorageError>;
     fn search(&self, type_: &[u8], query: &language::Operator, options: Option<&str>) -> Result<Box<StorageIterator>, WalletStorageError>;
+    fn count(&self, type_: &[u8], query: &language::Operator) -> Result<usize, WalletStorageError>;
+    // Counts matching records per distinct value of plaintext tag, records without this tag are skipped
+    fn count_by_tag(&self, type_: &[u8], query: &language::Operator, tag_name: &[u8]) -> Result<Vec<(String, usize)>, WalletStorageError>;
     fn close(&mut self) -> Result<(), WalletStorageError>;
+
+    // Plugged storages apply operations atomically only if batch handler is registered
+    fn supports_batch(&self) -> bool {
+        true
+    }
+
+    // Expired records are hidden from reads until they are purged
+    fn add_with_expiry(&self, _type_: &[u8], _id: &[u8], _value: &EncryptedValue, _tags: &[Tag], _expires_at: u64) -> Result<(), WalletStorageError> {
+        Err(WalletStorageError::UnsupportedOperation("Storage doesn't support record expiry".to_string()))
+    }
+
+    // Returns count of deleted records
+    fn purge_expired(&self) -> Result<usize, WalletStorageError> {
+        Err(WalletStorageError::UnsupportedOperation("Storage doesn't support record expiry".to_string()))
+    }
+
+    // Orphan tags are tags left without their records. Plugged storages return tags
+    // as a part of records, so orphan tags can't be found there
+    fn count_orphan_tags(&self) -> Result<usize, WalletStorageError> {
+        Ok(0)
+    }
+
+    // Returns count of deleted tags
+    fn delete_orphan_tags(&self) -> Result<usize, WalletStorageError> {
+        Ok(0)
+    }
 }
 
 pub trait WalletStorageType {
     fn create_storage(&self, id: &str, config: Option<&str>, credentials: Option<&str>, metadata: &[u8]) -> Result<(), WalletStorageError>;
     fn open_storage(&self, id: &str, config: Option<&str>, credentials: Option<&str>) -> Result<Box<WalletStorage>, WalletStorageError>;
     fn delete_storage(&self, id: &str, config: Option<&str>, credentials: Option<&str>) -> Result<(), WalletStorageError>;
+
+    // Only plugged storages need external handler to apply operations atomically
+    fn set_batch_handler(&self, _batch: WalletBatch) -> Result<(), WalletStorageError> {
+        Err(WalletStorageError::UnsupportedOperation("Storage type doesn't support custom batch handler".to_string()))
+    }
+
+    // Record versions of plugged storages are optional and read with external handler
+    fn set_version_handler(&self, _get_record_version: WalletGetRecordVersion) -> Result<(), WalletStorageError> {
+        Err(WalletStorageError::UnsupportedOperation("Storage type doesn't support custom version handler".to_string()))
+    }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use super::{StorageIterator, WalletStorageType, WalletStorage, StorageRecord, EncryptedValue, Tag, TagName};
+use super::{StorageIterator, WalletStorageType, WalletStorage, StorageRecord, StorageOperation, EncryptedValue, Tag, TagName};
 use super::super::{RecordOptions, SearchOptions};
 
 use api::wallet::*;
@@ -9,10 +9,11 @@ use utils::crypto::base64;
 use services::wallet::language;
 
 use serde_json;
+use std::cell::Cell;
 use std::ffi::{CString, CStr};
 use std::ptr;
 use std::{slice, str};
-use std::collections::HashMap;
+use std::collections::{HashMap, BTreeMap};
 use libc::c_char;
 
 #[derive(Debug, Deserialize)]
@@ -65,6 +66,7 @@ struct PluggedStorageIterator {
     get_record_id_handler: WalletGetRecordId,
     get_record_value_handler: WalletGetRecordValue,
     get_record_tags_handler: WalletGetRecordTags,
+    get_record_version_handler: Option<WalletGetRecordVersion>,
     free_record_handler: WalletFreeRecord,
     free_search_handler: WalletFreeSearch,
 }
@@ -81,6 +83,7 @@ impl PluggedStorageIterator {
             get_record_id_handler: storage.get_record_id_handler,
             get_record_value_handler: storage.get_record_value_handler,
             get_record_tags_handler: storage.get_record_tags_handler,
+            get_record_version_handler: storage.get_record_version_handler,
             free_record_handler: storage.free_record_handler,
             free_search_handler: storage.free_search_handler,
         }
@@ -159,11 +162,17 @@ impl StorageIterator for PluggedStorageIterator {
             Some(_tags_from_json(tags_json)?)
         } else { None };
 
+        let version = if self.options.retrieve_version {
+            _get_record_version(self.get_record_version_handler, self.storage_handle, record_handle)?
+        } else { None };
+
         Ok(Some(StorageRecord {
             type_: type_,
             id: id,
             value: value,
             tags: tags,
+            version: version,
+            expires_at: None,
         }))
     }
 
@@ -215,7 +224,9 @@ struct PluggedStorage {
     get_search_total_count_handler: WalletGetSearchTotalCount,
     fetch_search_next_record_handler: WalletFetchSearchNextRecord,
     free_search_handler: WalletFreeSearch,
-    close_handler: WalletClose
+    close_handler: WalletClose,
+    batch_handler: Option<WalletBatch>,
+    get_record_version_handler: Option<WalletGetRecordVersion>
 }
 
 impl PluggedStorage {
@@ -240,7 +251,9 @@ impl PluggedStorage {
            get_search_total_count_handler: WalletGetSearchTotalCount,
            fetch_search_next_record_handler: WalletFetchSearchNextRecord,
            free_search_handler: WalletFreeSearch,
-           close_handler: WalletClose) -> PluggedStorage {
+           close_handler: WalletClose,
+           batch_handler: Option<WalletBatch>,
+           get_record_version_handler: Option<WalletGetRecordVersion>) -> PluggedStorage {
         PluggedStorage {
             handle,
             add_record_handler,
@@ -264,11 +277,35 @@ impl PluggedStorage {
             fetch_search_next_record_handler,
             free_search_handler,
             close_handler,
+            batch_handler,
+            get_record_version_handler,
         }
     }
 }
 
-fn _tags_to_json(tags: &[Tag]) -> Result<String, WalletStorageError> {
+// Storages without version handler return records without version
+fn _get_record_version(get_record_version_handler: Option<WalletGetRecordVersion>,
+                       storage_handle: i32,
+                       record_handle: i32) -> Result<Option<u64>, WalletStorageError> {
+    let get_record_version_handler = match get_record_version_handler {
+        Some(handler) => handler,
+        None => return Ok(None)
+    };
+
+    let mut version: u64 = 0;
+
+    let err = (get_record_version_handler)(storage_handle,
+                                           record_handle,
+                                           &mut version);
+
+    if err != ErrorCode::Success {
+        return Err(WalletStorageError::PluggedStorageError(err));
+    }
+
+    Ok(Some(version))
+}
+
+fn _tags_to_map(tags: &[Tag]) -> HashMap<String, String> {
     let mut string_tags = HashMap::new();
     for tag in tags {
         match tag {
@@ -276,7 +313,11 @@ fn _tags_to_json(tags: &[Tag]) -> Result<String, WalletStorageError> {
             &Tag::PlainText(ref name, ref value) => string_tags.insert(format!("~{}", &base64::encode(&name)), value.to_string()),
         };
     }
-    serde_json::to_string(&string_tags).map_err(|err| WalletStorageError::IOError(err.to_string()))
+    string_tags
+}
+
+fn _tags_to_json(tags: &[Tag]) -> Result<String, WalletStorageError> {
+    serde_json::to_string(&_tags_to_map(tags)).map_err(|err| WalletStorageError::IOError(err.to_string()))
 }
 
 fn _tags_from_json(json: &str) -> Result<Vec<Tag>, WalletStorageError> {
@@ -305,7 +346,7 @@ fn _tags_from_json(json: &str) -> Result<Vec<Tag>, WalletStorageError> {
     Ok(tags)
 }
 
-fn _tags_names_to_json(tag_names: &[TagName]) -> Result<String, WalletStorageError> {
+fn _tags_names_to_vec(tag_names: &[TagName]) -> Vec<String> {
     let mut tags: Vec<String> = Vec::new();
 
     for tag_name in tag_names {
@@ -316,7 +357,63 @@ fn _tags_names_to_json(tag_names: &[TagName]) -> Result<String, WalletStorageErr
             }
         )
     }
-    serde_json::to_string(&tags).map_err(|err| WalletStorageError::IOError(err.to_string()))
+    tags
+}
+
+fn _tags_names_to_json(tag_names: &[TagName]) -> Result<String, WalletStorageError> {
+    serde_json::to_string(&_tags_names_to_vec(tag_names)).map_err(|err| WalletStorageError::IOError(err.to_string()))
+}
+
+fn _operations_to_json(operations: &[StorageOperation]) -> Result<String, WalletStorageError> {
+    let operations: Vec<serde_json::Value> = operations
+        .iter()
+        .map(|operation| match operation {
+            &StorageOperation::Add(ref type_, ref id, ref value, ref tags, _) => json!({
+                "op": "add",
+                "type": base64::encode(type_),
+                "id": base64::encode(id),
+                "value": base64::encode(&value.to_bytes()),
+                "tags": _tags_to_map(tags)
+            }),
+            &StorageOperation::Update(ref type_, ref id, ref value) => json!({
+                "op": "update",
+                "type": base64::encode(type_),
+                "id": base64::encode(id),
+                "value": base64::encode(&value.to_bytes())
+            }),
+            &StorageOperation::AddTags(ref type_, ref id, ref tags) => json!({
+                "op": "add_tags",
+                "type": base64::encode(type_),
+                "id": base64::encode(id),
+                "tags": _tags_to_map(tags)
+            }),
+            &StorageOperation::UpdateTags(ref type_, ref id, ref tags) => json!({
+                "op": "update_tags",
+                "type": base64::encode(type_),
+                "id": base64::encode(id),
+                "tags": _tags_to_map(tags)
+            }),
+            &StorageOperation::DeleteTags(ref type_, ref id, ref tag_names) => json!({
+                "op": "delete_tags",
+                "type": base64::encode(type_),
+                "id": base64::encode(id),
+                "tag_names": _tags_names_to_vec(tag_names)
+            }),
+            &StorageOperation::Delete(ref type_, ref id) => json!({
+                "op": "delete",
+                "type": base64::encode(type_),
+                "id": base64::encode(id)
+            }),
+            &StorageOperation::CheckVersion(ref type_, ref id, version) => json!({
+                "op": "check_version",
+                "type": base64::encode(type_),
+                "id": base64::encode(id),
+                "version": version
+            }),
+        })
+        .collect();
+
+    serde_json::to_string(&operations).map_err(|err| WalletStorageError::IOError(err.to_string()))
 }
 
 impl WalletStorage for PluggedStorage {
@@ -377,11 +474,17 @@ impl WalletStorage for PluggedStorage {
             Some(_tags_from_json(tags_json)?)
         } else { None };
 
+        let version = if options.retrieve_version {
+            _get_record_version(self.get_record_version_handler, self.handle, record_handle)?
+        } else { None };
+
         let result = StorageRecord {
             id: id.to_owned(),
             type_: if options.retrieve_type { Some(type_.to_vec()) } else { None },
             value,
-            tags
+            tags,
+            version,
+            expires_at: None
         };
 
         if err != ErrorCode::Success {
@@ -495,6 +598,44 @@ impl WalletStorage for PluggedStorage {
         Ok(())
     }
 
+    fn batch(&self, operations: &[StorageOperation]) -> Result<(), WalletStorageError> {
+        // Applying operations one by one can leave storage half-written, so batch handler is required
+        let batch_handler = self.batch_handler
+            .ok_or(WalletStorageError::UnsupportedOperation("Batch handler isn't registered for plugged storage".to_string()))?;
+
+        // Storage is expected to handle check_version operations only if it supports record versions
+        let check_version = operations.iter().any(|operation| match operation {
+            &StorageOperation::CheckVersion(..) => true,
+            _ => false
+        });
+
+        if check_version && self.get_record_version_handler.is_none() {
+            return Err(WalletStorageError::CommonError(
+                CommonError::InvalidState("Version handler isn't registered for plugged storage".to_string())));
+        }
+
+        let expiry = operations.iter().any(|operation| match operation {
+            &StorageOperation::Add(_, _, _, _, Some(_)) => true,
+            _ => false
+        });
+
+        if expiry {
+            return Err(WalletStorageError::UnsupportedOperation("Plugged storage doesn't support record expiry".to_string()));
+        }
+
+        let operations = CString::new(_operations_to_json(operations)?)?;
+
+        let err = (batch_handler)(self.handle, operations.as_ptr());
+
+        if err == ErrorCode::WalletItemVersionMismatch {
+            return Err(WalletStorageError::ItemVersionMismatch);
+        } else if err != ErrorCode::Success {
+            return Err(WalletStorageError::PluggedStorageError(err));
+        }
+
+        Ok(())
+    }
+
     fn get_storage_metadata(&self) -> Result<Vec<u8>, WalletStorageError> {
         let mut metadata_ptr: *const c_char = ptr::null_mut();
         let mut metadata_handle = -1;
@@ -547,6 +688,7 @@ impl WalletStorage for PluggedStorage {
                     retrieve_type: true,
                     retrieve_value: true,
                     retrieve_tags: true,
+                    ..SearchOptions::default()
                 }
             )
         ))
@@ -579,6 +721,44 @@ impl WalletStorage for PluggedStorage {
         ))
     }
 
+    fn count(&self, type_: &[u8], query: &language::Operator) -> Result<usize, WalletStorageError> {
+        let options = json!({
+            "retrieveRecords": false,
+            "retrieveTotalCount": true,
+        }).to_string();
+
+        self.search(type_, query, Some(&options))?
+            .get_total_count()?
+            .ok_or(WalletStorageError::CommonError(
+                CommonError::InvalidState("Plugged storage didn't return total count".to_string())))
+    }
+
+    // Plugged storage API has no aggregation, so tags of all matching records are fetched and counted here
+    fn count_by_tag(&self, type_: &[u8], query: &language::Operator, tag_name: &[u8]) -> Result<Vec<(String, usize)>, WalletStorageError> {
+        let options = json!({
+            "retrieveRecords": true,
+            "retrieveTotalCount": false,
+            "retrieveType": false,
+            "retrieveValue": false,
+            "retrieveTags": true,
+        }).to_string();
+
+        let mut iterator = self.search(type_, query, Some(&options))?;
+
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        while let Some(record) = iterator.next()? {
+            for tag in record.tags.unwrap_or(Vec::new()) {
+                if let Tag::PlainText(name, value) = tag {
+                    if name.as_slice() == tag_name {
+                        *counts.entry(value).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(counts.into_iter().collect())
+    }
+
     fn close(&mut self) -> Result<(), WalletStorageError> {
         let err = (self.close_handler)(self.handle);
 
@@ -591,6 +771,10 @@ impl WalletStorage for PluggedStorage {
 
         Ok(())
     }
+
+    fn supports_batch(&self) -> bool {
+        self.batch_handler.is_some()
+    }
 }
 
 impl Drop for PluggedStorage {
@@ -627,7 +811,9 @@ pub struct PluggedStorageType {
     search_all_records_handler: WalletSearchAllRecords,
     get_search_total_count_handler: WalletGetSearchTotalCount,
     fetch_search_next_record_handler: WalletFetchSearchNextRecord,
-    free_search_handler: WalletFreeSearch
+    free_search_handler: WalletFreeSearch,
+    batch_handler: Cell<Option<WalletBatch>>,
+    get_record_version_handler: Cell<Option<WalletGetRecordVersion>>
 }
 
 
@@ -681,6 +867,8 @@ impl PluggedStorageType {
             get_search_total_count_handler,
             fetch_search_next_record_handler,
             free_search_handler,
+            batch_handler: Cell::new(None),
+            get_record_version_handler: Cell::new(None),
         }
     }
 }
@@ -754,7 +942,9 @@ impl WalletStorageType for PluggedStorageType {
                 self.get_search_total_count_handler,
                 self.fetch_search_next_record_handler,
                 self.free_search_handler,
-                self.close_handler)))
+                self.close_handler,
+                self.batch_handler.get(),
+                self.get_record_version_handler.get())))
     }
 
     fn delete_storage(&self, id: &str, config: Option<&str>, credentials: Option<&str>) -> Result<(), WalletStorageError> {
@@ -778,6 +968,16 @@ impl WalletStorageType for PluggedStorageType {
 
         Ok(())
     }
+
+    fn set_batch_handler(&self, batch: WalletBatch) -> Result<(), WalletStorageError> {
+        self.batch_handler.set(Some(batch));
+        Ok(())
+    }
+
+    fn set_version_handler(&self, get_record_version: WalletGetRecordVersion) -> Result<(), WalletStorageError> {
+        self.get_record_version_handler.set(Some(get_record_version));
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1605,6 +1805,8 @@ mod tests {
             id: id.clone(),
             value: Some(RETURN_VALUE.read().unwrap().1.clone()),
             tags: Some(RETURN_TAGS.read().unwrap().1.clone()),
+            version: None,
+            expires_at: None,
         };
 
         assert_eq!(expected_storage_entity, storage_entity);
@@ -1657,6 +1859,8 @@ mod tests {
             id: id.clone(),
             value: Some(RETURN_VALUE.read().unwrap().1.clone()),
             tags: Some(RETURN_TAGS.read().unwrap().1.clone()),
+            version: None,
+            expires_at: None,
         };
 
         assert_eq!(expected_storage_entity, storage_entity);
@@ -1708,6 +1912,8 @@ mod tests {
             id: id.clone(),
             value: Some(RETURN_VALUE.read().unwrap().1.clone()),
             tags: None,
+            version: None,
+            expires_at: None,
         };
 
         assert_eq!(expected_storage_entity, storage_entity);
@@ -1754,6 +1960,8 @@ mod tests {
             id: id.clone(),
             value: None,
             tags: Some(RETURN_TAGS.read().unwrap().1.clone()),
+            version: None,
+            expires_at: None,
         };
 
         assert_eq!(expected_storage_entity, storage_entity);
@@ -1801,6 +2009,8 @@ mod tests {
             id: id.clone(),
             value: None,
             tags: None,
+            version: None,
+            expires_at: None,
         };
 
         assert_eq!(expected_storage_entity, storage_entity);
@@ -1921,6 +2131,8 @@ mod tests {
                 id: RETURN_ID.read().unwrap().1.clone(),
                 value: Some(RETURN_VALUE.read().unwrap().1.clone()),
                 tags: Some(RETURN_TAGS.read().unwrap().1.clone()),
+                version: None,
+                expires_at: None,
             };
 
             assert_eq!(expected_storage_entity, storage_entity.unwrap());
@@ -2021,6 +2233,8 @@ mod tests {
                 id: RETURN_ID.read().unwrap().1.clone(),
                 value: Some(RETURN_VALUE.read().unwrap().1.clone()),
                 tags: Some(RETURN_TAGS.read().unwrap().1.clone()),
+                version: None,
+                expires_at: None,
             };
 
             assert_eq!(expected_storage_entity, storage_entity.unwrap());
@@ -2102,6 +2316,8 @@ mod tests {
                 id: RETURN_ID.read().unwrap().1.clone(),
                 value: Some(RETURN_VALUE.read().unwrap().1.clone()),
                 tags: Some(RETURN_TAGS.read().unwrap().1.clone()),
+                version: None,
+                expires_at: None,
             };
 
             assert_eq!(expected_storage_entity, storage_entity.unwrap());
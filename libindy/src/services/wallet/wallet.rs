@@ -1,12 +1,15 @@
 extern crate sodiumoxide;
 
+use serde_json;
 use utils::crypto::memzero::memzero;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use utils::crypto::{hmacsha256, chacha20poly1305_ietf};
+use utils::crypto::{base64, hmacsha256, chacha20poly1305_ietf};
 
-use errors::wallet::WalletError;
+use errors::wallet::{WalletError, WalletQueryError, WalletStorageError};
 use errors::common::CommonError;
 
 use super::storage;
@@ -14,7 +17,15 @@ use super::iterator::WalletIterator;
 use super::encryption::*;
 use super::query_encryption::encrypt_query;
 use super::language;
-use super::WalletRecord;
+use super::cache::{RecordCache, CachedRecord};
+use super::{WalletRecord, RecordOptions, SearchOptions};
+use domain::wallet::{RecordOperation, WalletCheckReport, WalletCheckFailure};
+
+// Unreadable records are moved to records of this type on wallet check
+pub(super) const QUARANTINE_TYPE: &str = "Indy::QuarantinedRecord";
+
+// Wallet check stops walking records after this number of storage errors in a row
+const _CHECK_MAX_CONSECUTIVE_ERRORS: usize = 10;
 
 #[derive(Serialize, Deserialize)]
 pub(super) struct Keys {
@@ -123,11 +134,17 @@ pub(super) struct Wallet {
     id: String,
     storage: Box<storage::WalletStorage>,
     keys: Rc<Keys>,
+    cache: Option<RefCell<RecordCache>>,
 }
 
 impl Wallet {
-    pub fn new(id: String, storage: Box<storage::WalletStorage>, keys: Rc<Keys>) -> Wallet {
-        Wallet { id, storage, keys }
+    pub fn new(id: String, storage: Box<storage::WalletStorage>, keys: Rc<Keys>, cache_size: Option<usize>) -> Wallet {
+        let cache = match cache_size {
+            Some(size) if size > 0 => Some(RefCell::new(RecordCache::new(size))),
+            _ => None
+        };
+
+        Wallet { id, storage, keys, cache }
     }
 
     pub fn add(&self, type_: &str, name: &str, value: &str, tags: &HashMap<String, String>) -> Result<(), WalletError> {
@@ -139,7 +156,23 @@ impl Wallet {
         Ok(())
     }
 
+    pub fn add_with_expiry(&self, type_: &str, name: &str, value: &str, tags: &HashMap<String, String>, expires_at: u64) -> Result<(), WalletError> {
+        self._invalidate_cached(type_, name);
+        let etype = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
+        let ename = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
+        let evalue = EncryptedValue::encrypt(value, &self.keys.value_key);
+        let etags = encrypt_tags(tags, &self.keys.tag_name_key, &self.keys.tag_value_key, &self.keys.tags_hmac_key);
+        self.storage.add_with_expiry(&etype, &ename, &evalue, &etags, expires_at)?;
+        Ok(())
+    }
+
+    pub fn purge_expired(&self) -> Result<usize, WalletError> {
+        let res = self.storage.purge_expired()?;
+        Ok(res)
+    }
+
     pub fn add_tags(&self, type_: &str, name: &str, tags: &HashMap<String, String>) -> Result<(), WalletError> {
+        self._invalidate_cached(type_, name);
         let encrypted_type = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
         let encrypted_name = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
         let encrypted_tags = encrypt_tags(tags, &self.keys.tag_name_key, &self.keys.tag_value_key, &self.keys.tags_hmac_key);
@@ -148,6 +181,7 @@ impl Wallet {
     }
 
     pub fn update_tags(&self, type_: &str, name: &str, tags: &HashMap<String, String>) -> Result<(), WalletError> {
+        self._invalidate_cached(type_, name);
         let encrypted_type = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
         let encrypted_name = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
         let encrypted_tags = encrypt_tags(tags, &self.keys.tag_name_key, &self.keys.tag_value_key, &self.keys.tags_hmac_key);
@@ -156,6 +190,7 @@ impl Wallet {
     }
 
     pub fn delete_tags(&self, type_: &str, name: &str, tag_names: &[&str]) -> Result<(), WalletError> {
+        self._invalidate_cached(type_, name);
         let encrypted_type = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
         let encrypted_name = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
         let encrypted_tag_names = encrypt_tag_names(tag_names, &self.keys.tag_name_key, &self.keys.tags_hmac_key);
@@ -164,6 +199,7 @@ impl Wallet {
     }
 
     pub fn update(&self, type_: &str, name: &str, new_value: &str) -> Result<(), WalletError> {
+        self._invalidate_cached(type_, name);
         let encrypted_type = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
         let encrypted_name = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
         let encrypted_value = EncryptedValue::encrypt(new_value, &self.keys.value_key);
@@ -171,7 +207,124 @@ impl Wallet {
         Ok(())
     }
 
+    pub fn update_if_version(&self, type_: &str, name: &str, new_value: &str, version: u64) -> Result<(), WalletError> {
+        self._invalidate_cached(type_, name);
+        let encrypted_type = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
+        let encrypted_name = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
+        let encrypted_value = EncryptedValue::encrypt(new_value, &self.keys.value_key);
+        self.storage.batch(&[
+            storage::StorageOperation::CheckVersion(encrypted_type.clone(), encrypted_name.clone(), version),
+            storage::StorageOperation::Update(encrypted_type, encrypted_name, encrypted_value),
+        ])?;
+        Ok(())
+    }
+
+    pub fn update_tags_if_version(&self, type_: &str, name: &str, tags: &HashMap<String, String>, version: u64) -> Result<(), WalletError> {
+        self._invalidate_cached(type_, name);
+        let encrypted_type = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
+        let encrypted_name = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
+        let encrypted_tags = encrypt_tags(tags, &self.keys.tag_name_key, &self.keys.tag_value_key, &self.keys.tags_hmac_key);
+        self.storage.batch(&[
+            storage::StorageOperation::CheckVersion(encrypted_type.clone(), encrypted_name.clone(), version),
+            storage::StorageOperation::UpdateTags(encrypted_type, encrypted_name, encrypted_tags),
+        ])?;
+        Ok(())
+    }
+
+    pub fn delete_if_version(&self, type_: &str, name: &str, version: u64) -> Result<(), WalletError> {
+        self._invalidate_cached(type_, name);
+        let encrypted_type = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
+        let encrypted_name = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
+        self.storage.batch(&[
+            storage::StorageOperation::CheckVersion(encrypted_type.clone(), encrypted_name.clone(), version),
+            storage::StorageOperation::Delete(encrypted_type, encrypted_name),
+        ])?;
+        Ok(())
+    }
+
+    pub fn batch(&self, operations: &[RecordOperation]) -> Result<(), WalletError> {
+        let mut encrypted_operations = Vec::with_capacity(operations.len());
+
+        for operation in operations {
+            self._invalidate_cached(operation.type_(), operation.id());
+
+            let encrypted_type = encrypt_as_searchable(operation.type_().as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
+
+            let encrypted_operation = match *operation {
+                RecordOperation::Add { ref id, ref value, ref tags, expires_at, .. } =>
+                    storage::StorageOperation::Add(encrypted_type,
+                                                   self._encrypt_name(id),
+                                                   EncryptedValue::encrypt(value, &self.keys.value_key),
+                                                   tags.as_ref().map(|tags| self._encrypt_tags(tags)).unwrap_or(Vec::new()),
+                                                   expires_at),
+                RecordOperation::Update { ref id, ref value, .. } =>
+                    storage::StorageOperation::Update(encrypted_type,
+                                                      self._encrypt_name(id),
+                                                      EncryptedValue::encrypt(value, &self.keys.value_key)),
+                RecordOperation::AddTags { ref id, ref tags, .. } =>
+                    storage::StorageOperation::AddTags(encrypted_type, self._encrypt_name(id), self._encrypt_tags(tags)),
+                RecordOperation::UpdateTags { ref id, ref tags, .. } =>
+                    storage::StorageOperation::UpdateTags(encrypted_type, self._encrypt_name(id), self._encrypt_tags(tags)),
+                RecordOperation::DeleteTags { ref id, ref tag_names, .. } => {
+                    let tag_names: Vec<&str> = tag_names.iter().map(String::as_str).collect();
+                    storage::StorageOperation::DeleteTags(encrypted_type,
+                                                          self._encrypt_name(id),
+                                                          encrypt_tag_names(&tag_names, &self.keys.tag_name_key, &self.keys.tags_hmac_key))
+                }
+                RecordOperation::Delete { ref id, .. } =>
+                    storage::StorageOperation::Delete(encrypted_type, self._encrypt_name(id)),
+            };
+
+            encrypted_operations.push(encrypted_operation);
+        }
+
+        self.storage.batch(&encrypted_operations)?;
+        Ok(())
+    }
+
+    // Storages without atomic batches get operations one by one, so callers must tolerate partial application
+    pub fn apply_operations(&self, operations: &[RecordOperation]) -> Result<(), WalletError> {
+        if self.storage.supports_batch() {
+            return self.batch(operations);
+        }
+
+        for operation in operations {
+            match *operation {
+                RecordOperation::Add { ref type_, ref id, ref value, ref tags, expires_at } => {
+                    let tags = tags.clone().unwrap_or(HashMap::new());
+
+                    match expires_at {
+                        Some(expires_at) => self.add_with_expiry(type_, id, value, &tags, expires_at)?,
+                        None => self.add(type_, id, value, &tags)?
+                    }
+                }
+                RecordOperation::Update { ref type_, ref id, ref value } => self.update(type_, id, value)?,
+                RecordOperation::AddTags { ref type_, ref id, ref tags } => self.add_tags(type_, id, tags)?,
+                RecordOperation::UpdateTags { ref type_, ref id, ref tags } => self.update_tags(type_, id, tags)?,
+                RecordOperation::DeleteTags { ref type_, ref id, ref tag_names } => {
+                    let tag_names: Vec<&str> = tag_names.iter().map(String::as_str).collect();
+                    self.delete_tags(type_, id, &tag_names)?
+                }
+                RecordOperation::Delete { ref type_, ref id } => self.delete(type_, id)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn _encrypt_name(&self, name: &str) -> Vec<u8> {
+        encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key)
+    }
+
+    fn _encrypt_tags(&self, tags: &HashMap<String, String>) -> Vec<storage::Tag> {
+        encrypt_tags(tags, &self.keys.tag_name_key, &self.keys.tag_value_key, &self.keys.tags_hmac_key)
+    }
+
     pub fn get(&self, type_: &str, name: &str, options: &str) -> Result<WalletRecord, WalletError> {
+        if let Some(ref cache) = self.cache {
+            return self._get_cached(cache, type_, name, options);
+        }
+
         let etype = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
         let ename = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
 
@@ -184,10 +337,63 @@ impl Wallet {
 
         let tags = decrypt_tags(&result.tags, &self.keys.tag_name_key, &self.keys.tag_value_key)?;
 
-        Ok(WalletRecord::new(String::from(name), result.type_.map(|_| type_.to_string()), value, tags))
+        Ok(WalletRecord::new(String::from(name), result.type_.map(|_| type_.to_string()), value, tags, result.version))
+    }
+
+    // Cache keeps whole records, requested parts are taken from the cached one
+    fn _get_cached(&self, cache: &RefCell<RecordCache>, type_: &str, name: &str, options: &str) -> Result<WalletRecord, WalletError> {
+        let options: RecordOptions = serde_json::from_str(options)
+            .map_err(|err| CommonError::InvalidStructure(format!("Cannot deserialize RecordOptions: {:?}", err)))?;
+
+        let cached = cache.borrow_mut().get(type_, name);
+
+        // Expired record is hidden by storage, so it's read again to get ItemNotFound
+        let cached = match cached {
+            Some(ref record) if _is_expired(record.expires_at) => {
+                cache.borrow_mut().remove(type_, name);
+                None
+            }
+            cached => cached
+        };
+
+        let record = match cached {
+            Some(record) => record,
+            None => {
+                let etype = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
+                let ename = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
+
+                let result = self.storage.get(&etype, &ename, &RecordOptions::id_value_tags_version())?;
+
+                let value = match result.value {
+                    Some(encrypted_value) => encrypted_value.decrypt(&self.keys.value_key)?,
+                    None => return Err(WalletError::CommonError(CommonError::InvalidState("Storage returned record without value".to_string())))
+                };
+
+                let tags = decrypt_tags(&result.tags, &self.keys.tag_name_key, &self.keys.tag_value_key)?
+                    .unwrap_or_else(HashMap::new);
+
+                let record = CachedRecord { value, tags, version: result.version, expires_at: result.expires_at };
+                cache.borrow_mut().insert(type_, name, record.clone());
+                record
+            }
+        };
+
+        Ok(WalletRecord::new(String::from(name),
+                             if options.retrieve_type { Some(type_.to_string()) } else { None },
+                             if options.retrieve_value { Some(record.value.clone()) } else { None },
+                             if options.retrieve_tags { Some(record.tags.clone()) } else { None },
+                             if options.retrieve_version { record.version } else { None }))
+    }
+
+    fn _invalidate_cached(&self, type_: &str, name: &str) {
+        if let Some(ref cache) = self.cache {
+            cache.borrow_mut().remove(type_, name);
+        }
     }
 
     pub fn delete(&self, type_: &str, name: &str) -> Result<(), WalletError> {
+        self._invalidate_cached(type_, name);
+
         let etype = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
         let ename = encrypt_as_searchable(name.as_bytes(), &self.keys.name_key, &self.keys.item_hmac_key);
 
@@ -199,11 +405,63 @@ impl Wallet {
         let parsed_query = language::parse_from_json(query)?;
         let encrypted_query = encrypt_query(parsed_query, &self.keys)?;
         let encrypted_type_ = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
-        let storage_iterator = self.storage.search(&encrypted_type_, &encrypted_query, options)?;
+        let encrypted_options = match options {
+            Some(options) => Some(self._encrypt_search_options(options)?),
+            None => None
+        };
+        let storage_iterator = self.storage.search(&encrypted_type_, &encrypted_query, encrypted_options.as_ref().map(String::as_str))?;
         let wallet_iterator = WalletIterator::new(storage_iterator, Rc::clone(&self.keys));
         Ok(wallet_iterator)
     }
 
+    // Sort tag name is encrypted the same way as tag names in queries
+    fn _encrypt_search_options(&self, options: &str) -> Result<String, WalletError> {
+        let mut search_options: SearchOptions = serde_json::from_str(options)
+            .map_err(|err| CommonError::InvalidStructure(format!("Cannot deserialize SearchOptions: {:?}", err)))?;
+
+        let sort_by = match search_options.sort_by.take() {
+            Some(sort_by) => sort_by,
+            None => return Ok(options.to_string())
+        };
+
+        let encrypted_name = self._encrypt_plain_tag_name(&sort_by)
+            .ok_or(WalletQueryError::StructureErr("sortBy must be used only for nonencrypted tag".to_string()))?;
+
+        search_options.sort_by = Some(format!("~{}", base64::encode(&encrypted_name)));
+
+        let res = serde_json::to_string(&search_options)
+            .map_err(|err| CommonError::InvalidState(format!("Cannot serialize SearchOptions: {:?}", err)))?;
+
+        Ok(res)
+    }
+
+    // Returns None if tag name isn't "~" prefixed
+    fn _encrypt_plain_tag_name(&self, tag_name: &str) -> Option<Vec<u8>> {
+        if !tag_name.starts_with('~') || tag_name.len() == 1 {
+            return None;
+        }
+
+        Some(encrypt_as_searchable(tag_name[1..].as_bytes(), &self.keys.tag_name_key, &self.keys.tags_hmac_key))
+    }
+
+    pub fn count(&self, type_: &str, query: &str) -> Result<usize, WalletError> {
+        let parsed_query = language::parse_from_json(query)?;
+        let encrypted_query = encrypt_query(parsed_query, &self.keys)?;
+        let encrypted_type_ = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
+        let res = self.storage.count(&encrypted_type_, &encrypted_query)?;
+        Ok(res)
+    }
+
+    pub fn count_by_tag(&self, type_: &str, query: &str, tag_name: &str) -> Result<Vec<(String, usize)>, WalletError> {
+        let encrypted_tag_name = self._encrypt_plain_tag_name(tag_name)
+            .ok_or(WalletQueryError::StructureErr("Records can be grouped only by nonencrypted tag".to_string()))?;
+        let parsed_query = language::parse_from_json(query)?;
+        let encrypted_query = encrypt_query(parsed_query, &self.keys)?;
+        let encrypted_type_ = encrypt_as_searchable(type_.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key);
+        let res = self.storage.count_by_tag(&encrypted_type_, &encrypted_query, &encrypted_tag_name)?;
+        Ok(res)
+    }
+
     pub fn close(&mut self) -> Result<(), WalletError> {
         self.storage.close()?;
         Ok(())
@@ -217,6 +475,170 @@ impl Wallet {
     pub fn get_id<'a>(&'a self) -> &'a str {
         &self.id
     }
+
+    ///
+    /// Walks all records and validates decryption of their types, ids, values and tags.
+    /// If quarantine is set unreadable records are replaced with QUARANTINE_TYPE records
+    /// keeping their raw encrypted content, so the rest of the wallet stays readable,
+    /// and tags left without their records are deleted.
+    /// Storage errors met on the walk are reported, the walk goes on with the next record.
+    ///
+    pub fn check(&self, quarantine: bool) -> Result<WalletCheckReport, WalletError> {
+        let mut checked = 0;
+        let mut failed = Vec::new();
+        let mut storage_errors = Vec::new();
+        let mut broken_records = Vec::new();
+
+        {
+            let mut records = self.storage.get_all()?;
+            let mut consecutive_errors = 0;
+
+            loop {
+                let record = match records.next() {
+                    Ok(Some(record)) => record,
+                    Ok(None) => break,
+                    Err(err) => {
+                        storage_errors.push(format!("Can't read record: {}", err));
+                        consecutive_errors += 1;
+
+                        // storage can keep failing on the same record
+                        if consecutive_errors >= _CHECK_MAX_CONSECUTIVE_ERRORS {
+                            storage_errors.push("Check of the rest of records is aborted after repeated storage errors".to_string());
+                            break;
+                        }
+
+                        continue;
+                    }
+                };
+
+                consecutive_errors = 0;
+                checked += 1;
+
+                if let Err(error) = self._check_record(&record) {
+                    failed.push(WalletCheckFailure {
+                        type_: record.type_.as_ref().and_then(|type_| _decrypt_string(type_, &self.keys.type_key).ok()),
+                        id: _decrypt_string(&record.id, &self.keys.name_key).ok(),
+                        encrypted_id: base64::encode(&record.id),
+                        error,
+                    });
+                    broken_records.push(record);
+                }
+            }
+        }
+
+        let orphan_tags = self.storage.count_orphan_tags()?;
+
+        let mut quarantined = 0;
+
+        if quarantine {
+            for record in broken_records {
+                if self._quarantine_record(record)? {
+                    quarantined += 1;
+                }
+            }
+
+            if orphan_tags > 0 {
+                self.storage.delete_orphan_tags()?;
+            }
+        }
+
+        Ok(WalletCheckReport { checked, failed, quarantined, orphan_tags, storage_errors })
+    }
+
+    fn _check_record(&self, record: &storage::StorageRecord) -> Result<(), String> {
+        let type_ = match record.type_ {
+            Some(ref type_) => type_,
+            None => return Err("Record type is missing".to_string())
+        };
+
+        let decrypted_type = _decrypt_string(type_, &self.keys.type_key)
+            .map_err(|err| format!("Can't decrypt record type: {}", err))?;
+
+        if encrypt_as_searchable(decrypted_type.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key) != *type_ {
+            return Err("Record type doesn't match its encrypted form".to_string());
+        }
+
+        let decrypted_name = _decrypt_string(&record.id, &self.keys.name_key)
+            .map_err(|err| format!("Can't decrypt record id: {}", err))?;
+
+        if self._encrypt_name(&decrypted_name) != record.id {
+            return Err("Record id doesn't match its encrypted form".to_string());
+        }
+
+        match record.value {
+            Some(ref value) => value.decrypt(&self.keys.value_key)
+                .map_err(|err| format!("Can't decrypt record value: {}", err))?,
+            None => return Err("Record value is missing".to_string())
+        };
+
+        decrypt_tags(&record.tags, &self.keys.tag_name_key, &self.keys.tag_value_key)
+            .map_err(|err| format!("Can't decrypt record tags: {}", err))?;
+
+        Ok(())
+    }
+
+    // Returns false if record can't be addressed in the storage
+    fn _quarantine_record(&self, record: storage::StorageRecord) -> Result<bool, WalletError> {
+        let type_ = match record.type_ {
+            Some(type_) => type_,
+            None => return Ok(false)
+        };
+
+        // record could be cached before it was broken
+        if let (Ok(plain_type), Ok(plain_name)) = (_decrypt_string(&type_, &self.keys.type_key), _decrypt_string(&record.id, &self.keys.name_key)) {
+            self._invalidate_cached(&plain_type, &plain_name);
+        }
+
+        let tags: Vec<serde_json::Value> = record.tags.unwrap_or_else(Vec::new)
+            .iter()
+            .map(|tag| match *tag {
+                storage::Tag::Encrypted(ref name, ref value) =>
+                    json!({"encrypted": true, "name": base64::encode(name), "value": base64::encode(value)}),
+                storage::Tag::PlainText(ref name, ref value) =>
+                    json!({"encrypted": false, "name": base64::encode(name), "value": value}),
+            })
+            .collect();
+
+        let value = json!({
+            "type": base64::encode(&type_),
+            "id": base64::encode(&record.id),
+            "value": record.value.map(|value| base64::encode(&value.to_bytes())),
+            "tags": tags,
+        }).to_string();
+
+        let name = format!("{}:{}", base64::encode(&type_), base64::encode(&record.id));
+
+        // Batch isn't supported by all plugged storages, so record is copied first and deleted then.
+        // Copy left by interrupted quarantine has the same name and is reused on the next check
+        let res = self.storage.add(&encrypt_as_searchable(QUARANTINE_TYPE.as_bytes(), &self.keys.type_key, &self.keys.item_hmac_key),
+                                   &self._encrypt_name(&name),
+                                   &EncryptedValue::encrypt(&value, &self.keys.value_key),
+                                   &[]);
+
+        match res {
+            Ok(()) | Err(WalletStorageError::ItemAlreadyExists) => {}
+            Err(err) => return Err(WalletError::from(err))
+        }
+
+        self.storage.delete(&type_, &record.id)?;
+        Ok(true)
+    }
+}
+
+fn _is_expired(expires_at: Option<u64>) -> bool {
+    match expires_at {
+        Some(expires_at) => {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or(0);
+            expires_at <= now
+        }
+        None => false
+    }
+}
+
+fn _decrypt_string(data: &[u8], key: &chacha20poly1305_ietf::Key) -> Result<String, WalletError> {
+    let decrypted = decrypt_merged(data, key)?;
+    let decrypted = String::from_utf8(decrypted)?;
+    Ok(decrypted)
 }
 
 #[cfg(test)]
@@ -330,6 +752,86 @@ mod tests {
         assert_eq!(record.tags.unwrap(), _tags());
     }
 
+    #[test]
+    fn wallet_update_if_version_works() {
+        _cleanup();
+
+        let wallet = _wallet();
+        wallet.add(_type1(), _id1(), _value1(), &_tags()).unwrap();
+
+        let record = wallet.get(_type1(), _id1(), &RecordOptions::id_value_tags_version()).unwrap();
+        assert_eq!(record.version, Some(1));
+
+        wallet.update_if_version(_type1(), _id1(), _value2(), 1).unwrap();
+
+        let res = wallet.update_if_version(_type1(), _id1(), _value3(), 1);
+        assert_match!(Err(WalletError::ItemVersionMismatch), res);
+
+        let record = wallet.get(_type1(), _id1(), &RecordOptions::id_value_tags_version()).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+        assert_eq!(record.version, Some(2));
+    }
+
+    #[test]
+    fn wallet_update_if_version_works_for_cache() {
+        _cleanup();
+
+        let wallet = _wallet_with_cache(Some(10));
+        wallet.add(_type1(), _id1(), _value1(), &_tags()).unwrap();
+
+        let record = wallet.get(_type1(), _id1(), &RecordOptions::id_value_tags_version()).unwrap();
+        assert_eq!(record.version, Some(1));
+
+        wallet.update_tags_if_version(_type1(), _id1(), &HashMap::new(), 1).unwrap();
+
+        let record = wallet.get(_type1(), _id1(), &RecordOptions::id_value_tags_version()).unwrap();
+        assert_eq!(record.tags.unwrap(), HashMap::new());
+        assert_eq!(record.version, Some(2));
+
+        let res = wallet.delete_if_version(_type1(), _id1(), 1);
+        assert_match!(Err(WalletError::ItemVersionMismatch), res);
+
+        wallet.delete_if_version(_type1(), _id1(), 2).unwrap();
+
+        let res = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true));
+        assert_match!(Err(WalletError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn wallet_add_with_expiry_works() {
+        _cleanup();
+
+        let wallet = _wallet_with_cache(Some(10));
+        wallet.add_with_expiry(_type1(), _id1(), _value1(), &_tags(), 1).unwrap();
+        wallet.add_with_expiry(_type1(), _id2(), _value2(), &_tags(), u32::max_value() as u64).unwrap();
+
+        let res = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true));
+        assert_match!(Err(WalletError::ItemNotFound), res);
+
+        let record = wallet.get(_type1(), _id2(), &_fetch_options(false, true, true)).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+
+        // Expired record doesn't block adding a new one with the same id
+        wallet.add(_type1(), _id1(), _value3(), &_tags()).unwrap();
+
+        let record = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true)).unwrap();
+        assert_eq!(record.value.unwrap(), _value3());
+    }
+
+    #[test]
+    fn wallet_purge_expired_works() {
+        _cleanup();
+
+        let wallet = _wallet();
+        wallet.add_with_expiry(_type1(), _id1(), _value1(), &_tags(), 1).unwrap();
+        wallet.add(_type1(), _id2(), _value2(), &_tags()).unwrap();
+
+        assert_eq!(1, wallet.purge_expired().unwrap());
+        assert_eq!(0, wallet.purge_expired().unwrap());
+
+        wallet.get(_type1(), _id2(), &_fetch_options(false, true, true)).unwrap();
+    }
+
     #[test]
     fn wallet_update_works_for_non_existing_id() {
         _cleanup();
@@ -450,6 +952,167 @@ mod tests {
         assert_match!(Err(WalletError::ItemNotFound), res);
     }
 
+    #[test]
+    fn wallet_get_works_for_cache() {
+        _cleanup();
+
+        let wallet = _wallet_with_cache(Some(10));
+        wallet.add(_type1(), _id1(), _value1(), &_tags()).unwrap();
+
+        let record = wallet.get(_type1(), _id1(), &_fetch_options(false, true, false)).unwrap();
+        assert_eq!(record.value.unwrap(), _value1());
+        assert!(record.tags.is_none());
+        assert!(record.type_.is_none());
+
+        let record = wallet.get(_type1(), _id1(), &_fetch_options(true, true, true)).unwrap();
+        assert_eq!(record.type_.unwrap(), _type1());
+        assert_eq!(record.value.unwrap(), _value1());
+        assert_eq!(record.tags.unwrap(), _tags());
+
+        let res = wallet.get(_type2(), _id1(), &_fetch_options(false, true, false));
+        assert_match!(Err(WalletError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn wallet_check_works() {
+        _cleanup();
+
+        let wallet = _wallet();
+        wallet.add(_type1(), _id1(), _value1(), &_tags()).unwrap();
+        wallet.add(_type2(), _id2(), _value2(), &HashMap::new()).unwrap();
+
+        let report = wallet.check(false).unwrap();
+        assert_eq!(2, report.checked);
+        assert!(report.failed.is_empty());
+        assert_eq!(0, report.quarantined);
+    }
+
+    #[test]
+    fn wallet_check_works_for_broken_value() {
+        _cleanup();
+
+        let wallet = _wallet();
+        wallet.add(_type1(), _id1(), _value1(), &_tags()).unwrap();
+        wallet.add(_type1(), _id2(), _value2(), &_tags()).unwrap();
+        _break_value(&wallet, _type1(), _id1());
+
+        let report = wallet.check(false).unwrap();
+        assert_eq!(2, report.checked);
+        assert_eq!(1, report.failed.len());
+        assert_eq!(Some(_type1().to_string()), report.failed[0].type_);
+        assert_eq!(Some(_id1().to_string()), report.failed[0].id);
+        assert_eq!(0, report.quarantined);
+
+        let res = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true));
+        assert_match!(Err(WalletError::CommonError(_)), res);
+    }
+
+    #[test]
+    fn wallet_check_works_for_quarantine() {
+        _cleanup();
+
+        let wallet = _wallet();
+        wallet.add(_type1(), _id1(), _value1(), &_tags()).unwrap();
+        wallet.add(_type1(), _id2(), _value2(), &_tags()).unwrap();
+        _break_value(&wallet, _type1(), _id1());
+
+        let report = wallet.check(true).unwrap();
+        assert_eq!(1, report.failed.len());
+        assert_eq!(1, report.quarantined);
+
+        let res = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true));
+        assert_match!(Err(WalletError::ItemNotFound), res);
+
+        let mut iterator = wallet.get_all().unwrap();
+        let mut records = Vec::new();
+        while let Some(record) = iterator.next().unwrap() {
+            records.push(record);
+        }
+        assert_eq!(2, records.len());
+
+        let mut iterator = wallet.search(QUARANTINE_TYPE, "{}", Some(&_search_options(true, false, false, true, false))).unwrap();
+        let record = iterator.next().unwrap().unwrap();
+        let quarantined: serde_json::Value = serde_json::from_str(&record.value.unwrap()).unwrap();
+        assert_eq!(base64::encode(&wallet._encrypt_name(_id1())), quarantined["id"].as_str().unwrap());
+        assert!(iterator.next().unwrap().is_none());
+
+        let report = wallet.check(false).unwrap();
+        assert_eq!(2, report.checked);
+        assert!(report.failed.is_empty());
+    }
+
+    #[test]
+    fn wallet_check_works_for_interrupted_quarantine() {
+        _cleanup();
+
+        let wallet = _wallet();
+        wallet.add(_type1(), _id1(), _value1(), &_tags()).unwrap();
+        _break_value(&wallet, _type1(), _id1());
+
+        let broken_record = wallet.storage.get_all().unwrap().next().unwrap().unwrap();
+
+        let report = wallet.check(true).unwrap();
+        assert_eq!(1, report.quarantined);
+
+        // quarantine copy is added, but broken record isn't deleted
+        wallet.storage.add(broken_record.type_.as_ref().unwrap(),
+                           &broken_record.id,
+                           broken_record.value.as_ref().unwrap(),
+                           broken_record.tags.as_ref().unwrap()).unwrap();
+
+        let report = wallet.check(true).unwrap();
+        assert_eq!(1, report.failed.len());
+        assert_eq!(1, report.quarantined);
+
+        let report = wallet.check(false).unwrap();
+        assert_eq!(1, report.checked);
+        assert!(report.failed.is_empty());
+    }
+
+    #[test]
+    fn wallet_check_works_for_quarantine_of_cached() {
+        _cleanup();
+
+        let wallet = _wallet_with_cache(Some(10));
+        wallet.add(_type1(), _id1(), _value1(), &_tags()).unwrap();
+        wallet.get(_type1(), _id1(), &_fetch_options(false, true, true)).unwrap();
+        _break_value(&wallet, _type1(), _id1());
+
+        let report = wallet.check(true).unwrap();
+        assert_eq!(1, report.quarantined);
+
+        let res = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true));
+        assert_match!(Err(WalletError::ItemNotFound), res);
+    }
+
+    #[test]
+    fn wallet_get_works_for_cache_after_changes() {
+        _cleanup();
+
+        let wallet = _wallet_with_cache(Some(10));
+        wallet.add(_type1(), _id1(), _value1(), &_tags()).unwrap();
+        wallet.get(_type1(), _id1(), &_fetch_options(false, true, true)).unwrap();
+
+        wallet.update(_type1(), _id1(), _value2()).unwrap();
+        let record = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true)).unwrap();
+        assert_eq!(record.value.unwrap(), _value2());
+
+        let mut new_tags = HashMap::new();
+        new_tags.insert("tag_name_3".to_string(), "tag_value_3".to_string());
+
+        wallet.update_tags(_type1(), _id1(), &new_tags).unwrap();
+        let record = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true)).unwrap();
+        assert_eq!(record.tags.unwrap(), new_tags);
+
+        wallet.batch(&[RecordOperation::Update { type_: _type1().to_string(), id: _id1().to_string(), value: _value3().to_string() }]).unwrap();
+        let record = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true)).unwrap();
+        assert_eq!(record.value.unwrap(), _value3());
+
+        wallet.delete(_type1(), _id1()).unwrap();
+        let res = wallet.get(_type1(), _id1(), &_fetch_options(false, true, true));
+        assert_match!(Err(WalletError::ItemNotFound), res);
+    }
+
     #[test]
     fn wallet_delete_works_for_non_existing_id() {
         _cleanup();
@@ -532,12 +1195,14 @@ mod tests {
                 value: Some(_value1().to_string()),
                 tags: None,
                 type_: None,
+                version: None,
             },
             WalletRecord {
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
                 type_: None,
+                version: None,
             },
         ]);
 
@@ -564,12 +1229,14 @@ mod tests {
                 value: Some(_value1().to_string()),
                 tags: Some(_tags()),
                 type_: Some(_type1().to_string()),
+                version: None,
             },
             WalletRecord {
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: Some(_tags()),
                 type_: Some(_type1().to_string()),
+                version: None,
             },
         ]);
 
@@ -617,6 +1284,7 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             }
         ];
 
@@ -713,6 +1381,7 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             }
         ];
 
@@ -776,6 +1445,7 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             }
         ];
 
@@ -838,6 +1508,7 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             }
         ];
 
@@ -896,12 +1567,14 @@ mod tests {
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id3().to_string(),
                 value: Some(_value3().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -933,6 +1606,100 @@ mod tests {
         assert!(iterator.get_total_count().unwrap().is_none());
     }
 
+    #[test]
+    fn wallet_search_works_for_gt_numeric() {
+        _cleanup();
+
+        let wallet = _wallet();
+        wallet.add(_type1(), _id1(), _value1(), &jsonmap!({"~tag_name":"9"})).unwrap();
+        wallet.add(_type1(), _id2(), _value2(), &jsonmap!({"~tag_name":"10"})).unwrap();
+        wallet.add(_type1(), _id3(), _value3(), &jsonmap!({"~tag_name":"not a number"})).unwrap();
+
+        // string comparison is lexical
+        let mut iterator = wallet.search(_type1(),
+                                         &jsonstr!({"~tag_name": {"$gt": "9"}}),
+                                         Some(&_search_options(true, false, false, true, false))).unwrap();
+
+        let expected_records = _sort(vec![
+            WalletRecord {
+                type_: None,
+                id: _id3().to_string(),
+                value: Some(_value3().to_string()),
+                tags: None,
+                version: None,
+            },
+        ]);
+
+        assert_eq!(_fetch_all(&mut iterator), expected_records);
+
+        // number comparison is numeric and skips non numeric values
+        let mut iterator = wallet.search(_type1(),
+                                         &jsonstr!({"~tag_name": {"$gt": 9}}),
+                                         Some(&_search_options(true, false, false, true, false))).unwrap();
+
+        let expected_records = _sort(vec![
+            WalletRecord {
+                type_: None,
+                id: _id2().to_string(),
+                value: Some(_value2().to_string()),
+                tags: None,
+                version: None,
+            },
+        ]);
+
+        assert_eq!(_fetch_all(&mut iterator), expected_records);
+
+        let mut iterator = wallet.search(_type1(),
+                                         &jsonstr!({"~tag_name": {"$lte": 9.5}}),
+                                         Some(&_search_options(true, false, false, true, false))).unwrap();
+
+        let expected_records = _sort(vec![
+            WalletRecord {
+                type_: None,
+                id: _id1().to_string(),
+                value: Some(_value1().to_string()),
+                tags: None,
+                version: None,
+            },
+        ]);
+
+        assert_eq!(_fetch_all(&mut iterator), expected_records);
+    }
+
+    #[test]
+    fn wallet_count_works() {
+        _cleanup();
+
+        let wallet = _wallet();
+        wallet.add(_type1(), _id1(), _value1(), &jsonmap!({"tag_name":"1", "~tag_name_2":"a"})).unwrap();
+        wallet.add(_type1(), _id2(), _value2(), &jsonmap!({"tag_name":"2", "~tag_name_2":"a"})).unwrap();
+        wallet.add(_type1(), _id3(), _value3(), &jsonmap!({"tag_name":"1", "~tag_name_2":"b"})).unwrap();
+        wallet.add(_type2(), _id1(), _value1(), &jsonmap!({"tag_name":"1"})).unwrap();
+
+        assert_eq!(3, wallet.count(_type1(), "{}").unwrap());
+        assert_eq!(2, wallet.count(_type1(), &jsonstr!({"tag_name": "1"})).unwrap());
+        assert_eq!(1, wallet.count(_type2(), "{}").unwrap());
+    }
+
+    #[test]
+    fn wallet_count_by_tag_works() {
+        _cleanup();
+
+        let wallet = _wallet();
+        wallet.add(_type1(), _id1(), _value1(), &jsonmap!({"tag_name":"1", "~tag_name_2":"a"})).unwrap();
+        wallet.add(_type1(), _id2(), _value2(), &jsonmap!({"tag_name":"2", "~tag_name_2":"a"})).unwrap();
+        wallet.add(_type1(), _id3(), _value3(), &jsonmap!({"tag_name":"1", "~tag_name_2":"b"})).unwrap();
+
+        let counts = wallet.count_by_tag(_type1(), "{}", "~tag_name_2").unwrap();
+        assert_eq!(vec![("a".to_string(), 2), ("b".to_string(), 1)], counts);
+
+        let counts = wallet.count_by_tag(_type1(), &jsonstr!({"tag_name": "1"}), "~tag_name_2").unwrap();
+        assert_eq!(vec![("a".to_string(), 1), ("b".to_string(), 1)], counts);
+
+        let res = wallet.count_by_tag(_type1(), "{}", "tag_name");
+        assert_match!(Err(WalletError::QueryError(_)), res);
+    }
+
     #[test]
     fn wallet_search_works_for_gt_encrypted() {
         _cleanup();
@@ -965,12 +1732,14 @@ mod tests {
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id3().to_string(),
                 value: Some(_value3().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1034,12 +1803,14 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1103,12 +1874,14 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1172,12 +1945,14 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1241,12 +2016,14 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id3().to_string(),
                 value: Some(_value3().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1305,12 +2082,14 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id3().to_string(),
                 value: Some(_value3().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1389,6 +2168,7 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             }
         ];
 
@@ -1408,6 +2188,7 @@ mod tests {
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
+                version: None,
             }
         ];
 
@@ -1427,12 +2208,14 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1532,18 +2315,21 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id3().to_string(),
                 value: Some(_value3().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1567,12 +2353,14 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id3().to_string(),
                 value: Some(_value3().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1595,6 +2383,7 @@ mod tests {
                 id: _id3().to_string(),
                 value: Some(_value3().to_string()),
                 tags: None,
+                version: None,
             },
         ];
 
@@ -1666,18 +2455,21 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id2().to_string(),
                 value: Some(_value2().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id3().to_string(),
                 value: Some(_value3().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1694,12 +2486,14 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
             WalletRecord {
                 type_: None,
                 id: _id3().to_string(),
                 value: Some(_value3().to_string()),
                 tags: None,
+                version: None,
             },
         ]);
 
@@ -1756,6 +2550,7 @@ mod tests {
                 id: _id1().to_string(),
                 value: Some(_value1().to_string()),
                 tags: None,
+                version: None,
             },
         ];
 
@@ -1803,11 +2598,21 @@ mod tests {
         jsonmap!({"tag1": "tag_value_1"})
     }
 
+    fn _break_value(wallet: &Wallet, type_: &str, name: &str) {
+        let encrypted_type = encrypt_as_searchable(type_.as_bytes(), &wallet.keys.type_key, &wallet.keys.item_hmac_key);
+        let truncated_value = EncryptedValue::new(vec![1, 2, 3], vec![4, 5, 6]);
+        wallet.storage.update(&encrypted_type, &wallet._encrypt_name(name), &truncated_value).unwrap();
+    }
+
     fn _wallet_id() -> &'static str {
         "w1"
     }
 
     fn _wallet() -> Wallet {
+        _wallet_with_cache(None)
+    }
+
+    fn _wallet_with_cache(cache_size: Option<usize>) -> Wallet {
         let storage_type = SQLiteStorageType::new();
         let master_key = _master_key();
 
@@ -1832,7 +2637,7 @@ mod tests {
 
         let storage = storage_type.open_storage(_wallet_id(), None, None).unwrap();
 
-        Wallet::new(_wallet_id().to_string(), storage, Rc::new(keys))
+        Wallet::new(_wallet_id().to_string(), storage, Rc::new(keys), cache_size)
     }
 
     fn _exists_wallet() -> Wallet {
@@ -1848,7 +2653,7 @@ mod tests {
         let master_key = _master_key();
         let keys = Keys::deserialize_encrypted(&metadata.keys, &master_key).unwrap();
 
-        Wallet::new(_wallet_id().to_string(), storage, Rc::new(keys))
+        Wallet::new(_wallet_id().to_string(), storage, Rc::new(keys), None)
     }
 
     fn _master_key() -> chacha20poly1305_ietf::Key {
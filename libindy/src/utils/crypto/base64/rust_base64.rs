@@ -11,6 +11,29 @@ pub fn decode(doc: &str) -> Result<Vec<u8>, CommonError> {
         .map_err(|err| CommonError::InvalidStructure(format!("{}", err)))
 }
 
+pub fn decode_urlsafe(doc: &str) -> Result<Vec<u8>, CommonError> {
+    base64::decode_config(doc, base64::URL_SAFE)
+        .map_err(|err| CommonError::InvalidStructure(format!("{}", err)))
+}
+
+pub fn encode_urlsafe_nopad(doc: &[u8]) -> String {
+    base64::encode_config(doc, base64::URL_SAFE_NO_PAD)
+}
+
+pub fn decode_urlsafe_nopad(doc: &str) -> Result<Vec<u8>, CommonError> {
+    if doc.ends_with('=') {
+        return Err(CommonError::InvalidStructure("Unexpected padding in base64url string".to_string()));
+    }
+
+    // Restore padding, so decoding doesn't depend on padding handling of the decoder
+    let mut doc = doc.to_string();
+    while doc.len() % 4 != 0 {
+        doc.push('=');
+    }
+
+    decode_urlsafe(&doc)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -28,4 +51,31 @@ mod tests {
         assert!(result.is_ok(), "Got error");
         assert_eq!(&[1, 2, 3], &result.unwrap()[..]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn decode_urlsafe_works() {
+        let result = decode_urlsafe("-_8=");
+
+        assert!(result.is_ok(), "Got error");
+        assert_eq!(&[251, 255], &result.unwrap()[..]);
+    }
+
+    #[test]
+    fn encode_urlsafe_nopad_works() {
+        let result = encode_urlsafe_nopad(&[251, 255]);
+        assert_eq!("-_8", &result);
+    }
+
+    #[test]
+    fn decode_urlsafe_nopad_works() {
+        let result = decode_urlsafe_nopad("-_8");
+
+        assert!(result.is_ok(), "Got error");
+        assert_eq!(&[251, 255], &result.unwrap()[..]);
+    }
+
+    #[test]
+    fn decode_urlsafe_nopad_works_for_padded_string() {
+        assert!(decode_urlsafe_nopad("-_8=").is_err());
+    }
+}
@@ -71,6 +71,32 @@ pub fn decrypt(data: &[u8], key: &Key, nonce: &Nonce) -> Result<Vec<u8>, CommonE
         .map_err(|err| CommonError::InvalidStructure(format!("Unable to decrypt data: {:?}", err)))
 }
 
+pub fn encrypt_detached(data: &[u8], ad: &[u8], key: &Key, nonce: &Nonce) -> (Vec<u8>, Vec<u8>) {
+    let mut encrypted_data = chacha20poly1305_ietf::seal(
+        data,
+        Some(ad),
+        &nonce.0,
+        &key.0,
+    );
+
+    let tag = encrypted_data.split_off(data.len());
+
+    (encrypted_data, tag)
+}
+
+pub fn decrypt_detached(data: &[u8], tag: &[u8], ad: &[u8], key: &Key, nonce: &Nonce) -> Result<Vec<u8>, CommonError> {
+    let mut encrypted_data = data.to_vec();
+    encrypted_data.extend_from_slice(tag);
+
+    chacha20poly1305_ietf::open(
+        &encrypted_data,
+        Some(ad),
+        &nonce.0,
+        &key.0,
+    )
+        .map_err(|err| CommonError::InvalidStructure(format!("Unable to decrypt data: {:?}", err)))
+}
+
 pub struct Writer<W: Write> {
     buffer: Vec<u8>,
     chunk_size: usize,
@@ -94,6 +120,10 @@ impl<W: Write> Writer<W> {
     pub fn into_inner(self) -> W {
         self.inner
     }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
 }
 
 impl<W: Write> Write for Writer<W> {
@@ -224,6 +254,23 @@ mod tests {
         assert_eq!(data, u);
     }
 
+    #[test]
+    fn encrypt_decrypt_detached_works() {
+        let data = randombytes(100);
+        let ad = randombytes(20);
+        let key = gen_key();
+        let nonce = gen_nonce();
+
+        let (c, tag) = encrypt_detached(&data, &ad, &key, &nonce);
+        assert_eq!(TAGBYTES, tag.len());
+
+        let u = decrypt_detached(&c, &tag, &ad, &key, &nonce).unwrap();
+        assert_eq!(data, u);
+
+        let res = decrypt_detached(&c, &tag, &randombytes(20), &key, &nonce);
+        assert!(res.is_err());
+    }
+
     #[test]
     fn encrypt_decrypt_works_for_nonce() {
         let data = randombytes(16);
@@ -0,0 +1,266 @@
+extern crate openssl;
+
+use errors::common::CommonError;
+use errors::crypto::CryptoError;
+
+use self::openssl::bn::{BigNum, BigNumContext};
+use self::openssl::ec::{EcGroup, EcKey, EcKeyBuilder, EcPoint, POINT_CONVERSION_COMPRESSED, POINT_CONVERSION_UNCOMPRESSED};
+use self::openssl::hash::MessageDigest;
+use self::openssl::nid;
+use self::openssl::pkey::PKey;
+use self::openssl::sign::{Signer, Verifier};
+
+use utils::crypto::hash::hash;
+
+pub const SECRETKEYBYTES: usize = 32;
+pub const PUBLICKEYBYTES: usize = 33;
+pub const SHAREDSECRETBYTES: usize = 32;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Curve {
+    Secp256k1,
+    P256
+}
+
+impl Curve {
+    fn group(&self) -> Result<EcGroup, CryptoError> {
+        let nid = match *self {
+            Curve::Secp256k1 => nid::SECP256K1,
+            Curve::P256 => nid::X9_62_PRIME256V1
+        };
+
+        EcGroup::from_curve_name(nid)
+            .map_err(|err| CryptoError::CommonError(CommonError::from(err)))
+    }
+}
+
+/// Creates key pair. Public key is returned as compressed SEC1 point (33 bytes),
+/// secret key as big-endian scalar (32 bytes).
+///
+/// If seed is given secret key is derived as sha256(seed) mod n, so the same seed
+/// always gives the same key pair.
+pub fn create_key_pair(curve: Curve, seed: Option<&[u8]>) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+    let group = curve.group()?;
+    let mut ctx = _ctx()?;
+
+    let sk = match seed {
+        Some(seed) => {
+            let mut order = _bn()?;
+            group.order(&mut order, &mut ctx).map_err(_err)?;
+
+            let digest = BigNum::from_slice(&hash(seed)?).map_err(_err)?;
+
+            let mut sk = _bn()?;
+            sk.nnmod(&digest, &order, &mut ctx).map_err(_err)?;
+
+            if sk.num_bits() == 0 {
+                return Err(CryptoError::CommonError(
+                    CommonError::InvalidStructure("Seed gives invalid secret key".to_string())));
+            }
+
+            sk
+        }
+        None => {
+            let key = EcKey::generate(&group).map_err(_err)?;
+            key.private_key()
+                .ok_or(CryptoError::CommonError(CommonError::InvalidState("Generated EC key has no private key".to_string())))?
+                .to_owned().map_err(_err)?
+        }
+    };
+
+    let mut vk = EcPoint::new(&group).map_err(_err)?;
+    vk.mul_generator(&group, &sk, &ctx).map_err(_err)?;
+
+    let vk = vk.to_bytes(&group, POINT_CONVERSION_COMPRESSED, &mut ctx).map_err(_err)?;
+    let sk = _to_fixed_bytes(&sk.to_vec(), SECRETKEYBYTES);
+
+    Ok((vk, sk))
+}
+
+/// Computes public key (compressed SEC1 point) of the secret key.
+pub fn public_key(curve: Curve, sk: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let group = curve.group()?;
+    let mut ctx = _ctx()?;
+
+    let sk = _secret_scalar(sk)?;
+
+    let mut vk = EcPoint::new(&group).map_err(_err)?;
+    vk.mul_generator(&group, &sk, &ctx).map_err(_err)?;
+
+    vk.to_bytes(&group, POINT_CONVERSION_COMPRESSED, &mut ctx).map_err(_err)
+}
+
+pub fn validate_public_key(curve: Curve, vk: &[u8]) -> Result<(), CryptoError> {
+    _public_key(curve, vk)?;
+    Ok(())
+}
+
+/// Signs sha256 digest of the document by ECDSA. Signature is DER encoded.
+pub fn sign(curve: Curve, sk: &[u8], doc: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let key = _private_key(curve, sk)?;
+    let key = PKey::from_ec_key(key).map_err(_err)?;
+
+    let mut signer = Signer::new(MessageDigest::sha256(), &key).map_err(_err)?;
+    signer.update(doc).map_err(_err)?;
+
+    signer.sign_to_vec().map_err(_err)
+}
+
+pub fn verify(curve: Curve, vk: &[u8], doc: &[u8], signature: &[u8]) -> Result<bool, CryptoError> {
+    let key = _public_key(curve, vk)?;
+    let key = PKey::from_ec_key(key).map_err(_err)?;
+
+    let mut verifier = Verifier::new(MessageDigest::sha256(), &key).map_err(_err)?;
+    verifier.update(doc).map_err(_err)?;
+
+    // Malformed DER is reported by openssl as error, but for caller it is just invalid signature
+    Ok(verifier.verify(signature).unwrap_or(false))
+}
+
+/// Computes ECDH shared secret: x coordinate of sk * vk point (32 bytes).
+pub fn ecdh(curve: Curve, sk: &[u8], vk: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let group = curve.group()?;
+    let mut ctx = _ctx()?;
+
+    let sk = _secret_scalar(sk)?;
+    let vk = _public_point(&group, vk)?;
+
+    let mut shared = EcPoint::new(&group).map_err(_err)?;
+    shared.mul(&group, &vk, &sk, &ctx).map_err(_err)?;
+
+    // Uncompressed point is 0x04 || x || y
+    let shared = shared.to_bytes(&group, POINT_CONVERSION_UNCOMPRESSED, &mut ctx).map_err(_err)?;
+
+    Ok(shared[1..1 + SHAREDSECRETBYTES].to_vec())
+}
+
+fn _private_key(curve: Curve, sk: &[u8]) -> Result<EcKey, CryptoError> {
+    let group = curve.group()?;
+    let ctx = _ctx()?;
+
+    let sk = _secret_scalar(sk)?;
+
+    let mut vk = EcPoint::new(&group).map_err(_err)?;
+    vk.mul_generator(&group, &sk, &ctx).map_err(_err)?;
+
+    let mut key = EcKeyBuilder::new().map_err(_err)?;
+    key.set_group(&group).map_err(_err)?;
+    key.set_private_key(&sk).map_err(_err)?;
+    key.set_public_key(&vk).map_err(_err)?;
+
+    Ok(key.build())
+}
+
+fn _public_key(curve: Curve, vk: &[u8]) -> Result<EcKey, CryptoError> {
+    let group = curve.group()?;
+    let vk = _public_point(&group, vk)?;
+
+    let key = EcKey::from_public_key(&group, &vk).map_err(_err)?;
+    key.check_key().map_err(_err)?;
+
+    Ok(key)
+}
+
+fn _public_point(group: &EcGroup, vk: &[u8]) -> Result<EcPoint, CryptoError> {
+    if vk.len() != PUBLICKEYBYTES {
+        return Err(CryptoError::CommonError(
+            CommonError::InvalidStructure(format!("Invalid EC public key length: {}", vk.len()))));
+    }
+
+    let mut ctx = _ctx()?;
+
+    EcPoint::from_bytes(group, vk, &mut ctx).map_err(_err)
+}
+
+fn _secret_scalar(sk: &[u8]) -> Result<BigNum, CryptoError> {
+    if sk.len() != SECRETKEYBYTES {
+        return Err(CryptoError::CommonError(
+            CommonError::InvalidStructure(format!("Invalid EC secret key length: {}", sk.len()))));
+    }
+
+    BigNum::from_slice(sk).map_err(_err)
+}
+
+fn _to_fixed_bytes(bytes: &[u8], len: usize) -> Vec<u8> {
+    let mut res = vec![0u8; len - bytes.len()];
+    res.extend_from_slice(bytes);
+    res
+}
+
+fn _ctx() -> Result<BigNumContext, CryptoError> {
+    BigNumContext::new().map_err(_err)
+}
+
+fn _bn() -> Result<BigNum, CryptoError> {
+    BigNum::new().map_err(_err)
+}
+
+fn _err(err: openssl::error::ErrorStack) -> CryptoError {
+    CryptoError::CommonError(CommonError::from(err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &'static [u8] = b"00000000000000000000000000000My1";
+
+    #[test]
+    fn create_key_pair_works_for_seed() {
+        for curve in [Curve::Secp256k1, Curve::P256].iter() {
+            let (vk1, sk1) = create_key_pair(*curve, Some(SEED)).unwrap();
+            let (vk2, sk2) = create_key_pair(*curve, Some(SEED)).unwrap();
+
+            assert_eq!(PUBLICKEYBYTES, vk1.len());
+            assert_eq!(SECRETKEYBYTES, sk1.len());
+            assert_eq!(vk1, vk2);
+            assert_eq!(sk1, sk2);
+        }
+    }
+
+    #[test]
+    fn create_key_pair_works_for_different_curves() {
+        let (vk1, _) = create_key_pair(Curve::Secp256k1, Some(SEED)).unwrap();
+        let (vk2, _) = create_key_pair(Curve::P256, Some(SEED)).unwrap();
+        assert_ne!(vk1, vk2);
+    }
+
+    #[test]
+    fn sign_verify_works() {
+        for curve in [Curve::Secp256k1, Curve::P256].iter() {
+            let (vk, sk) = create_key_pair(*curve, None).unwrap();
+            let (other_vk, _) = create_key_pair(*curve, None).unwrap();
+
+            let doc = b"Hello World";
+            let signature = sign(*curve, &sk, doc).unwrap();
+
+            assert!(verify(*curve, &vk, doc, &signature).unwrap());
+            assert!(!verify(*curve, &vk, b"Other doc", &signature).unwrap());
+            assert!(!verify(*curve, &other_vk, doc, &signature).unwrap());
+            assert!(!verify(*curve, &vk, doc, b"invalid signature").unwrap());
+        }
+    }
+
+    #[test]
+    fn ecdh_works() {
+        for curve in [Curve::Secp256k1, Curve::P256].iter() {
+            let (vk1, sk1) = create_key_pair(*curve, None).unwrap();
+            let (vk2, sk2) = create_key_pair(*curve, None).unwrap();
+
+            let shared1 = ecdh(*curve, &sk1, &vk2).unwrap();
+            let shared2 = ecdh(*curve, &sk2, &vk1).unwrap();
+
+            assert_eq!(SHAREDSECRETBYTES, shared1.len());
+            assert_eq!(shared1, shared2);
+        }
+    }
+
+    #[test]
+    fn validate_public_key_works_for_invalid_point() {
+        let mut vk = vec![0x02];
+        vk.extend_from_slice(&[0xFF; 32]);
+
+        assert!(validate_public_key(Curve::Secp256k1, &vk).is_err());
+        assert!(validate_public_key(Curve::P256, &[0x02; 10]).is_err());
+    }
+}
@@ -62,6 +62,11 @@ pub fn verify(public_key: &PublicKey, doc: &[u8], signature: &Signature) -> Resu
     ))
 }
 
+pub fn sk_to_vk(sk: &SecretKey) -> Result<PublicKey, CryptoError> {
+    // Secret key is stored as seed || public key
+    PublicKey::from_slice(&(sk.0).0[SEEDBYTES..])
+}
+
 pub fn sk_to_curve25519(sk: &SecretKey) -> Result<ed25519_box::SecretKey, CryptoError> {
     let mut to: [u8; ENC_SECRETKEYBYTES] = [0; ENC_SECRETKEYBYTES];
     unsafe {
@@ -10,6 +10,10 @@ pub mod ed25519_sign;
 // TODO: The name is misleading as the operations do not happen over ed25519 curve
 pub mod ed25519_box;
 
+#[cfg(feature = "ec_openssl")]
+#[path = "ec/openssl.rs"]
+pub mod ec;
+
 #[cfg(feature = "base58_rust_base58")]
 #[path = "base58/rust_base58.rs"]
 pub mod base58;
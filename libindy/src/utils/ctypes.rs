@@ -122,6 +122,21 @@ macro_rules! check_useful_c_byte_array {
     }
 }
 
+macro_rules! check_useful_opt_c_byte_array {
+    ($ptr:ident, $len:expr, $err:expr) => {
+        let $ptr = if $ptr.is_null() {
+            None
+        } else {
+            if $len <= 0 {
+                return $err
+            }
+
+            let $ptr = unsafe { $crate::std::slice::from_raw_parts($ptr, $len as usize) };
+            Some($ptr.to_vec())
+        };
+    }
+}
+
 //Returnable pointer is valid only before first vector modification
 pub fn vec_to_pointer(v: &Vec<u8>) -> (*const u8, u32) {
     let len = v.len() as u32;
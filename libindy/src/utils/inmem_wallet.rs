@@ -2,6 +2,7 @@ extern crate libc;
 extern crate time;
 extern crate indy_crypto;
 extern crate serde_json;
+extern crate base64;
 
 use api::ErrorCode;
 use utils::ctypes;
@@ -23,7 +24,8 @@ struct InmemWalletRecord {
     type_: CString,
     id: CString,
     value: Vec<u8>,
-    tags: CString
+    tags: CString,
+    version: u64
 }
 
 #[derive(Debug, Clone)]
@@ -130,6 +132,7 @@ impl InmemWallet {
             id: CString::new(id).unwrap(),
             value,
             tags: CString::new(tags_json).unwrap(),
+            version: 1,
         });
         ErrorCode::Success
     }
@@ -160,7 +163,10 @@ impl InmemWallet {
         let wallet = wallets.get_mut(&wallet_context.id).unwrap();
 
         match wallet.records.get_mut(&InmemWallet::build_record_id(&type_, &id)) {
-            Some(ref mut record) => record.value = joined_value,
+            Some(ref mut record) => {
+                record.value = joined_value;
+                record.version += 1;
+            }
             None => return ErrorCode::WalletItemNotFound
         }
 
@@ -301,6 +307,28 @@ impl InmemWallet {
     }
 
 
+    pub extern "C" fn get_record_version(xhandle: i32,
+                                         record_handle: i32,
+                                         version_p: *mut u64) -> ErrorCode {
+        let handles = INMEM_OPEN_WALLETS.lock().unwrap();
+
+        if !handles.contains_key(&xhandle) {
+            return ErrorCode::CommonInvalidState;
+        }
+
+        let handles = ACTIVE_RECORDS.lock().unwrap();
+
+        if !handles.contains_key(&record_handle) {
+            return ErrorCode::CommonInvalidState;
+        }
+
+        let record = handles.get(&record_handle).unwrap();
+
+        unsafe { *version_p = record.version; }
+
+        ErrorCode::Success
+    }
+
     pub extern "C" fn free_record(xhandle: i32, record_handle: i32) -> ErrorCode {
         let handles = INMEM_OPEN_WALLETS.lock().unwrap();
 
@@ -359,6 +387,7 @@ impl InmemWallet {
                 let new_tags_json = serde_json::to_string(&curr_tags).unwrap();
 
                 record.tags = CString::new(new_tags_json).unwrap();
+                record.version += 1;
             }
             None => return ErrorCode::WalletItemNotFound
         }
@@ -391,7 +420,10 @@ impl InmemWallet {
         let wallet = wallets.get_mut(&wallet_context.id).unwrap();
 
         match wallet.records.get_mut(&InmemWallet::build_record_id(&type_, &id)) {
-            Some(ref mut record) => record.tags = CString::new(tags_json).unwrap(),
+            Some(ref mut record) => {
+                record.tags = CString::new(tags_json).unwrap();
+                record.version += 1;
+            }
             None => return ErrorCode::WalletItemNotFound
         }
 
@@ -440,7 +472,8 @@ impl InmemWallet {
 
                 let new_tags_json = serde_json::to_string(&curr_tags).unwrap();
 
-                record.tags = CString::new(new_tags_json).unwrap()
+                record.tags = CString::new(new_tags_json).unwrap();
+                record.version += 1;
             }
             None => return ErrorCode::WalletItemNotFound
         }
@@ -481,6 +514,119 @@ impl InmemWallet {
         ErrorCode::Success
     }
 
+    pub extern "C" fn batch(xhandle: i32,
+                            operations_json: *const c_char) -> ErrorCode {
+        check_useful_c_str!(operations_json, ErrorCode::CommonInvalidStructure);
+
+        let operations = match serde_json::from_str::<Vec<serde_json::Value>>(&operations_json) {
+            Ok(operations) => operations,
+            Err(_) => return ErrorCode::CommonInvalidStructure
+        };
+
+        let handles = INMEM_OPEN_WALLETS.lock().unwrap();
+
+        if !handles.contains_key(&xhandle) {
+            return ErrorCode::CommonInvalidState;
+        }
+
+        let wallet_context = handles.get(&xhandle).unwrap();
+
+        let mut wallets = INMEM_WALLETS.lock().unwrap();
+
+        if !wallets.contains_key(&wallet_context.id) {
+            return ErrorCode::CommonInvalidState;
+        }
+
+        let wallet = wallets.get_mut(&wallet_context.id).unwrap();
+
+        // Operations are applied to the copy, so nothing is changed if any of them fails
+        let mut records = wallet.records.clone();
+
+        for operation in operations {
+            let (op, type_, id) = match (operation["op"].as_str(), operation["type"].as_str(), operation["id"].as_str()) {
+                (Some(op), Some(type_), Some(id)) => (op, type_, id),
+                _ => return ErrorCode::CommonInvalidStructure
+            };
+
+            let key = InmemWallet::build_record_id(type_, id);
+
+            if op == "add" {
+                let value = match operation["value"].as_str().map(|value| base64::decode(value)) {
+                    Some(Ok(value)) => value,
+                    _ => return ErrorCode::CommonInvalidStructure
+                };
+
+                records.insert(key, InmemWalletRecord {
+                    type_: CString::new(type_).unwrap(),
+                    id: CString::new(id).unwrap(),
+                    value,
+                    tags: CString::new(operation["tags"].to_string()).unwrap(),
+                    version: 1,
+                });
+                continue;
+            }
+
+            if op == "delete" {
+                if records.remove(&key).is_none() {
+                    return ErrorCode::WalletItemNotFound;
+                }
+                continue;
+            }
+
+            let record = match records.get_mut(&key) {
+                Some(record) => record,
+                None => return ErrorCode::WalletItemNotFound
+            };
+
+            match op {
+                "check_version" => {
+                    match operation["version"].as_u64() {
+                        Some(version) if version == record.version => continue,
+                        Some(_) => return ErrorCode::WalletItemVersionMismatch,
+                        None => return ErrorCode::CommonInvalidStructure
+                    }
+                }
+                "update" => {
+                    record.value = match operation["value"].as_str().map(|value| base64::decode(value)) {
+                        Some(Ok(value)) => value,
+                        _ => return ErrorCode::CommonInvalidStructure
+                    };
+                }
+                "add_tags" | "update_tags" | "delete_tags" => {
+                    let mut tags = if op == "update_tags" {
+                        HashMap::new()
+                    } else {
+                        match serde_json::from_str::<HashMap<String, String>>(record.tags.to_str().unwrap()) {
+                            Ok(tags) => tags,
+                            Err(_) => return ErrorCode::CommonInvalidStructure
+                        }
+                    };
+
+                    if op == "delete_tags" {
+                        match serde_json::from_value::<Vec<String>>(operation["tag_names"].clone()) {
+                            Ok(tag_names) => for tag_name in tag_names { tags.remove(&tag_name); },
+                            Err(_) => return ErrorCode::CommonInvalidStructure
+                        }
+                    } else {
+                        match serde_json::from_value::<HashMap<String, String>>(operation["tags"].clone()) {
+                            Ok(new_tags) => tags.extend(new_tags),
+                            Err(_) => return ErrorCode::CommonInvalidStructure
+                        }
+                    }
+
+                    record.tags = CString::new(serde_json::to_string(&tags).unwrap()).unwrap();
+                }
+                _ => return ErrorCode::CommonInvalidStructure
+            }
+
+            record.version += 1;
+        }
+
+        wallet.records = records;
+
+        ErrorCode::Success
+    }
+
     pub extern "C" fn get_storage_metadata(xhandle: i32, metadata_ptr: *mut *const c_char, metadata_handle: *mut i32) -> ErrorCode {
         let handles = INMEM_OPEN_WALLETS.lock().unwrap();
 
@@ -42,6 +42,7 @@ use utils::domain::anoncreds::revocation_registry_definition::RevocationRegistry
 use utils::domain::anoncreds::credential::CredentialInfo;
 use utils::domain::anoncreds::credential_for_proof_request::{CredentialsForProofRequest, RequestedCredential};
 use utils::domain::anoncreds::proof::Proof;
+use utils::domain::anoncreds::proof_request::{ProofRequestProblem, ProofRequestProblemType};
 use utils::domain::anoncreds::revocation_state::RevocationState;
 use utils::domain::anoncreds::revocation_registry::RevocationRegistry;
 
@@ -135,6 +136,93 @@ mod high_cases {
         }
     }
 
+    mod prover_master_secrets_management {
+        use super::*;
+
+        const PASSPHRASE: &'static str = "export_passphrase";
+
+        fn _list_master_secrets(wallet_handle: i32) -> Vec<serde_json::Value> {
+            let master_secrets_json = anoncreds::prover_list_master_secrets(wallet_handle).unwrap();
+            serde_json::from_str(&master_secrets_json).unwrap()
+        }
+
+        #[test]
+        fn prover_list_master_secrets_works_for_bound_credentials() {
+            anoncreds::init_common_wallet();
+
+            let wallet_handle = wallet::open_wallet(ANONCREDS_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            let master_secrets = _list_master_secrets(wallet_handle);
+            assert_eq!(1, master_secrets.len());
+            assert_eq!(COMMON_MASTER_SECRET, master_secrets[0]["id"]);
+            assert_eq!(3, master_secrets[0]["cred_count"]);
+
+            wallet::close_wallet(wallet_handle).unwrap();
+        }
+
+        #[test]
+        fn prover_delete_master_secret_works_for_referenced_master_secret() {
+            anoncreds::init_common_wallet();
+
+            let wallet_handle = wallet::open_wallet(ANONCREDS_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            let res = anoncreds::prover_delete_master_secret(wallet_handle, COMMON_MASTER_SECRET);
+            assert_eq!(res.unwrap_err(), ErrorCode::AnoncredsMasterSecretInUseError);
+
+            wallet::close_wallet(wallet_handle).unwrap();
+        }
+
+        #[test]
+        fn prover_delete_master_secret_works_for_unused_master_secret() {
+            let wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            anoncreds::prover_create_master_secret(wallet_handle, COMMON_MASTER_SECRET).unwrap();
+            assert_eq!(1, _list_master_secrets(wallet_handle).len());
+
+            anoncreds::prover_delete_master_secret(wallet_handle, COMMON_MASTER_SECRET).unwrap();
+            assert_eq!(0, _list_master_secrets(wallet_handle).len());
+
+            wallet::close_wallet(wallet_handle).unwrap();
+        }
+
+        #[test]
+        fn prover_export_import_master_secret_works() {
+            let wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            let other_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            anoncreds::prover_create_master_secret(wallet_handle, COMMON_MASTER_SECRET).unwrap();
+
+            let exported_master_secret_json = anoncreds::prover_export_master_secret(wallet_handle, COMMON_MASTER_SECRET, PASSPHRASE).unwrap();
+
+            let master_secret_id = anoncreds::prover_import_master_secret(other_wallet_handle,
+                                                                          Some(COMMON_MASTER_SECRET),
+                                                                          &exported_master_secret_json,
+                                                                          PASSPHRASE).unwrap();
+            assert_eq!(COMMON_MASTER_SECRET, master_secret_id);
+
+            let master_secrets = _list_master_secrets(other_wallet_handle);
+            assert_eq!(COMMON_MASTER_SECRET, master_secrets[0]["id"]);
+            assert_eq!(0, master_secrets[0]["cred_count"]);
+
+            wallet::close_wallet(wallet_handle).unwrap();
+            wallet::close_wallet(other_wallet_handle).unwrap();
+        }
+
+        #[test]
+        fn prover_import_master_secret_works_for_wrong_passphrase() {
+            let wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            anoncreds::prover_create_master_secret(wallet_handle, COMMON_MASTER_SECRET).unwrap();
+
+            let exported_master_secret_json = anoncreds::prover_export_master_secret(wallet_handle, COMMON_MASTER_SECRET, PASSPHRASE).unwrap();
+
+            let res = anoncreds::prover_import_master_secret(wallet_handle, None, &exported_master_secret_json, "other_passphrase");
+            assert_eq!(res.unwrap_err(), ErrorCode::CommonInvalidStructure);
+
+            wallet::close_wallet(wallet_handle).unwrap();
+        }
+    }
+
     mod prover_create_credential_req {
         use super::*;
 
@@ -2475,6 +2563,99 @@ mod high_cases {
         }
     }
 
+    mod prover_select_credentials_for_proof_req {
+        use super::*;
+
+        fn _proof_req() -> String {
+            json!({
+               "nonce":"123432421212",
+               "name":"proof_req_1",
+               "version":"0.1",
+               "requested_attributes": {
+                   "attr1_referent": { "name":"name" },
+                   "attr2_referent": { "name":"not_present_attr" }
+               },
+               "requested_predicates": {
+                   "predicate1_referent": { "name":"age", "p_type":">=", "p_value":18 }
+               },
+            }).to_string()
+        }
+
+        #[test]
+        fn prover_select_credentials_for_proof_req_works_for_default_policy() {
+            anoncreds::init_common_wallet();
+
+            let wallet_handle = wallet::open_wallet(ANONCREDS_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            let (requested_credentials_json, unmet_referents_json) =
+                anoncreds::prover_select_credentials_for_proof_req(wallet_handle, &_proof_req(), None).unwrap();
+
+            let requested_credentials: serde_json::Value = serde_json::from_str(&requested_credentials_json).unwrap();
+            assert_eq!(requested_credentials["requested_attributes"]["attr1_referent"]["cred_id"], CREDENTIAL3_ID);
+            assert_eq!(requested_credentials["requested_attributes"]["attr1_referent"]["revealed"], true);
+            assert_eq!(requested_credentials["requested_predicates"]["predicate1_referent"]["cred_id"], CREDENTIAL3_ID);
+
+            let unmet_referents: Vec<String> = serde_json::from_str(&unmet_referents_json).unwrap();
+            assert_eq!(vec!["attr2_referent".to_string()], unmet_referents);
+
+            wallet::close_wallet(wallet_handle).unwrap();
+        }
+
+        #[test]
+        fn prover_select_credentials_for_proof_req_works_for_preferred_issuer() {
+            anoncreds::init_common_wallet();
+
+            let wallet_handle = wallet::open_wallet(ANONCREDS_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            let selection_policy = json!({"issuer_did": ISSUER_DID}).to_string();
+
+            let (requested_credentials_json, _) =
+                anoncreds::prover_select_credentials_for_proof_req(wallet_handle, &_proof_req(), Some(&selection_policy)).unwrap();
+
+            let requested_credentials: serde_json::Value = serde_json::from_str(&requested_credentials_json).unwrap();
+            assert_eq!(requested_credentials["requested_attributes"]["attr1_referent"]["cred_id"], CREDENTIAL1_ID);
+            assert_eq!(requested_credentials["requested_predicates"]["predicate1_referent"]["cred_id"], CREDENTIAL1_ID);
+
+            wallet::close_wallet(wallet_handle).unwrap();
+        }
+
+        #[test]
+        fn prover_select_credentials_for_proof_req_works_for_invalid_policy() {
+            anoncreds::init_common_wallet();
+
+            let wallet_handle = wallet::open_wallet(ANONCREDS_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            let selection_policy = json!({"strategy": "oldest"}).to_string();
+
+            let res = anoncreds::prover_select_credentials_for_proof_req(wallet_handle, &_proof_req(), Some(&selection_policy));
+            assert_eq!(res.unwrap_err(), ErrorCode::CommonInvalidParam4);
+
+            wallet::close_wallet(wallet_handle).unwrap();
+        }
+
+        #[test]
+        fn prover_select_credentials_for_proof_req_works_for_restriction_on_master_secret_id() {
+            anoncreds::init_common_wallet();
+
+            let wallet_handle = wallet::open_wallet(ANONCREDS_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            let proof_req = json!({
+               "nonce":"123432421212",
+               "name":"proof_req_1",
+               "version":"0.1",
+               "requested_attributes": {
+                   "attr1_referent": { "name":"name", "restrictions": { "master_secret_id": COMMON_MASTER_SECRET } }
+               },
+               "requested_predicates": {},
+            }).to_string();
+
+            let res = anoncreds::prover_select_credentials_for_proof_req(wallet_handle, &proof_req, None);
+            assert_eq!(res.unwrap_err(), ErrorCode::CommonInvalidStructure);
+
+            wallet::close_wallet(wallet_handle).unwrap();
+        }
+    }
+
     mod prover_search_credentials_for_proof_req {
         use super::*;
 
@@ -3122,6 +3303,114 @@ mod high_cases {
                                                          "{}").unwrap();
             assert!(!valid);
         }
+
+        #[test]
+        fn verifier_verify_proof_with_options_works_for_default_options() {
+            let valid = anoncreds::verifier_verify_proof_with_options(&anoncreds::proof_request_attr(),
+                                                                      &anoncreds::proof_json(),
+                                                                      &anoncreds::schemas_for_proof(),
+                                                                      &anoncreds::cred_defs_for_proof(),
+                                                                      "{}",
+                                                                      "{}",
+                                                                      None).unwrap();
+            assert!(valid);
+        }
+
+        #[test]
+        fn verifier_verify_proof_with_options_works_for_strict_encoding_and_non_standard_encoded_value() {
+            let options_json = json!({"strict_encoding": true}).to_string();
+
+            let valid = anoncreds::verifier_verify_proof_with_options(&anoncreds::proof_request_attr(),
+                                                                      &anoncreds::proof_json(),
+                                                                      &anoncreds::schemas_for_proof(),
+                                                                      &anoncreds::cred_defs_for_proof(),
+                                                                      "{}",
+                                                                      "{}",
+                                                                      Some(&options_json)).unwrap();
+            assert!(!valid);
+        }
+    }
+
+    mod validate_proof_request {
+        use super::*;
+
+        #[test]
+        fn validate_proof_request_works_for_valid_proof_request() {
+            let problems_json = anoncreds::validate_proof_request(&anoncreds::proof_request_attr_and_predicate(),
+                                                                  Some(&anoncreds::schemas_for_proof()), None).unwrap();
+            let problems: Vec<ProofRequestProblem> = serde_json::from_str(&problems_json).unwrap();
+            assert!(problems.is_empty());
+        }
+
+        #[test]
+        fn validate_proof_request_works_for_problems() {
+            let proof_req_json = json!({
+               "nonce":"123432421212",
+               "name":"proof_req_1",
+               "version":"0.1",
+               "requested_attributes": json!({
+                   "attr1_referent": json!({
+                       "name":"name",
+                       "restrictions": json!({ "isuer_did": ISSUER_DID })
+                   })
+               }),
+               "requested_predicates": json!({
+                   "predicate1_referent": json!({
+                       "name":"weight",
+                       "p_type":">=",
+                       "p_value":18,
+                       "restrictions": json!({ "schema_id": anoncreds::gvt_schema_id() }),
+                       "non_revoked": json!({ "from":200, "to":300 })
+                   })
+               }),
+               "non_revoked": json!({ "from":80, "to":100 })
+            }).to_string();
+
+            let problems_json = anoncreds::validate_proof_request(&proof_req_json, Some(&anoncreds::schemas_for_proof()), None).unwrap();
+            let problems: Vec<ProofRequestProblem> = serde_json::from_str(&problems_json).unwrap();
+
+            let attr_problems: Vec<ProofRequestProblemType> = problems.iter()
+                .filter(|problem| problem.referent == Some("attr1_referent".to_string()))
+                .map(|problem| problem.type_.clone())
+                .collect();
+            assert_eq!(vec![ProofRequestProblemType::UnknownRestrictionKey], attr_problems);
+
+            let predicate_problems: Vec<ProofRequestProblemType> = problems.iter()
+                .filter(|problem| problem.referent == Some("predicate1_referent".to_string()))
+                .map(|problem| problem.type_.clone())
+                .collect();
+            assert_eq!(vec![ProofRequestProblemType::ConflictingNonRevokedInterval, ProofRequestProblemType::AttributeNotInSchema], predicate_problems);
+        }
+
+        #[test]
+        fn validate_proof_request_works_without_schemas() {
+            let problems_json = anoncreds::validate_proof_request(&anoncreds::proof_request_attr(), None, None).unwrap();
+            let problems: Vec<ProofRequestProblem> = serde_json::from_str(&problems_json).unwrap();
+            assert!(problems.is_empty());
+        }
+
+        #[test]
+        fn validate_proof_request_works_for_predicate_on_non_numeric_attribute() {
+            let attr_types_json = format!(r#"{{"{}": {{"name": "string", "age": "number"}}}}"#, anoncreds::gvt_schema_id());
+
+            let proof_req_json = json!({
+               "nonce":"123432421212",
+               "name":"proof_req_1",
+               "version":"0.1",
+               "requested_attributes": json!({}),
+               "requested_predicates": json!({
+                   "predicate1_referent": json!({"name":"name", "p_type":">=", "p_value":1}),
+                   "predicate2_referent": json!({"name":"age", "p_type":">=", "p_value":18})
+               })
+            }).to_string();
+
+            let problems_json = anoncreds::validate_proof_request(&proof_req_json, Some(&anoncreds::schemas_for_proof()), Some(&attr_types_json)).unwrap();
+            let problems: Vec<ProofRequestProblem> = serde_json::from_str(&problems_json).unwrap();
+
+            assert_eq!(1, problems.len());
+            assert_eq!(ProofRequestProblemType::PredicateOnNonNumericAttribute, problems[0].type_);
+            assert_eq!(Some("predicate1_referent".to_string()), problems[0].referent);
+        }
     }
 }
 
@@ -5777,6 +6066,131 @@ mod demos {
     }
 
 
+    #[cfg(feature = "revocation_tests")]
+    #[test]
+    fn anoncreds_works_for_issuance_on_demand_revocation_strategy_revoke_credentials() {
+        utils::setup();
+
+        //1. Issuer creates wallet, gets wallet handle
+        let issuer_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+        //2. Prover creates wallet, gets wallet handle
+        let prover_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+        //3 Issuer creates Schema, Credential Definition and Revocation Registry
+        let (_, _,
+            cred_def_id, cred_def_json,
+            rev_reg_id, revoc_reg_def_json, _,
+            blob_storage_reader_handle) = anoncreds::multi_steps_issuer_revocation_preparation(issuer_wallet_handle,
+                                                                                               ISSUER_DID,
+                                                                                               GVT_SCHEMA_NAME,
+                                                                                               GVT_SCHEMA_ATTRIBUTES,
+                                                                                               r#"{"max_cred_num":5, "issuance_type":"ISSUANCE_ON_DEMAND"}"#);
+
+        //4. Issuance two Credentials for Prover
+        anoncreds::prover_create_master_secret(prover_wallet_handle, COMMON_MASTER_SECRET).unwrap();
+
+        let (cred_rev_id_1, _) = anoncreds::multi_steps_create_revocation_credential(
+            COMMON_MASTER_SECRET,
+            prover_wallet_handle,
+            issuer_wallet_handle,
+            CREDENTIAL1_ID,
+            &anoncreds::gvt_credential_values_json(),
+            &cred_def_id,
+            &cred_def_json,
+            &rev_reg_id,
+            &revoc_reg_def_json,
+            blob_storage_reader_handle,
+        );
+
+        let (cred_rev_id_2, _) = anoncreds::multi_steps_create_revocation_credential(
+            COMMON_MASTER_SECRET,
+            prover_wallet_handle,
+            issuer_wallet_handle,
+            CREDENTIAL2_ID,
+            &anoncreds::gvt2_credential_values_json(),
+            &cred_def_id,
+            &cred_def_json,
+            &rev_reg_id,
+            &revoc_reg_def_json,
+            blob_storage_reader_handle,
+        );
+
+        //5. Issuer revokes both Credentials at once
+        let cred_revoc_ids_json = json!([cred_rev_id_1, cred_rev_id_2]).to_string();
+        let revoc_reg_delta_json = anoncreds::issuer_revoke_credentials(issuer_wallet_handle,
+                                                                        blob_storage_reader_handle,
+                                                                        &rev_reg_id,
+                                                                        &cred_revoc_ids_json).unwrap();
+
+        //6. Check that single delta contains both revoked Credentials
+        let revoc_reg_delta: serde_json::Value = serde_json::from_str(&revoc_reg_delta_json).unwrap();
+        let revoked: HashSet<u32> = serde_json::from_value(revoc_reg_delta["value"]["revoked"].clone()).unwrap();
+
+        let expected_revoked: HashSet<u32> = vec![cred_rev_id_1.parse().unwrap(), cred_rev_id_2.parse().unwrap()].into_iter().collect();
+        assert_eq!(expected_revoked, revoked);
+
+        //7. Issuer can not revoke already revoked Credential
+        let res = anoncreds::issuer_revoke_credential(issuer_wallet_handle, blob_storage_reader_handle, &rev_reg_id, &cred_rev_id_1);
+        assert_eq!(res.unwrap_err(), ErrorCode::AnoncredsInvalidUserRevocId);
+
+        wallet::close_wallet(issuer_wallet_handle).unwrap();
+        wallet::close_wallet(prover_wallet_handle).unwrap();
+
+        utils::tear_down();
+    }
+
+    #[cfg(feature = "revocation_tests")]
+    #[test]
+    fn anoncreds_works_for_revoke_credentials_with_not_issued_credential_id() {
+        utils::setup();
+
+        //1. Issuer creates wallet, gets wallet handle
+        let issuer_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+        //2. Prover creates wallet, gets wallet handle
+        let prover_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+        //3 Issuer creates Schema, Credential Definition and Revocation Registry
+        let (_, _,
+            cred_def_id, cred_def_json,
+            rev_reg_id, revoc_reg_def_json, _,
+            blob_storage_reader_handle) = anoncreds::multi_steps_issuer_revocation_preparation(issuer_wallet_handle,
+                                                                                               ISSUER_DID,
+                                                                                               GVT_SCHEMA_NAME,
+                                                                                               GVT_SCHEMA_ATTRIBUTES,
+                                                                                               r#"{"max_cred_num":5, "issuance_type":"ISSUANCE_ON_DEMAND"}"#);
+
+        //4. Issuance Credential for Prover
+        anoncreds::prover_create_master_secret(prover_wallet_handle, COMMON_MASTER_SECRET).unwrap();
+
+        let (cred_rev_id, _) = anoncreds::multi_steps_create_revocation_credential(
+            COMMON_MASTER_SECRET,
+            prover_wallet_handle,
+            issuer_wallet_handle,
+            CREDENTIAL1_ID,
+            &anoncreds::gvt_credential_values_json(),
+            &cred_def_id,
+            &cred_def_json,
+            &rev_reg_id,
+            &revoc_reg_def_json,
+            blob_storage_reader_handle,
+        );
+
+        //5. Issuer revokes issued and not issued Credentials
+        let cred_revoc_ids_json = json!([cred_rev_id, "3"]).to_string();
+        let res = anoncreds::issuer_revoke_credentials(issuer_wallet_handle, blob_storage_reader_handle, &rev_reg_id, &cred_revoc_ids_json);
+        assert_eq!(res.unwrap_err(), ErrorCode::AnoncredsInvalidUserRevocId);
+
+        //6. Issued Credential is not revoked
+        anoncreds::issuer_revoke_credential(issuer_wallet_handle, blob_storage_reader_handle, &rev_reg_id, &cred_rev_id).unwrap();
+
+        wallet::close_wallet(issuer_wallet_handle).unwrap();
+        wallet::close_wallet(prover_wallet_handle).unwrap();
+
+        utils::tear_down();
+    }
+
     #[test]
     fn anoncreds_works_for_multiple_requested_predicates_from_one_credential() {
         utils::setup();
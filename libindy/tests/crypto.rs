@@ -16,6 +16,7 @@ extern crate serde_derive;
 #[macro_use]
 extern crate serde_json;
 
+extern crate base64;
 extern crate byteorder;
 extern crate indy;
 extern crate indy_crypto;
@@ -32,7 +33,7 @@ use indy::api as api;
 #[macro_use]
 mod utils;
 
-use utils::{wallet, crypto, did};
+use utils::{wallet, crypto, did, ledger};
 use utils::constants::*;
 
 use indy::api::ErrorCode;
@@ -40,12 +41,29 @@ use indy::api::ErrorCode;
 pub const ENCRYPTED_MESSAGE: &'static [u8; 45] = &[187, 227, 10, 29, 46, 178, 12, 179, 197, 69, 171, 70, 228, 204, 52, 22, 199, 54, 62, 13, 115, 5, 216, 66, 20, 131, 121, 29, 251, 224, 253, 201, 75, 73, 225, 237, 219, 133, 35, 217, 131, 135, 232, 129, 32];
 pub const SIGNATURE: &'static [u8; 64] = &[169, 215, 8, 225, 7, 107, 110, 9, 193, 162, 202, 214, 162, 66, 238, 211, 63, 209, 12, 196, 8, 211, 55, 27, 120, 94, 204, 147, 53, 104, 103, 61, 60, 249, 237, 127, 103, 46, 220, 223, 10, 95, 75, 53, 245, 210, 241, 151, 191, 41, 48, 30, 9, 16, 78, 252, 157, 206, 210, 145, 125, 133, 109, 11];
 
+// Packed with libsodium outside of libindy for recipient MY1_SEED and sender MY2_SEED
+pub const PACKED_AUTHCRYPT_MESSAGE: &'static str = r#"{"protected":"eyJlbmMiOiJjaGFjaGEyMHBvbHkxMzA1X2lldGYiLCJ0eXAiOiJKV00vMS4wIiwiYWxnIjoiQXV0aGNyeXB0IiwicmVjaXBpZW50cyI6W3siZW5jcnlwdGVkX2tleSI6IllIY3gtaXQ2LTNvUTF5Ymh6cFpvNFl2RXBNZnJBWUI0UnB5Smt4X3pYSkNJR2NYVzJaRUV6dE9kUUxsZkcxTHAiLCJoZWFkZXIiOnsia2lkIjoiR2paV3NCTGdaQ1IxOGFMNDY4SkFUN3c5Q1pSaUJucHhVUFBneVF4aDR2b2EiLCJpdiI6Ikk3RHFYb3loNUhPdjVBMzV4MmV0YXpIWkxqczVmbmloIiwic2VuZGVyIjoiWUdrNFhWeDI5RWk4QnBJVHlHcERKVTQtRUdXU3hTYWFYU0tRS2Y1cUZXR2lIempOQ2prOWUtaHUyVGFEQU9CcFZ3ZDc4bEVlaUVPOXp3Znhvd0ctLXc0Z3ZRVWp1OUJmdFJ5SEkxSmo1V21pSFdZdnh2eGwzeUNtZFE9PSJ9fV19","iv":"yv15OMOMkyLNq-me","ciphertext":"S1Q2HfhSyEUQSDn85wIwWKF9gFL0yav7LQD7IfA=","tag":"Lew869gkJg7EdhkXj1sNTA=="}"#;
+pub const PACKED_ANONCRYPT_BINARY_MESSAGE: &'static str = r#"{"protected":"eyJlbmMiOiJjaGFjaGEyMHBvbHkxMzA1X2lldGYiLCJ0eXAiOiJKV00vMS4wIiwiYWxnIjoiQW5vbmNyeXB0IiwicmVjaXBpZW50cyI6W3siZW5jcnlwdGVkX2tleSI6Ik9kRW8xV0ppdERaSlhfVXVlUlRBajY2SXFfZ3lURUVEaW5WdFlNcWFhRGpZOFY2SW1tSDJqTjZ0N1p0TGtOcWxzZWZSZkR0emlpa0xDcnVqOWI1TWZjeVcwOExfWjFaTGVSWU1JWXExOTN3PSIsImhlYWRlciI6eyJraWQiOiJHalpXc0JMZ1pDUjE4YUw0NjhKQVQ3dzlDWlJpQm5weFVQUGd5UXhoNHZvYSJ9fV19","iv":"6jCg1A2E-GZqfqJ5","ciphertext":"i4IEDZ-1","tag":"vi7gl2sH9JK7RGGszvxvfw=="}"#;
+pub const BINARY_MESSAGE: &'static [u8; 6] = &[0, 159, 146, 150, 255, 1];
+
 fn setup_with_key() -> (i32, String) {
     let wallet_handle = utils::setup_with_wallet();
     let verkey = crypto::create_key(wallet_handle, None).unwrap();
     (wallet_handle, verkey)
 }
 
+fn base64_urlsafe_encode(doc: &[u8]) -> String {
+    base64::encode_config(doc, base64::URL_SAFE_NO_PAD)
+}
+
+fn base64_urlsafe_decode(s: &str) -> Vec<u8> {
+    let mut s = s.to_string();
+    while s.len() % 4 != 0 {
+        s.push('=');
+    }
+    base64::decode_config(&s, base64::URL_SAFE).unwrap()
+}
+
 mod high_cases {
     use super::*;
 
@@ -73,6 +91,32 @@ mod high_cases {
             utils::tear_down_with_wallet(wallet_handle);
         }
 
+        #[test]
+        #[cfg(feature = "ec_openssl")]
+        fn indy_create_key_works_for_ec_crypto_types() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            for crypto_type in ["secp256k1", "secp256r1"].iter() {
+                let verkey = crypto::create_key_with_crypto_type(wallet_handle, Some(MY1_SEED), crypto_type).unwrap();
+
+                let parts: Vec<&str> = verkey.split(':').collect();
+                assert_eq!(parts[0].from_base58().unwrap().len(), 33);
+                assert_eq!(parts[1], *crypto_type);
+            }
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_create_key_works_for_unknown_crypto_type() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = crypto::create_key_with_crypto_type(wallet_handle, None, "unknown_crypto_type");
+            assert_eq!(ErrorCode::UnknownCryptoTypeError, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
         #[test]
         fn indy_create_key_works_for_invalid_wallet_handle() {
             let wallet_handle = utils::setup_with_wallet();
@@ -253,6 +297,23 @@ mod high_cases {
         }
 
 
+        #[test]
+        #[cfg(feature = "ec_openssl")]
+        fn indy_crypto_sign_verify_works_for_ec_crypto_types() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            for crypto_type in ["secp256k1", "secp256r1"].iter() {
+                let verkey = crypto::create_key_with_crypto_type(wallet_handle, None, crypto_type).unwrap();
+
+                let signature = crypto::sign(wallet_handle, &verkey, MESSAGE.as_bytes()).unwrap();
+
+                assert!(crypto::verify(&verkey, MESSAGE.as_bytes(), &signature).unwrap());
+                assert!(!crypto::verify(&verkey, "other message".as_bytes(), &signature).unwrap());
+            }
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
         #[test]
         fn indy_crypto_verify_works_for_other_signer() {
             let valid = crypto::verify(&VERKEY_MY2, MESSAGE.as_bytes(), SIGNATURE).unwrap();
@@ -267,6 +328,103 @@ mod high_cases {
         }
     }
 
+    mod crypto_verify_for_did {
+        use super::*;
+
+        fn _write_nym_with_abbreviated_verkey() -> (i32, i32, String) {
+            let (trustee_wallet_handle, pool_handle, trustee_did) = utils::setup_trustee();
+            let (did, verkey) = did::create_and_store_my_did(trustee_wallet_handle, None).unwrap();
+            let abbr_verkey = did::abbreviate_verkey(&did, &verkey).unwrap();
+
+            let nym_request = ledger::build_nym_request(&trustee_did, &did, Some(&abbr_verkey), None, None).unwrap();
+            let nym_resp = ledger::sign_and_submit_request(pool_handle, trustee_wallet_handle, &trustee_did, &nym_request).unwrap();
+
+            let get_nym_request = ledger::build_get_nym_request(Some(&did), &did).unwrap();
+            ledger::submit_request_with_retries(pool_handle, &get_nym_request, &nym_resp).unwrap();
+
+            (trustee_wallet_handle, pool_handle, did)
+        }
+
+        #[test]
+        fn indy_crypto_verify_for_did_works_for_my_did() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let (did, _) = did::create_and_store_my_did(wallet_handle, Some(MY1_SEED)).unwrap();
+
+            assert!(crypto::verify_for_did(-1, wallet_handle, &did, MESSAGE.as_bytes(), SIGNATURE, false).unwrap());
+            assert!(!crypto::verify_for_did(-1, wallet_handle, &did, "other message".as_bytes(), SIGNATURE, false).unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_verify_for_did_works_for_their_did() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            did::store_their_did_from_parts(wallet_handle, DID_MY1, VERKEY_MY1).unwrap();
+
+            assert!(crypto::verify_for_did(-1, wallet_handle, DID_MY1, MESSAGE.as_bytes(), SIGNATURE, false).unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_verify_for_did_works_for_get_key_from_ledger() {
+            let (trustee_wallet_handle, pool_handle, did) = _write_nym_with_abbreviated_verkey();
+            let verkey = did::key_for_local_did(trustee_wallet_handle, &did).unwrap();
+            let signature = crypto::sign(trustee_wallet_handle, &verkey, MESSAGE.as_bytes()).unwrap();
+
+            let wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            assert!(crypto::verify_for_did(pool_handle, wallet_handle, &did, MESSAGE.as_bytes(), &signature, false).unwrap());
+
+            let cached_verkey = did::key_for_local_did(wallet_handle, &did).unwrap();
+            assert_eq!(verkey, cached_verkey);
+
+            wallet::close_wallet(trustee_wallet_handle).unwrap();
+            utils::tear_down_with_wallet_and_pool(wallet_handle, pool_handle);
+        }
+
+        #[test]
+        fn indy_crypto_verify_for_did_works_for_require_current_and_outdated_cache() {
+            let (trustee_wallet_handle, pool_handle, did) = _write_nym_with_abbreviated_verkey();
+            let verkey = did::key_for_local_did(trustee_wallet_handle, &did).unwrap();
+            let signature = crypto::sign(trustee_wallet_handle, &verkey, MESSAGE.as_bytes()).unwrap();
+
+            let wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            did::store_their_did_from_parts(wallet_handle, &did, VERKEY_MY2).unwrap();
+
+            assert!(!crypto::verify_for_did(pool_handle, wallet_handle, &did, MESSAGE.as_bytes(), &signature, false).unwrap());
+            assert!(crypto::verify_for_did(pool_handle, wallet_handle, &did, MESSAGE.as_bytes(), &signature, true).unwrap());
+
+            let cached_verkey = did::key_for_local_did(wallet_handle, &did).unwrap();
+            assert_eq!(verkey, cached_verkey);
+
+            wallet::close_wallet(trustee_wallet_handle).unwrap();
+            utils::tear_down_with_wallet_and_pool(wallet_handle, pool_handle);
+        }
+
+        #[test]
+        fn indy_crypto_verify_for_did_works_for_unknown_did() {
+            let (wallet_handle, pool_handle) = utils::setup_with_wallet_and_pool();
+
+            let res = crypto::verify_for_did(pool_handle, wallet_handle, DID, MESSAGE.as_bytes(), SIGNATURE, true);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet_and_pool(wallet_handle, pool_handle);
+        }
+
+        #[test]
+        fn indy_crypto_verify_for_did_works_for_invalid_did() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = crypto::verify_for_did(-1, wallet_handle, INVALID_BASE58_DID, MESSAGE.as_bytes(), SIGNATURE, false);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
     mod auth_crypt {
         use super::*;
 
@@ -352,6 +510,25 @@ mod high_cases {
             utils::tear_down_with_wallet(sender_wallet_handle);
         }
 
+        #[test]
+        #[cfg(feature = "ec_openssl")]
+        fn indy_crypto_auth_decrypt_works_for_ec_crypto_types() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            for crypto_type in ["secp256k1", "secp256r1"].iter() {
+                let sender_vk = crypto::create_key_with_crypto_type(wallet_handle, None, crypto_type).unwrap();
+                let recipient_vk = crypto::create_key_with_crypto_type(wallet_handle, None, crypto_type).unwrap();
+
+                let encrypted_msg = crypto::auth_crypt(wallet_handle, &sender_vk, &recipient_vk, MESSAGE.as_bytes()).unwrap();
+
+                let (vk, msg) = crypto::auth_decrypt(wallet_handle, &recipient_vk, &encrypted_msg).unwrap();
+                assert_eq!(MESSAGE.as_bytes().to_vec(), msg);
+                assert_eq!(sender_vk, vk);
+            }
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
         #[test]
         fn indy_crypto_auth_decrypt_works_for_invalid_msg() {
             let sender_wallet_handle = utils::setup_with_wallet();
@@ -475,6 +652,891 @@ mod high_cases {
             utils::tear_down_with_wallet(wallet_handle);
         }
     }
+
+    mod encrypt_stream {
+        use super::*;
+
+        fn _encrypt(wallet_handle: i32, receiver_keys: &str, sender: Option<&str>, data: &[u8], feed_size: usize) -> Vec<u8> {
+            let stream_handle = crypto::encrypt_stream_open(wallet_handle, receiver_keys, sender).unwrap();
+
+            let mut encrypted = Vec::new();
+            for part in data.chunks(feed_size) {
+                encrypted.extend(crypto::encrypt_stream_feed(stream_handle, part).unwrap());
+            }
+            encrypted.extend(crypto::encrypt_stream_finish(stream_handle).unwrap());
+
+            encrypted
+        }
+
+        fn _decrypt(wallet_handle: i32, encrypted: &[u8], feed_size: usize) -> Result<(String, Vec<u8>), ErrorCode> {
+            let stream_handle = crypto::decrypt_stream_open(wallet_handle)?;
+
+            let mut data = Vec::new();
+            for part in encrypted.chunks(feed_size) {
+                data.extend(crypto::decrypt_stream_feed(stream_handle, part)?);
+            }
+
+            let (metadata, rest) = crypto::decrypt_stream_finish(stream_handle)?;
+            data.extend(rest);
+
+            Ok((metadata, data))
+        }
+
+        fn _large_data() -> Vec<u8> {
+            (0..200_000).map(|i| (i % 251) as u8).collect()
+        }
+
+        #[test]
+        fn indy_crypto_stream_works_for_anoncrypt() {
+            let (sender_wallet_handle, _) = setup_with_key();
+
+            let recipient_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            let recipient_vk = crypto::create_key(recipient_wallet_handle, None).unwrap();
+
+            let data = _large_data();
+            let encrypted = _encrypt(sender_wallet_handle, &json!([recipient_vk]).to_string(), None, &data, 10_000);
+
+            let (metadata, decrypted) = _decrypt(recipient_wallet_handle, &encrypted, 7_000).unwrap();
+            let metadata: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+
+            assert_eq!(data, decrypted);
+            assert_eq!(recipient_vk, metadata["recipient_verkey"].as_str().unwrap());
+            assert!(metadata["sender_verkey"].is_null());
+
+            wallet::close_wallet(recipient_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(sender_wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_stream_works_for_authcrypt_multiple_recipients() {
+            let (sender_wallet_handle, sender_vk) = setup_with_key();
+
+            let recipient1_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            let recipient1_vk = crypto::create_key(recipient1_wallet_handle, None).unwrap();
+
+            let recipient2_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            let recipient2_vk = crypto::create_key(recipient2_wallet_handle, None).unwrap();
+
+            let data = _large_data();
+            let receiver_keys = json!([recipient1_vk, recipient2_vk]).to_string();
+            let encrypted = _encrypt(sender_wallet_handle, &receiver_keys, Some(&sender_vk), &data, 100_000);
+
+            for &(wallet_handle, ref verkey) in [(recipient1_wallet_handle, &recipient1_vk), (recipient2_wallet_handle, &recipient2_vk)].iter() {
+                let (metadata, decrypted) = _decrypt(wallet_handle, &encrypted, 1_000).unwrap();
+                let metadata: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+
+                assert_eq!(data, decrypted);
+                assert_eq!(verkey.as_str(), metadata["recipient_verkey"].as_str().unwrap());
+                assert_eq!(sender_vk, metadata["sender_verkey"].as_str().unwrap());
+            }
+
+            wallet::close_wallet(recipient1_wallet_handle).unwrap();
+            wallet::close_wallet(recipient2_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(sender_wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_stream_works_for_truncated_stream() {
+            let (wallet_handle, verkey) = setup_with_key();
+
+            let encrypted = _encrypt(wallet_handle, &json!([verkey]).to_string(), None, &_large_data(), 10_000);
+
+            let res = _decrypt(wallet_handle, &encrypted[..encrypted.len() / 2], 10_000);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_stream_works_for_unknown_recipient() {
+            let (sender_wallet_handle, _) = setup_with_key();
+
+            let encrypted = _encrypt(sender_wallet_handle, &json!([VERKEY_MY2]).to_string(), None, MESSAGE.as_bytes(), 10);
+
+            let recipient_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            let res = _decrypt(recipient_wallet_handle, &encrypted, 10_000);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            wallet::close_wallet(recipient_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(sender_wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_stream_works_for_closed_stream() {
+            let (wallet_handle, verkey) = setup_with_key();
+
+            let stream_handle = crypto::encrypt_stream_open(wallet_handle, &json!([verkey]).to_string(), None).unwrap();
+            crypto::encrypt_stream_finish(stream_handle).unwrap();
+
+            let res = crypto::encrypt_stream_feed(stream_handle, MESSAGE.as_bytes());
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_stream_works_for_header_in_first_feed() {
+            let (wallet_handle, verkey) = setup_with_key();
+
+            let stream_handle = crypto::encrypt_stream_open(wallet_handle, &json!([verkey]).to_string(), None).unwrap();
+            let header = crypto::encrypt_stream_feed(stream_handle, &[1, 2, 3]).unwrap();
+            crypto::encrypt_stream_close(stream_handle).unwrap();
+
+            let stream_handle = crypto::decrypt_stream_open(wallet_handle).unwrap();
+            let data = crypto::decrypt_stream_feed(stream_handle, &header).unwrap();
+            assert!(data.is_empty());
+            crypto::decrypt_stream_close(stream_handle).unwrap();
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_stream_close_works() {
+            let (wallet_handle, verkey) = setup_with_key();
+
+            let stream_handle = crypto::encrypt_stream_open(wallet_handle, &json!([verkey]).to_string(), None).unwrap();
+            crypto::encrypt_stream_feed(stream_handle, MESSAGE.as_bytes()).unwrap();
+            crypto::encrypt_stream_close(stream_handle).unwrap();
+
+            let res = crypto::encrypt_stream_finish(stream_handle);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            let stream_handle = crypto::decrypt_stream_open(wallet_handle).unwrap();
+            crypto::decrypt_stream_close(stream_handle).unwrap();
+
+            let res = crypto::decrypt_stream_finish(stream_handle);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_stream_close_works_for_closed_stream() {
+            let (wallet_handle, verkey) = setup_with_key();
+
+            let stream_handle = crypto::encrypt_stream_open(wallet_handle, &json!([verkey]).to_string(), None).unwrap();
+            crypto::encrypt_stream_close(stream_handle).unwrap();
+
+            let res = crypto::encrypt_stream_close(stream_handle);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            let stream_handle = crypto::decrypt_stream_open(wallet_handle).unwrap();
+            crypto::decrypt_stream_close(stream_handle).unwrap();
+
+            let res = crypto::decrypt_stream_close(stream_handle);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod pack_message {
+        use super::*;
+
+        #[test]
+        fn indy_pack_message_works_for_anoncrypt_multiple_recipients() {
+            let (sender_wallet_handle, _) = setup_with_key();
+
+            let recipient1_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            let recipient1_vk = crypto::create_key(recipient1_wallet_handle, None).unwrap();
+
+            let recipient2_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            let recipient2_vk = crypto::create_key(recipient2_wallet_handle, None).unwrap();
+
+            let receiver_keys = json!([recipient1_vk, recipient2_vk]).to_string();
+
+            let jwe = crypto::pack_message(sender_wallet_handle, MESSAGE.as_bytes(), &receiver_keys, None).unwrap();
+
+            for &(wallet_handle, ref verkey) in [(recipient1_wallet_handle, &recipient1_vk), (recipient2_wallet_handle, &recipient2_vk)].iter() {
+                let unpacked = crypto::unpack_message(wallet_handle, &jwe).unwrap();
+                let unpacked: serde_json::Value = serde_json::from_slice(&unpacked).unwrap();
+
+                assert_eq!(MESSAGE.as_bytes(), base64_urlsafe_decode(unpacked["message"].as_str().unwrap()).as_slice());
+                assert_eq!(verkey.as_str(), unpacked["recipient_verkey"].as_str().unwrap());
+                assert!(unpacked["sender_verkey"].is_null());
+            }
+
+            wallet::close_wallet(recipient1_wallet_handle).unwrap();
+            wallet::close_wallet(recipient2_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(sender_wallet_handle);
+        }
+
+        #[test]
+        fn indy_pack_message_works_for_authcrypt_multiple_recipients() {
+            let (sender_wallet_handle, sender_vk) = setup_with_key();
+
+            let recipient1_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            let recipient1_vk = crypto::create_key(recipient1_wallet_handle, None).unwrap();
+
+            let recipient2_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            let recipient2_vk = crypto::create_key(recipient2_wallet_handle, None).unwrap();
+
+            let receiver_keys = json!([recipient1_vk, recipient2_vk]).to_string();
+
+            let jwe = crypto::pack_message(sender_wallet_handle, MESSAGE.as_bytes(), &receiver_keys, Some(&sender_vk)).unwrap();
+
+            for &(wallet_handle, ref verkey) in [(recipient1_wallet_handle, &recipient1_vk), (recipient2_wallet_handle, &recipient2_vk)].iter() {
+                let unpacked = crypto::unpack_message(wallet_handle, &jwe).unwrap();
+                let unpacked: serde_json::Value = serde_json::from_slice(&unpacked).unwrap();
+
+                assert_eq!(MESSAGE.as_bytes(), base64_urlsafe_decode(unpacked["message"].as_str().unwrap()).as_slice());
+                assert_eq!(verkey.as_str(), unpacked["recipient_verkey"].as_str().unwrap());
+                assert_eq!(sender_vk.as_str(), unpacked["sender_verkey"].as_str().unwrap());
+            }
+
+            wallet::close_wallet(recipient1_wallet_handle).unwrap();
+            wallet::close_wallet(recipient2_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(sender_wallet_handle);
+        }
+
+        #[test]
+        fn indy_pack_message_works_for_unpadded_fields() {
+            let (sender_wallet_handle, sender_vk) = setup_with_key();
+
+            let receiver_keys = json!([VERKEY_MY1]).to_string();
+
+            let jwe = crypto::pack_message(sender_wallet_handle, MESSAGE.as_bytes(), &receiver_keys, Some(&sender_vk)).unwrap();
+            let jwe: serde_json::Value = serde_json::from_slice(&jwe).unwrap();
+
+            for field in ["protected", "iv", "ciphertext", "tag"].iter() {
+                assert!(!jwe[*field].as_str().unwrap().contains('='));
+            }
+
+            let protected: serde_json::Value = serde_json::from_slice(&base64_urlsafe_decode(jwe["protected"].as_str().unwrap())).unwrap();
+            let recipient = &protected["recipients"][0];
+            assert!(!recipient["encrypted_key"].as_str().unwrap().contains('='));
+            assert!(!recipient["header"]["iv"].as_str().unwrap().contains('='));
+            assert!(!recipient["header"]["sender"].as_str().unwrap().contains('='));
+
+            utils::tear_down_with_wallet(sender_wallet_handle);
+        }
+
+        #[test]
+        fn indy_pack_message_works_for_empty_receiver_keys() {
+            let (wallet_handle, _) = setup_with_key();
+
+            let res = crypto::pack_message(wallet_handle, MESSAGE.as_bytes(), "[]", None);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_pack_message_works_for_invalid_receiver_key() {
+            let (wallet_handle, _) = setup_with_key();
+
+            let receiver_keys = json!([INVALID_BASE58_VERKEY]).to_string();
+
+            let res = crypto::pack_message(wallet_handle, MESSAGE.as_bytes(), &receiver_keys, None);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_pack_message_works_for_unknown_sender_verkey() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let receiver_keys = json!([VERKEY_MY2]).to_string();
+
+            let res = crypto::pack_message(wallet_handle, MESSAGE.as_bytes(), &receiver_keys, Some(VERKEY));
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod unpack_message {
+        use super::*;
+
+        #[test]
+        fn indy_unpack_message_works_for_authcrypt_interop_vector() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_key(wallet_handle, Some(MY1_SEED)).unwrap();
+
+            let unpacked = crypto::unpack_message(wallet_handle, PACKED_AUTHCRYPT_MESSAGE.as_bytes()).unwrap();
+            let unpacked: serde_json::Value = serde_json::from_slice(&unpacked).unwrap();
+
+            assert_eq!(json!({
+                "message": base64_urlsafe_encode(MESSAGE.as_bytes()),
+                "recipient_verkey": VERKEY_MY1,
+                "sender_verkey": VERKEY_MY2
+            }), unpacked);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_unpack_message_works_for_unpadded_interop_vector() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_key(wallet_handle, Some(MY1_SEED)).unwrap();
+
+            // Padding of fields not covered by authentication tag can be dropped
+            let mut jwe: serde_json::Value = serde_json::from_str(PACKED_AUTHCRYPT_MESSAGE).unwrap();
+            for field in ["iv", "ciphertext", "tag"].iter() {
+                let value = jwe[*field].as_str().unwrap().trim_right_matches('=').to_string();
+                jwe[*field] = json!(value);
+            }
+
+            let unpacked = crypto::unpack_message(wallet_handle, jwe.to_string().as_bytes()).unwrap();
+            let unpacked: serde_json::Value = serde_json::from_slice(&unpacked).unwrap();
+
+            assert_eq!(MESSAGE.as_bytes(), base64_urlsafe_decode(unpacked["message"].as_str().unwrap()).as_slice());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_unpack_message_works_for_anoncrypt_interop_vector_with_binary_message() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_key(wallet_handle, Some(MY1_SEED)).unwrap();
+
+            let unpacked = crypto::unpack_message(wallet_handle, PACKED_ANONCRYPT_BINARY_MESSAGE.as_bytes()).unwrap();
+            let unpacked: serde_json::Value = serde_json::from_slice(&unpacked).unwrap();
+
+            assert_eq!(json!({
+                "message": "AJ-Slv8B",
+                "recipient_verkey": VERKEY_MY1
+            }), unpacked);
+            assert_eq!(BINARY_MESSAGE.to_vec(), base64_urlsafe_decode("AJ-Slv8B"));
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_unpack_message_works_for_binary_message() {
+            let (wallet_handle, verkey) = setup_with_key();
+
+            let receiver_keys = json!([verkey]).to_string();
+
+            let jwe = crypto::pack_message(wallet_handle, BINARY_MESSAGE, &receiver_keys, None).unwrap();
+
+            let unpacked = crypto::unpack_message(wallet_handle, &jwe).unwrap();
+            let unpacked: serde_json::Value = serde_json::from_slice(&unpacked).unwrap();
+
+            assert_eq!(BINARY_MESSAGE.to_vec(), base64_urlsafe_decode(unpacked["message"].as_str().unwrap()));
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_unpack_message_works_for_unknown_recipient() {
+            let (wallet_handle, sender_vk) = setup_with_key();
+
+            let receiver_keys = json!([VERKEY_MY1, VERKEY_MY2]).to_string();
+
+            let jwe = crypto::pack_message(wallet_handle, MESSAGE.as_bytes(), &receiver_keys, Some(&sender_vk)).unwrap();
+
+            let res = crypto::unpack_message(wallet_handle, &jwe);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_unpack_message_works_for_tampered_ciphertext() {
+            let (wallet_handle, verkey) = setup_with_key();
+
+            let receiver_keys = json!([verkey]).to_string();
+
+            let jwe = crypto::pack_message(wallet_handle, MESSAGE.as_bytes(), &receiver_keys, None).unwrap();
+
+            let mut jwe: serde_json::Value = serde_json::from_slice(&jwe).unwrap();
+            jwe["ciphertext"] = json!("AAAAAAAAAAAAAAAAAAAAAAAAAAAA");
+            let jwe = serde_json::to_vec(&jwe).unwrap();
+
+            let res = crypto::unpack_message(wallet_handle, &jwe);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_unpack_message_works_for_invalid_jwe() {
+            let (wallet_handle, _) = setup_with_key();
+
+            let res = crypto::unpack_message(wallet_handle, "not a jwe".as_bytes());
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod jws {
+        use super::*;
+
+        #[test]
+        fn indy_crypto_jws_sign_verify_works_for_compact() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (my_did, _) = did::create_and_store_my_did(wallet_handle, Some(MY1_SEED)).unwrap();
+
+            let jws = crypto::jws_sign(wallet_handle, &my_did, MESSAGE.as_bytes(), None).unwrap();
+            assert_eq!(3, jws.split('.').count());
+
+            assert!(crypto::jws_verify(wallet_handle, &jws, None, None).unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_sign_works_for_kid_from_did() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (my_did, _) = did::create_and_store_my_did(wallet_handle, Some(MY1_SEED)).unwrap();
+
+            let jws = crypto::jws_sign(wallet_handle, &my_did, MESSAGE.as_bytes(), Some(r#"{"serialization": "json", "typ": "JWT"}"#)).unwrap();
+            let jws: serde_json::Value = serde_json::from_str(&jws).unwrap();
+
+            let protected = base64_urlsafe_decode(jws["protected"].as_str().unwrap());
+            let protected: serde_json::Value = serde_json::from_slice(&protected).unwrap();
+
+            assert_eq!(json!({"alg": "EdDSA", "kid": my_did, "typ": "JWT"}), protected);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_sign_works_for_kid_from_verkey() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (_, my_verkey) = did::create_and_store_my_did(wallet_handle, Some(MY1_SEED)).unwrap();
+
+            let jws = crypto::jws_sign(wallet_handle, &my_verkey, MESSAGE.as_bytes(), None).unwrap();
+
+            let protected = base64_urlsafe_decode(jws.split('.').next().unwrap());
+            let protected: serde_json::Value = serde_json::from_slice(&protected).unwrap();
+
+            assert_eq!(json!({"alg": "EdDSA", "kid": my_verkey}), protected);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_sign_verify_works_for_key_without_did() {
+            let wallet_handle = utils::setup_with_wallet();
+            let verkey = crypto::create_key(wallet_handle, None).unwrap();
+
+            let jws = crypto::jws_sign(wallet_handle, &verkey, MESSAGE.as_bytes(), None).unwrap();
+
+            assert!(crypto::jws_verify(wallet_handle, &jws, None, None).unwrap());
+            assert!(crypto::jws_verify(wallet_handle, &jws, None, Some(&json!({"signer": verkey}).to_string())).unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_sign_verify_works_for_json_serialization() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (_, verkey) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            let jws = crypto::jws_sign(wallet_handle, &verkey, MESSAGE.as_bytes(), Some(r#"{"serialization": "json"}"#)).unwrap();
+
+            let jws_json: serde_json::Value = serde_json::from_str(&jws).unwrap();
+            assert!(jws_json["payload"].is_string());
+            assert!(jws_json["signature"].is_string());
+
+            assert!(crypto::jws_verify(wallet_handle, &jws, None, None).unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_sign_verify_works_for_detached_payload() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (_, verkey) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            let jws = crypto::jws_sign(wallet_handle, &verkey, MESSAGE.as_bytes(), Some(r#"{"detached": true}"#)).unwrap();
+            assert_eq!("", jws.split('.').nth(1).unwrap());
+
+            assert!(crypto::jws_verify(wallet_handle, &jws, Some(MESSAGE.as_bytes()), None).unwrap());
+            assert!(!crypto::jws_verify(wallet_handle, &jws, Some("other message".as_bytes()), None).unwrap());
+
+            let res = crypto::jws_verify(wallet_handle, &jws, None, None);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_verify_works_for_their_did() {
+            let signer_wallet_handle = utils::setup_with_wallet();
+            let (signer_did, signer_verkey) = did::create_and_store_my_did(signer_wallet_handle, Some(MY1_SEED)).unwrap();
+
+            let jws = crypto::jws_sign(signer_wallet_handle, &signer_did, MESSAGE.as_bytes(), None).unwrap();
+
+            let verifier_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            let res = crypto::jws_verify(verifier_wallet_handle, &jws, None, None);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            did::store_their_did_from_parts(verifier_wallet_handle, &signer_did, &signer_verkey).unwrap();
+
+            assert!(crypto::jws_verify(verifier_wallet_handle, &jws, None, None).unwrap());
+
+            wallet::close_wallet(verifier_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(signer_wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_verify_works_for_verkey_kid() {
+            let signer_wallet_handle = utils::setup_with_wallet();
+            let verkey = crypto::create_key(signer_wallet_handle, Some(MY1_SEED)).unwrap();
+
+            let protected = base64_urlsafe_encode(json!({"alg": "EdDSA", "kid": verkey}).to_string().as_bytes());
+            let payload = base64_urlsafe_encode(MESSAGE.as_bytes());
+            let signature = crypto::sign(signer_wallet_handle, &verkey, format!("{}.{}", protected, payload).as_bytes()).unwrap();
+            let jws = format!("{}.{}.{}", protected, payload, base64_urlsafe_encode(&signature));
+
+            let verifier_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            let res = crypto::jws_verify(verifier_wallet_handle, &jws, None, None);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            assert!(crypto::jws_verify(verifier_wallet_handle, &jws, None, Some(r#"{"allow_verkey_kid": true}"#)).unwrap());
+
+            wallet::close_wallet(verifier_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(signer_wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_verify_works_for_expected_signer() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (my_did, my_verkey) = did::create_and_store_my_did(wallet_handle, Some(MY1_SEED)).unwrap();
+            let (other_did, _) = did::create_and_store_my_did(wallet_handle, Some(MY2_SEED)).unwrap();
+
+            let jws = crypto::jws_sign(wallet_handle, &my_did, MESSAGE.as_bytes(), None).unwrap();
+
+            assert!(crypto::jws_verify(wallet_handle, &jws, None, Some(&json!({"signer": my_did}).to_string())).unwrap());
+            assert!(crypto::jws_verify(wallet_handle, &jws, None, Some(&json!({"signer": my_verkey}).to_string())).unwrap());
+            assert!(!crypto::jws_verify(wallet_handle, &jws, None, Some(&json!({"signer": other_did}).to_string())).unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_verify_works_for_tampered_payload() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (_, verkey) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            let jws = crypto::jws_sign(wallet_handle, &verkey, MESSAGE.as_bytes(), None).unwrap();
+
+            let parts: Vec<&str> = jws.split('.').collect();
+            let jws = format!("{}.{}.{}", parts[0], "b3RoZXIgbWVzc2FnZQ", parts[2]);
+
+            assert!(!crypto::jws_verify(wallet_handle, &jws, None, None).unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_sign_works_for_unknown_signer() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = crypto::jws_sign(wallet_handle, DID, MESSAGE.as_bytes(), None);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        #[cfg(feature = "ec_openssl")]
+        fn indy_crypto_jws_sign_works_for_not_ed25519_key() {
+            let wallet_handle = utils::setup_with_wallet();
+            let verkey = crypto::create_key_with_crypto_type(wallet_handle, None, "secp256k1").unwrap();
+
+            let res = crypto::jws_sign(wallet_handle, &verkey, MESSAGE.as_bytes(), None);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_crypto_jws_sign_works_for_invalid_options() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (_, verkey) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            let res = crypto::jws_sign(wallet_handle, &verkey, MESSAGE.as_bytes(), Some(r#"{"serialization": "unknown"}"#));
+            assert_eq!(ErrorCode::CommonInvalidParam6, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod export_import_key {
+        use super::*;
+
+        const PASSPHRASE: &'static str = "passphrase";
+
+        #[test]
+        fn indy_export_import_key_works() {
+            let wallet_handle = utils::setup_with_wallet();
+            let verkey = crypto::create_key(wallet_handle, Some(MY1_SEED)).unwrap();
+            crypto::set_key_metadata(wallet_handle, &verkey, METADATA).unwrap();
+
+            let exported_key = crypto::export_key(wallet_handle, &verkey, PASSPHRASE).unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            let imported_verkey = crypto::import_key(other_wallet_handle, &exported_key, PASSPHRASE).unwrap();
+            assert_eq!(verkey, imported_verkey);
+
+            assert_eq!(METADATA, crypto::get_key_metadata(other_wallet_handle, &verkey).unwrap());
+
+            let signature = crypto::sign(other_wallet_handle, &verkey, MESSAGE.as_bytes()).unwrap();
+            assert!(crypto::verify(&verkey, MESSAGE.as_bytes(), &signature).unwrap());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_export_import_key_works_for_plugged_wallet_without_batch() {
+            let wallet_handle = utils::setup_with_wallet();
+            let verkey = crypto::create_key(wallet_handle, Some(MY1_SEED)).unwrap();
+            crypto::set_key_metadata(wallet_handle, &verkey, METADATA).unwrap();
+
+            let exported_key = crypto::export_key(wallet_handle, &verkey, PASSPHRASE).unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_plugged_wallet().unwrap();
+
+            let imported_verkey = crypto::import_key(other_wallet_handle, &exported_key, PASSPHRASE).unwrap();
+            assert_eq!(verkey, imported_verkey);
+
+            assert_eq!(METADATA, crypto::get_key_metadata(other_wallet_handle, &verkey).unwrap());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_import_key_works_for_wrong_passphrase() {
+            let wallet_handle = utils::setup_with_wallet();
+            let verkey = crypto::create_key(wallet_handle, None).unwrap();
+
+            let exported_key = crypto::export_key(wallet_handle, &verkey, PASSPHRASE).unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            let res = crypto::import_key(other_wallet_handle, &exported_key, "wrong passphrase");
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_import_key_works_for_existing_key() {
+            let wallet_handle = utils::setup_with_wallet();
+            let verkey = crypto::create_key(wallet_handle, None).unwrap();
+
+            let exported_key = crypto::export_key(wallet_handle, &verkey, PASSPHRASE).unwrap();
+
+            let res = crypto::import_key(wallet_handle, &exported_key, PASSPHRASE);
+            assert_eq!(ErrorCode::WalletItemAlreadyExists, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_export_key_works_for_unknown_key() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = crypto::export_key(wallet_handle, VERKEY_MY1, PASSPHRASE);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod key_derivation {
+        use super::*;
+
+        const MNEMONIC: &'static str = "some backed up mnemonic";
+        const PASSPHRASE: &'static str = "passphrase";
+
+        #[test]
+        fn indy_create_key_derived_works_for_same_mnemonic() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, Some(MNEMONIC)).unwrap();
+            let verkey = crypto::create_key_derived(wallet_handle, "m/0/1").unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            crypto::create_root_seed(other_wallet_handle, Some(MNEMONIC)).unwrap();
+            let other_verkey = crypto::create_key_derived(other_wallet_handle, "m/0'/1'").unwrap();
+
+            assert_eq!(verkey, other_verkey);
+
+            let signature = crypto::sign(other_wallet_handle, &other_verkey, MESSAGE.as_bytes()).unwrap();
+            assert!(crypto::verify(&verkey, MESSAGE.as_bytes(), &signature).unwrap());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_create_key_derived_works_for_different_paths() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, None).unwrap();
+
+            let verkey1 = crypto::create_key_derived(wallet_handle, "m/0/1").unwrap();
+            let verkey2 = crypto::create_key_derived(wallet_handle, "m/0/2").unwrap();
+            assert_ne!(verkey1, verkey2);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_create_key_derived_works_for_invalid_path() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, None).unwrap();
+
+            let res = crypto::create_key_derived(wallet_handle, "0/a");
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_create_key_derived_works_for_missed_root_seed() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = crypto::create_key_derived(wallet_handle, "m/0");
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_create_root_seed_works_for_existing_root_seed() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, None).unwrap();
+
+            let res = crypto::create_root_seed(wallet_handle, Some(MNEMONIC));
+            assert_eq!(ErrorCode::WalletItemAlreadyExists, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_export_import_root_seed_works() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, None).unwrap();
+            let verkey = crypto::create_key_derived(wallet_handle, "m/0/1").unwrap();
+
+            let exported_root_seed = crypto::export_root_seed(wallet_handle, PASSPHRASE).unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            crypto::import_root_seed(other_wallet_handle, &exported_root_seed, PASSPHRASE).unwrap();
+
+            assert_eq!(verkey, crypto::create_key_derived(other_wallet_handle, "m/0/1").unwrap());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_import_root_seed_works_for_wrong_passphrase() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, None).unwrap();
+
+            let exported_root_seed = crypto::export_root_seed(wallet_handle, PASSPHRASE).unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            let res = crypto::import_root_seed(other_wallet_handle, &exported_root_seed, "other passphrase");
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_import_root_seed_works_for_existing_root_seed() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, None).unwrap();
+
+            let exported_root_seed = crypto::export_root_seed(wallet_handle, PASSPHRASE).unwrap();
+
+            let res = crypto::import_root_seed(wallet_handle, &exported_root_seed, PASSPHRASE);
+            assert_eq!(ErrorCode::WalletItemAlreadyExists, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_export_root_seed_works_for_missed_root_seed() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = crypto::export_root_seed(wallet_handle, PASSPHRASE);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod search_keys {
+        use super::*;
+
+        #[test]
+        fn indy_search_keys_works() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let verkey1 = crypto::create_key(wallet_handle, Some(MY1_SEED)).unwrap();
+            let verkey2 = crypto::create_key(wallet_handle, Some(MY2_SEED)).unwrap();
+            crypto::set_key_metadata(wallet_handle, &verkey1, METADATA).unwrap();
+
+            crypto::set_key_tags(wallet_handle, &verkey1, r#"{"label": "Alice"}"#).unwrap();
+            crypto::set_key_tags(wallet_handle, &verkey2, r#"{"label": "Bob"}"#).unwrap();
+
+            let keys = crypto::search_keys(wallet_handle, r#"{"label": "Alice"}"#).unwrap();
+            let keys: Vec<serde_json::Value> = serde_json::from_str(&keys).unwrap();
+            assert_eq!(1, keys.len());
+            assert_eq!(verkey1, keys[0]["verkey"].as_str().unwrap());
+            assert_eq!(METADATA, keys[0]["metadata"].as_str().unwrap());
+            assert_eq!("Alice", keys[0]["tags"]["label"].as_str().unwrap());
+            let created_at = keys[0]["tags"]["~created_at"].as_str().unwrap().to_string();
+
+            let keys = crypto::search_keys(wallet_handle, &json!({"~created_at": {"$lte": created_at}}).to_string()).unwrap();
+            let keys: Vec<serde_json::Value> = serde_json::from_str(&keys).unwrap();
+            assert_eq!(2, keys.len());
+
+            let keys = crypto::search_keys(wallet_handle, &json!({"~created_at": {"$gt": created_at}}).to_string()).unwrap();
+            assert_eq!("[]", keys);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_set_key_tags_works_for_reserved_tag() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let verkey = crypto::create_key(wallet_handle, None).unwrap();
+
+            let res = crypto::set_key_tags(wallet_handle, &verkey, r#"{"~created_at": "1530000001"}"#);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_set_key_tags_works_for_unknown_key() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = crypto::set_key_tags(wallet_handle, VERKEY_MY1, r#"{"label": "Alice"}"#);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_search_keys_works_for_invalid_query() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = crypto::search_keys(wallet_handle, r#"{"label": {"$unknown": "Alice"}}"#);
+            assert_eq!(ErrorCode::WalletQueryError, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
 }
 
 mod load {
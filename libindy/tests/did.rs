@@ -29,7 +29,7 @@ use indy::api as api;
 #[macro_use]
 mod utils;
 
-use utils::{wallet, did, pool, ledger};
+use utils::{wallet, did, crypto, pool, ledger};
 use utils::constants::*;
 use utils::types::ResponseType;
 
@@ -873,6 +873,66 @@ mod high_cases {
         }
     }
 
+    mod rotate_did_key {
+        use super::*;
+
+        #[test]
+        fn indy_rotate_did_key_works() {
+            let (wallet_handle, pool_handle) = utils::setup_with_wallet_and_pool();
+
+            let (my_did, my_verkey) = did::create_store_and_publish_my_did_from_trustee(wallet_handle, pool_handle).unwrap();
+
+            let new_verkey = did::rotate_did_key(pool_handle, wallet_handle, &my_did, "{}").unwrap();
+            assert_ne!(my_verkey, new_verkey);
+            assert_eq!(new_verkey, did::key_for_local_did(wallet_handle, &my_did).unwrap());
+
+            let schema_request = ledger::build_schema_request(&my_did, SCHEMA_DATA).unwrap();
+            let response = ledger::sign_and_submit_request(pool_handle, wallet_handle, &my_did, &schema_request).unwrap();
+            pool::check_response_type(&response, ResponseType::REPLY);
+
+            utils::tear_down_with_wallet_and_pool(wallet_handle, pool_handle);
+        }
+
+        #[test]
+        fn indy_rotate_did_key_works_for_not_published_did() {
+            let (wallet_handle, pool_handle) = utils::setup_with_wallet_and_pool();
+
+            let (my_did, my_verkey) = did::create_my_did(wallet_handle, "{}").unwrap();
+
+            let res = did::rotate_did_key(pool_handle, wallet_handle, &my_did, "{}");
+            assert_eq!(ErrorCode::LedgerInvalidTransaction, res.unwrap_err());
+
+            // Temporary keys are rolled back
+            assert_eq!(my_verkey, did::key_for_local_did(wallet_handle, &my_did).unwrap());
+            let res = did::replace_keys_apply(wallet_handle, &my_did);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet_and_pool(wallet_handle, pool_handle);
+        }
+
+        #[test]
+        fn indy_rotate_did_key_works_for_unknown_did() {
+            let (wallet_handle, pool_handle) = utils::setup_with_wallet_and_pool();
+
+            let res = did::rotate_did_key(pool_handle, wallet_handle, DID, "{}");
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet_and_pool(wallet_handle, pool_handle);
+        }
+
+        #[test]
+        fn indy_rotate_did_key_works_for_invalid_key_info() {
+            let (wallet_handle, pool_handle) = utils::setup_with_wallet_and_pool();
+
+            let (my_did, _) = did::create_my_did(wallet_handle, "{}").unwrap();
+
+            let res = did::rotate_did_key(pool_handle, wallet_handle, &my_did, "invalid");
+            assert_eq!(ErrorCode::CommonInvalidParam5, res.unwrap_err());
+
+            utils::tear_down_with_wallet_and_pool(wallet_handle, pool_handle);
+        }
+    }
+
     mod abbreviate_verkey {
         use super::*;
 
@@ -914,4 +974,244 @@ mod high_cases {
             assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
         }
     }
+
+    mod export_import_did {
+        use super::*;
+
+        const PASSPHRASE: &'static str = "passphrase";
+
+        #[test]
+        fn indy_export_import_did_works() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (my_did, my_verkey) = did::create_and_store_my_did(wallet_handle, Some(MY1_SEED)).unwrap();
+            did::set_did_metadata(wallet_handle, &my_did, METADATA).unwrap();
+
+            let exported_did = did::export_did(wallet_handle, &my_did, PASSPHRASE).unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            let imported_did = did::import_did(other_wallet_handle, &exported_did, PASSPHRASE).unwrap();
+            assert_eq!(my_did, imported_did);
+
+            assert_eq!(my_verkey, did::key_for_local_did(other_wallet_handle, &my_did).unwrap());
+            assert_eq!(METADATA, did::get_did_metadata(other_wallet_handle, &my_did).unwrap());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_export_import_did_works_for_plugged_wallet_without_batch() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (my_did, my_verkey) = did::create_and_store_my_did(wallet_handle, Some(MY1_SEED)).unwrap();
+            did::set_did_metadata(wallet_handle, &my_did, METADATA).unwrap();
+
+            let exported_did = did::export_did(wallet_handle, &my_did, PASSPHRASE).unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_plugged_wallet().unwrap();
+
+            let imported_did = did::import_did(other_wallet_handle, &exported_did, PASSPHRASE).unwrap();
+            assert_eq!(my_did, imported_did);
+
+            assert_eq!(my_verkey, did::key_for_local_did(other_wallet_handle, &my_did).unwrap());
+            assert_eq!(METADATA, did::get_did_metadata(other_wallet_handle, &my_did).unwrap());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_import_did_works_for_wrong_passphrase() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (my_did, _) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            let exported_did = did::export_did(wallet_handle, &my_did, PASSPHRASE).unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+
+            let res = did::import_did(other_wallet_handle, &exported_did, "wrong passphrase");
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_import_did_works_for_existing_did() {
+            let wallet_handle = utils::setup_with_wallet();
+            let (my_did, _) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            let exported_did = did::export_did(wallet_handle, &my_did, PASSPHRASE).unwrap();
+
+            let res = did::import_did(wallet_handle, &exported_did, PASSPHRASE);
+            assert_eq!(ErrorCode::DidAlreadyExistsError, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_export_did_works_for_their_did() {
+            let wallet_handle = utils::setup_with_wallet();
+            did::store_their_did_from_parts(wallet_handle, DID, VERKEY).unwrap();
+
+            let res = did::export_did(wallet_handle, DID, PASSPHRASE);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_import_did_works_for_invalid_json() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = did::import_did(wallet_handle, r#"{"salt": "AAAA"}"#, PASSPHRASE);
+            assert_eq!(ErrorCode::CommonInvalidParam3, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod create_my_did_derived {
+        use super::*;
+
+        const MNEMONIC: &'static str = "some backed up mnemonic";
+
+        #[test]
+        fn indy_create_and_store_my_did_derived_works_for_same_mnemonic() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, Some(MNEMONIC)).unwrap();
+            let (did, verkey) = did::create_and_store_my_did_derived(wallet_handle, "m/1/0").unwrap();
+
+            let other_wallet_handle = wallet::create_and_open_default_wallet().unwrap();
+            crypto::create_root_seed(other_wallet_handle, Some(MNEMONIC)).unwrap();
+            let (other_did, other_verkey) = did::create_and_store_my_did_derived(other_wallet_handle, "m/1/0").unwrap();
+
+            assert_eq!(did, other_did);
+            assert_eq!(verkey, other_verkey);
+            assert_eq!(verkey, did::key_for_local_did(other_wallet_handle, &did).unwrap());
+
+            wallet::close_wallet(other_wallet_handle).unwrap();
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_create_and_store_my_did_derived_works_for_different_paths() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, None).unwrap();
+
+            let (did1, _) = did::create_and_store_my_did_derived(wallet_handle, "m/1/0").unwrap();
+            let (did2, _) = did::create_and_store_my_did_derived(wallet_handle, "m/1/1").unwrap();
+            assert_ne!(did1, did2);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_create_and_store_my_did_derived_works_for_duplicate() {
+            let wallet_handle = utils::setup_with_wallet();
+            crypto::create_root_seed(wallet_handle, None).unwrap();
+            did::create_and_store_my_did_derived(wallet_handle, "m/1/0").unwrap();
+
+            let res = did::create_and_store_my_did_derived(wallet_handle, "m/1/0");
+            assert_eq!(ErrorCode::DidAlreadyExistsError, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_create_and_store_my_did_derived_works_for_missed_root_seed() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = did::create_and_store_my_did_derived(wallet_handle, "m/1/0");
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod search_my_dids {
+        use super::*;
+
+        #[test]
+        fn indy_search_my_dids_works() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let (did1, verkey1) = did::create_and_store_my_did(wallet_handle, Some(MY1_SEED)).unwrap();
+            let (did2, _) = did::create_and_store_my_did(wallet_handle, Some(MY2_SEED)).unwrap();
+            did::set_did_metadata(wallet_handle, &did1, METADATA).unwrap();
+
+            did::set_did_tags(wallet_handle, &did1, r#"{"label": "Alice"}"#).unwrap();
+            did::set_did_tags(wallet_handle, &did2, r#"{"label": "Bob"}"#).unwrap();
+
+            let dids = did::search_my_dids(wallet_handle, r#"{"label": "Alice"}"#).unwrap();
+            let dids: Vec<serde_json::Value> = serde_json::from_str(&dids).unwrap();
+            assert_eq!(1, dids.len());
+            assert_eq!(did1, dids[0]["did"].as_str().unwrap());
+            assert_eq!(verkey1, dids[0]["verkey"].as_str().unwrap());
+            assert_eq!(METADATA, dids[0]["metadata"].as_str().unwrap());
+            assert_eq!("Alice", dids[0]["tags"]["label"].as_str().unwrap());
+            let created_at = dids[0]["tags"]["~created_at"].as_str().unwrap().to_string();
+
+            let dids = did::search_my_dids(wallet_handle, &json!({"~created_at": {"$gt": created_at}}).to_string()).unwrap();
+            assert_eq!("[]", dids);
+
+            let dids = did::search_my_dids(wallet_handle, "{}").unwrap();
+            let dids: Vec<serde_json::Value> = serde_json::from_str(&dids).unwrap();
+            assert_eq!(2, dids.len());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_search_my_dids_works_for_replaced_tags() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let (did, _) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            did::set_did_tags(wallet_handle, &did, r#"{"label": "Alice"}"#).unwrap();
+            did::set_did_tags(wallet_handle, &did, r#"{"connection_id": "1"}"#).unwrap();
+
+            let dids = did::search_my_dids(wallet_handle, r#"{"label": "Alice"}"#).unwrap();
+            assert_eq!("[]", dids);
+
+            let dids = did::search_my_dids(wallet_handle, r#"{"connection_id": "1"}"#).unwrap();
+            let dids: Vec<serde_json::Value> = serde_json::from_str(&dids).unwrap();
+            assert_eq!(1, dids.len());
+            assert!(dids[0]["tags"]["~created_at"].is_string());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_set_did_tags_works_for_unknown_did() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = did::set_did_tags(wallet_handle, DID, r#"{"label": "Alice"}"#);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_set_did_tags_works_for_invalid_tags() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let (did, _) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            let res = did::set_did_tags(wallet_handle, &did, r#"{"label": 1}"#);
+            assert_eq!(ErrorCode::CommonInvalidParam4, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_search_my_dids_works_for_invalid_query() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = did::search_my_dids(wallet_handle, r#"{"label": {"$unknown": "Alice"}}"#);
+            assert_eq!(ErrorCode::WalletQueryError, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
 }
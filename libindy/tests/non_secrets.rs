@@ -583,6 +583,476 @@ mod high_cases {
         }
     }
 
+    mod conditional_update {
+        use super::*;
+
+        #[test]
+        fn indy_update_wallet_record_value_if_version_works() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+            let version = get_wallet_record_version(wallet_handle, TYPE, ID).unwrap();
+
+            update_wallet_record_value_if_version(wallet_handle, TYPE, ID, VALUE_2, version).unwrap();
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE_2);
+            assert!(get_wallet_record_version(wallet_handle, TYPE, ID).unwrap() > version);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_wallet_record_value_if_version_works_for_second_record() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+            update_wallet_record_value(wallet_handle, TYPE, ID, VALUE_2).unwrap();
+
+            add_wallet_record(wallet_handle, TYPE, ID_2, VALUE, None).unwrap();
+            let version = get_wallet_record_version(wallet_handle, TYPE, ID_2).unwrap();
+
+            update_wallet_record_value_if_version(wallet_handle, TYPE, ID_2, VALUE_2, version).unwrap();
+            check_record_field(wallet_handle, TYPE, ID_2, "value", VALUE_2);
+
+            let updated_version = get_wallet_record_version(wallet_handle, TYPE, ID_2).unwrap();
+            assert!(updated_version > version);
+
+            let res = update_wallet_record_value_if_version(wallet_handle, TYPE, ID_2, VALUE_3, version);
+            assert_eq!(ErrorCode::WalletItemVersionMismatch, res.unwrap_err());
+
+            update_wallet_record_tags_if_version(wallet_handle, TYPE, ID_2, TAGS, updated_version).unwrap();
+            assert!(get_wallet_record_version(wallet_handle, TYPE, ID_2).unwrap() > updated_version);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_wallet_record_value_if_version_works_for_plugged_wallet() {
+            let wallet_handle = utils::setup_with_plugged_wallet_with_optional_handlers();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+            let version = get_wallet_record_version(wallet_handle, TYPE, ID).unwrap();
+
+            update_wallet_record_value_if_version(wallet_handle, TYPE, ID, VALUE_2, version).unwrap();
+
+            let res = update_wallet_record_value_if_version(wallet_handle, TYPE, ID, VALUE_3, version);
+            assert_eq!(ErrorCode::WalletItemVersionMismatch, res.unwrap_err());
+
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE_2);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_wallet_record_value_if_version_works_for_concurrent_change() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+            let version = get_wallet_record_version(wallet_handle, TYPE, ID).unwrap();
+
+            update_wallet_record_value(wallet_handle, TYPE, ID, VALUE_2).unwrap();
+
+            let res = update_wallet_record_value_if_version(wallet_handle, TYPE, ID, VALUE_3, version);
+            assert_eq!(ErrorCode::WalletItemVersionMismatch, res.unwrap_err());
+
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE_2);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_wallet_record_value_if_version_works_for_not_found_record() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = update_wallet_record_value_if_version(wallet_handle, TYPE, ID, VALUE, 1);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_wallet_record_tags_if_version_works() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, Some(TAGS_EMPTY)).unwrap();
+            let version = get_wallet_record_version(wallet_handle, TYPE, ID).unwrap();
+
+            update_wallet_record_tags_if_version(wallet_handle, TYPE, ID, TAGS, version).unwrap();
+            check_record_field(wallet_handle, TYPE, ID, "tags", TAGS);
+
+            let res = update_wallet_record_tags_if_version(wallet_handle, TYPE, ID, TAGS_2, version);
+            assert_eq!(ErrorCode::WalletItemVersionMismatch, res.unwrap_err());
+
+            check_record_field(wallet_handle, TYPE, ID, "tags", TAGS);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_delete_wallet_record_if_version_works() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+            let version = get_wallet_record_version(wallet_handle, TYPE, ID).unwrap();
+
+            add_wallet_record_tags(wallet_handle, TYPE, ID, TAGS).unwrap();
+
+            let res = delete_wallet_record_if_version(wallet_handle, TYPE, ID, version);
+            assert_eq!(ErrorCode::WalletItemVersionMismatch, res.unwrap_err());
+
+            let version = get_wallet_record_version(wallet_handle, TYPE, ID).unwrap();
+            delete_wallet_record_if_version(wallet_handle, TYPE, ID, version).unwrap();
+
+            let res = get_wallet_record(wallet_handle, TYPE, ID, OPTIONS_EMPTY);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_get_wallet_record_works_without_version_by_default() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+
+            let record = get_wallet_record(wallet_handle, TYPE, ID, OPTIONS_EMPTY).unwrap();
+            let record: serde_json::Value = serde_json::from_str(&record).unwrap();
+            assert!(record.get("version").is_none());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_wallet_record_value_if_version_works_for_invalid_handle() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+
+            let res = update_wallet_record_value_if_version(wallet_handle + 1, TYPE, ID, VALUE_2, 1);
+            assert_eq!(ErrorCode::WalletInvalidHandle, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_wallet_record_value_if_version_works_for_invalid_type() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = update_wallet_record_value_if_version(wallet_handle, FORBIDDEN_TYPE, ID, VALUE, 1);
+            assert_eq!(ErrorCode::WalletAccessFailed, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod record_expiry {
+        use super::*;
+
+        const EXPIRED_AT: u64 = 1;
+        const EXPIRES_AT: u64 = 4_000_000_000;
+
+        fn _expiry_options(expires_at: u64) -> String {
+            json!({"expiresAt": expires_at}).to_string()
+        }
+
+        #[test]
+        fn indy_add_wallet_record_works_for_expiry() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record_with_options(wallet_handle, TYPE, ID, VALUE, Some(TAGS), Some(&_expiry_options(EXPIRES_AT))).unwrap();
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE);
+            check_record_field(wallet_handle, TYPE, ID, "tags", TAGS);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_add_wallet_record_works_for_expired() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record_with_options(wallet_handle, TYPE, ID, VALUE, Some(TAGS), Some(&_expiry_options(EXPIRED_AT))).unwrap();
+            add_wallet_record(wallet_handle, TYPE, ID_2, VALUE_2, Some(TAGS)).unwrap();
+
+            let res = get_wallet_record(wallet_handle, TYPE, ID, OPTIONS_EMPTY);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            assert_eq!(1, count_wallet_records(wallet_handle, TYPE, QUERY_EMPTY).unwrap());
+
+            // Expired record doesn't block adding a new one with the same id
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE_3, None).unwrap();
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE_3);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_add_wallet_record_works_for_empty_options() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record_with_options(wallet_handle, TYPE, ID, VALUE, None, Some(OPTIONS_EMPTY)).unwrap();
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_add_wallet_record_works_for_invalid_options() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = add_wallet_record_with_options(wallet_handle, TYPE, ID, VALUE, None, Some(r#"{"expiresAt": -1}"#));
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_add_wallet_record_works_for_expiry_and_plugged_wallet() {
+            let wallet_handle = utils::setup_with_plugged_wallet();
+
+            let res = add_wallet_record_with_options(wallet_handle, TYPE, ID, VALUE, None, Some(&_expiry_options(EXPIRES_AT)));
+            assert_eq!(ErrorCode::WalletStorageError, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_expiry() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let operations = json!([
+                {"op": "add", "type": TYPE, "id": ID, "value": VALUE, "expires_at": EXPIRED_AT},
+                {"op": "add", "type": TYPE, "id": ID_2, "value": VALUE_2, "expires_at": EXPIRES_AT},
+            ]).to_string();
+
+            wallet_batch(wallet_handle, &operations).unwrap();
+
+            let res = get_wallet_record(wallet_handle, TYPE, ID, OPTIONS_EMPTY);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            check_record_field(wallet_handle, TYPE, ID_2, "value", VALUE_2);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_purge_expired_wallet_records_works() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record_with_options(wallet_handle, TYPE, ID, VALUE, Some(TAGS), Some(&_expiry_options(EXPIRED_AT))).unwrap();
+            add_wallet_record_with_options(wallet_handle, TYPE_2, ID, VALUE, None, Some(&_expiry_options(EXPIRED_AT))).unwrap();
+            add_wallet_record_with_options(wallet_handle, TYPE, ID_2, VALUE_2, None, Some(&_expiry_options(EXPIRES_AT))).unwrap();
+            add_wallet_record(wallet_handle, TYPE, ID_3, VALUE_3, None).unwrap();
+
+            assert_eq!(2, purge_expired_wallet_records(wallet_handle).unwrap());
+            assert_eq!(0, purge_expired_wallet_records(wallet_handle).unwrap());
+
+            assert_eq!(2, count_wallet_records(wallet_handle, TYPE, QUERY_EMPTY).unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_purge_expired_wallet_records_works_for_plugged_wallet() {
+            let wallet_handle = utils::setup_with_plugged_wallet();
+
+            let res = purge_expired_wallet_records(wallet_handle);
+            assert_eq!(ErrorCode::WalletStorageError, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_purge_expired_wallet_records_works_for_invalid_handle() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = purge_expired_wallet_records(wallet_handle + 1);
+            assert_eq!(ErrorCode::WalletInvalidHandle, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod batch {
+        use super::*;
+
+        fn _operations() -> String {
+            json!([
+                {"op": "add", "type": TYPE, "id": ID_2, "value": VALUE_2, "tags": {"tagName1": "str1"}},
+                {"op": "update", "type": TYPE, "id": ID, "value": VALUE_3},
+                {"op": "update_tags", "type": TYPE, "id": ID, "tags": {"tagName1": "str2"}},
+                {"op": "add_tags", "type": TYPE, "id": ID_2, "tags": {"~tagName2": "5"}},
+                {"op": "delete_tags", "type": TYPE, "id": ID_2, "tag_names": ["tagName1"]},
+            ]).to_string()
+        }
+
+        fn _check_batch_applied(wallet_handle: i32) {
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE_3);
+            check_record_field(wallet_handle, TYPE, ID, "tags", r#"{"tagName1":"str2"}"#);
+            check_record_field(wallet_handle, TYPE, ID_2, "value", VALUE_2);
+            check_record_field(wallet_handle, TYPE, ID_2, "tags", r#"{"~tagName2":"5"}"#);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, Some(TAGS)).unwrap();
+
+            wallet_batch(wallet_handle, &_operations()).unwrap();
+
+            _check_batch_applied(wallet_handle);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_plugged_wallet() {
+            let wallet_handle = utils::setup_with_plugged_wallet_with_optional_handlers();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, Some(TAGS)).unwrap();
+
+            wallet_batch(wallet_handle, &_operations()).unwrap();
+
+            _check_batch_applied(wallet_handle);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_plugged_wallet_without_batch() {
+            let wallet_handle = utils::setup_with_plugged_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, Some(TAGS)).unwrap();
+
+            let res = wallet_batch(wallet_handle, &_operations());
+            assert_eq!(ErrorCode::WalletStorageError, res.unwrap_err());
+
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_delete() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+
+            let operations = json!([
+                {"op": "delete", "type": TYPE, "id": ID},
+                {"op": "add", "type": TYPE, "id": ID, "value": VALUE_2},
+            ]).to_string();
+
+            wallet_batch(wallet_handle, &operations).unwrap();
+
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE_2);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_rollback() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+
+            let operations = json!([
+                {"op": "add", "type": TYPE, "id": ID_2, "value": VALUE_2},
+                {"op": "update", "type": TYPE, "id": ID, "value": VALUE_3},
+                {"op": "delete", "type": TYPE, "id": ID_3},
+            ]).to_string();
+
+            let res = wallet_batch(wallet_handle, &operations);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE);
+
+            let res = get_wallet_record(wallet_handle, TYPE, ID_2, OPTIONS_EMPTY);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_rollback_for_plugged_wallet() {
+            let wallet_handle = utils::setup_with_plugged_wallet_with_optional_handlers();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+
+            let operations = json!([
+                {"op": "update", "type": TYPE, "id": ID, "value": VALUE_3},
+                {"op": "delete", "type": TYPE, "id": ID_3},
+            ]).to_string();
+
+            let res = wallet_batch(wallet_handle, &operations);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            check_record_field(wallet_handle, TYPE, ID, "value", VALUE);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_already_existing_record() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            add_wallet_record(wallet_handle, TYPE, ID, VALUE, None).unwrap();
+
+            let operations = json!([
+                {"op": "add", "type": TYPE, "id": ID_2, "value": VALUE_2},
+                {"op": "add", "type": TYPE, "id": ID, "value": VALUE_3},
+            ]).to_string();
+
+            let res = wallet_batch(wallet_handle, &operations);
+            assert_eq!(ErrorCode::WalletItemAlreadyExists, res.unwrap_err());
+
+            let res = get_wallet_record(wallet_handle, TYPE, ID_2, OPTIONS_EMPTY);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_invalid_operations() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = wallet_batch(wallet_handle, r#"[{"op": "unknown", "type": "TestType", "id": "RecordId"}]"#);
+            assert_eq!(ErrorCode::CommonInvalidParam3, res.unwrap_err());
+
+            let res = wallet_batch(wallet_handle, r#"[{"op": "update", "type": "TestType", "id": "RecordId"}]"#);
+            assert_eq!(ErrorCode::CommonInvalidParam3, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_invalid_type() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let operations = json!([
+                {"op": "add", "type": TYPE, "id": ID, "value": VALUE},
+                {"op": "delete", "type": FORBIDDEN_TYPE, "id": ID},
+            ]).to_string();
+
+            let res = wallet_batch(wallet_handle, &operations);
+            assert_eq!(ErrorCode::WalletAccessFailed, res.unwrap_err());
+
+            let res = get_wallet_record(wallet_handle, TYPE, ID, OPTIONS_EMPTY);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_wallet_batch_works_for_invalid_handle() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = wallet_batch(wallet_handle + 1, &_operations());
+            assert_eq!(ErrorCode::WalletInvalidHandle, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
     mod get_record {
         use super::*;
 
@@ -1169,6 +1639,86 @@ mod high_cases {
 
                 tear_down(wallet_handle, search_handle);
             }
+
+            fn check_search_records_order(search_records: &str, expected_ids: Vec<&str>) {
+                let search_records: SearchRecords = serde_json::from_str(&search_records).unwrap();
+
+                let ids: Vec<String> = search_records.records.unwrap().into_iter().map(|record| record.id).collect();
+                assert_eq!(expected_ids, ids);
+            }
+
+            #[test]
+            fn indy_wallet_search_for_sort_by_tag() {
+                let wallet_handle = setup();
+
+                let options = json!({
+                    "sortBy": "~tagName3"
+                }).to_string();
+
+                let search_handle = open_wallet_search(wallet_handle, TYPE, QUERY_EMPTY, &options).unwrap();
+
+                let records = fetch_wallet_search_next_records(wallet_handle, search_handle, 5).unwrap();
+
+                // record without plaintext tag goes last
+                check_search_records_order(&records, vec![ID_2, ID_4, ID_5, ID, ID_3]);
+
+                tear_down(wallet_handle, search_handle);
+            }
+
+            #[test]
+            fn indy_wallet_search_for_sort_by_tag_descending() {
+                let wallet_handle = setup();
+
+                let options = json!({
+                    "sortBy": "~tagName3",
+                    "sortOrder": "desc"
+                }).to_string();
+
+                let search_handle = open_wallet_search(wallet_handle, TYPE, QUERY_EMPTY, &options).unwrap();
+
+                let records = fetch_wallet_search_next_records(wallet_handle, search_handle, 5).unwrap();
+
+                check_search_records_order(&records, vec![ID, ID_5, ID_4, ID_2, ID_3]);
+
+                tear_down(wallet_handle, search_handle);
+            }
+
+            #[test]
+            fn indy_wallet_search_for_offset_and_limit() {
+                let wallet_handle = setup();
+
+                let options = json!({
+                    "retrieveTotalCount": true,
+                    "sortBy": "~tagName3",
+                    "offset": 1,
+                    "limit": 2
+                }).to_string();
+
+                let search_handle = open_wallet_search(wallet_handle, TYPE, QUERY_EMPTY, &options).unwrap();
+
+                let records = fetch_wallet_search_next_records(wallet_handle, search_handle, 5).unwrap();
+
+                check_search_records_order(&records, vec![ID_4, ID_5]);
+
+                let search_records: SearchRecords = serde_json::from_str(&records).unwrap();
+                assert_eq!(Some(5), search_records.total_count);
+
+                tear_down(wallet_handle, search_handle);
+            }
+
+            #[test]
+            fn indy_wallet_search_for_sort_by_encrypted_tag() {
+                let wallet_handle = setup();
+
+                let options = json!({
+                    "sortBy": "tagName1"
+                }).to_string();
+
+                let res = open_wallet_search(wallet_handle, TYPE, QUERY_EMPTY, &options);
+                assert_eq!(ErrorCode::WalletQueryError, res.unwrap_err());
+
+                wallet::close_wallet(wallet_handle).unwrap();
+            }
         }
 
         #[test]
@@ -1279,6 +1829,169 @@ mod high_cases {
                 wallet::close_wallet(wallet_handle).unwrap();
             }
         }
+
+        mod numeric_queries {
+            use super::*;
+
+            const STORAGE_TYPES: &'static [&'static str] = &["default", "kv"];
+
+            // Numeric queries must give the same result on all built-in storages
+            fn _search_values(query_json: &str) -> Vec<Vec<String>> {
+                STORAGE_TYPES.iter().map(|storage_type| {
+                    utils::setup();
+
+                    let wallet_handle = wallet::create_and_open_wallet(Some(storage_type)).unwrap();
+
+                    for (i, amount) in ["9", "10", "-1.5", "007", "1-2", "1.2.3", "1e3", "abc",
+                        "9007199254740992", "9007199254740993", "99999999999999999999"].iter().enumerate() {
+                        let tags = json!({"~amount": amount}).to_string();
+                        add_wallet_record(wallet_handle, TYPE, &format!("{}", i), amount, Some(&tags)).unwrap();
+                    }
+
+                    let search_handle = open_wallet_search(wallet_handle, TYPE, query_json, OPTIONS_ID_TYPE_VALUE).unwrap();
+                    let search_records = fetch_wallet_search_next_records(wallet_handle, search_handle, 20).unwrap();
+                    let search_records: SearchRecords = serde_json::from_str(&search_records).unwrap();
+
+                    let mut values: Vec<String> = search_records.records.unwrap_or_default()
+                        .into_iter()
+                        .map(|record| record.value.unwrap())
+                        .collect();
+                    values.sort();
+
+                    close_wallet_search(search_handle).unwrap();
+                    utils::tear_down_with_wallet(wallet_handle);
+
+                    values
+                }).collect()
+            }
+
+            fn _check(query_json: &str, expected: Vec<&str>) {
+                let mut expected: Vec<String> = expected.into_iter().map(String::from).collect();
+                expected.sort();
+
+                for values in _search_values(query_json) {
+                    assert_eq!(expected, values);
+                }
+            }
+
+            #[test]
+            fn indy_wallet_search_for_numeric_gt_query() {
+                _check(r#"{"~amount": {"$gt": 9}}"#, vec!["10", "9007199254740992", "9007199254740993", "99999999999999999999"]);
+            }
+
+            #[test]
+            fn indy_wallet_search_for_numeric_query_above_2_pow_53() {
+                _check(r#"{"~amount": {"$gt": 9007199254740992}}"#, vec!["9007199254740993", "99999999999999999999"]);
+                _check(r#"{"~amount": {"$lte": 9007199254740992}}"#, vec!["9", "10", "-1.5", "007", "9007199254740992"]);
+            }
+
+            #[test]
+            fn indy_wallet_search_for_numeric_float_query() {
+                _check(r#"{"~amount": {"$lt": 7.5}}"#, vec!["-1.5", "007"]);
+                _check(r#"{"~amount": {"$gte": 1e3}}"#, vec!["9007199254740992", "9007199254740993", "99999999999999999999"]);
+            }
+
+            #[test]
+            fn indy_wallet_search_for_numeric_query_skips_non_numbers() {
+                _check(r#"{"~amount": {"$gt": -100}}"#, vec!["9", "10", "-1.5", "007", "9007199254740992", "9007199254740993", "99999999999999999999"]);
+            }
+        }
+
+        mod count {
+            use super::*;
+
+            #[test]
+            fn indy_count_wallet_records_works() {
+                let wallet_handle = setup();
+
+                let count = count_wallet_records(wallet_handle, TYPE, QUERY_EMPTY).unwrap();
+                assert_eq!(5, count);
+
+                wallet::close_wallet(wallet_handle).unwrap();
+            }
+
+            #[test]
+            fn indy_count_wallet_records_works_for_query() {
+                let wallet_handle = setup();
+
+                let query_json = json!({
+                    "tagName1": "str1"
+                }).to_string();
+
+                let count = count_wallet_records(wallet_handle, TYPE, &query_json).unwrap();
+                assert_eq!(2, count);
+
+                wallet::close_wallet(wallet_handle).unwrap();
+            }
+
+            #[test]
+            fn indy_count_wallet_records_works_for_unknown_type() {
+                let wallet_handle = setup();
+
+                let count = count_wallet_records(wallet_handle, "UnknownType", QUERY_EMPTY).unwrap();
+                assert_eq!(0, count);
+
+                wallet::close_wallet(wallet_handle).unwrap();
+            }
+
+            #[test]
+            fn indy_count_wallet_records_works_for_invalid_wallet_handle() {
+                let wallet_handle = setup();
+
+                let res = count_wallet_records(wallet_handle + 1, TYPE, QUERY_EMPTY);
+                assert_eq!(ErrorCode::WalletInvalidHandle, res.unwrap_err());
+
+                wallet::close_wallet(wallet_handle).unwrap();
+            }
+
+            #[test]
+            fn indy_count_wallet_records_by_tag_works() {
+                let wallet_handle = setup();
+
+                let counts_json = count_wallet_records_by_tag(wallet_handle, TYPE, QUERY_EMPTY, "~tagName2").unwrap();
+                let counts: HashMap<String, usize> = serde_json::from_str(&counts_json).unwrap();
+
+                let expected: HashMap<String, usize> = serde_json::from_value(json!({
+                    "5": 1,
+                    "pre_str3": 1,
+                    "4": 1,
+                    "str3": 1
+                })).unwrap();
+                assert_eq!(expected, counts);
+
+                wallet::close_wallet(wallet_handle).unwrap();
+            }
+
+            #[test]
+            fn indy_count_wallet_records_by_tag_works_for_query() {
+                let wallet_handle = setup();
+
+                let query_json = json!({
+                    "~tagName3": {"$gt": 5}
+                }).to_string();
+
+                let counts_json = count_wallet_records_by_tag(wallet_handle, TYPE, &query_json, "~tagName2").unwrap();
+                let counts: HashMap<String, usize> = serde_json::from_str(&counts_json).unwrap();
+
+                let expected: HashMap<String, usize> = serde_json::from_value(json!({
+                    "5": 1,
+                    "str3": 1
+                })).unwrap();
+                assert_eq!(expected, counts);
+
+                wallet::close_wallet(wallet_handle).unwrap();
+            }
+
+            #[test]
+            fn indy_count_wallet_records_by_tag_works_for_encrypted_tag() {
+                let wallet_handle = setup();
+
+                let res = count_wallet_records_by_tag(wallet_handle, TYPE, QUERY_EMPTY, "tagName1");
+                assert_eq!(ErrorCode::WalletQueryError, res.unwrap_err());
+
+                wallet::close_wallet(wallet_handle).unwrap();
+            }
+        }
     }
 }
 
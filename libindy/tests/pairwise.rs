@@ -29,7 +29,7 @@ use indy::api as api;
 #[macro_use]
 mod utils;
 
-use utils::{did, pairwise};
+use utils::{did, pairwise, crypto};
 use utils::constants::*;
 
 use indy::api::ErrorCode;
@@ -301,5 +301,317 @@ mod high_cases {
             utils::tear_down_with_wallet(wallet_handle);
         }
     }
-}
 
+    mod search_pairwise {
+        use super::*;
+
+        #[test]
+        fn indy_search_pairwise_works() {
+            let (wallet_handle, my_did) = utils::setup_did();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            did::store_their_did_from_parts(wallet_handle, DID, VERKEY).unwrap();
+
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, Some(METADATA)).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID, &my_did, None).unwrap();
+
+            pairwise::set_pairwise_tags(wallet_handle, DID_TRUSTEE, r#"{"connection_id": "1"}"#).unwrap();
+            pairwise::set_pairwise_tags(wallet_handle, DID, r#"{"connection_id": "2"}"#).unwrap();
+
+            let list_pairwise = pairwise::search_pairwise(wallet_handle, r#"{"connection_id": "1"}"#).unwrap();
+            let list_pairwise: Vec<serde_json::Value> = serde_json::from_str(&list_pairwise).unwrap();
+            assert_eq!(1, list_pairwise.len());
+            assert_eq!(my_did, list_pairwise[0]["my_did"].as_str().unwrap());
+            assert_eq!(DID_TRUSTEE, list_pairwise[0]["their_did"].as_str().unwrap());
+            assert_eq!(METADATA, list_pairwise[0]["metadata"].as_str().unwrap());
+            assert_eq!("1", list_pairwise[0]["tags"]["connection_id"].as_str().unwrap());
+            let created_at = list_pairwise[0]["tags"]["~created_at"].as_str().unwrap().to_string();
+
+            let list_pairwise = pairwise::search_pairwise(wallet_handle, &json!({"~created_at": {"$lte": created_at}}).to_string()).unwrap();
+            let list_pairwise: Vec<serde_json::Value> = serde_json::from_str(&list_pairwise).unwrap();
+            assert_eq!(2, list_pairwise.len());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_search_pairwise_works_for_metadata_update() {
+            let (wallet_handle, my_did) = utils::setup_did();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, None).unwrap();
+            pairwise::set_pairwise_tags(wallet_handle, DID_TRUSTEE, r#"{"connection_id": "1"}"#).unwrap();
+
+            pairwise::set_pairwise_metadata(wallet_handle, DID_TRUSTEE, Some(METADATA)).unwrap();
+
+            let list_pairwise = pairwise::search_pairwise(wallet_handle, r#"{"connection_id": "1"}"#).unwrap();
+            let list_pairwise: Vec<serde_json::Value> = serde_json::from_str(&list_pairwise).unwrap();
+            assert_eq!(1, list_pairwise.len());
+            assert_eq!(METADATA, list_pairwise[0]["metadata"].as_str().unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_search_pairwise_works_for_my_did() {
+            let (wallet_handle, my_did) = utils::setup_did();
+            let (my_did_2, _) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            did::store_their_did_from_parts(wallet_handle, DID, VERKEY).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, None).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID, &my_did_2, None).unwrap();
+
+            let list_pairwise = pairwise::search_pairwise(wallet_handle, &json!({"my_did": my_did_2}).to_string()).unwrap();
+            let list_pairwise: Vec<serde_json::Value> = serde_json::from_str(&list_pairwise).unwrap();
+            assert_eq!(1, list_pairwise.len());
+            assert_eq!(DID, list_pairwise[0]["their_did"].as_str().unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_set_pairwise_tags_works_for_reserved_tag() {
+            let (wallet_handle, my_did) = utils::setup_did();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, None).unwrap();
+
+            let res = pairwise::set_pairwise_tags(wallet_handle, DID_TRUSTEE, r#"{"my_did": "other"}"#);
+            assert_eq!(ErrorCode::CommonInvalidStructure, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_set_pairwise_tags_works_for_not_created_pairwise() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = pairwise::set_pairwise_tags(wallet_handle, DID_TRUSTEE, r#"{"connection_id": "1"}"#);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod delete_pairwise {
+        use super::*;
+
+        #[test]
+        fn indy_delete_pairwise_works() {
+            let (wallet_handle, my_did) = utils::setup_did();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, None).unwrap();
+
+            pairwise::delete_pairwise(wallet_handle, DID_TRUSTEE, false).unwrap();
+
+            assert!(!pairwise::pairwise_exists(wallet_handle, DID_TRUSTEE).unwrap());
+            did::key_for_local_did(wallet_handle, DID_TRUSTEE).unwrap();
+            did::key_for_local_did(wallet_handle, &my_did).unwrap();
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_delete_pairwise_works_for_cascade() {
+            let (wallet_handle, my_did) = utils::setup_did();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, None).unwrap();
+
+            pairwise::delete_pairwise(wallet_handle, DID_TRUSTEE, true).unwrap();
+
+            assert!(!pairwise::pairwise_exists(wallet_handle, DID_TRUSTEE).unwrap());
+            assert_eq!(ErrorCode::WalletItemNotFound, did::key_for_local_did(wallet_handle, DID_TRUSTEE).unwrap_err());
+
+            let my_verkey = did::key_for_local_did(wallet_handle, &my_did).unwrap();
+            crypto::sign(wallet_handle, &my_verkey, MESSAGE.as_bytes()).unwrap();
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_delete_pairwise_works_for_cascade_and_plugged_wallet_without_batch() {
+            let wallet_handle = utils::setup_with_plugged_wallet();
+            let (my_did, _) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, None).unwrap();
+
+            pairwise::delete_pairwise(wallet_handle, DID_TRUSTEE, true).unwrap();
+
+            assert!(!pairwise::pairwise_exists(wallet_handle, DID_TRUSTEE).unwrap());
+            assert_eq!(ErrorCode::WalletItemNotFound, did::key_for_local_did(wallet_handle, DID_TRUSTEE).unwrap_err());
+            did::key_for_local_did(wallet_handle, &my_did).unwrap();
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_delete_pairwise_works_for_plugged_wallet_without_batch() {
+            let wallet_handle = utils::setup_with_plugged_wallet();
+            let (my_did, _) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, None).unwrap();
+
+            pairwise::delete_pairwise(wallet_handle, DID_TRUSTEE, false).unwrap();
+
+            assert!(!pairwise::pairwise_exists(wallet_handle, DID_TRUSTEE).unwrap());
+            did::key_for_local_did(wallet_handle, DID_TRUSTEE).unwrap();
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_delete_pairwise_works_for_not_created_pairwise() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = pairwise::delete_pairwise(wallet_handle, DID_TRUSTEE, false);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod update_pairwise_their_did {
+        use super::*;
+
+        #[test]
+        fn indy_update_pairwise_their_did_works() {
+            let (wallet_handle, my_did) = utils::setup_did();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            did::store_their_did_from_parts(wallet_handle, DID, VERKEY).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, Some(METADATA)).unwrap();
+            pairwise::set_pairwise_tags(wallet_handle, DID_TRUSTEE, r#"{"connection_id": "1"}"#).unwrap();
+
+            pairwise::update_pairwise_their_did(wallet_handle, DID_TRUSTEE, DID).unwrap();
+
+            assert!(!pairwise::pairwise_exists(wallet_handle, DID_TRUSTEE).unwrap());
+
+            let pairwise_info = pairwise::get_pairwise(wallet_handle, DID).unwrap();
+            assert_eq!(format!(r#"{{"my_did":"{}","metadata":"{}"}}"#, my_did, METADATA), pairwise_info);
+
+            let list_pairwise = pairwise::search_pairwise(wallet_handle, r#"{"connection_id": "1"}"#).unwrap();
+            let list_pairwise: Vec<serde_json::Value> = serde_json::from_str(&list_pairwise).unwrap();
+            assert_eq!(1, list_pairwise.len());
+            assert_eq!(DID, list_pairwise[0]["their_did"].as_str().unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_pairwise_their_did_works_for_plugged_wallet_without_batch() {
+            let wallet_handle = utils::setup_with_plugged_wallet();
+            let (my_did, _) = did::create_and_store_my_did(wallet_handle, None).unwrap();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            did::store_their_did_from_parts(wallet_handle, DID, VERKEY).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, Some(METADATA)).unwrap();
+
+            pairwise::update_pairwise_their_did(wallet_handle, DID_TRUSTEE, DID).unwrap();
+
+            assert!(!pairwise::pairwise_exists(wallet_handle, DID_TRUSTEE).unwrap());
+
+            let pairwise_info = pairwise::get_pairwise(wallet_handle, DID).unwrap();
+            assert_eq!(format!(r#"{{"my_did":"{}","metadata":"{}"}}"#, my_did, METADATA), pairwise_info);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_pairwise_their_did_works_for_unknown_new_their_did() {
+            let (wallet_handle, my_did) = utils::setup_did();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, None).unwrap();
+
+            let res = pairwise::update_pairwise_their_did(wallet_handle, DID_TRUSTEE, DID);
+            assert_eq!(ErrorCode::WalletItemNotFound, res.unwrap_err());
+            assert!(pairwise::pairwise_exists(wallet_handle, DID_TRUSTEE).unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_update_pairwise_their_did_works_for_existing_pairwise() {
+            let (wallet_handle, my_did) = utils::setup_did();
+
+            did::store_their_did_from_parts(wallet_handle, DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            did::store_their_did_from_parts(wallet_handle, DID, VERKEY).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID_TRUSTEE, &my_did, None).unwrap();
+            pairwise::create_pairwise(wallet_handle, DID, &my_did, None).unwrap();
+
+            let res = pairwise::update_pairwise_their_did(wallet_handle, DID_TRUSTEE, DID);
+            assert_eq!(ErrorCode::WalletItemAlreadyExists, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
+    mod pairwise_search {
+        use super::*;
+
+        fn _create_pairwise(wallet_handle: i32, my_did: &str) {
+            for (i, &(their_did, their_verkey)) in [(DID_TRUSTEE, VERKEY_TRUSTEE), (DID, VERKEY), (DID_MY1, VERKEY_MY1)].iter().enumerate() {
+                did::store_their_did_from_parts(wallet_handle, their_did, their_verkey).unwrap();
+                pairwise::create_pairwise(wallet_handle, their_did, my_did, None).unwrap();
+                pairwise::set_pairwise_tags(wallet_handle, their_did, &format!(r#"{{"connection_id": "{}"}}"#, i)).unwrap();
+            }
+        }
+
+        #[test]
+        fn indy_pairwise_search_works() {
+            let (wallet_handle, my_did) = utils::setup_did();
+            _create_pairwise(wallet_handle, &my_did);
+
+            let (search_handle, total_count) = pairwise::open_pairwise_search(wallet_handle, None).unwrap();
+            assert_eq!(3, total_count);
+
+            let list_pairwise = pairwise::fetch_pairwise_search_next(search_handle, 2).unwrap();
+            let list_pairwise: Vec<serde_json::Value> = serde_json::from_str(&list_pairwise).unwrap();
+            assert_eq!(2, list_pairwise.len());
+
+            let list_pairwise = pairwise::fetch_pairwise_search_next(search_handle, 2).unwrap();
+            let list_pairwise: Vec<serde_json::Value> = serde_json::from_str(&list_pairwise).unwrap();
+            assert_eq!(1, list_pairwise.len());
+
+            pairwise::close_pairwise_search(search_handle).unwrap();
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_pairwise_search_works_for_query() {
+            let (wallet_handle, my_did) = utils::setup_did();
+            _create_pairwise(wallet_handle, &my_did);
+
+            let (search_handle, total_count) = pairwise::open_pairwise_search(wallet_handle, Some(r#"{"connection_id": "1"}"#)).unwrap();
+            assert_eq!(1, total_count);
+
+            let list_pairwise = pairwise::fetch_pairwise_search_next(search_handle, 10).unwrap();
+            let list_pairwise: Vec<serde_json::Value> = serde_json::from_str(&list_pairwise).unwrap();
+            assert_eq!(1, list_pairwise.len());
+            assert_eq!(DID, list_pairwise[0]["their_did"].as_str().unwrap());
+
+            pairwise::close_pairwise_search(search_handle).unwrap();
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_fetch_pairwise_search_next_works_for_closed_search() {
+            let (wallet_handle, _) = utils::setup_did();
+
+            let (search_handle, _) = pairwise::open_pairwise_search(wallet_handle, None).unwrap();
+            pairwise::close_pairwise_search(search_handle).unwrap();
+
+            let res = pairwise::fetch_pairwise_search_next(search_handle, 10);
+            assert_eq!(ErrorCode::WalletInvalidHandle, res.unwrap_err());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+}
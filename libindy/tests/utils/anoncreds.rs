@@ -165,6 +165,22 @@ pub fn issuer_revoke_credential(wallet_handle: i32, blob_storage_reader_handle:
     super::results::result_to_string(err, receiver)
 }
 
+pub fn issuer_revoke_credentials(wallet_handle: i32, blob_storage_reader_handle: i32, rev_reg_id: &str, cred_revoc_ids_json: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let rev_reg_id = CString::new(rev_reg_id).unwrap();
+    let cred_revoc_ids_json = CString::new(cred_revoc_ids_json).unwrap();
+
+    let err = indy_issuer_revoke_credentials(command_handle,
+                                             wallet_handle,
+                                             blob_storage_reader_handle,
+                                             rev_reg_id.as_ptr(),
+                                             cred_revoc_ids_json.as_ptr(),
+                                             cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
 pub fn issuer_merge_revocation_registry_deltas(rev_reg_delta: &str, other_rev_reg_delta: &str) -> Result<String, ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
 
@@ -189,6 +205,52 @@ pub fn prover_create_master_secret(wallet_handle: i32, master_secret_id: &str) -
     super::results::result_to_string(err, receiver)
 }
 
+pub fn prover_list_master_secrets(wallet_handle: i32) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let err = indy_prover_list_master_secrets(command_handle, wallet_handle, cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
+pub fn prover_export_master_secret(wallet_handle: i32, master_secret_id: &str, passphrase: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let master_secret_id = CString::new(master_secret_id).unwrap();
+    let passphrase = CString::new(passphrase).unwrap();
+
+    let err = indy_prover_export_master_secret(command_handle, wallet_handle, master_secret_id.as_ptr(), passphrase.as_ptr(), cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
+pub fn prover_import_master_secret(wallet_handle: i32, master_secret_id: Option<&str>, exported_master_secret_json: &str, passphrase: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let master_secret_id = master_secret_id.map(ctypes::str_to_cstring);
+    let exported_master_secret_json = CString::new(exported_master_secret_json).unwrap();
+    let passphrase = CString::new(passphrase).unwrap();
+
+    let err = indy_prover_import_master_secret(command_handle,
+                                               wallet_handle,
+                                               master_secret_id.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                               exported_master_secret_json.as_ptr(),
+                                               passphrase.as_ptr(),
+                                               cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
+pub fn prover_delete_master_secret(wallet_handle: i32, master_secret_id: &str) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let master_secret_id = CString::new(master_secret_id).unwrap();
+
+    let err = indy_prover_delete_master_secret(command_handle, wallet_handle, master_secret_id.as_ptr(), cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
 pub fn prover_create_credential_req(wallet_handle: i32, prover_did: &str, cred_offer_json: &str,
                                     cred_def_json: &str, master_secret_id: &str) -> Result<(String, String), ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string_string();
@@ -308,6 +370,21 @@ pub fn prover_get_credentials_for_proof_req(wallet_handle: i32, proof_request_js
     super::results::result_to_string(err, receiver)
 }
 
+pub fn prover_select_credentials_for_proof_req(wallet_handle: i32, proof_request_json: &str, selection_policy_json: Option<&str>) -> Result<(String, String), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string_string();
+
+    let proof_request_json = CString::new(proof_request_json).unwrap();
+    let selection_policy_json = selection_policy_json.map(ctypes::str_to_cstring);
+
+    let err = indy_prover_select_credentials_for_proof_req(command_handle,
+                                                           wallet_handle,
+                                                           proof_request_json.as_ptr(),
+                                                           selection_policy_json.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                                           cb);
+
+    super::results::result_to_string_string(err, receiver)
+}
+
 pub fn prover_search_credentials_for_proof_req(wallet_handle: i32, proof_request_json: &str, extra_query_json: Option<&str>) -> Result<i32, ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_i32();
 
@@ -395,6 +472,48 @@ pub fn verifier_verify_proof(proof_request_json: &str, proof_json: &str, schemas
     super::results::result_to_bool(err, receiver)
 }
 
+pub fn verifier_verify_proof_with_options(proof_request_json: &str, proof_json: &str, schemas_json: &str,
+                                          cred_defs_json: &str, rev_reg_defs_json: &str, rev_regs_json: &str,
+                                          options_json: Option<&str>) -> Result<bool, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_bool();
+
+    let proof_request_json = CString::new(proof_request_json).unwrap();
+    let proof_json = CString::new(proof_json).unwrap();
+    let schemas_json = CString::new(schemas_json).unwrap();
+    let credential_defs_json = CString::new(cred_defs_json).unwrap();
+    let rev_reg_defs_json = CString::new(rev_reg_defs_json).unwrap();
+    let rev_regs_json = CString::new(rev_regs_json).unwrap();
+    let options_json = options_json.map(ctypes::str_to_cstring);
+
+    let err = indy_verifier_verify_proof_with_options(command_handle,
+                                                      proof_request_json.as_ptr(),
+                                                      proof_json.as_ptr(),
+                                                      schemas_json.as_ptr(),
+                                                      credential_defs_json.as_ptr(),
+                                                      rev_reg_defs_json.as_ptr(),
+                                                      rev_regs_json.as_ptr(),
+                                                      options_json.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                                      cb);
+
+    super::results::result_to_bool(err, receiver)
+}
+
+pub fn validate_proof_request(proof_request_json: &str, schemas_json: Option<&str>, attr_types_json: Option<&str>) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let proof_request_json = CString::new(proof_request_json).unwrap();
+    let schemas_json = schemas_json.map(ctypes::str_to_cstring);
+    let attr_types_json = attr_types_json.map(ctypes::str_to_cstring);
+
+    let err = indy_validate_proof_request(command_handle,
+                                          proof_request_json.as_ptr(),
+                                          schemas_json.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                          attr_types_json.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                          cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
 pub fn create_revocation_state(blob_storage_reader_handle: i32, rev_reg_def_json: &str,
                                rev_reg_delta_json: &str, timestamp: u64, cred_rev_id: &str) -> Result<String, ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
@@ -66,6 +66,32 @@ lazy_static! {
         (receiver, command_handle, Some(_callback))
     }
 
+    pub fn _closure_to_cb_ec_usize() -> (Receiver<(ErrorCode, usize)>, i32,
+                                         Option<extern fn(command_handle: i32, err: ErrorCode,
+                                                          c_usize: usize)>) {
+        let (sender, receiver) = channel();
+
+        lazy_static! {
+            static ref CALLBACKS: Mutex<HashMap<i32, Box<FnMut(ErrorCode, usize) + Send>>> = Default::default();
+        }
+
+        let closure = Box::new(move |err, val| {
+            sender.send((err, val)).unwrap();
+        });
+
+        extern "C" fn _callback(command_handle: i32, err: ErrorCode, c_usize: usize) {
+            let mut callbacks = CALLBACKS.lock().unwrap();
+            let mut cb = callbacks.remove(&command_handle).unwrap();
+            cb(err, c_usize)
+        }
+
+        let mut callbacks = CALLBACKS.lock().unwrap();
+        let command_handle = (COMMAND_HANDLE_COUNTER.fetch_add(1, Ordering::SeqCst) + 1) as i32;
+        callbacks.insert(command_handle, closure);
+
+        (receiver, command_handle, Some(_callback))
+    }
+
     pub fn _closure_to_cb_ec_i32_usize() -> (Receiver<(ErrorCode, i32, usize)>, i32,
                                              Option<extern fn(command_handle: i32, err: ErrorCode,
                                                               c_i32: i32, c_usize: usize)>) {
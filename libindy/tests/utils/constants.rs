@@ -3,7 +3,8 @@ pub const PROTOCOL_VERSION: usize = 2;
 pub const POOL: &'static str = "pool_1";
 pub const WALLET: &'static str = "wallet_1";
 pub const TYPE: &'static str = "default";
-pub const INMEM_TYPE: &'static str = "inmem";
+pub const INMEM_TYPE: &'static str = "plugged_inmem";
+pub const INMEM_BATCH_TYPE: &'static str = "plugged_inmem_batch";
 pub const SIGNATURE_TYPE: &'static str = "CL";
 pub const TRUSTEE_SEED: &'static str = "000000000000000000000000Trustee1";
 pub const STEWARD_SEED: &'static str = "000000000000000000000000Steward1";
@@ -49,5 +50,7 @@ pub const WALLET_CREDENTIALS_ARGON2I_INT: &'static str = r#"{"key":"key", "key_d
 pub const WALLET_CREDENTIALS_RAW: &'static str = r#"{"key":"8dvfYSt5d1taSd6yJdpjq4emkwsPDDLYxkNFysFD2cZY", "key_derivation_method":"RAW"}"#;
 pub const WALLET_CONFIG: &'static str = r#"{"id":"wallet_1"}"#;
 pub const DEFAULT_WALLET_CONFIG: &'static str = r#"{"id":"wallet_1","storage_type":"default"}"#;
-pub const INMEM_WALLET_CONFIG: &'static str = r#"{"id":"wallet_1","storage_type":"inmem"}"#;
+pub const INMEM_WALLET_CONFIG: &'static str = r#"{"id":"wallet_1","storage_type":"plugged_inmem"}"#;
+pub const BUILTIN_INMEM_WALLET_CONFIG: &'static str = r#"{"id":"wallet_1","storage_type":"inmem"}"#;
+pub const KV_WALLET_CONFIG: &'static str = r#"{"id":"wallet_1","storage_type":"kv"}"#;
 pub const UNKNOWN_WALLET_CONFIG: &'static str = r#"{"id":"wallet_1","storage_type":"unknown"}"#;
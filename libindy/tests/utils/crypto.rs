@@ -1,6 +1,7 @@
 extern crate libc;
 
 use std::ffi::CString;
+use std::ptr::null;
 
 use indy::api::crypto::*;
 use indy::api::ErrorCode;
@@ -21,6 +22,20 @@ pub fn create_key(wallet_handle: i32, seed: Option<&str>) -> Result<String, Erro
     super::results::result_to_string(err, receiver)
 }
 
+pub fn create_key_with_crypto_type(wallet_handle: i32, seed: Option<&str>, crypto_type: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let key_json = json!({"seed": seed, "crypto_type": crypto_type}).to_string();
+    let key_json = CString::new(key_json).unwrap();
+
+    let err = indy_create_key(command_handle,
+                              wallet_handle,
+                              key_json.as_ptr(),
+                              cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
 pub fn set_key_metadata(wallet_handle: i32, verkey: &str, metadata: &str) -> Result<(), ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
 
@@ -77,6 +92,25 @@ pub fn verify(their_vk: &str, msg: &[u8], signature: &[u8]) -> Result<bool, Erro
     super::results::result_to_bool(err, receiver)
 }
 
+pub fn verify_for_did(pool_handle: i32, wallet_handle: i32, did: &str, msg: &[u8], signature: &[u8], require_current: bool) -> Result<bool, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_bool();
+
+    let did = CString::new(did).unwrap();
+
+    let err = indy_crypto_verify_for_did(command_handle,
+                                         pool_handle,
+                                         wallet_handle,
+                                         did.as_ptr(),
+                                         msg.as_ptr() as *const u8,
+                                         msg.len() as u32,
+                                         signature.as_ptr() as *const u8,
+                                         signature.len() as u32,
+                                         require_current,
+                                         cb);
+
+    super::results::result_to_bool(err, receiver)
+}
+
 pub fn auth_crypt(wallet_handle: i32, my_vk: &str, their_vk: &str, msg: &[u8]) -> Result<Vec<u8>, ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_vec_u8();
 
@@ -140,3 +174,238 @@ pub fn anon_decrypt(wallet_handle: i32, my_vk: &str, encrypted_msg: &[u8]) -> Re
 
     super::results::result_to_vec_u8(err, receiver)
 }
+
+pub fn pack_message(wallet_handle: i32, message: &[u8], receiver_keys: &str, sender: Option<&str>) -> Result<Vec<u8>, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_vec_u8();
+
+    let receiver_keys = CString::new(receiver_keys).unwrap();
+    let sender = sender.map(|s| CString::new(s).unwrap());
+
+    let err =
+        indy_pack_message(command_handle,
+                          wallet_handle,
+                          message.as_ptr() as *const u8,
+                          message.len() as u32,
+                          receiver_keys.as_ptr(),
+                          sender.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                          cb);
+
+    super::results::result_to_vec_u8(err, receiver)
+}
+
+pub fn unpack_message(wallet_handle: i32, jwe: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_vec_u8();
+
+    let err =
+        indy_unpack_message(command_handle,
+                            wallet_handle,
+                            jwe.as_ptr() as *const u8,
+                            jwe.len() as u32,
+                            cb);
+
+    super::results::result_to_vec_u8(err, receiver)
+}
+
+pub fn encrypt_stream_open(wallet_handle: i32, receiver_keys: &str, sender: Option<&str>) -> Result<i32, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_i32();
+
+    let receiver_keys = CString::new(receiver_keys).unwrap();
+    let sender = sender.map(|s| CString::new(s).unwrap());
+
+    let err =
+        indy_crypto_encrypt_stream_open(command_handle,
+                                        wallet_handle,
+                                        receiver_keys.as_ptr(),
+                                        sender.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                        cb);
+
+    super::results::result_to_int(err, receiver)
+}
+
+pub fn encrypt_stream_feed(stream_handle: i32, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_vec_u8();
+
+    let err =
+        indy_crypto_encrypt_stream_feed(command_handle,
+                                        stream_handle,
+                                        data.as_ptr() as *const u8,
+                                        data.len() as u32,
+                                        cb);
+
+    super::results::result_to_vec_u8(err, receiver)
+}
+
+pub fn encrypt_stream_finish(stream_handle: i32) -> Result<Vec<u8>, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_vec_u8();
+
+    let err = indy_crypto_encrypt_stream_finish(command_handle, stream_handle, cb);
+
+    super::results::result_to_vec_u8(err, receiver)
+}
+
+pub fn encrypt_stream_close(stream_handle: i32) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let err = indy_crypto_encrypt_stream_close(command_handle, stream_handle, cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
+pub fn decrypt_stream_open(wallet_handle: i32) -> Result<i32, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_i32();
+
+    let err = indy_crypto_decrypt_stream_open(command_handle, wallet_handle, cb);
+
+    super::results::result_to_int(err, receiver)
+}
+
+pub fn decrypt_stream_feed(stream_handle: i32, encrypted: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_vec_u8();
+
+    let err =
+        indy_crypto_decrypt_stream_feed(command_handle,
+                                        stream_handle,
+                                        encrypted.as_ptr() as *const u8,
+                                        encrypted.len() as u32,
+                                        cb);
+
+    super::results::result_to_vec_u8(err, receiver)
+}
+
+pub fn decrypt_stream_finish(stream_handle: i32) -> Result<(String, Vec<u8>), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string_vec_u8();
+
+    let err = indy_crypto_decrypt_stream_finish(command_handle, stream_handle, cb);
+
+    super::results::result_to_string_vec_u8(err, receiver)
+}
+
+pub fn decrypt_stream_close(stream_handle: i32) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let err = indy_crypto_decrypt_stream_close(command_handle, stream_handle, cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
+pub fn jws_sign(wallet_handle: i32, signer: &str, payload: &[u8], options_json: Option<&str>) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let signer = CString::new(signer).unwrap();
+    let options_json = options_json.map(|s| CString::new(s).unwrap());
+
+    let err =
+        indy_crypto_jws_sign(command_handle,
+                             wallet_handle,
+                             signer.as_ptr(),
+                             payload.as_ptr() as *const u8,
+                             payload.len() as u32,
+                             options_json.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                             cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
+pub fn jws_verify(wallet_handle: i32, jws: &str, detached_payload: Option<&[u8]>, options_json: Option<&str>) -> Result<bool, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_bool();
+
+    let jws = CString::new(jws).unwrap();
+    let options_json = options_json.map(|s| CString::new(s).unwrap());
+
+    let err =
+        indy_crypto_jws_verify(command_handle,
+                               wallet_handle,
+                               jws.as_ptr(),
+                               detached_payload.map(|p| p.as_ptr() as *const u8).unwrap_or(null()),
+                               detached_payload.map(|p| p.len() as u32).unwrap_or(0),
+                               options_json.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                               cb);
+
+    super::results::result_to_bool(err, receiver)
+}
+
+pub fn export_key(wallet_handle: i32, verkey: &str, passphrase: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let verkey = CString::new(verkey).unwrap();
+    let passphrase = CString::new(passphrase).unwrap();
+
+    let err = indy_export_key(command_handle, wallet_handle, verkey.as_ptr(), passphrase.as_ptr(), cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
+pub fn import_key(wallet_handle: i32, exported_key: &str, passphrase: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let exported_key = CString::new(exported_key).unwrap();
+    let passphrase = CString::new(passphrase).unwrap();
+
+    let err = indy_import_key(command_handle, wallet_handle, exported_key.as_ptr(), passphrase.as_ptr(), cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
+pub fn create_root_seed(wallet_handle: i32, mnemonic: Option<&str>) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let mnemonic = mnemonic.map(|s| CString::new(s).unwrap());
+
+    let err = indy_create_root_seed(command_handle, wallet_handle,
+                                    mnemonic.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                    cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
+pub fn export_root_seed(wallet_handle: i32, passphrase: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let passphrase = CString::new(passphrase).unwrap();
+
+    let err = indy_export_root_seed(command_handle, wallet_handle, passphrase.as_ptr(), cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
+pub fn import_root_seed(wallet_handle: i32, exported_root_seed: &str, passphrase: &str) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let exported_root_seed = CString::new(exported_root_seed).unwrap();
+    let passphrase = CString::new(passphrase).unwrap();
+
+    let err = indy_import_root_seed(command_handle, wallet_handle, exported_root_seed.as_ptr(), passphrase.as_ptr(), cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
+pub fn create_key_derived(wallet_handle: i32, path: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let path = CString::new(path).unwrap();
+
+    let err = indy_create_key_derived(command_handle, wallet_handle, path.as_ptr(), cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
+pub fn set_key_tags(wallet_handle: i32, verkey: &str, tags_json: &str) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let verkey = CString::new(verkey).unwrap();
+    let tags_json = CString::new(tags_json).unwrap();
+
+    let err = indy_set_key_tags(command_handle, wallet_handle, verkey.as_ptr(), tags_json.as_ptr(), cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
+pub fn search_keys(wallet_handle: i32, query_json: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let query_json = CString::new(query_json).unwrap();
+
+    let err = indy_search_keys(command_handle, wallet_handle, query_json.as_ptr(), cb);
+
+    super::results::result_to_string(err, receiver)
+}
@@ -100,6 +100,17 @@ use utils::types::ResponseType;
         Ok(verkey)
     }
 
+    pub fn rotate_did_key(pool_handle: i32, wallet_handle: i32, did: &str, key_info: &str) -> Result<String, ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+        let did = CString::new(did).unwrap();
+        let key_info = CString::new(key_info).unwrap();
+
+        let err = indy_rotate_did_key(command_handle, pool_handle, wallet_handle, did.as_ptr(), key_info.as_ptr(), cb);
+
+        super::results::result_to_string(err, receiver)
+    }
+
     pub fn key_for_did(pool_handle: i32, wallet_handle: i32, did: &str) -> Result<String, ErrorCode> {
         let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
 
@@ -187,4 +198,57 @@ use utils::types::ResponseType;
         let err = indy_abbreviate_verkey(command_handle, did.as_ptr(), verkey.as_ptr(), cb);
 
         super::results::result_to_string(err, receiver)
-    }
\ No newline at end of file
+    }
+
+    pub fn export_did(wallet_handle: i32, did: &str, passphrase: &str) -> Result<String, ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+        let did = CString::new(did).unwrap();
+        let passphrase = CString::new(passphrase).unwrap();
+
+        let err = indy_export_did(command_handle, wallet_handle, did.as_ptr(), passphrase.as_ptr(), cb);
+
+        super::results::result_to_string(err, receiver)
+    }
+
+    pub fn import_did(wallet_handle: i32, exported_did: &str, passphrase: &str) -> Result<String, ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+        let exported_did = CString::new(exported_did).unwrap();
+        let passphrase = CString::new(passphrase).unwrap();
+
+        let err = indy_import_did(command_handle, wallet_handle, exported_did.as_ptr(), passphrase.as_ptr(), cb);
+
+        super::results::result_to_string(err, receiver)
+    }
+
+    pub fn create_and_store_my_did_derived(wallet_handle: i32, path: &str) -> Result<(String, String), ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string_string();
+
+        let path = CString::new(path).unwrap();
+
+        let err = indy_create_and_store_my_did_derived(command_handle, wallet_handle, path.as_ptr(), cb);
+
+        super::results::result_to_string_string(err, receiver)
+    }
+
+    pub fn set_did_tags(wallet_handle: i32, did: &str, tags_json: &str) -> Result<(), ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+        let did = CString::new(did).unwrap();
+        let tags_json = CString::new(tags_json).unwrap();
+
+        let err = indy_set_did_tags(command_handle, wallet_handle, did.as_ptr(), tags_json.as_ptr(), cb);
+
+        super::results::result_to_empty(err, receiver)
+    }
+
+    pub fn search_my_dids(wallet_handle: i32, query_json: &str) -> Result<String, ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+        let query_json = CString::new(query_json).unwrap();
+
+        let err = indy_search_my_dids(command_handle, wallet_handle, query_json.as_ptr(), cb);
+
+        super::results::result_to_string(err, receiver)
+    }
@@ -61,6 +61,11 @@ pub fn setup_with_plugged_wallet() -> i32 {
     wallet::create_and_open_plugged_wallet().unwrap()
 }
 
+pub fn setup_with_plugged_wallet_with_optional_handlers() -> i32 {
+    setup();
+    wallet::create_and_open_plugged_wallet_with_optional_handlers().unwrap()
+}
+
 pub fn tear_down_with_wallet(wallet_handle: i32) {
     wallet::close_wallet(wallet_handle).unwrap();
     tear_down();
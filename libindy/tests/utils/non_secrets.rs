@@ -29,6 +29,7 @@ pub const QUERY_EMPTY: &'static str = r#"{}"#;
 pub const OPTIONS_EMPTY: &'static str = r#"{}"#;
 pub const OPTIONS_ID_TYPE_VALUE: &'static str = r#"{"retrieveType":true, "retrieveValue":true, "retrieveTags":false}"#;
 pub const OPTIONS_FULL: &'static str = r#"{"retrieveType":true, "retrieveValue":true, "retrieveTags":true, "retrieveTotalCount":true}"#;
+pub const OPTIONS_VERSION: &'static str = r#"{"retrieveValue":true, "retrieveVersion":true}"#;
 pub const TAGS_EMPTY: &'static str = r#"{}"#;
 pub const TAGS: &'static str = r#"{"tagName1":"str1","~tagName2":"5","~tagName3":"8"}"#;
 pub const TAGS_2: &'static str = r#"{"tagName1":"str2","~tagName2":"pre_str3","~tagName3":"2"}"#;
@@ -56,6 +57,28 @@ pub fn add_wallet_record(wallet_handle: i32, type_: &str, id: &str, value: &str,
     super::results::result_to_empty(err, receiver)
 }
 
+pub fn add_wallet_record_with_options(wallet_handle: i32, type_: &str, id: &str, value: &str, tags_json: Option<&str>, options_json: Option<&str>) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let type_ = CString::new(type_).unwrap();
+    let id = CString::new(id).unwrap();
+    let value = CString::new(value).unwrap();
+    let tags_json = tags_json.map(ctypes::str_to_cstring);
+    let options_json = options_json.map(ctypes::str_to_cstring);
+
+    let err =
+        indy_add_wallet_record_with_options(command_handle,
+                                            wallet_handle,
+                                            type_.as_ptr(),
+                                            id.as_ptr(),
+                                            value.as_ptr(),
+                                            tags_json.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                            options_json.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                            cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
 pub fn update_wallet_record_value(wallet_handle: i32, type_: &str, id: &str, value: &str) -> Result<(), ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
 
@@ -74,6 +97,44 @@ pub fn update_wallet_record_value(wallet_handle: i32, type_: &str, id: &str, val
     super::results::result_to_empty(err, receiver)
 }
 
+pub fn update_wallet_record_value_if_version(wallet_handle: i32, type_: &str, id: &str, value: &str, version: u64) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let type_ = CString::new(type_).unwrap();
+    let id = CString::new(id).unwrap();
+    let value = CString::new(value).unwrap();
+
+    let err =
+        indy_update_wallet_record_value_if_version(command_handle,
+                                                   wallet_handle,
+                                                   type_.as_ptr(),
+                                                   id.as_ptr(),
+                                                   value.as_ptr(),
+                                                   version,
+                                                   cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
+pub fn update_wallet_record_tags_if_version(wallet_handle: i32, type_: &str, id: &str, tags_json: &str, version: u64) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let type_ = CString::new(type_).unwrap();
+    let id = CString::new(id).unwrap();
+    let tags_json = CString::new(tags_json).unwrap();
+
+    let err =
+        indy_update_wallet_record_tags_if_version(command_handle,
+                                                  wallet_handle,
+                                                  type_.as_ptr(),
+                                                  id.as_ptr(),
+                                                  tags_json.as_ptr(),
+                                                  version,
+                                                  cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
 pub fn update_wallet_record_tags(wallet_handle: i32, type_: &str, id: &str, tags_json: &str) -> Result<(), ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
 
@@ -144,6 +205,43 @@ pub fn delete_wallet_record(wallet_handle: i32, type_: &str, id: &str) -> Result
     super::results::result_to_empty(err, receiver)
 }
 
+pub fn delete_wallet_record_if_version(wallet_handle: i32, type_: &str, id: &str, version: u64) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let type_ = CString::new(type_).unwrap();
+    let id = CString::new(id).unwrap();
+
+    let err =
+        indy_delete_wallet_record_if_version(command_handle,
+                                             wallet_handle,
+                                             type_.as_ptr(),
+                                             id.as_ptr(),
+                                             version,
+                                             cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
+pub fn get_wallet_record_version(wallet_handle: i32, type_: &str, id: &str) -> Option<u64> {
+    let record = get_wallet_record(wallet_handle, type_, id, OPTIONS_VERSION).unwrap();
+    let record: serde_json::Value = serde_json::from_str(&record).unwrap();
+    record["version"].as_u64()
+}
+
+pub fn wallet_batch(wallet_handle: i32, operations_json: &str) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let operations_json = CString::new(operations_json).unwrap();
+
+    let err =
+        indy_wallet_batch(command_handle,
+                          wallet_handle,
+                          operations_json.as_ptr(),
+                          cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
 pub fn get_wallet_record(wallet_handle: i32, type_: &str, id: &str, options_json: &str) -> Result<String, ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
 
@@ -204,6 +302,51 @@ pub fn close_wallet_search(wallet_search_handle: i32) -> Result<(), ErrorCode> {
     super::results::result_to_empty(err, receiver)
 }
 
+pub fn count_wallet_records(wallet_handle: i32, type_: &str, query_json: &str) -> Result<usize, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_usize();
+
+    let type_ = CString::new(type_).unwrap();
+    let query_json = CString::new(query_json).unwrap();
+
+    let err =
+        indy_count_wallet_records(command_handle,
+                                  wallet_handle,
+                                  type_.as_ptr(),
+                                  query_json.as_ptr(),
+                                  cb);
+
+    super::results::result_to_usize(err, receiver)
+}
+
+pub fn purge_expired_wallet_records(wallet_handle: i32) -> Result<usize, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_usize();
+
+    let err =
+        indy_purge_expired_wallet_records(command_handle,
+                                          wallet_handle,
+                                          cb);
+
+    super::results::result_to_usize(err, receiver)
+}
+
+pub fn count_wallet_records_by_tag(wallet_handle: i32, type_: &str, query_json: &str, tag_name: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+    let type_ = CString::new(type_).unwrap();
+    let query_json = CString::new(query_json).unwrap();
+    let tag_name = CString::new(tag_name).unwrap();
+
+    let err =
+        indy_count_wallet_records_by_tag(command_handle,
+                                         wallet_handle,
+                                         type_.as_ptr(),
+                                         query_json.as_ptr(),
+                                         tag_name.as_ptr(),
+                                         cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
 pub fn tags_1() -> HashMap<String, String> {
     serde_json::from_str(TAGS).unwrap()
 }
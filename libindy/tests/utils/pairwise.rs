@@ -68,4 +68,73 @@ use std::ptr::null;
                                        cb);
 
         super::results::result_to_empty(err, receiver)
-    }
\ No newline at end of file
+    }
+    pub fn set_pairwise_tags(wallet_handle: i32, their_did: &str, tags_json: &str) -> Result<(), ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+        let their_did = CString::new(their_did).unwrap();
+        let tags_json = CString::new(tags_json).unwrap();
+
+        let err = indy_set_pairwise_tags(command_handle, wallet_handle, their_did.as_ptr(), tags_json.as_ptr(), cb);
+
+        super::results::result_to_empty(err, receiver)
+    }
+
+    pub fn search_pairwise(wallet_handle: i32, query_json: &str) -> Result<String, ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+        let query_json = CString::new(query_json).unwrap();
+
+        let err = indy_search_pairwise(command_handle, wallet_handle, query_json.as_ptr(), cb);
+
+        super::results::result_to_string(err, receiver)
+    }
+
+    pub fn delete_pairwise(wallet_handle: i32, their_did: &str, cascade: bool) -> Result<(), ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+        let their_did = CString::new(their_did).unwrap();
+
+        let err = indy_delete_pairwise(command_handle, wallet_handle, their_did.as_ptr(), cascade, cb);
+
+        super::results::result_to_empty(err, receiver)
+    }
+
+    pub fn update_pairwise_their_did(wallet_handle: i32, their_did: &str, new_their_did: &str) -> Result<(), ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+        let their_did = CString::new(their_did).unwrap();
+        let new_their_did = CString::new(new_their_did).unwrap();
+
+        let err = indy_update_pairwise_their_did(command_handle, wallet_handle, their_did.as_ptr(), new_their_did.as_ptr(), cb);
+
+        super::results::result_to_empty(err, receiver)
+    }
+
+    pub fn open_pairwise_search(wallet_handle: i32, query_json: Option<&str>) -> Result<(i32, usize), ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_i32_usize();
+
+        let query_json = query_json.map(|s| CString::new(s).unwrap());
+
+        let err = indy_open_pairwise_search(command_handle, wallet_handle,
+                                            query_json.as_ref().map(|s| s.as_ptr()).unwrap_or(null()),
+                                            cb);
+
+        super::results::result_to_int_usize(err, receiver)
+    }
+
+    pub fn fetch_pairwise_search_next(search_handle: i32, count: usize) -> Result<String, ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+
+        let err = indy_fetch_pairwise_search_next(command_handle, search_handle, count, cb);
+
+        super::results::result_to_string(err, receiver)
+    }
+
+    pub fn close_pairwise_search(search_handle: i32) -> Result<(), ErrorCode> {
+        let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+        let err = indy_close_pairwise_search(command_handle, search_handle, cb);
+
+        super::results::result_to_empty(err, receiver)
+    }
@@ -8,7 +8,7 @@ use std::collections::HashSet;
 use std::ffi::CString;
 use std::sync::Mutex;
 use std::ptr::null;
-use utils::constants::{TYPE, INMEM_TYPE, WALLET_CREDENTIALS};
+use utils::constants::{TYPE, INMEM_TYPE, INMEM_BATCH_TYPE, WALLET_CREDENTIALS};
 
 use std::path::{Path, PathBuf};
 
@@ -65,6 +65,52 @@ pub fn register_wallet_storage(xtype: &str, force_create: bool) -> Result<(), Er
     super::results::result_to_empty(err, receiver)
 }
 
+pub fn register_wallet_storage_with_optional_handlers(xtype: &str, force_create: bool) -> Result<(), ErrorCode> {
+    lazy_static! {
+            static ref REGISERED_WALLETS: Mutex<HashSet<String>> = Default::default();
+        }
+
+    let mut wallets = REGISERED_WALLETS.lock().unwrap();
+
+    if wallets.contains(xtype) & !force_create {
+        return Ok(());
+    }
+
+    register_wallet_storage(xtype, true)?;
+    register_wallet_storage_batch(xtype)?;
+    register_wallet_storage_versioning(xtype)?;
+
+    wallets.insert(xtype.to_string());
+
+    Ok(())
+}
+
+pub fn register_wallet_storage_batch(xtype: &str) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let xxtype = CString::new(xtype).unwrap();
+
+    let err = indy_register_wallet_storage_batch(command_handle,
+                                                 xxtype.as_ptr(),
+                                                 Some(InmemWallet::batch),
+                                                 cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
+pub fn register_wallet_storage_versioning(xtype: &str) -> Result<(), ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
+
+    let xxtype = CString::new(xtype).unwrap();
+
+    let err = indy_register_wallet_storage_versioning(command_handle,
+                                                      xxtype.as_ptr(),
+                                                      Some(InmemWallet::get_record_version),
+                                                      cb);
+
+    super::results::result_to_empty(err, receiver)
+}
+
 pub fn create_wallet(config: &str, credentials: &str) -> Result<(), ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
 
@@ -121,7 +167,18 @@ pub fn create_and_open_plugged_wallet() -> Result<i32, ErrorCode> {
             "storage_type": INMEM_TYPE
         }).to_string();
 
-    register_wallet_storage("inmem", false).unwrap();
+    register_wallet_storage(INMEM_TYPE, false).unwrap();
+    create_wallet(&config, WALLET_CREDENTIALS)?;
+    open_wallet(&config, WALLET_CREDENTIALS)
+}
+
+pub fn create_and_open_plugged_wallet_with_optional_handlers() -> Result<i32, ErrorCode> {
+    let config = json!({
+            "id": format!("default-wallet_id-{}", sequence::get_next_id()),
+            "storage_type": INMEM_BATCH_TYPE
+        }).to_string();
+
+    register_wallet_storage_with_optional_handlers(INMEM_BATCH_TYPE, false).unwrap();
     create_wallet(&config, WALLET_CREDENTIALS)?;
     open_wallet(&config, WALLET_CREDENTIALS)
 }
@@ -154,6 +211,15 @@ pub fn export_wallet(wallet_handle: i32, export_config_json: &str) -> Result<(),
     super::results::result_to_empty(err, receiver)
 }
 
+pub fn check_wallet(wallet_handle: i32, check_config_json: &str) -> Result<String, ErrorCode> {
+    let (receiver, command_handle, cb) = callback::_closure_to_cb_ec_string();
+    let check_config_json = CString::new(check_config_json).unwrap();
+
+    let err = indy_check_wallet(command_handle, wallet_handle, check_config_json.as_ptr(), cb);
+
+    super::results::result_to_string(err, receiver)
+}
+
 pub fn import_wallet(config: &str, credentials: &str, import_config: &str) -> Result<(), ErrorCode> {
     let (receiver, command_handle, cb) = callback::_closure_to_cb_ec();
 
@@ -98,6 +98,25 @@ mod high_cases {
             utils::tear_down();
         }
 
+        #[test]
+        fn indy_create_wallet_works_for_builtin_inmem() {
+            utils::setup();
+
+            wallet::create_wallet(BUILTIN_INMEM_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+            wallet::delete_wallet(BUILTIN_INMEM_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            utils::tear_down();
+        }
+
+        #[test]
+        fn indy_create_wallet_works_for_kv() {
+            utils::setup();
+
+            wallet::create_wallet(KV_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            utils::tear_down();
+        }
+
         #[test]
         fn indy_create_wallet_works_for_unknown_type() {
             utils::setup();
@@ -242,6 +261,31 @@ mod high_cases {
             InmemWallet::cleanup();
             utils::tear_down();
         }
+
+        #[test]
+        fn indy_open_wallet_works_for_builtin_inmem() {
+            utils::setup();
+
+            wallet::create_wallet(BUILTIN_INMEM_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+            let wallet_handle = wallet::open_wallet(BUILTIN_INMEM_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            wallet::close_wallet(wallet_handle).unwrap();
+            wallet::delete_wallet(BUILTIN_INMEM_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            utils::tear_down();
+        }
+
+        #[test]
+        fn indy_open_wallet_works_for_kv() {
+            utils::setup();
+
+            wallet::create_wallet(KV_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+            let wallet_handle = wallet::open_wallet(KV_WALLET_CONFIG, WALLET_CREDENTIALS).unwrap();
+
+            wallet::close_wallet(wallet_handle).unwrap();
+
+            utils::tear_down();
+        }
     }
 
     mod close_wallet {
@@ -301,6 +345,44 @@ mod high_cases {
         }
     }
 
+    mod check_wallet {
+        use super::*;
+
+        #[test]
+        fn indy_check_wallet_works() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            did::create_my_did(wallet_handle, "{}").unwrap();
+            did::create_my_did(wallet_handle, "{}").unwrap();
+
+            let report_json = wallet::check_wallet(wallet_handle, "{}").unwrap();
+            let report: serde_json::Value = serde_json::from_str(&report_json).unwrap();
+
+            assert!(report["checked"].as_u64().unwrap() > 0);
+            assert!(report["failed"].as_array().unwrap().is_empty());
+            assert_eq!(0, report["quarantined"].as_u64().unwrap());
+            assert_eq!(0, report["orphan_tags"].as_u64().unwrap());
+            assert!(report["storage_errors"].as_array().unwrap().is_empty());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_check_wallet_works_for_quarantine() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            did::create_my_did(wallet_handle, "{}").unwrap();
+
+            let report_json = wallet::check_wallet(wallet_handle, r#"{"quarantine": true}"#).unwrap();
+            let report: serde_json::Value = serde_json::from_str(&report_json).unwrap();
+
+            assert!(report["failed"].as_array().unwrap().is_empty());
+            assert_eq!(0, report["quarantined"].as_u64().unwrap());
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
     mod import_wallet {
         use super::*;
 
@@ -399,6 +481,16 @@ mod medium_cases {
             utils::tear_down();
         }
 
+        #[test]
+        fn indy_register_wallet_storage_does_not_work_for_builtin_type() {
+            utils::setup();
+
+            let res = wallet::register_wallet_storage("inmem", true);
+            assert_eq!(res.unwrap_err(), ErrorCode::WalletTypeAlreadyRegisteredError);
+
+            utils::tear_down();
+        }
+
         #[test]
         fn indy_register_wallet_storage_does_not_work_with_null_params() {
             utils::setup();
@@ -656,6 +748,30 @@ mod medium_cases {
         }
     }
 
+    mod check_wallet {
+        use super::*;
+
+        #[test]
+        fn indy_check_wallet_returns_error_if_invalid_config() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = wallet::check_wallet(wallet_handle, r#"{"quarantine": "yes"}"#);
+            assert_eq!(res.unwrap_err(), ErrorCode::CommonInvalidStructure);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+
+        #[test]
+        fn indy_check_wallet_returns_error_if_invalid_handle() {
+            let wallet_handle = utils::setup_with_wallet();
+
+            let res = wallet::check_wallet(wallet_handle + 1, "{}");
+            assert_eq!(res.unwrap_err(), ErrorCode::WalletInvalidHandle);
+
+            utils::tear_down_with_wallet(wallet_handle);
+        }
+    }
+
     mod import_wallet {
         use super::*;
 